@@ -0,0 +1,288 @@
+use std::collections::BTreeSet;
+
+use glob::Pattern;
+
+use crate::config::{ComponentMapping, TeamMapping};
+use crate::insights::{CIInsights, ComponentMetrics, JobMetrics, TeamMetrics};
+
+struct Matcher {
+    name: String,
+    patterns: Vec<Pattern>,
+}
+
+impl Matcher {
+    fn new(name: &str, patterns: &[String]) -> Self {
+        Self {
+            name: name.to_string(),
+            // Patterns are validated when the config is loaded
+            patterns: patterns
+                .iter()
+                .filter_map(|p| Pattern::new(p).ok())
+                .collect(),
+        }
+    }
+
+    fn matches(&self, job_name: &str) -> bool {
+        self.patterns.iter().any(|p| p.matches(job_name))
+    }
+}
+
+#[derive(Default)]
+struct Totals {
+    jobs: BTreeSet<String>,
+    executions: usize,
+    duration_seconds: f64,
+    failed: usize,
+    flaky: usize,
+    critical_path_seconds: f64,
+}
+
+impl Totals {
+    #[allow(clippy::cast_precision_loss)]
+    fn add(&mut self, job: &JobMetrics) {
+        self.jobs.insert(job.name.clone());
+        self.executions += job.total_executions;
+        self.duration_seconds += job.avg_duration_seconds * job.total_executions as f64;
+        self.failed += job.failed_executions.count;
+        self.flaky += job.flaky_retries.count;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn per_execution(&self, value: f64) -> f64 {
+        if self.executions == 0 {
+            0.0
+        } else {
+            value / self.executions as f64
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn into_team_metrics(self, team: String) -> TeamMetrics {
+        TeamMetrics {
+            team,
+            failure_rate: self.per_execution(self.failed as f64) * 100.0,
+            flakiness_rate: self.per_execution(self.flaky as f64) * 100.0,
+            avg_duration_seconds: self.per_execution(self.duration_seconds),
+            total_duration_seconds: self.duration_seconds,
+            total_executions: self.executions,
+            failed_executions: self.failed,
+            flaky_retries: self.flaky,
+            jobs: self.jobs.into_iter().collect(),
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn into_component_metrics(
+        self,
+        component: String,
+        path: Option<String>,
+        total_critical_path_seconds: f64,
+    ) -> ComponentMetrics {
+        ComponentMetrics {
+            component,
+            path,
+            failure_rate: self.per_execution(self.failed as f64) * 100.0,
+            flakiness_rate: self.per_execution(self.flaky as f64) * 100.0,
+            avg_duration_seconds: self.per_execution(self.duration_seconds),
+            total_duration_seconds: self.duration_seconds,
+            total_executions: self.executions,
+            critical_path_seconds: self.critical_path_seconds,
+            critical_path_share: if total_critical_path_seconds > 0.0 {
+                self.critical_path_seconds / total_critical_path_seconds * 100.0
+            } else {
+                0.0
+            },
+            jobs: self.jobs.into_iter().collect(),
+        }
+    }
+}
+
+/// Sums job metrics per group; a job belongs to the first group with a matching
+/// pattern and jobs matching no group are left out.
+fn accumulate(insights: &CIInsights, matchers: &[Matcher]) -> Vec<Totals> {
+    let mut totals: Vec<Totals> = matchers.iter().map(|_| Totals::default()).collect();
+
+    let jobs = insights
+        .pipeline_types
+        .iter()
+        .flat_map(|pt| &pt.metrics.jobs);
+
+    for job in jobs {
+        if let Some(index) = matchers.iter().position(|m| m.matches(&job.name)) {
+            totals[index].add(job);
+        }
+    }
+
+    totals
+}
+
+/// Adds each group's time on the critical path (the slowest job to feedback plus
+/// its predecessors), weighted by how many pipelines each type ran. Returns the
+/// total critical path time across all jobs, attributed or not.
+#[allow(clippy::cast_precision_loss)]
+fn accumulate_critical_path(
+    insights: &CIInsights,
+    matchers: &[Matcher],
+    totals: &mut [Totals],
+) -> f64 {
+    let mut total_seconds = 0.0;
+
+    for pipeline_type in &insights.pipeline_types {
+        let Some(last_job) = pipeline_type.metrics.jobs.iter().max_by(|a, b| {
+            a.avg_time_to_feedback_seconds
+                .total_cmp(&b.avg_time_to_feedback_seconds)
+        }) else {
+            continue;
+        };
+
+        let weight = pipeline_type.metrics.total_pipelines as f64;
+        let path = last_job
+            .predecessors
+            .iter()
+            .map(|p| (p.name.as_str(), p.avg_duration_seconds))
+            .chain(std::iter::once((
+                last_job.name.as_str(),
+                last_job.avg_duration_seconds,
+            )));
+
+        for (name, duration) in path {
+            total_seconds += duration * weight;
+            if let Some(index) = matchers.iter().position(|m| m.matches(name)) {
+                totals[index].critical_path_seconds += duration * weight;
+            }
+        }
+    }
+
+    total_seconds
+}
+
+/// Aggregates job metrics per team.
+pub fn attribute_teams(insights: &CIInsights, teams: &[TeamMapping]) -> Vec<TeamMetrics> {
+    let matchers: Vec<Matcher> = teams
+        .iter()
+        .map(|team| Matcher::new(&team.name, &team.jobs))
+        .collect();
+    let totals = accumulate(insights, &matchers);
+
+    let mut metrics: Vec<TeamMetrics> = matchers
+        .into_iter()
+        .zip(totals)
+        .map(|(matcher, totals)| totals.into_team_metrics(matcher.name))
+        .collect();
+
+    metrics.sort_by(|a, b| {
+        b.total_duration_seconds
+            .total_cmp(&a.total_duration_seconds)
+    });
+    metrics
+}
+
+/// Aggregates job metrics per monorepo component, sorted by how much of the
+/// critical path each component owns.
+pub fn attribute_components(
+    insights: &CIInsights,
+    components: &[ComponentMapping],
+) -> Vec<ComponentMetrics> {
+    let matchers: Vec<Matcher> = components
+        .iter()
+        .map(|component| Matcher::new(&component.name, &component.jobs))
+        .collect();
+    let mut totals = accumulate(insights, &matchers);
+    let total_critical_path_seconds = accumulate_critical_path(insights, &matchers, &mut totals);
+
+    let mut metrics: Vec<ComponentMetrics> = components
+        .iter()
+        .zip(totals)
+        .map(|(component, totals)| {
+            totals.into_component_metrics(
+                component.name.clone(),
+                component.path.clone(),
+                total_critical_path_seconds,
+            )
+        })
+        .collect();
+
+    metrics.sort_by(|a, b| b.critical_path_seconds.total_cmp(&a.critical_path_seconds));
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+    use crate::insights::PredecessorJob;
+
+    fn team(name: &str, jobs: &[&str]) -> TeamMapping {
+        TeamMapping {
+            name: name.to_string(),
+            jobs: jobs.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn test_attribute_teams_first_match_wins() {
+        let report = insights(vec![
+            pipeline_type(
+                "MR",
+                1,
+                1,
+                vec![job("build", 100.0, 2), job("test:unit", 50.0, 2)],
+            ),
+            pipeline_type("Main", 1, 0, vec![job("build", 200.0, 1)]),
+        ]);
+
+        let teams = attribute_teams(
+            &report,
+            &[
+                team("platform", &["build*"]),
+                team("qa", &["test:*", "build"]),
+            ],
+        );
+
+        let platform = teams.iter().find(|t| t.team == "platform").unwrap();
+        let qa = teams.iter().find(|t| t.team == "qa").unwrap();
+
+        assert_eq!(platform.total_executions, 3);
+        assert!((platform.total_duration_seconds - 400.0).abs() < f64::EPSILON);
+        assert_eq!(qa.jobs, vec!["test:unit"]);
+        assert_eq!(teams[0].team, "platform");
+    }
+
+    #[test]
+    fn test_attribute_components_critical_path_share() {
+        let mut deploy = job("deploy", 30.0, 2);
+        deploy.avg_time_to_feedback_seconds = 130.0;
+        deploy.predecessors = vec![PredecessorJob {
+            name: "frontend:build".to_string(),
+            avg_duration_seconds: 100.0,
+        }];
+        let mut frontend = job("frontend:build", 100.0, 2);
+        frontend.avg_time_to_feedback_seconds = 100.0;
+        let report = insights(vec![pipeline_type(
+            "Main",
+            2,
+            0,
+            vec![frontend, deploy, job("backend:lint", 10.0, 2)],
+        )]);
+
+        let components = attribute_components(
+            &report,
+            &[
+                ComponentMapping {
+                    name: "backend".to_string(),
+                    path: Some("api/".to_string()),
+                    jobs: vec!["backend:*".to_string()],
+                },
+                ComponentMapping {
+                    name: "web".to_string(),
+                    path: Some("web/".to_string()),
+                    jobs: vec!["frontend:*".to_string()],
+                },
+            ],
+        );
+
+        assert_eq!(components[0].component, "web");
+        assert!((components[0].critical_path_share - 100.0 / 1.3).abs() < 1e-9);
+        assert!(components[1].critical_path_share.abs() < f64::EPSILON);
+    }
+}
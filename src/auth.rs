@@ -7,6 +7,7 @@ impl From<&str> for Token {
 }
 
 impl Token {
+    #[must_use]
     pub fn as_str(&self) -> &str {
         &self.0
     }
@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::insights::{CIInsights, Summary};
+
+/// Headline metrics of one project with their normalized scores.
+///
+/// Each score places the project between the worst (0) and best (100)
+/// benchmarked project on that metric; `score` averages the three.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectBenchmark {
+    pub rank: usize,
+    pub project: String,
+    pub total_pipelines: usize,
+    pub success_rate: f64,
+    pub avg_duration_seconds: f64,
+    pub avg_time_to_feedback_seconds: f64,
+    pub success_rate_score: f64,
+    pub duration_score: f64,
+    pub time_to_feedback_score: f64,
+    pub score: f64,
+}
+
+/// Projects ranked from best to worst overall score.
+#[derive(Debug, Serialize)]
+pub struct Benchmark {
+    pub collected_at: DateTime<Utc>,
+    pub projects: Vec<ProjectBenchmark>,
+}
+
+/// Time to feedback across pipeline types, weighted by pipeline count.
+#[allow(clippy::cast_precision_loss)]
+fn avg_time_to_feedback(insights: &CIInsights) -> f64 {
+    let (weighted, pipelines) =
+        insights
+            .pipeline_types
+            .iter()
+            .fold((0.0, 0), |(sum, count), pt| {
+                (
+                    sum + pt.metrics.avg_time_to_feedback_seconds
+                        * pt.metrics.total_pipelines as f64,
+                    count + pt.metrics.total_pipelines,
+                )
+            });
+
+    if pipelines == 0 {
+        0.0
+    } else {
+        weighted / pipelines as f64
+    }
+}
+
+/// Scales `value` to 0-100 between the worst and best of `values`; every
+/// project scores 100 when they all tie.
+fn normalize(value: f64, values: &[f64], higher_is_better: bool) -> f64 {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if (max - min).abs() < f64::EPSILON {
+        return 100.0;
+    }
+
+    let position = (value - min) / (max - min);
+    if higher_is_better {
+        position * 100.0
+    } else {
+        (1.0 - position) * 100.0
+    }
+}
+
+pub fn benchmark(reports: &[CIInsights]) -> Benchmark {
+    let metrics: Vec<(f64, f64, f64)> = reports
+        .iter()
+        .map(|insights| {
+            let summary = insights
+                .summary
+                .clone()
+                .unwrap_or_else(|| Summary::of(insights));
+            (
+                summary.success_rate,
+                summary.avg_duration_seconds,
+                avg_time_to_feedback(insights),
+            )
+        })
+        .collect();
+    let success_rates: Vec<f64> = metrics.iter().map(|m| m.0).collect();
+    let durations: Vec<f64> = metrics.iter().map(|m| m.1).collect();
+    let feedback_times: Vec<f64> = metrics.iter().map(|m| m.2).collect();
+
+    let mut projects: Vec<ProjectBenchmark> = reports
+        .iter()
+        .zip(&metrics)
+        .map(|(insights, &(success_rate, duration, feedback))| {
+            let success_rate_score = normalize(success_rate, &success_rates, true);
+            let duration_score = normalize(duration, &durations, false);
+            let time_to_feedback_score = normalize(feedback, &feedback_times, false);
+            ProjectBenchmark {
+                rank: 0,
+                project: insights.project.clone(),
+                total_pipelines: insights.total_pipelines,
+                success_rate,
+                avg_duration_seconds: duration,
+                avg_time_to_feedback_seconds: feedback,
+                success_rate_score,
+                duration_score,
+                time_to_feedback_score,
+                score: (success_rate_score + duration_score + time_to_feedback_score) / 3.0,
+            }
+        })
+        .collect();
+
+    projects.sort_by(|a, b| b.score.total_cmp(&a.score));
+    for (index, project) in projects.iter_mut().enumerate() {
+        project.rank = index + 1;
+    }
+
+    Benchmark {
+        collected_at: Utc::now(),
+        projects,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, pipeline_type};
+
+    fn report(project: &str, successful: usize, failed: usize, duration: f64) -> CIInsights {
+        let mut pipeline_type = pipeline_type("Default", successful, failed, vec![]);
+        pipeline_type.metrics.avg_duration_seconds = duration;
+        pipeline_type.metrics.avg_time_to_feedback_seconds = duration / 2.0;
+        let mut report = insights(vec![pipeline_type]);
+        report.project = project.to_string();
+        report
+    }
+
+    #[test]
+    fn test_benchmark_ranks_by_normalized_score() {
+        let reports = vec![
+            report("slow/flaky", 5, 5, 1200.0),
+            report("fast/green", 10, 0, 300.0),
+            report("middle", 8, 2, 600.0),
+        ];
+
+        let benchmark = benchmark(&reports);
+
+        let ranked: Vec<&str> = benchmark
+            .projects
+            .iter()
+            .map(|p| p.project.as_str())
+            .collect();
+        assert_eq!(ranked, vec!["fast/green", "middle", "slow/flaky"]);
+        assert!((benchmark.projects[0].score - 100.0).abs() < 1e-9);
+        assert!(benchmark.projects[2].score.abs() < 1e-9);
+        assert!((benchmark.projects[1].success_rate_score - 60.0).abs() < 1e-9);
+    }
+}
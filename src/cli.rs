@@ -1,10 +1,68 @@
 use anyhow::Result;
-use clap::{value_parser, Parser, Subcommand};
-use log::info;
-use std::path::PathBuf;
+#[cfg(feature = "gitlab")]
+use chrono::Utc;
+use chrono_tz::Tz;
+use clap::parser::ValueSource;
+use clap::{value_parser, ArgMatches, Parser, Subcommand, ValueEnum};
+#[cfg(feature = "gitlab")]
+use log::debug;
+use log::{info, warn};
+#[cfg(feature = "gitlab")]
+use reqwest::header::{HeaderName, HeaderValue};
+#[cfg(feature = "gitlab")]
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+#[cfg(feature = "gitlab")]
+use std::time::Duration;
+#[cfg(feature = "gitlab")]
+use url::Url;
 
+#[cfg(feature = "gitlab")]
+use crate::attribution::{attribute_components, attribute_teams};
+#[cfg(feature = "gitlab")]
 use crate::auth::Token;
-use crate::providers::GitLabProvider;
+#[cfg(feature = "gitlab")]
+use crate::benchmark;
+#[cfg(feature = "gitlab")]
+use crate::cloudevents::{self, Sink};
+#[cfg(feature = "gitlab")]
+use crate::config::Instance;
+#[cfg(feature = "gitlab")]
+use crate::config::Profile;
+use crate::config::{Config, PROJECT_CONFIG};
+use crate::digest;
+use crate::error::CILensError;
+use crate::export::bigquery::{BigQuery, BIGQUERY_API_URL};
+#[cfg(feature = "kafka")]
+use crate::export::{self, kafka::MessageFormat};
+#[cfg(feature = "gitlab")]
+use crate::git_remote;
+use crate::init::{self, InitOptions};
+use crate::insights::CIInsights;
+#[cfg(feature = "gitlab")]
+use crate::insights::CombinedInsights;
+#[cfg(feature = "gitlab")]
+use crate::leaderboard::{self, LEADERBOARD_SIZE};
+use crate::lint_ci;
+#[cfg(feature = "gitlab")]
+use crate::loki;
+use crate::notify::jira::{FlakyJobState, Jira};
+use crate::notify::{self, HealthSummary};
+use crate::output::badge::{self, BadgeMetric};
+use crate::output::compress::Compression;
+use crate::output::human::Style;
+#[cfg(feature = "gitlab")]
+use crate::output::{grafana, pdf, prometheus, split, template, xlsx};
+#[cfg(feature = "gitlab")]
+use crate::providers::{
+    AnalysisOptions, Api, CollectOptions, DedupePreference, GitLabProvider, HttpOptions, JobSort,
+    PipelineStatus, Sample,
+};
+use crate::quarantine::{self, QuarantineList};
+#[cfg(feature = "gitlab")]
+use crate::redact::{self, RedactMode};
 
 #[derive(Parser)]
 #[command(name = "cilens")]
@@ -16,69 +74,1732 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     output: Option<PathBuf>,
 
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        conflicts_with_all = ["output", "template"],
+        help = "Write index.json plus one JSON file per pipeline type into DIR"
+    )]
+    output_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        conflicts_with = "output_dir",
+        help = "Compress the output; detected from a .gz/.zst --output extension when omitted"
+    )]
+    compress: Option<Compression>,
+
     #[arg(short, long, global = true, default_value_t = false)]
     pretty: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Only log errors, whatever RUST_LOG enables"
+    )]
+    quiet: bool,
+
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Json)]
+    format: OutputFormat,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Render the report through a Tera template instead of --format"
+    )]
+    template: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Push metrics to this Prometheus Pushgateway after collection"
+    )]
+    push_gateway: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        default_value = "cilens",
+        help = "Pushgateway job name used to group pushed metrics"
+    )]
+    push_job: String,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ErrorFormat::Text,
+        help = "Format used to report errors on stderr"
+    )]
+    error_format: ErrorFormat,
+
+    #[arg(
+        long,
+        global = true,
+        env = "CILENS_CONFIG",
+        help = "TOML config file (e.g. team mappings); defaults to ./.cilens.toml if present"
+    )]
+    config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        env = "CILENS_PROFILE",
+        help = "Take options not given on the command line from this [profile.<name>] of the config"
+    )]
+    profile: Option<String>,
+
+    #[cfg(feature = "gitlab")]
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "sequential",
+        help = "Anonymize project, job, ref, image and user names and URLs before output (hash: stable aliases)"
+    )]
+    redact: Option<RedactMode>,
+
+    #[cfg(feature = "gitlab")]
+    #[arg(
+        long,
+        global = true,
+        env = "CILENS_REDACT_SALT",
+        default_value = "",
+        hide_env_values = true,
+        help = "Secret mixed into --redact=hash aliases so they cannot be reversed by guessing names"
+    )]
+    redact_salt: String,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Print durations as \"12m 4s\" and round percentages in text outputs (JSON keeps raw seconds)"
+    )]
+    human: bool,
+
+    #[arg(
+        long,
+        global = true,
+        env = "CILENS_TIMEZONE",
+        default_value = "UTC",
+        value_parser = parse_timezone,
+        help = "IANA timezone (e.g. Europe/Berlin) weekly trends are bucketed and timestamps are printed in"
+    )]
+    timezone: Tz,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Json,
+    Prometheus,
+    /// Excel workbook with a summary sheet and one sheet per pipeline type
+    Xlsx,
+    /// Printable health report (durations and rates follow --human)
+    Pdf,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ListFormat {
+    Yaml,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ErrorFormat {
+    Text,
+    Json,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    Gitlab {
-        #[arg(long, env = "GITLAB_TOKEN")]
-        token: Option<String>,
+    #[cfg(feature = "gitlab")]
+    Gitlab(Box<GitLabArgs>),
+    /// Send a summary of a collected report to a chat channel
+    Notify {
+        #[command(subcommand)]
+        channel: NotifyChannel,
+    },
+    /// Analyze a .gitlab-ci.yml DAG and suggest `needs` that shorten feedback time
+    LintCi {
+        path: PathBuf,
+
+        #[arg(long, help = "Insights JSON report providing observed job durations")]
+        insights: Option<PathBuf>,
+    },
+    /// List jobs flaky enough to be marked `allow_failure`, tracking recoveries
+    Quarantine {
+        #[arg(long, help = "Insights JSON report to read job flakiness from")]
+        input: PathBuf,
+
+        #[arg(
+            long,
+            default_value = "5%",
+            value_parser = parse_percentage,
+            help = "Flakiness rate above which a job is quarantined (e.g. 5%)"
+        )]
+        threshold: f64,
+
+        #[arg(
+            long,
+            help = "Quarantine list from a previous run, to track recoveries"
+        )]
+        previous: Option<PathBuf>,
+
+        #[arg(long, value_enum, default_value_t = ListFormat::Yaml)]
+        list_format: ListFormat,
+    },
+    /// Summarize how CI health changed over the last week(s) from saved reports
+    Digest {
+        #[arg(
+            long,
+            help = "Directory of insights JSON reports from previous runs (the latest one is summarized)"
+        )]
+        history: PathBuf,
+
+        #[arg(
+            long,
+            default_value = "7d",
+            value_parser = parse_duration,
+            help = "How far back the report to compare against was collected (e.g. 7d, 14d)"
+        )]
+        since: u64,
+
+        #[arg(
+            long,
+            default_value_t = 3,
+            help = "Number of regressions, improvements and flaky jobs to list"
+        )]
+        top: usize,
+    },
+    /// Publish a collected report to a data platform
+    Export {
+        #[command(subcommand)]
+        sink: ExportSink,
+    },
+    /// Render an SVG status badge from a collected report
+    Badge {
+        #[arg(long, help = "Insights JSON report to read the metric from")]
+        input: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = BadgeMetric::SuccessRate)]
+        metric: BadgeMetric,
+    },
+    /// Collect several projects and rank them on success rate, pipeline duration and time to feedback
+    #[cfg(feature = "gitlab")]
+    Benchmark {
+        #[arg(
+            long = "project",
+            value_name = "PATH",
+            required = true,
+            help = "Project to benchmark (repeat for each project, at least two)"
+        )]
+        projects: Vec<String>,
+
+        #[command(flatten)]
+        args: Box<CollectArgs>,
+    },
+    /// Write a starter config, proposing the project of the git remote
+    Init {
+        #[arg(long, default_value = PROJECT_CONFIG, help = "Where to write the config")]
+        path: PathBuf,
+
+        #[arg(long, help = "GitLab URL (detected from the git remote when omitted)")]
+        base_url: Option<String>,
+
+        #[arg(
+            long,
+            help = "Project path (detected from the git remote when omitted)"
+        )]
+        project_path: Option<String>,
+
+        #[arg(
+            long,
+            help = "Environment variable holding the token [default: GITLAB_TOKEN]"
+        )]
+        token_env: Option<String>,
+
+        #[arg(
+            long = "name",
+            help = "Name of the profile to write (default: the project name)"
+        )]
+        profile_name: Option<String>,
+
+        #[arg(
+            long,
+            short,
+            help = "Accept detected and default values instead of asking"
+        )]
+        yes: bool,
+
+        #[arg(long, help = "Replace an existing config")]
+        force: bool,
+    },
+    /// Print a Grafana dashboard for the exported Prometheus metrics
+    #[cfg(feature = "gitlab")]
+    GrafanaDashboard {
+        #[arg(long, default_value = "CILens")]
+        title: String,
+    },
+}
+
+#[cfg(feature = "gitlab")]
+#[derive(clap::Args)]
+#[command(group = clap::ArgGroup::new("event_sink").args(["cloudevents_sink", "loki_url"]).multiple(true))]
+struct GitLabArgs {
+    #[arg(
+        long,
+        help = "Project to analyze; when omitted, every [[projects]] entry of the --config file is collected into one report, or else the project of the git remote"
+    )]
+    project_path: Option<String>,
+
+    #[command(flatten)]
+    collect: CollectArgs,
+
+    #[arg(
+        long,
+        help = "Skip pipeline types and output one ranked list of all jobs (cheaper, JSON only)"
+    )]
+    jobs_only: bool,
+
+    #[arg(
+        long,
+        env = "CILENS_LOKI_URL",
+        help = "Push detected regressions and anomalous image changes as log lines to this Grafana Loki (credentials in the URL are sent as basic auth)"
+    )]
+    loki_url: Option<Url>,
+
+    #[arg(long, env = "CILENS_LOKI_TENANT", requires = "loki_url")]
+    loki_tenant: Option<String>,
+
+    #[arg(
+        long,
+        requires = "event_sink",
+        help = "Previous insights JSON report to detect job duration regressions against"
+    )]
+    regression_baseline: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value = "10%",
+        value_parser = parse_percentage,
+        help = "Job duration change over the baseline (or after an image change) that counts as an anomaly (e.g. 10%)"
+    )]
+    regression_threshold: f64,
+
+    #[command(subcommand)]
+    action: Option<GitLabAction>,
+}
+
+// Options selecting, collecting and analyzing pipelines, shared by `gitlab`
+// and `benchmark` (a doc comment would become the `gitlab` help text)
+#[cfg(feature = "gitlab")]
+#[derive(clap::Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct CollectArgs {
+    #[arg(long, env = "GITLAB_TOKEN")]
+    token: Option<String>,
+
+    #[arg(long, default_value = "https://gitlab.com")]
+    base_url: String,
+
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+
+    #[arg(long, name = "ref")]
+    ref_: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "success,failed",
+        help = "Pipeline statuses to fetch and analyze (comma separated); --limit is split evenly between them"
+    )]
+    statuses: Vec<PipelineStatus>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Api::Auto,
+        help = "API to collect pipelines and jobs through; auto switches to REST when GraphQL is disabled or restricted"
+    )]
+    api: Api,
+
+    #[cfg(feature = "wasm-plugins")]
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Run the analyzers compiled to WebAssembly (*.wasm) in DIR; each adds a report section"
+    )]
+    plugins_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value = "60s",
+        value_parser = parse_duration,
+        help = "Give up on a GitLab API request after this long (e.g. 30s, 2m)"
+    )]
+    request_timeout: u64,
+
+    #[arg(
+        long,
+        default_value = "10s",
+        value_parser = parse_duration,
+        help = "Give up connecting to GitLab after this long"
+    )]
+    connect_timeout: u64,
+
+    #[arg(
+        long,
+        default_value_t = 32,
+        help = "Idle keep-alive connections to keep open to GitLab"
+    )]
+    pool_max_idle: usize,
+
+    #[arg(
+        long,
+        default_value = "90s",
+        value_parser = parse_duration,
+        help = "Close keep-alive connections idle for this long"
+    )]
+    pool_idle_timeout: u64,
+
+    #[arg(
+        long = "header",
+        value_name = "NAME: VALUE",
+        value_parser = parse_header,
+        help = "Send this header with every GitLab API request (repeatable), e.g. for a proxy in front of GitLab"
+    )]
+    headers: Vec<(HeaderName, HeaderValue)>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        env = "CILENS_HTTP_CACHE",
+        help = "Cache REST responses in DIR and revalidate them with ETag/Last-Modified on later runs"
+    )]
+    http_cache: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Save every GitLab API response to DIR, for replaying the run offline"
+    )]
+    record_fixtures: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with = "record_fixtures",
+        help = "Answer GitLab API requests from responses saved with --record-fixtures"
+    )]
+    replay_fixtures: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Stop with an error once this many GitLab API requests were sent (per project)"
+    )]
+    max_requests: Option<usize>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["ref", "default_branch_only"],
+        help = "Analyze these pipeline IDs (comma separated, or - to read them from stdin) instead of the latest --limit"
+    )]
+    pipeline_ids: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = parse_percentage,
+        conflicts_with = "pipeline_ids",
+        help = "Only analyze this share of the --limit latest pipelines (e.g. 10%), favouring recent ones"
+    )]
+    sample: Option<f64>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["sample", "pipeline_ids"],
+        help = "Only analyze this many of the --limit latest pipelines, favouring recent ones"
+    )]
+    sample_count: Option<usize>,
+
+    #[arg(
+        long,
+        conflicts_with = "ref",
+        help = "Only analyze pipelines of the project's default branch"
+    )]
+    default_branch_only: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["ref", "default_branch_only"],
+        help = "Contrast default-branch health with all-branch health"
+    )]
+    compare_default_branch: bool,
+
+    #[arg(
+        long,
+        help = "Keep only one pipeline per commit SHA (e.g. drop the branch pipeline when an MR pipeline ran too)"
+    )]
+    dedupe_by_sha: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = DedupePreference::MergeRequest,
+        requires = "dedupe_by_sha",
+        help = "Which pipeline --dedupe-by-sha keeps for a commit"
+    )]
+    dedupe_prefer: DedupePreference,
+
+    #[arg(
+        long,
+        default_value_t = 1,
+        help = "Minimum percentage for pipeline type filtering (0-100)",
+        value_parser = value_parser!(u8).range(0..=100),
+    )]
+    min_type_percentage: u8,
+
+    #[arg(
+        long,
+        env = "OTEL_EXPORTER_OTLP_ENDPOINT",
+        help = "Export pipelines as traces to this OTLP/HTTP collector (e.g. http://localhost:4318)"
+    )]
+    otlp_endpoint: Option<String>,
+
+    #[arg(
+        long,
+        env = "CILENS_CLOUDEVENTS_SINK",
+        help = "Emit a CloudEvent per collected pipeline and detected regression to this HTTP endpoint or kafka://brokers/topic"
+    )]
+    cloudevents_sink: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Include per-environment deployment metrics and reverts on the default branch"
+    )]
+    deployments: bool,
+
+    #[arg(
+        long,
+        help = "Correlate job image changes (read from the CI config at each commit) with duration and failure shifts"
+    )]
+    images: bool,
 
-        #[arg(long, default_value = "https://gitlab.com")]
-        base_url: String,
+    #[arg(
+        long,
+        help = "Download the logs of each job's latest attempts to measure cache hit rates, restore and dependency install times"
+    )]
+    analyze_logs: bool,
 
+    #[arg(
+        long,
+        help = "Include the namespace's CI minutes usage, quota and month-end projection (GitLab.com)"
+    )]
+    ci_minutes: bool,
+
+    #[arg(
+        long,
+        requires = "ci_minutes",
+        help = "Monthly CI minutes quota, for namespaces whose limit GitLab does not disclose"
+    )]
+    ci_minutes_quota: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = value_parser!(u64).range(1..),
+        help = "Report jobs that failed in each of the last N default-branch pipelines that ran them"
+    )]
+    broken_streak: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Measure lead time to green: from each default-branch commit to the end of its first successful pipeline"
+    )]
+    lead_time: bool,
+
+    #[arg(
+        long,
+        help = "Compare the pipelines of each active pipeline schedule with its cron expression to report drift and missed runs"
+    )]
+    schedules: bool,
+
+    #[arg(
+        long,
+        help = "Break pipelines down by triggering user to spot noisy or failing (automation) accounts"
+    )]
+    by_user: bool,
+
+    #[arg(
+        long,
+        value_parser = parse_duration,
+        help = "Target pipeline duration (e.g. 15m, 1h30m, 900s) to report SLA attainment for"
+    )]
+    sla: Option<u64>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Add job duration histograms with these bucket bounds in seconds (e.g. 30,60,300)"
+    )]
+    histogram_buckets: Option<Vec<f64>>,
+
+    #[arg(long, help = "Only report the first N jobs of each pipeline type")]
+    top_jobs: Option<usize>,
+
+    #[arg(
+        long,
+        default_value_t = 5,
+        help = "Keep the N flakiest jobs of each pipeline type even past --top-jobs"
+    )]
+    top_flaky: usize,
+
+    #[arg(
+        long,
+        default_value_t = 1,
+        help = "Hide jobs that executed fewer than N times"
+    )]
+    min_executions: usize,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = JobSort::Feedback,
+        help = "Order jobs of each pipeline type by this metric, highest first"
+    )]
+    sort_jobs: JobSort,
+
+    #[arg(
+        long,
+        help = "Break parallel/matrix jobs down per variant (they are always grouped under their base name)"
+    )]
+    matrix_variants: bool,
+
+    #[arg(
+        long,
+        help = "Break pipeline type metrics down per ref pattern (main, feature branches, tags, merge requests)"
+    )]
+    by_ref: bool,
+
+    #[arg(
+        long,
+        value_parser = parse_duration,
+        default_value = "14d",
+        help = "Half-life of a pipeline's weight in each job's recency-weighted flakiness_score (e.g. 7d, 36h; 0 weighs every pipeline the same)"
+    )]
+    flakiness_half_life: u64,
+
+    #[arg(
+        long,
+        default_value_t = 50,
+        help = "Recommend running jobs that passed N executions without a failure less often (0 disables)"
+    )]
+    always_green_after: usize,
+
+    #[arg(
+        long,
+        value_parser = parse_percentage,
+        default_value = "95%",
+        help = "Recommend the smallest `retry` that lets flaky jobs pass this share of their runs (e.g. 99%, 0 disables)"
+    )]
+    target_success_rate: f64,
+}
+
+#[cfg(feature = "gitlab")]
+#[derive(Subcommand)]
+enum GitLabAction {
+    /// Compare a merge request's branch against its target and comment the results
+    Comment {
         #[arg(long)]
-        project_path: String,
+        merge_request: u64,
+    },
+    /// Report per-runner status, job load, queueing and failure correlation as JSON
+    Runners {
+        #[arg(
+            long,
+            value_parser = parse_duration,
+            default_value = "2m",
+            help = "p95 queue time per runner tag above which the tag is flagged as under-provisioned"
+        )]
+        queue_slo: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum NotifyChannel {
+    #[command(flatten)]
+    Summary(SummaryChannel),
+    /// Open (or comment on) a Jira issue per job flaky for several consecutive runs
+    Jira(Box<JiraArgs>),
+}
+
+/// Channels a health summary of a report is sent to.
+#[derive(Subcommand)]
+enum SummaryChannel {
+    Slack {
+        #[arg(long, env = "SLACK_WEBHOOK_URL")]
+        webhook_url: String,
+
+        #[command(flatten)]
+        report: ReportArgs,
+    },
+    /// Post an Adaptive Card to a Microsoft Teams webhook
+    Teams {
+        #[arg(long, env = "TEAMS_WEBHOOK_URL")]
+        webhook_url: String,
+
+        #[command(flatten)]
+        report: ReportArgs,
+    },
+    /// Trigger a `PagerDuty` incident (Events API v2) naming the violated condition
+    Pagerduty {
+        #[arg(long, env = "PAGERDUTY_ROUTING_KEY")]
+        routing_key: String,
+
+        #[command(flatten)]
+        report: ReportArgs,
+    },
+    /// Create an Opsgenie alert naming the violated condition
+    Opsgenie {
+        #[arg(long, env = "OPSGENIE_API_KEY")]
+        api_key: String,
+
+        #[arg(
+            long,
+            default_value = "https://api.opsgenie.com",
+            help = "Opsgenie API URL (https://api.eu.opsgenie.com for EU accounts)"
+        )]
+        api_url: String,
+
+        #[command(flatten)]
+        report: ReportArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportSink {
+    /// Publish one message per job of each pipeline type to a Kafka topic
+    #[cfg(feature = "kafka")]
+    Kafka {
+        #[arg(long, help = "Insights JSON report produced by a previous run")]
+        input: PathBuf,
+
+        #[arg(
+            long,
+            env = "KAFKA_BROKERS",
+            help = "Comma-separated bootstrap brokers"
+        )]
+        brokers: String,
+
+        #[arg(long, default_value = "ci-insights")]
+        topic: String,
+
+        #[arg(long, value_enum, default_value_t = MessageFormat::Json)]
+        format: MessageFormat,
+
+        #[arg(
+            long,
+            env = "SCHEMA_REGISTRY_URL",
+            required_if_eq("format", "avro"),
+            help = "Confluent schema registry the Avro schema is registered in (subject <topic>-value)"
+        )]
+        schema_registry: Option<String>,
+    },
+    /// Stream job and pipeline type rows into `BigQuery` tables, creating them if missing
+    Bigquery {
+        #[arg(long, help = "Insights JSON report produced by a previous run")]
+        input: PathBuf,
 
-        #[arg(long, default_value_t = 20)]
-        limit: usize,
+        #[arg(long, help = "Google Cloud project of the dataset")]
+        project: String,
 
-        #[arg(long, name = "ref")]
-        ref_: Option<String>,
+        #[arg(long)]
+        dataset: String,
 
         #[arg(
             long,
-            default_value_t = 1,
-            help = "Minimum percentage for pipeline type filtering (0-100)",
-            value_parser = value_parser!(u8).range(0..=100),
+            default_value = "cilens_",
+            help = "Prefix of the <prefix>jobs and <prefix>pipeline_types tables"
         )]
-        min_type_percentage: u8,
+        table_prefix: String,
+
+        #[arg(
+            long,
+            env = "GOOGLE_OAUTH_ACCESS_TOKEN",
+            help = "OAuth access token (e.g. from `gcloud auth print-access-token`)"
+        )]
+        access_token: String,
     },
 }
 
+#[derive(clap::Args)]
+struct JiraArgs {
+    #[arg(
+        long,
+        env = "JIRA_URL",
+        help = "Jira base URL (e.g. https://acme.atlassian.net)"
+    )]
+    url: String,
+
+    #[arg(long, env = "JIRA_EMAIL")]
+    email: String,
+
+    #[arg(long, env = "JIRA_API_TOKEN")]
+    api_token: String,
+
+    #[arg(long, help = "Key of the Jira project issues are opened in")]
+    project_key: String,
+
+    #[arg(long, default_value = "Bug")]
+    issue_type: String,
+
+    #[arg(long, help = "Insights JSON report produced by a previous run")]
+    input: PathBuf,
+
+    #[arg(
+        long,
+        default_value = "5%",
+        value_parser = parse_percentage,
+        help = "Flakiness rate above which a job counts as flaky (e.g. 5%)"
+    )]
+    threshold: f64,
+
+    #[arg(
+        long,
+        default_value_t = 3,
+        help = "Runs in a row a job must be flaky before an issue is opened"
+    )]
+    consecutive_runs: u32,
+
+    #[arg(
+        long,
+        help = "JSON file tracking flaky streaks and opened issues between runs (created if missing)"
+    )]
+    state: PathBuf,
+}
+
+impl SummaryChannel {
+    fn report(&self) -> &ReportArgs {
+        match self {
+            Self::Slack { report, .. }
+            | Self::Teams { report, .. }
+            | Self::Pagerduty { report, .. }
+            | Self::Opsgenie { report, .. } => report,
+        }
+    }
+
+    /// The backend delivering the summary; alerting channels include `condition`.
+    fn backend(&self, condition: String) -> Box<dyn notify::Channel> {
+        match self {
+            Self::Slack { webhook_url, .. } => Box::new(notify::slack::Slack {
+                webhook_url: webhook_url.clone(),
+            }),
+            Self::Teams { webhook_url, .. } => Box::new(notify::teams::Teams {
+                webhook_url: webhook_url.clone(),
+            }),
+            Self::Pagerduty { routing_key, .. } => Box::new(notify::alert::PagerDuty {
+                routing_key: routing_key.clone(),
+                condition,
+            }),
+            Self::Opsgenie {
+                api_key, api_url, ..
+            } => Box::new(notify::alert::Opsgenie {
+                api_url: api_url.clone(),
+                api_key: api_key.clone(),
+                condition,
+            }),
+        }
+    }
+}
+
+#[derive(clap::Args)]
+struct ReportArgs {
+    #[arg(long, help = "Insights JSON report produced by a previous run")]
+    input: PathBuf,
+
+    #[arg(long, help = "Older insights JSON report to compare against")]
+    baseline: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Only notify when the overall success rate is below this percentage",
+        value_parser = value_parser!(f64),
+    )]
+    min_success_rate: Option<f64>,
+
+    #[arg(
+        long,
+        default_value_t = 3,
+        help = "Number of regressions and flaky jobs to list"
+    )]
+    top: usize,
+}
+
+/// Parses a percentage such as `5%` or `5`.
+fn parse_percentage(value: &str) -> std::result::Result<f64, String> {
+    let number = value.trim().trim_end_matches('%');
+    let percentage: f64 = number
+        .parse()
+        .map_err(|_| format!("'{value}' is not a percentage"))?;
+
+    if !(0.0..=100.0).contains(&percentage) {
+        return Err(format!("'{value}' is not between 0% and 100%"));
+    }
+
+    Ok(percentage)
+}
+
+/// Parses a duration such as `7d`, `15m`, `1h30m`, `90s` or `900` (seconds) into seconds.
+fn parse_duration(value: &str) -> std::result::Result<u64, String> {
+    let invalid = || format!("'{value}' is not a duration (e.g. 15m, 1h30m, 900s)");
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Ok(seconds);
+    }
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'd' => 86400,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        let amount: u64 = number.parse().map_err(|_| invalid())?;
+        total = amount
+            .checked_mul(unit)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+
+    if !number.is_empty() || value.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(total)
+}
+
+/// Parses a `--header` such as `X-Proxy-Token: abc`.
+#[cfg(feature = "gitlab")]
+fn parse_header(value: &str) -> std::result::Result<(HeaderName, HeaderValue), String> {
+    let invalid = |reason: String| format!("'{value}' is not a 'Name: value' header: {reason}");
+    let (name, header_value) = value
+        .split_once(':')
+        .ok_or_else(|| invalid("missing ':'".to_string()))?;
+
+    Ok((
+        HeaderName::from_bytes(name.trim().as_bytes()).map_err(|e| invalid(e.to_string()))?,
+        HeaderValue::from_str(header_value.trim()).map_err(|e| invalid(e.to_string()))?,
+    ))
+}
+
+/// Parses an IANA timezone name such as `Europe/Berlin`.
+fn parse_timezone(value: &str) -> std::result::Result<Tz, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{value}' is not an IANA timezone (e.g. Europe/Berlin)"))
+}
+
+/// Parses `--pipeline-ids`: IDs separated by commas or whitespace, or `-` to read them from stdin.
+#[cfg(feature = "gitlab")]
+fn read_pipeline_ids(value: &str) -> Result<Vec<u64>> {
+    let input = if value == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        value.to_string()
+    };
+
+    let ids = parse_pipeline_ids(&input)?;
+    if ids.is_empty() {
+        return Err(CILensError::Config("No pipeline IDs given".to_string()).into());
+    }
+    Ok(ids)
+}
+
+#[cfg(feature = "gitlab")]
+fn parse_pipeline_ids(input: &str) -> std::result::Result<Vec<u64>, CILensError> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse()
+                .map_err(|_| CILensError::Config(format!("Invalid pipeline ID: '{id}'")))
+        })
+        .collect()
+}
+
+/// Whether the user set `id` on the command line or through its environment
+/// variable, rather than leaving it at its default.
+fn is_given(matches: &ArgMatches, id: &str) -> bool {
+    matches!(
+        matches.value_source(id),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    )
+}
+
+fn parse_profile_value<T: ValueEnum>(option: &str, value: &str) -> Result<T> {
+    Ok(T::from_str(value, true)
+        .map_err(|e| CILensError::Config(format!("Invalid {option} '{value}' in profile: {e}")))?)
+}
+
+#[cfg(feature = "gitlab")]
+fn apply_gitlab_profile(
+    args: &mut GitLabArgs,
+    matches: &ArgMatches,
+    profile: &Profile,
+) -> Result<()> {
+    if !is_given(matches, "base_url") {
+        if let Some(base_url) = &profile.base_url {
+            args.collect.base_url.clone_from(base_url);
+        }
+    }
+    if !is_given(matches, "token") {
+        if let Some(var) = &profile.token_env {
+            args.collect.token = std::env::var(var).ok();
+        }
+    }
+    if !is_given(matches, "project_path") && profile.project_path.is_some() {
+        args.project_path.clone_from(&profile.project_path);
+    }
+    if !is_given(matches, "ref") && profile.ref_.is_some() {
+        args.collect.ref_.clone_from(&profile.ref_);
+    }
+    if !is_given(matches, "limit") {
+        args.collect.limit = profile.limit.unwrap_or(args.collect.limit);
+    }
+    if !is_given(matches, "statuses") {
+        if let Some(statuses) = &profile.statuses {
+            args.collect.statuses = statuses
+                .iter()
+                .map(|status| parse_profile_value("status", status))
+                .collect::<Result<_>>()?;
+        }
+    }
+    if !is_given(matches, "default_branch_only") {
+        args.collect.default_branch_only = profile
+            .default_branch_only
+            .unwrap_or(args.collect.default_branch_only);
+    }
+    Ok(())
+}
+
+/// Reads the token of a config instance from the environment variable it names.
+#[cfg(feature = "gitlab")]
+fn instance_token(instance: &Instance) -> Option<Token> {
+    let var = instance.token_env.as_deref()?;
+    let token = std::env::var(var).ok();
+    if token.is_none() {
+        warn!(
+            "{var} is not set; accessing instance '{}' without a token",
+            instance.name
+        );
+    }
+    token.as_deref().map(Token::from)
+}
+
+/// Applies the provider settings shared by single- and multi-project runs.
+#[cfg(feature = "gitlab")]
+fn configure_provider(
+    provider: GitLabProvider,
+    args: &CollectArgs,
+    timezone: Tz,
+) -> Result<GitLabProvider> {
+    let http = HttpOptions {
+        request_timeout: Duration::from_secs(args.request_timeout),
+        connect_timeout: Duration::from_secs(args.connect_timeout),
+        pool_max_idle_per_host: args.pool_max_idle,
+        pool_idle_timeout: Duration::from_secs(args.pool_idle_timeout),
+    };
+    let mut provider = provider
+        .with_timezone(timezone)
+        .with_api(args.api)
+        .with_http_options(&http)?
+        .with_max_requests(args.max_requests);
+    if !args.headers.is_empty() {
+        provider = provider.with_headers(args.headers.iter().cloned().collect());
+    }
+    if let Some(dir) = &args.http_cache {
+        provider = provider.with_http_cache(dir)?;
+    }
+    if let Some(dir) = &args.record_fixtures {
+        provider = provider.with_recording(dir)?;
+    }
+    if let Some(dir) = &args.replay_fixtures {
+        provider = provider.with_replay(dir);
+    }
+    #[cfg(feature = "wasm-plugins")]
+    let provider = match &args.plugins_dir {
+        Some(dir) => provider.with_plugins_dir(dir)?,
+        None => provider,
+    };
+    Ok(provider)
+}
+
+#[cfg(feature = "gitlab")]
+fn collect_options(args: &CollectArgs) -> Result<CollectOptions> {
+    let pipeline_ids = args
+        .pipeline_ids
+        .as_deref()
+        .map(read_pipeline_ids)
+        .transpose()?;
+
+    Ok(CollectOptions {
+        limit: args.limit,
+        ref_: args.ref_.clone(),
+        statuses: args.statuses.clone(),
+        otlp_endpoint: args.otlp_endpoint.clone(),
+        cloudevents_sink: args
+            .cloudevents_sink
+            .as_deref()
+            .map(Sink::parse)
+            .transpose()?,
+        include_deployments: args.deployments,
+        include_images: args.images,
+        analyze_logs: args.analyze_logs,
+        lead_time: args.lead_time,
+        include_schedules: args.schedules,
+        include_ci_minutes: args.ci_minutes,
+        ci_minutes_quota: args.ci_minutes_quota,
+        broken_streak: args
+            .broken_streak
+            .map(|streak| usize::try_from(streak).unwrap_or(usize::MAX)),
+        by_user: args.by_user,
+        default_branch_only: args.default_branch_only,
+        compare_default_branch: args.compare_default_branch,
+        pipeline_ids,
+        dedupe_by_sha: args.dedupe_by_sha.then_some(args.dedupe_prefer),
+        sample: args
+            .sample
+            .map(Sample::Percentage)
+            .or(args.sample_count.map(Sample::Count)),
+        analysis: AnalysisOptions {
+            min_type_percentage: args.min_type_percentage,
+            sla_seconds: args.sla,
+            histogram_buckets: args.histogram_buckets.clone().map(|mut bounds| {
+                bounds.sort_by(f64::total_cmp);
+                bounds.dedup();
+                bounds
+            }),
+            top_jobs: args.top_jobs,
+            top_flaky: args.top_flaky,
+            min_executions: args.min_executions,
+            sort_jobs: args.sort_jobs,
+            matrix_variants: args.matrix_variants,
+            by_ref: args.by_ref,
+            flakiness_half_life: args.flakiness_half_life,
+            always_green_executions: args.always_green_after,
+            target_success_rate: args.target_success_rate,
+            // Filled in from the config
+            type_names: HashMap::new(),
+            // Filled in from the global --timezone
+            timezone: Tz::UTC,
+        },
+    })
+}
+
+async fn execute_export(sink: &ExportSink) -> Result<()> {
+    match sink {
+        #[cfg(feature = "kafka")]
+        ExportSink::Kafka {
+            input,
+            brokers,
+            topic,
+            format,
+            schema_registry,
+        } => {
+            let insights = CIInsights::from_file(input)?;
+            let schema_id = match (format, schema_registry) {
+                (MessageFormat::Avro, Some(registry)) => {
+                    Some(export::avro::register_schema(registry, &format!("{topic}-value")).await?)
+                }
+                _ => None,
+            };
+
+            let messages = export::kafka::messages(&insights, schema_id)?;
+            crate::kafka::publish(brokers, topic, messages).await?;
+        }
+        ExportSink::Bigquery {
+            input,
+            project,
+            dataset,
+            table_prefix,
+            access_token,
+        } => {
+            let insights = CIInsights::from_file(input)?;
+            BigQuery {
+                api_url: BIGQUERY_API_URL.to_string(),
+                project: project.clone(),
+                dataset: dataset.clone(),
+                table_prefix: table_prefix.clone(),
+                access_token: access_token.clone(),
+            }
+            .export(&insights)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn execute_jira(args: &JiraArgs) -> Result<()> {
+    let insights = CIInsights::from_file(&args.input)?;
+    let mut state = FlakyJobState::from_file(&args.state)?;
+
+    let chronic = state.update(&insights, args.threshold, args.consecutive_runs);
+    let jira = Jira {
+        url: args.url.clone(),
+        email: args.email.clone(),
+        api_token: args.api_token.clone(),
+        project_key: args.project_key.clone(),
+        issue_type: args.issue_type.clone(),
+    };
+    // Save issues opened before a failure, so they're commented on next time
+    let synced = jira.sync(&insights, &mut state, &chronic).await;
+    state.write(&args.state)?;
+
+    Ok(synced?)
+}
+
 impl Cli {
-    async fn execute_gitlab(
-        &self,
-        token: Option<&String>,
-        base_url: &str,
-        project_path: &str,
-        limit: usize,
-        ref_: Option<&str>,
-        min_type_percentage: u8,
-    ) -> Result<()> {
+    fn style(&self) -> Style {
+        Style {
+            human: self.human,
+            timezone: self.timezone,
+        }
+    }
+
+    /// Options of `args`, with the pipeline type names of `config` and the
+    /// global `--timezone` filled in.
+    #[cfg(feature = "gitlab")]
+    fn analysis_options(&self, args: &CollectArgs, config: &Config) -> Result<CollectOptions> {
+        let mut options = collect_options(args)?;
+        options.analysis.type_names = config.pipeline_type_names();
+        options.analysis.timezone = self.timezone;
+        Ok(options)
+    }
+
+    #[cfg(feature = "gitlab")]
+    async fn execute_gitlab(&self, args: &GitLabArgs) -> Result<()> {
+        if self.output_dir.is_some()
+            && matches!(
+                self.format,
+                OutputFormat::Prometheus | OutputFormat::Xlsx | OutputFormat::Pdf
+            )
+        {
+            return Err(CILensError::Config(
+                "--output-dir writes JSON files and cannot be combined with --format prometheus, xlsx or pdf"
+                    .to_string(),
+            )
+            .into());
+        }
+
+        let config = self.load_config()?;
+        let options = self.analysis_options(&args.collect, &config)?;
+
+        let Some(project_path) = &args.project_path else {
+            return self.execute_gitlab_projects(args, &config, &options).await;
+        };
+
         info!("Collecting GitLab insights for project: {project_path}");
 
-        let token = token.map(|t| Token::from(t.as_str()));
+        let token = args.collect.token.as_deref().map(Token::from);
+
+        let provider = configure_provider(
+            GitLabProvider::new(&args.collect.base_url, project_path.clone(), token)?,
+            &args.collect,
+            self.timezone,
+        )?;
+
+        match &args.action {
+            Some(GitLabAction::Comment { merge_request }) => {
+                provider
+                    .comment_on_merge_request(*merge_request, &options, self.style())
+                    .await?;
+                return Ok(());
+            }
+            Some(GitLabAction::Runners { queue_slo }) => {
+                let report = provider.collect_runner_report(&options, *queue_slo).await?;
+                let output = if self.pretty {
+                    serde_json::to_string_pretty(&report)?
+                } else {
+                    serde_json::to_string(&report)?
+                };
+                return self.write(&output);
+            }
+            None => {}
+        }
 
-        let provider = GitLabProvider::new(base_url, project_path.to_owned(), token)?;
+        if args.jobs_only {
+            let mut ranking = provider.collect_job_ranking(&options).await?;
+            if let Some(mode) = self.redact {
+                redact::redact_job_ranking(&mut ranking, mode, &self.redact_salt);
+            }
+            let output = if self.pretty {
+                serde_json::to_string_pretty(&ranking)?
+            } else {
+                serde_json::to_string(&ranking)?
+            };
+            return self.write(&output);
+        }
 
-        let insights = provider
-            .collect_insights(limit, ref_, min_type_percentage)
+        let mut insights = provider.collect_insights(&options).await?;
+        let baseline = args
+            .regression_baseline
+            .as_deref()
+            .map(CIInsights::from_file)
+            .transpose()?;
+        if let (Some(sink), Some(baseline)) = (&options.cloudevents_sink, &baseline) {
+            let events =
+                cloudevents::regression_events(&insights, baseline, args.regression_threshold);
+            cloudevents::emit(sink, &events).await?;
+        }
+        if let Some(url) = &args.loki_url {
+            let lines =
+                loki::anomaly_lines(&insights, baseline.as_ref(), args.regression_threshold);
+            loki::push(url, args.loki_tenant.as_deref(), &insights.project, &lines).await?;
+        }
+        self.finish_reports(std::slice::from_mut(&mut insights), &config)
             .await?;
 
-        let json_output = if self.pretty {
-            serde_json::to_string_pretty(&insights)?
+        self.write_output(&insights)
+    }
+
+    /// Collects every project listed in the config, each from its own
+    /// instance, into one combined report.
+    #[cfg(feature = "gitlab")]
+    async fn execute_gitlab_projects(
+        &self,
+        args: &GitLabArgs,
+        config: &Config,
+        options: &CollectOptions,
+    ) -> Result<()> {
+        if config.projects.is_empty() {
+            return Err(CILensError::Config(
+                "--project-path is required unless the --config file lists [[projects]]"
+                    .to_string(),
+            )
+            .into());
+        }
+        if args.action.is_some()
+            || args.jobs_only
+            || args.regression_baseline.is_some()
+            || args.loki_url.is_some()
+        {
+            return Err(CILensError::Config(
+                "GitLab subcommands, --jobs-only, --regression-baseline and --loki-url analyze a single project and need --project-path"
+                    .to_string(),
+            )
+            .into());
+        }
+
+        let mut reports = Vec::with_capacity(config.projects.len());
+        for project in &config.projects {
+            let (base_url, token) =
+                match project.instance.as_deref().and_then(|n| config.instance(n)) {
+                    Some(instance) => (instance.base_url.as_str(), instance_token(instance)),
+                    None => (
+                        args.collect.base_url.as_str(),
+                        args.collect.token.as_deref().map(Token::from),
+                    ),
+                };
+
+            info!(
+                "Collecting GitLab insights for project: {} ({base_url})",
+                project.path
+            );
+
+            let provider = configure_provider(
+                GitLabProvider::new(base_url, project.path.clone(), token)?,
+                &args.collect,
+                self.timezone,
+            )?;
+            reports.push(provider.collect_insights(options).await?);
+        }
+
+        self.finish_reports(&mut reports, config).await?;
+
+        if let Some(dir) = &self.output_dir {
+            // One subdirectory per project, e.g. reports/group__project/index.json
+            for insights in &reports {
+                let project_dir = dir.join(insights.project.replace('/', "__"));
+                split::write(&project_dir, insights, self.pretty)?;
+            }
+            return Ok(());
+        }
+
+        let output = match (&self.template, self.format) {
+            (None, OutputFormat::Prometheus) => prometheus::render_all(&reports),
+            (None, OutputFormat::Xlsx | OutputFormat::Pdf) => {
+                return Err(CILensError::Config(
+                    "--format xlsx and pdf render a single project and need --project-path"
+                        .to_string(),
+                )
+                .into());
+            }
+            (template_path, _) => {
+                let combined = CombinedInsights {
+                    collected_at: Utc::now(),
+                    leaderboards: leaderboard::leaderboards(&reports, LEADERBOARD_SIZE),
+                    projects: reports,
+                };
+                if let Some(path) = template_path {
+                    template::render(path, &combined)?
+                } else if self.pretty {
+                    serde_json::to_string_pretty(&combined)?
+                } else {
+                    serde_json::to_string(&combined)?
+                }
+            }
+        };
+
+        self.write(&output)
+    }
+
+    /// Applies config attribution and redaction to freshly collected reports
+    /// and pushes them to the Pushgateway, if configured.
+    #[cfg(feature = "gitlab")]
+    async fn finish_reports(&self, reports: &mut [CIInsights], config: &Config) -> Result<()> {
+        for insights in reports.iter_mut() {
+            if !config.teams.is_empty() {
+                insights.teams = Some(attribute_teams(insights, &config.teams));
+            }
+            if !config.components.is_empty() {
+                insights.components = Some(attribute_components(insights, &config.components));
+            }
+        }
+
+        if let Some(mode) = self.redact {
+            redact::redact(reports, mode, &self.redact_salt);
+        }
+
+        if let Some(gateway) = &self.push_gateway {
+            for insights in reports.iter() {
+                prometheus::push_to_gateway(gateway, &self.push_job, insights).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fills the options `matches` left at their defaults from the `--profile`
+    /// selected in the config.
+    pub fn apply_profile(&mut self, matches: &ArgMatches) -> Result<()> {
+        let Some(name) = &self.profile else {
+            return Ok(());
+        };
+        let config = self.load_config()?;
+        let profile = config.profile(name)?.clone();
+        info!("Using profile: {name}");
+
+        if !is_given(matches, "format") {
+            if let Some(format) = &profile.format {
+                self.format = parse_profile_value("format", format)?;
+            }
+        }
+        if !is_given(matches, "output") && profile.output.is_some() {
+            self.output.clone_from(&profile.output);
+        }
+        if !is_given(matches, "pretty") {
+            self.pretty = profile.pretty.unwrap_or(self.pretty);
+        }
+
+        #[cfg(feature = "gitlab")]
+        if let (Commands::Gitlab(args), Some(("gitlab", matches))) =
+            (&mut self.command, matches.subcommand())
+        {
+            apply_gitlab_profile(args, matches, &profile)?;
+        }
+
+        Ok(())
+    }
+
+    /// Infers the project of `gitlab` runs given none (and no config
+    /// `[[projects]]`) from the `origin` remote of the working directory,
+    /// taking the instance from it too unless a base URL was set.
+    #[cfg(feature = "gitlab")]
+    pub fn detect_project(&mut self, matches: &ArgMatches) -> Result<()> {
+        if let (Commands::Gitlab(args), Some(("gitlab", gitlab_matches))) =
+            (&self.command, matches.subcommand())
+        {
+            if args.project_path.is_some() {
+                return Ok(());
+            }
+            let config = self.load_config()?;
+            if !config.projects.is_empty() {
+                return Ok(());
+            }
+            let Some(remote) = git_remote::detect() else {
+                return Ok(());
+            };
+            if remote.is_other_forge() {
+                debug!(
+                    "Not detecting the project from the git remote, {} is not GitLab",
+                    remote.base_url
+                );
+                return Ok(());
+            }
+
+            let profile_base_url = self
+                .profile
+                .as_deref()
+                .and_then(|name| config.profile(name).ok())
+                .and_then(|profile| profile.base_url.clone());
+            let base_url = if is_given(gitlab_matches, "base_url") || profile_base_url.is_some() {
+                args.collect.base_url.clone()
+            } else {
+                remote.base_url
+            };
+
+            if !init::confirm(&format!(
+                "Analyze {} on {base_url}, the project of the git remote?",
+                remote.project_path
+            ))? {
+                return Err(CILensError::Config(
+                    "--project-path is required unless the --config file lists [[projects]]"
+                        .to_string(),
+                )
+                .into());
+            }
+            info!(
+                "Detected project {} on {base_url} from the git remote",
+                remote.project_path
+            );
+
+            if let Commands::Gitlab(args) = &mut self.command {
+                args.collect.base_url = base_url;
+                args.project_path = Some(remote.project_path);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn load_config(&self) -> Result<Config> {
+        if let Some(path) = &self.config {
+            return Ok(Config::from_file(path)?);
+        }
+
+        let project_config = Path::new(PROJECT_CONFIG);
+        if project_config.is_file() {
+            info!("Using {PROJECT_CONFIG} from the working directory");
+            return Ok(Config::from_file(project_config)?);
+        }
+
+        Ok(Config::default())
+    }
+
+    async fn execute_notify(&self, channel: &NotifyChannel) -> Result<()> {
+        let channel = match channel {
+            NotifyChannel::Summary(channel) => channel,
+            NotifyChannel::Jira(args) => return execute_jira(args).await,
+        };
+
+        let report = channel.report();
+        let insights = CIInsights::from_file(&report.input)?;
+        let baseline = report
+            .baseline
+            .as_deref()
+            .map(CIInsights::from_file)
+            .transpose()?;
+
+        let summary = HealthSummary::new(&insights, baseline.as_ref(), report.top);
+
+        if !summary.is_degraded(report.min_success_rate) {
+            info!(
+                "CI health of {} is within threshold, skipping notification",
+                summary.project
+            );
+            return Ok(());
+        }
+
+        let backend = channel.backend(summary.breach(report.min_success_rate));
+        notify::send(backend.as_ref(), &summary, self.style()).await?;
+
+        Ok(())
+    }
+
+    fn execute_lint_ci(&self, path: &Path, insights: Option<&Path>) -> Result<()> {
+        let insights = insights.map(CIInsights::from_file).transpose()?;
+        let report = lint_ci::lint(path, insights.as_ref())?;
+
+        for problem in &report.problems {
+            warn!("{problem}");
+        }
+
+        let output = if self.pretty {
+            serde_json::to_string_pretty(&report)?
         } else {
-            serde_json::to_string(&insights)?
+            serde_json::to_string(&report)?
+        };
+
+        self.write(&output)
+    }
+
+    fn execute_quarantine(
+        &self,
+        input: &Path,
+        threshold: f64,
+        previous: Option<&Path>,
+        list_format: ListFormat,
+    ) -> Result<()> {
+        let insights = CIInsights::from_file(input)?;
+        let previous = previous.map(QuarantineList::from_file).transpose()?;
+
+        let list = quarantine::build(&insights, threshold, previous.as_ref());
+
+        for job in &list.recovered {
+            info!(
+                "{} recovered (flakiness {:.1}%), quarantined since {}",
+                job.name, job.flakiness_rate, job.quarantined_since
+            );
+        }
+
+        let output = match list_format {
+            ListFormat::Yaml => list.to_yaml()?,
+            ListFormat::Json if self.pretty => serde_json::to_string_pretty(&list)?,
+            ListFormat::Json => serde_json::to_string(&list)?,
         };
 
-        if let Some(output_path) = &self.output {
-            std::fs::write(output_path, json_output)?;
-            info!("Insights written to: {}", output_path.display());
+        self.write(&output)
+    }
+
+    fn execute_digest(&self, history: &Path, since: u64, top: usize) -> Result<()> {
+        let reports = digest::load_history(history)?;
+        let since = chrono::Duration::seconds(i64::try_from(since)?);
+        let Some((current, previous)) = digest::select(&reports, since) else {
+            return Err(CILensError::Config(format!(
+                "No insights reports found in {}",
+                history.display()
+            ))
+            .into());
+        };
+
+        let summary = HealthSummary::new(current, previous, top);
+        self.write(&digest::format(&summary, since.num_days(), self.style()))
+    }
+
+    #[cfg(feature = "gitlab")]
+    async fn execute_benchmark(&self, projects: &[String], args: &CollectArgs) -> Result<()> {
+        if projects.len() < 2 {
+            return Err(
+                CILensError::Config("benchmark needs at least two --project".to_string()).into(),
+            );
+        }
+
+        let options = self.analysis_options(args, &self.load_config()?)?;
+
+        let mut reports = Vec::with_capacity(projects.len());
+        for project in projects {
+            info!("Collecting GitLab insights for project: {project}");
+            let token = args.token.as_deref().map(Token::from);
+            let provider = configure_provider(
+                GitLabProvider::new(&args.base_url, project.clone(), token)?,
+                args,
+                self.timezone,
+            )?;
+            reports.push(provider.collect_insights(&options).await?);
+        }
+
+        let benchmark = benchmark::benchmark(&reports);
+        let output = if self.pretty {
+            serde_json::to_string_pretty(&benchmark)?
         } else {
-            println!("{json_output}");
+            serde_json::to_string(&benchmark)?
+        };
+
+        self.write(&output)
+    }
+
+    #[cfg(feature = "gitlab")]
+    fn execute_grafana_dashboard(&self, title: &str) -> Result<()> {
+        let dashboard = grafana::dashboard(title);
+        let output = if self.pretty {
+            serde_json::to_string_pretty(&dashboard)?
+        } else {
+            serde_json::to_string(&dashboard)?
+        };
+
+        self.write(&output)
+    }
+
+    #[cfg(feature = "gitlab")]
+    fn write_output(&self, insights: &CIInsights) -> Result<()> {
+        if let Some(path) = &self.template {
+            return self.write(&template::render(path, insights)?);
+        }
+        if let Some(dir) = &self.output_dir {
+            return Ok(split::write(dir, insights, self.pretty)?);
+        }
+
+        let output = match self.format {
+            OutputFormat::Json if self.pretty => serde_json::to_string_pretty(insights)?,
+            OutputFormat::Json => serde_json::to_string(insights)?,
+            OutputFormat::Prometheus => prometheus::render(insights),
+            OutputFormat::Xlsx => return self.write_bytes(&xlsx::render(insights)?),
+            OutputFormat::Pdf => return self.write_bytes(&pdf::render(insights, self.style())),
+        };
+
+        self.write(&output)
+    }
+
+    /// Sends logs to stderr, keeping stdout for the report so it can be piped.
+    /// Levels come from `RUST_LOG` unless `--quiet` is set.
+    pub fn init_logging(&self) {
+        let mut builder = if self.quiet {
+            let mut builder = env_logger::Builder::new();
+            builder.filter_level(log::LevelFilter::Error);
+            builder
+        } else {
+            env_logger::Builder::from_default_env()
+        };
+        builder.target(env_logger::Target::Stderr).init();
+    }
+
+    fn compression(&self) -> Option<Compression> {
+        self.compress
+            .or_else(|| self.output.as_deref().and_then(Compression::from_path))
+    }
+
+    fn write(&self, output: &str) -> Result<()> {
+        if self.output.is_none() && self.compression().is_none() {
+            writeln!(std::io::stdout().lock(), "{output}")?;
+            return Ok(());
+        }
+
+        self.write_bytes(output.as_bytes())
+    }
+
+    /// Writes `output` to `--output` (or stdout), compressed if requested.
+    fn write_bytes(&self, output: &[u8]) -> Result<()> {
+        match (&self.output, self.compression()) {
+            (Some(output_path), Some(compression)) => {
+                let file = std::io::BufWriter::new(std::fs::File::create(output_path)?);
+                compression.write_to(file, output)?;
+                info!("Output written to: {}", output_path.display());
+            }
+            (Some(output_path), None) => {
+                std::fs::write(output_path, output)?;
+                info!("Output written to: {}", output_path.display());
+            }
+            (None, Some(compression)) => {
+                compression.write_to(std::io::stdout().lock(), output)?;
+            }
+            (None, None) => std::io::stdout().lock().write_all(output)?,
         }
 
         Ok(())
@@ -86,24 +1807,182 @@ impl Cli {
 
     pub async fn execute(&self) -> Result<()> {
         match &self.command {
-            Commands::Gitlab {
-                token,
+            #[cfg(feature = "gitlab")]
+            Commands::Gitlab(args) => self.execute_gitlab(args).await,
+            Commands::Notify { channel } => self.execute_notify(channel).await,
+            Commands::LintCi { path, insights } => self.execute_lint_ci(path, insights.as_deref()),
+            Commands::Quarantine {
+                input,
+                threshold,
+                previous,
+                list_format,
+            } => self.execute_quarantine(input, *threshold, previous.as_deref(), *list_format),
+            Commands::Digest {
+                history,
+                since,
+                top,
+            } => self.execute_digest(history, *since, *top),
+            Commands::Export { sink } => execute_export(sink).await,
+            Commands::Badge { input, metric } => {
+                self.write(&badge::badge(&CIInsights::from_file(input)?, *metric))
+            }
+            #[cfg(feature = "gitlab")]
+            Commands::Benchmark { projects, args } => self.execute_benchmark(projects, args).await,
+            Commands::Init {
+                path,
                 base_url,
                 project_path,
-                limit,
-                ref_,
-                min_type_percentage,
+                token_env,
+                profile_name,
+                yes,
+                force,
             } => {
-                self.execute_gitlab(
-                    token.as_ref(),
-                    base_url,
-                    project_path,
-                    *limit,
-                    ref_.as_deref(),
-                    *min_type_percentage,
-                )
-                .await
+                let options = InitOptions {
+                    base_url: base_url.clone(),
+                    project_path: project_path.clone(),
+                    token_env: token_env.clone(),
+                    profile: profile_name.clone(),
+                    yes: *yes,
+                };
+                let profile = init::run(path, options, *force)?;
+                if !self.quiet {
+                    eprintln!(
+                        "Wrote {}; collect with: cilens --profile {profile} gitlab",
+                        path.display()
+                    );
+                }
+                Ok(())
+            }
+            #[cfg(feature = "gitlab")]
+            Commands::GrafanaDashboard { title } => self.execute_grafana_dashboard(title),
+        }
+    }
+
+    /// Prints `err` to stderr in the requested format and returns the exit code for it.
+    pub fn report_error(&self, err: &anyhow::Error) -> ExitCode {
+        let cilens_error = err.downcast_ref::<CILensError>();
+        let exit_code = cilens_error.map_or(1, CILensError::exit_code);
+        let retryable = cilens_error.is_some_and(CILensError::is_retryable);
+
+        match self.error_format {
+            ErrorFormat::Text => {
+                eprintln!("Error: {err:?}");
+                if retryable {
+                    eprintln!("This error looks transient, retrying may succeed.");
+                }
+            }
+            ErrorFormat::Json => {
+                let report = serde_json::json!({
+                    "error": {
+                        "kind": cilens_error.map_or("internal", CILensError::kind),
+                        "message": err.to_string(),
+                        "exit_code": exit_code,
+                        "retryable": retryable,
+                    }
+                });
+                eprintln!("{report}");
             }
         }
+
+        ExitCode::from(exit_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("15m"), Ok(900));
+        assert_eq!(parse_duration("1h30m"), Ok(5400));
+        assert_eq!(parse_duration("900"), Ok(900));
+        assert_eq!(parse_duration("7d"), Ok(604_800));
+        assert!(parse_duration("15x").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("").is_err());
+        assert_eq!(parse_duration("0"), Ok(0));
+        assert_eq!(parse_duration("0d"), Ok(0));
+        assert!(parse_duration("999999999999999999d").is_err());
+    }
+
+    #[cfg(feature = "gitlab")]
+    #[test]
+    fn test_parse_header() {
+        let (name, value) = parse_header("X-Proxy-Token:  abc ").unwrap();
+        assert_eq!(name, "x-proxy-token");
+        assert_eq!(value, "abc");
+        assert!(parse_header("X-Proxy-Token").is_err());
+        assert!(parse_header("Bad Name: abc").is_err());
+    }
+
+    #[cfg(feature = "gitlab")]
+    #[test]
+    fn test_parse_pipeline_ids() {
+        assert_eq!(
+            parse_pipeline_ids("123,456\n789").unwrap(),
+            vec![123, 456, 789]
+        );
+        assert!(parse_pipeline_ids("123,abc").is_err());
+    }
+
+    #[cfg(feature = "gitlab")]
+    #[test]
+    fn test_profile_fills_options_not_given_on_the_command_line() {
+        use clap::{CommandFactory, FromArgMatches};
+
+        let path = std::env::temp_dir().join(format!("cilens-profile-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+            [profile.backend]
+            base_url = "https://gitlab.example.com"
+            project_path = "team/backend"
+            limit = 100
+            statuses = ["success", "canceled"]
+            pretty = true
+            "#,
+        )
+        .unwrap();
+
+        let matches = Cli::command().get_matches_from([
+            "cilens",
+            "--config",
+            path.to_str().unwrap(),
+            "--profile",
+            "backend",
+            "gitlab",
+            "--limit",
+            "5",
+        ]);
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        cli.apply_profile(&matches).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let Commands::Gitlab(args) = &cli.command else {
+            panic!("expected the gitlab command");
+        };
+        assert_eq!(args.collect.base_url, "https://gitlab.example.com");
+        assert_eq!(args.project_path.as_deref(), Some("team/backend"));
+        assert_eq!(args.collect.limit, 5);
+        assert_eq!(
+            args.collect.statuses,
+            [PipelineStatus::Success, PipelineStatus::Canceled]
+        );
+        assert!(cli.pretty);
+    }
+
+    #[test]
+    fn test_quiet_is_accepted_after_the_subcommand() {
+        let cli = Cli::try_parse_from(["cilens", "lint-ci", ".gitlab-ci.yml", "-q"]).unwrap();
+
+        assert!(cli.quiet);
+        assert!(!cli.pretty);
+    }
+
+    #[test]
+    fn test_parse_percentage() {
+        assert_eq!(parse_percentage("5%"), Ok(5.0));
+        assert!(parse_percentage("120%").is_err());
     }
 }
@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::Serialize;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::error::{CILensError, Result};
+use crate::insights::CIInsights;
+use crate::notify::find_changes;
+
+pub const PIPELINE_COLLECTED: &str = "io.cilens.pipeline.collected";
+pub const JOB_REGRESSED: &str = "io.cilens.job.regressed";
+
+const BATCH_SIZE: usize = 100;
+
+/// A `CloudEvents` 1.0 event in the structured JSON format.
+#[derive(Debug, Clone, Serialize)]
+pub struct CloudEvent {
+    pub specversion: &'static str,
+    pub id: String,
+    pub source: String,
+    #[serde(rename = "type")]
+    pub type_: &'static str,
+    pub subject: String,
+    pub time: DateTime<Utc>,
+    pub datacontenttype: &'static str,
+    pub data: Value,
+}
+
+impl CloudEvent {
+    /// Events of a project share its source, so consumers can filter by project.
+    pub fn new(
+        type_: &'static str,
+        project: &str,
+        subject: String,
+        id: String,
+        time: DateTime<Utc>,
+        data: Value,
+    ) -> Self {
+        Self {
+            specversion: "1.0",
+            id,
+            source: format!("urn:cilens:project:{project}"),
+            type_,
+            subject,
+            time,
+            datacontenttype: "application/json",
+            data,
+        }
+    }
+}
+
+/// Where events are delivered: an HTTP endpoint accepting batched events, or a
+/// Kafka topic (`kafka://broker1:9092,broker2:9092/topic`).
+#[derive(Debug, Clone)]
+pub enum Sink {
+    Http(Url),
+    #[cfg(feature = "kafka")]
+    Kafka {
+        brokers: String,
+        topic: String,
+    },
+}
+
+impl Sink {
+    pub fn parse(value: &str) -> Result<Self> {
+        #[cfg(not(feature = "kafka"))]
+        if value.starts_with("kafka://") {
+            return Err(CILensError::Config(
+                "Kafka sinks need cilens built with the `kafka` feature".to_string(),
+            ));
+        }
+
+        #[cfg(feature = "kafka")]
+        if let Some(rest) = value.strip_prefix("kafka://") {
+            return match rest.split_once('/') {
+                Some((brokers, topic)) if !brokers.is_empty() && !topic.is_empty() => {
+                    Ok(Self::Kafka {
+                        brokers: brokers.to_string(),
+                        topic: topic.to_string(),
+                    })
+                }
+                _ => Err(CILensError::Config(format!(
+                    "Invalid Kafka sink '{value}', expected kafka://brokers/topic"
+                ))),
+            };
+        }
+
+        Url::parse(value)
+            .map(Self::Http)
+            .map_err(|e| CILensError::Config(format!("Invalid CloudEvents sink '{value}': {e}")))
+    }
+}
+
+/// Job duration regressions of `insights` against `baseline` above `threshold` percent.
+pub fn regression_events(
+    insights: &CIInsights,
+    baseline: &CIInsights,
+    threshold: f64,
+) -> Vec<CloudEvent> {
+    find_changes(insights, baseline, usize::MAX, true)
+        .into_iter()
+        .filter(|change| change.delta_percentage() > threshold)
+        .map(|change| {
+            CloudEvent::new(
+                JOB_REGRESSED,
+                &insights.project,
+                change.name.clone(),
+                format!(
+                    "regression:{}:{}:{}",
+                    insights.project,
+                    change.name,
+                    insights.collected_at.timestamp()
+                ),
+                insights.collected_at,
+                json!({
+                    "job": change.name,
+                    "baseline_seconds": change.baseline_seconds,
+                    "current_seconds": change.current_seconds,
+                    "delta_percentage": change.delta_percentage(),
+                    "baseline_collected_at": baseline.collected_at,
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Delivers `events` to `sink`: in batches (`application/cloudevents-batch+json`)
+/// over HTTP, or one message per event keyed by subject on Kafka.
+pub async fn emit(sink: &Sink, events: &[CloudEvent]) -> Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    match sink {
+        Sink::Http(url) => {
+            let client = reqwest::Client::new();
+            for batch in events.chunks(BATCH_SIZE) {
+                client
+                    .post(url.clone())
+                    .header(
+                        reqwest::header::CONTENT_TYPE,
+                        "application/cloudevents-batch+json",
+                    )
+                    .body(serde_json::to_vec(batch)?)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+            info!("Emitted {} CloudEvents to {url}", events.len());
+        }
+        #[cfg(feature = "kafka")]
+        Sink::Kafka { brokers, topic } => {
+            let messages = events
+                .iter()
+                .map(|event| Ok((event.subject.clone(), serde_json::to_vec(event)?)))
+                .collect::<Result<Vec<_>>>()?;
+            crate::kafka::publish(brokers, topic, messages).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    #[cfg(feature = "kafka")]
+    #[test]
+    fn test_sink_parses_kafka_brokers_and_topic() {
+        let Sink::Kafka { brokers, topic } =
+            Sink::parse("kafka://a:9092,b:9092/ci-events").unwrap()
+        else {
+            panic!("expected a Kafka sink");
+        };
+
+        assert_eq!(brokers, "a:9092,b:9092");
+        assert_eq!(topic, "ci-events");
+        assert!(Sink::parse("kafka://a:9092").is_err());
+        assert!(matches!(
+            Sink::parse("https://events.example.com").unwrap(),
+            Sink::Http(_)
+        ));
+    }
+
+    #[test]
+    fn test_regression_events_skip_changes_below_threshold() {
+        let baseline = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![job("build", 100.0, 1), job("lint", 100.0, 1)],
+        )]);
+        let current = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![job("build", 150.0, 1), job("lint", 105.0, 1)],
+        )]);
+
+        let events = regression_events(&current, &baseline, 10.0);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].type_, JOB_REGRESSED);
+        assert_eq!(events[0].subject, "build");
+        assert_eq!(events[0].source, "urn:cilens:project:group/project");
+        assert!((events[0].data["delta_percentage"].as_f64().unwrap() - 50.0).abs() < 1e-9);
+    }
+}
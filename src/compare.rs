@@ -0,0 +1,108 @@
+use std::collections::BTreeSet;
+
+use crate::insights::CIInsights;
+
+/// Average duration of a job in two reports; `None` when the job is absent from one side.
+#[derive(Debug, Clone)]
+pub struct JobDelta {
+    pub name: String,
+    pub base_seconds: Option<f64>,
+    pub head_seconds: Option<f64>,
+}
+
+impl JobDelta {
+    pub fn delta_seconds(&self) -> Option<f64> {
+        Some(self.head_seconds? - self.base_seconds?)
+    }
+}
+
+/// Pairs up jobs from `base` and `head`, sorted by largest absolute duration change.
+///
+/// Jobs that only exist on one side sort after all jobs present in both.
+pub fn compare_jobs(base: &CIInsights, head: &CIInsights) -> Vec<JobDelta> {
+    let base_durations = base.job_durations();
+    let head_durations = head.job_durations();
+
+    let names: BTreeSet<&str> = base_durations
+        .keys()
+        .chain(head_durations.keys())
+        .copied()
+        .collect();
+
+    let mut deltas: Vec<JobDelta> = names
+        .into_iter()
+        .map(|name| JobDelta {
+            name: name.to_string(),
+            base_seconds: base_durations.get(name).copied(),
+            head_seconds: head_durations.get(name).copied(),
+        })
+        .collect();
+
+    deltas.sort_by(|a, b| {
+        let a = a.delta_seconds().map_or(-1.0, f64::abs);
+        let b = b.delta_seconds().map_or(-1.0, f64::abs);
+        b.total_cmp(&a)
+    });
+
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    #[test]
+    fn test_compare_jobs_sorts_by_largest_change() {
+        let base = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![job("lint", 30.0, 1), job("build", 100.0, 1)],
+        )]);
+        let head = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![job("lint", 35.0, 1), job("build", 160.0, 1)],
+        )]);
+
+        let deltas = compare_jobs(&base, &head);
+
+        assert_eq!(deltas[0].name, "build");
+        assert_eq!(deltas[0].delta_seconds(), Some(60.0));
+        assert_eq!(deltas[1].name, "lint");
+    }
+
+    #[test]
+    fn test_compare_jobs_keeps_one_sided_jobs_last() {
+        let base = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![job("old", 10.0, 1)],
+        )]);
+        let head = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![job("old", 12.0, 1), job("new", 50.0, 1)],
+        )]);
+
+        let deltas = compare_jobs(&base, &head);
+
+        assert_eq!(deltas[0].name, "old");
+        assert_eq!(deltas[1].name, "new");
+        assert_eq!(deltas[1].base_seconds, None);
+    }
+
+    #[test]
+    fn test_job_durations_weighted_by_executions() {
+        let report = insights(vec![
+            pipeline_type("A", 1, 0, vec![job("test", 10.0, 3)]),
+            pipeline_type("B", 1, 0, vec![job("test", 30.0, 1)]),
+        ]);
+
+        assert!((report.job_durations()["test"] - 15.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "gitlab")]
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::{CILensError, Result};
+
+/// Config file picked up from the working directory when `--config` is not
+/// given, so it can be committed to the analyzed repository.
+pub const PROJECT_CONFIG: &str = ".cilens.toml";
+
+/// Optional settings loaded from a TOML file via `--config` (or `.cilens.toml`).
+// Configs are parsed by every build so the same file stays valid, though
+// only the GitLab provider reads most of it
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "gitlab"), allow(dead_code))]
+pub struct Config {
+    #[serde(default)]
+    pub teams: Vec<TeamMapping>,
+    #[serde(default)]
+    pub components: Vec<ComponentMapping>,
+    #[serde(default)]
+    pub instances: Vec<Instance>,
+    /// Projects collected into one combined report when `--project-path` is not given
+    #[serde(default)]
+    pub projects: Vec<ProjectEntry>,
+    /// Stable labels for pipeline types, replacing the heuristic ones
+    #[serde(default)]
+    pub pipeline_types: Vec<PipelineTypeName>,
+    /// Named bundles of `gitlab` options, selected with `--profile`
+    #[serde(default)]
+    pub profile: BTreeMap<String, Profile>,
+}
+
+/// Defaults for `gitlab` options not given on the command line (or through
+/// their environment variables), e.g. `[profile.backend]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "gitlab"), allow(dead_code))]
+pub struct Profile {
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Environment variable holding the token; tokens are never read from the file
+    #[serde(default)]
+    pub token_env: Option<String>,
+    #[serde(default)]
+    pub project_path: Option<String>,
+    #[serde(rename = "ref", default)]
+    pub ref_: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Values of `--statuses`, e.g. `["success", "failed"]`
+    #[serde(default)]
+    pub statuses: Option<Vec<String>>,
+    #[serde(default)]
+    pub default_branch_only: Option<bool>,
+    /// Value of `--format`, e.g. `prometheus`
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub output: Option<PathBuf>,
+    #[serde(default)]
+    pub pretty: Option<bool>,
+}
+
+/// Labels the pipeline type whose jobs are exactly `jobs` (in any order).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "gitlab"), allow(dead_code))]
+pub struct PipelineTypeName {
+    pub name: String,
+    pub jobs: Vec<String>,
+}
+
+/// A GitLab instance other than the one given by `--base-url`/`--token`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "gitlab"), allow(dead_code))]
+pub struct Instance {
+    pub name: String,
+    pub base_url: String,
+    /// Environment variable holding the instance's token; tokens are never read from the file
+    #[serde(default)]
+    pub token_env: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectEntry {
+    pub path: String,
+    /// Name of the instance hosting the project (`--base-url` when unset)
+    #[serde(default)]
+    pub instance: Option<String>,
+}
+
+/// Assigns every job whose name matches one of `jobs` (glob patterns) to a team.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TeamMapping {
+    pub name: String,
+    pub jobs: Vec<String>,
+}
+
+/// Assigns jobs matching `jobs` (glob patterns) to a monorepo component,
+/// optionally documenting the directory it lives in.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "gitlab"), allow(dead_code))]
+pub struct ComponentMapping {
+    pub name: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    pub jobs: Vec<String>,
+}
+
+impl Config {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+            .map_err(|e| CILensError::Config(format!("Invalid config {}: {e}", path.display())))
+    }
+
+    fn parse(content: &str) -> std::result::Result<Self, String> {
+        let config: Self = toml::from_str(content).map_err(|e| e.to_string())?;
+
+        for team in &config.teams {
+            for pattern in &team.jobs {
+                glob::Pattern::new(pattern).map_err(|e| {
+                    format!("team '{}' has invalid pattern '{pattern}': {e}", team.name)
+                })?;
+            }
+        }
+
+        for project in &config.projects {
+            if let Some(instance) = &project.instance {
+                if config.instance(instance).is_none() {
+                    return Err(format!(
+                        "project '{}' refers to unknown instance '{instance}'",
+                        project.path
+                    ));
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Pinned pipeline type labels keyed by job signature (sorted, deduplicated job names).
+    #[cfg(feature = "gitlab")]
+    pub fn pipeline_type_names(&self) -> HashMap<Vec<String>, String> {
+        self.pipeline_types
+            .iter()
+            .map(|pipeline_type| {
+                let mut jobs = pipeline_type.jobs.clone();
+                jobs.sort();
+                jobs.dedup();
+                (jobs, pipeline_type.name.clone())
+            })
+            .collect()
+    }
+
+    pub fn instance(&self, name: &str) -> Option<&Instance> {
+        self.instances.iter().find(|instance| instance.name == name)
+    }
+
+    /// The profile called `name`, failing with the defined ones when there is none.
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        self.profile.get(name).ok_or_else(|| {
+            let defined: Vec<&str> = self.profile.keys().map(String::as_str).collect();
+            CILensError::Config(if defined.is_empty() {
+                format!("Unknown profile '{name}': the config defines no [profile.<name>] tables")
+            } else {
+                format!(
+                    "Unknown profile '{name}', expected one of: {}",
+                    defined.join(", ")
+                )
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_teams() {
+        let config = Config::parse(
+            r#"
+            [[teams]]
+            name = "platform"
+            jobs = ["build*", "deploy:*"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.teams.len(), 1);
+        assert_eq!(config.teams[0].jobs, vec!["build*", "deploy:*"]);
+    }
+
+    #[cfg(feature = "gitlab")]
+    #[test]
+    fn test_pipeline_type_names_are_keyed_by_sorted_jobs() {
+        let config = Config::parse(
+            r#"
+            [[pipeline_types]]
+            name = "Nightly"
+            jobs = ["test", "build", "test"]
+            "#,
+        )
+        .unwrap();
+
+        let names = config.pipeline_type_names();
+
+        assert_eq!(
+            names.get(&vec!["build".to_string(), "test".to_string()]),
+            Some(&"Nightly".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_components() {
+        let config = Config::parse(
+            r#"
+            [[components]]
+            name = "web"
+            path = "web/"
+            jobs = ["frontend:*"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.components[0].path.as_deref(), Some("web/"));
+    }
+
+    #[test]
+    fn test_parse_projects_on_several_instances() {
+        let config = Config::parse(
+            r#"
+            [[instances]]
+            name = "internal"
+            base_url = "https://gitlab.example.com"
+            token_env = "INTERNAL_GITLAB_TOKEN"
+
+            [[projects]]
+            path = "group/public"
+
+            [[projects]]
+            path = "team/private"
+            instance = "internal"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.projects.len(), 2);
+        assert_eq!(
+            config.instance("internal").unwrap().token_env.as_deref(),
+            Some("INTERNAL_GITLAB_TOKEN")
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_instance() {
+        let result = Config::parse(
+            r#"
+            [[projects]]
+            path = "team/private"
+            instance = "internal"
+            "#,
+        );
+
+        assert!(result.unwrap_err().contains("unknown instance 'internal'"));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_pattern() {
+        let result = Config::parse(
+            r#"
+            [[teams]]
+            name = "platform"
+            jobs = ["build[*"]
+            "#,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let config = Config::parse(
+            r#"
+            [profile.backend]
+            base_url = "https://gitlab.example.com"
+            project_path = "team/backend"
+            ref = "main"
+            statuses = ["success", "failed", "canceled"]
+
+            [profile.frontend]
+            project_path = "team/frontend"
+            format = "prometheus"
+            "#,
+        )
+        .unwrap();
+
+        let backend = config.profile("backend").unwrap();
+        assert_eq!(backend.ref_.as_deref(), Some("main"));
+        assert_eq!(backend.statuses.as_ref().map(Vec::len), Some(3));
+
+        let err = config.profile("mobile").unwrap_err().to_string();
+        assert!(err.contains("expected one of: backend, frontend"));
+    }
+}
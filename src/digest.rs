@@ -0,0 +1,170 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use chrono::Duration;
+use log::warn;
+
+use crate::error::Result;
+use crate::insights::CIInsights;
+use crate::notify::{HealthSummary, JobChange};
+use crate::output::human::Style;
+
+/// Reads every report saved in `dir` (`*.json`, optionally gzip/zstd compressed), oldest first.
+///
+/// Files that are not insights reports are skipped with a warning.
+pub fn load_history(dir: &Path) -> Result<Vec<CIInsights>> {
+    let mut reports = Vec::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_report = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                [".json", ".json.gz", ".json.zst"]
+                    .iter()
+                    .any(|extension| name.ends_with(extension))
+            });
+        if !is_report {
+            continue;
+        }
+
+        match CIInsights::from_file(&path) {
+            Ok(report) => reports.push(report),
+            Err(e) => warn!("Skipping {}: {e}", path.display()),
+        }
+    }
+
+    reports.sort_by_key(|report| report.collected_at);
+    Ok(reports)
+}
+
+/// Picks the latest report and, of the same project, the latest one collected
+/// at least `since` before it.
+pub fn select(
+    history: &[CIInsights],
+    since: Duration,
+) -> Option<(&CIInsights, Option<&CIInsights>)> {
+    let current = history.last()?;
+    let cutoff = current.collected_at - since;
+    let previous = history
+        .iter()
+        .rev()
+        .find(|report| report.project == current.project && report.collected_at <= cutoff);
+
+    Some((current, previous))
+}
+
+fn write_changes(text: &mut String, title: &str, changes: &[JobChange], style: Style) {
+    if changes.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(text, "\n{title}");
+    for change in changes {
+        let _ = writeln!(
+            text,
+            "- {}: {} → {} ({})",
+            change.name,
+            style.duration(change.baseline_seconds),
+            style.duration(change.current_seconds),
+            style.percentage_change(change.delta_percentage())
+        );
+    }
+}
+
+/// Formats a plain-text narrative of `summary`, short enough for an email or a chat message.
+pub fn format(summary: &HealthSummary, since_days: i64, style: Style) -> String {
+    let mut text = format!(
+        "CI digest for {} ({} pipelines)\n\nSuccess rate was {}",
+        summary.project,
+        summary.total_pipelines,
+        style.percentage(summary.success_rate)
+    );
+
+    match summary.baseline_success_rate {
+        Some(previous) => {
+            let delta = summary.success_rate - previous;
+            let trend = if delta > 0.0 {
+                "up"
+            } else if delta < 0.0 {
+                "down"
+            } else {
+                "unchanged"
+            };
+            let _ = writeln!(
+                text,
+                ", {trend} {:.1} pts from {} {since_days} days earlier.",
+                delta.abs(),
+                style.percentage(previous)
+            );
+        }
+        None => {
+            let _ = writeln!(
+                text,
+                ". No report from {since_days} days earlier to compare against."
+            );
+        }
+    }
+
+    write_changes(&mut text, "Top regressions", &summary.regressions, style);
+    write_changes(&mut text, "Top improvements", &summary.improvements, style);
+
+    if !summary.flakiest_jobs.is_empty() {
+        text.push_str("\nFlakiest jobs\n");
+        for job in &summary.flakiest_jobs {
+            let _ = writeln!(
+                text,
+                "- {}: {} flaky",
+                job.name,
+                style.percentage(job.flakiness_rate)
+            );
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    fn report(collected_at: &str, duration: f64, failed: usize) -> CIInsights {
+        let mut report = insights(vec![pipeline_type(
+            "Default",
+            10 - failed,
+            failed,
+            vec![job("build", duration, 10)],
+        )]);
+        report.collected_at = collected_at.parse().unwrap();
+        report
+    }
+
+    #[test]
+    fn test_select_skips_reports_newer_than_the_window() {
+        let history = vec![
+            report("2025-01-01T00:00:00Z", 100.0, 0),
+            report("2025-01-06T00:00:00Z", 110.0, 0),
+            report("2025-01-08T00:00:00Z", 120.0, 0),
+        ];
+
+        let (current, previous) = select(&history, Duration::days(7)).unwrap();
+
+        assert_eq!(current.collected_at, history[2].collected_at);
+        assert_eq!(previous.unwrap().collected_at, history[0].collected_at);
+    }
+
+    #[test]
+    fn test_format_reports_success_rate_delta_and_changes() {
+        let previous = report("2025-01-01T00:00:00Z", 100.0, 0);
+        let current = report("2025-01-08T00:00:00Z", 150.0, 1);
+        let summary = HealthSummary::new(&current, Some(&previous), 3);
+
+        let text = format(&summary, 7, Style::default());
+
+        assert!(text.contains("Success rate was 90.0%, down 10.0 pts from 100.0% 7 days earlier."));
+        assert!(text.contains("Top regressions\n- build: 100s → 150s (+50%)"));
+        assert!(!text.contains("Top improvements"));
+    }
+}
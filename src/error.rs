@@ -1,13 +1,37 @@
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+// Provider failures keep their exit codes in builds without providers
+#[cfg_attr(not(feature = "gitlab"), allow(dead_code))]
 pub enum CILensError {
     #[error("Invalid configuration: {0}")]
     Config(String),
 
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("GraphQL error: {0}")]
+    GraphQL(String),
+
     #[error("Network error: {0}")]
     Network(#[from] reqwest::Error),
 
+    #[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+    #[error("Analysis error: {0}")]
+    Analysis(String),
+
+    #[error("Request budget exceeded: {0}")]
+    BudgetExceeded(String),
+
+    #[error("Export failed: {0}")]
+    Export(String),
+
     #[error("JSON serialization error: {0}")]
     Json(#[from] serde_json::Error),
 
@@ -15,4 +39,142 @@ pub enum CILensError {
     Io(#[from] std::io::Error),
 }
 
+impl CILensError {
+    /// Stable process exit code for this error, so wrappers can branch on it.
+    #[must_use]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Config(_) => 2,
+            Self::Auth(_) => 3,
+            Self::NotFound(_) => 4,
+            Self::RateLimited(_) => 5,
+            Self::GraphQL(_) => 6,
+            Self::Network(_) => 7,
+            Self::Analysis(_) => 8,
+            Self::Json(_) | Self::Io(_) => 9,
+            Self::BudgetExceeded(_) => 10,
+            Self::Export(_) => 11,
+        }
+    }
+
+    /// Whether the failure is transient, i.e. rerunning the same command may succeed.
+    ///
+    /// Timeouts, connection/DNS failures, throttling and gateway errors are
+    /// retryable; authentication, missing resources and bad input are terminal.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited(_) => true,
+            Self::Network(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status()
+                        .is_some_and(|status| matches!(status.as_u16(), 500 | 502 | 503 | 504))
+            }
+            Self::Config(_)
+            | Self::Auth(_)
+            | Self::NotFound(_)
+            | Self::GraphQL(_)
+            | Self::Analysis(_)
+            | Self::BudgetExceeded(_)
+            | Self::Export(_)
+            | Self::Json(_)
+            | Self::Io(_) => false,
+        }
+    }
+
+    /// Short machine-readable name of the error variant.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config",
+            Self::Auth(_) => "auth",
+            Self::RateLimited(_) => "rate_limited",
+            Self::NotFound(_) => "not_found",
+            Self::GraphQL(_) => "graphql",
+            Self::Network(_) => "network",
+            Self::Analysis(_) => "analysis",
+            Self::BudgetExceeded(_) => "budget_exceeded",
+            Self::Export(_) => "export",
+            Self::Json(_) => "json",
+            Self::Io(_) => "io",
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, CILensError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct_per_category() {
+        let errors = [
+            CILensError::Config(String::new()),
+            CILensError::Auth(String::new()),
+            CILensError::NotFound(String::new()),
+            CILensError::RateLimited(String::new()),
+            CILensError::GraphQL(String::new()),
+            CILensError::Analysis(String::new()),
+            CILensError::BudgetExceeded(String::new()),
+            CILensError::Export(String::new()),
+        ];
+
+        let mut codes: Vec<u8> = errors.iter().map(CILensError::exit_code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&0));
+        assert!(!codes.contains(&1));
+    }
+
+    #[test]
+    fn test_terminal_errors_are_not_retryable() {
+        assert!(!CILensError::Auth(String::new()).is_retryable());
+        assert!(!CILensError::NotFound(String::new()).is_retryable());
+        assert!(!CILensError::Config(String::new()).is_retryable());
+    }
+
+    #[test]
+    fn test_rate_limited_is_retryable() {
+        assert!(CILensError::RateLimited(String::new()).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_error_is_retryable() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/")
+            .with_status(502)
+            .create_async()
+            .await;
+
+        let error = reqwest::get(server.url())
+            .await
+            .unwrap()
+            .error_for_status()
+            .unwrap_err();
+
+        assert!(CILensError::Network(error).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_connection_failure_is_retryable() {
+        // Port 1 on localhost is reserved and refuses connections
+        let error = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+
+        assert!(CILensError::Network(error).is_retryable());
+    }
+
+    #[test]
+    fn test_kind_names() {
+        assert_eq!(CILensError::Auth(String::new()).kind(), "auth");
+        assert_eq!(
+            CILensError::RateLimited(String::new()).kind(),
+            "rate_limited"
+        );
+        assert_eq!(CILensError::NotFound(String::new()).kind(), "not_found");
+    }
+}
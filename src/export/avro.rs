@@ -0,0 +1,120 @@
+use serde_json::{json, Value};
+
+use super::JobRecord;
+use crate::error::{CILensError, Result};
+
+/// Avro schema of [`JobRecord`]; field order is the binary encoding order.
+pub const JOB_RECORD_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "JobRecord",
+  "namespace": "io.cilens",
+  "fields": [
+    {"name": "project", "type": "string"},
+    {"name": "collected_at", "type": "string"},
+    {"name": "pipeline_type", "type": "string"},
+    {"name": "job", "type": "string"},
+    {"name": "avg_duration_seconds", "type": "double"},
+    {"name": "avg_time_to_feedback_seconds", "type": "double"},
+    {"name": "flakiness_rate", "type": "double"},
+    {"name": "failure_rate", "type": "double"},
+    {"name": "total_executions", "type": "long"}
+  ]
+}"#;
+
+/// Zig-zag encoded variable-length `long`.
+fn write_long(out: &mut Vec<u8>, value: i64) {
+    #[allow(clippy::cast_sign_loss)]
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        #[allow(clippy::cast_possible_truncation)]
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    out.push(n as u8);
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    write_long(out, i64::try_from(value.len()).unwrap_or(i64::MAX));
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn write_double(out: &mut Vec<u8>, value: f64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Encodes `record` as an Avro binary datum of [`JOB_RECORD_SCHEMA`].
+pub fn encode(record: &JobRecord) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_string(&mut out, &record.project);
+    write_string(&mut out, &record.collected_at);
+    write_string(&mut out, &record.pipeline_type);
+    write_string(&mut out, &record.job);
+    write_double(&mut out, record.avg_duration_seconds);
+    write_double(&mut out, record.avg_time_to_feedback_seconds);
+    write_double(&mut out, record.flakiness_rate);
+    write_double(&mut out, record.failure_rate);
+    write_long(&mut out, record.total_executions);
+    out
+}
+
+/// Prefixes `datum` with the Confluent wire format header (magic byte and
+/// schema ID), so registry-aware consumers can decode it.
+pub fn frame(schema_id: u32, datum: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(datum.len() + 5);
+    out.push(0);
+    out.extend_from_slice(&schema_id.to_be_bytes());
+    out.extend_from_slice(datum);
+    out
+}
+
+/// Registers [`JOB_RECORD_SCHEMA`] under `subject` (a no-op if already
+/// registered) and returns its schema ID.
+pub async fn register_schema(registry_url: &str, subject: &str) -> Result<u32> {
+    let response: Value = reqwest::Client::new()
+        .post(format!(
+            "{}/subjects/{subject}/versions",
+            registry_url.trim_end_matches('/')
+        ))
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/vnd.schemaregistry.v1+json",
+        )
+        .json(&json!({ "schema": JOB_RECORD_SCHEMA }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    response["id"]
+        .as_u64()
+        .and_then(|id| u32::try_from(id).ok())
+        .ok_or_else(|| {
+            CILensError::Export("Schema registry did not return a schema ID".to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_long_uses_zigzag_varints() {
+        let encode = |value| {
+            let mut out = Vec::new();
+            write_long(&mut out, value);
+            out
+        };
+
+        assert_eq!(encode(0), vec![0x00]);
+        assert_eq!(encode(-1), vec![0x01]);
+        assert_eq!(encode(1), vec![0x02]);
+        assert_eq!(encode(64), vec![0x80, 0x01]);
+    }
+
+    #[test]
+    fn test_frame_prefixes_magic_byte_and_schema_id() {
+        assert_eq!(frame(7, &[0xaa]), vec![0, 0, 0, 0, 7, 0xaa]);
+    }
+}
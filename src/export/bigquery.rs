@@ -0,0 +1,262 @@
+use log::info;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::{job_records, pipeline_type_records};
+use crate::error::{CILensError, Result};
+use crate::insights::CIInsights;
+
+pub const BIGQUERY_API_URL: &str = "https://bigquery.googleapis.com/bigquery/v2";
+
+/// insertAll accepts up to 50,000 rows per request, but recommends about 500.
+const BATCH_SIZE: usize = 500;
+
+struct Table {
+    suffix: &'static str,
+    /// `(name, BigQuery type)`, matching the fields of the exported record
+    fields: &'static [(&'static str, &'static str)],
+}
+
+const JOBS: Table = Table {
+    suffix: "jobs",
+    fields: &[
+        ("project", "STRING"),
+        ("collected_at", "TIMESTAMP"),
+        ("pipeline_type", "STRING"),
+        ("job", "STRING"),
+        ("avg_duration_seconds", "FLOAT"),
+        ("avg_time_to_feedback_seconds", "FLOAT"),
+        ("flakiness_rate", "FLOAT"),
+        ("failure_rate", "FLOAT"),
+        ("total_executions", "INTEGER"),
+    ],
+};
+
+const PIPELINE_TYPES: Table = Table {
+    suffix: "pipeline_types",
+    fields: &[
+        ("project", "STRING"),
+        ("collected_at", "TIMESTAMP"),
+        ("pipeline_type", "STRING"),
+        ("total_pipelines", "INTEGER"),
+        ("success_rate", "FLOAT"),
+        ("avg_duration_seconds", "FLOAT"),
+        ("avg_time_to_feedback_seconds", "FLOAT"),
+        ("failed_pipeline_minutes", "FLOAT"),
+    ],
+};
+
+/// Streams report rows into `<table_prefix>jobs` and `<table_prefix>pipeline_types`
+/// of a dataset, creating the tables on first use.
+pub struct BigQuery {
+    pub api_url: String,
+    pub project: String,
+    pub dataset: String,
+    pub table_prefix: String,
+    /// OAuth access token, e.g. from `gcloud auth print-access-token`
+    pub access_token: String,
+}
+
+impl BigQuery {
+    fn tables_url(&self) -> String {
+        format!(
+            "{}/projects/{}/datasets/{}/tables",
+            self.api_url.trim_end_matches('/'),
+            self.project,
+            self.dataset
+        )
+    }
+
+    fn table_id(&self, table: &Table) -> String {
+        format!("{}{}", self.table_prefix, table.suffix)
+    }
+
+    async fn ensure_table(&self, client: &reqwest::Client, table: &Table) -> Result<()> {
+        let table_id = self.table_id(table);
+        let response = client
+            .get(format!("{}/{table_id}", self.tables_url()))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?;
+        if response.status() != StatusCode::NOT_FOUND {
+            response.error_for_status()?;
+            return Ok(());
+        }
+
+        let fields: Vec<Value> = table
+            .fields
+            .iter()
+            .map(|(name, type_)| json!({ "name": name, "type": type_, "mode": "REQUIRED" }))
+            .collect();
+        client
+            .post(self.tables_url())
+            .bearer_auth(&self.access_token)
+            .json(&json!({
+                "tableReference": {
+                    "projectId": self.project,
+                    "datasetId": self.dataset,
+                    "tableId": table_id,
+                },
+                "schema": { "fields": fields },
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+        info!("Created BigQuery table {}.{table_id}", self.dataset);
+
+        Ok(())
+    }
+
+    /// Inserts `(insert_id, row)` pairs; `BigQuery` drops rows whose insert ID it
+    /// saw in the last minute, so retried exports don't duplicate rows.
+    async fn insert<T: Serialize>(
+        &self,
+        client: &reqwest::Client,
+        table: &Table,
+        rows: &[(String, T)],
+    ) -> Result<()> {
+        let table_id = self.table_id(table);
+        for batch in rows.chunks(BATCH_SIZE) {
+            let rows: Vec<Value> = batch
+                .iter()
+                .map(|(insert_id, row)| {
+                    Ok(json!({ "insertId": insert_id, "json": serde_json::to_value(row)? }))
+                })
+                .collect::<Result<_>>()?;
+            let response: Value = client
+                .post(format!("{}/{table_id}/insertAll", self.tables_url()))
+                .bearer_auth(&self.access_token)
+                .json(&json!({ "rows": rows }))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            if let Some(errors) = response["insertErrors"].as_array() {
+                return Err(CILensError::Export(format!(
+                    "BigQuery rejected {} rows of {table_id}: {}",
+                    errors.len(),
+                    errors[0]["errors"]
+                )));
+            }
+        }
+        info!(
+            "Inserted {} rows into BigQuery table {}.{table_id}",
+            rows.len(),
+            self.dataset
+        );
+
+        Ok(())
+    }
+
+    pub async fn export(&self, insights: &CIInsights) -> Result<()> {
+        let client = reqwest::Client::new();
+        let insert_id = |key: String| format!("{key}@{}", insights.collected_at.timestamp());
+
+        let jobs: Vec<_> = job_records(insights)
+            .into_iter()
+            .map(|record| (insert_id(record.key()), record))
+            .collect();
+        let pipeline_types: Vec<_> = pipeline_type_records(insights)
+            .into_iter()
+            .map(|record| (insert_id(record.key()), record))
+            .collect();
+
+        self.ensure_table(&client, &JOBS).await?;
+        self.ensure_table(&client, &PIPELINE_TYPES).await?;
+        self.insert(&client, &JOBS, &jobs).await?;
+        self.insert(&client, &PIPELINE_TYPES, &pipeline_types)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    #[tokio::test]
+    async fn test_export_creates_missing_tables_and_inserts_rows() {
+        let mut server = mockito::Server::new_async().await;
+        let tables = "/projects/p/datasets/ci/tables";
+        let _existing = server
+            .mock("GET", format!("{tables}/cilens_jobs").as_str())
+            .with_body("{}")
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", format!("{tables}/cilens_pipeline_types").as_str())
+            .with_status(404)
+            .create_async()
+            .await;
+        let create = server
+            .mock("POST", tables)
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "tableReference": { "tableId": "cilens_pipeline_types" }
+            })))
+            .with_body("{}")
+            .create_async()
+            .await;
+        let insert_jobs = server
+            .mock("POST", format!("{tables}/cilens_jobs/insertAll").as_str())
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "rows": [{ "json": { "job": "lint", "total_executions": 2 } }]
+            })))
+            .with_body("{}")
+            .create_async()
+            .await;
+        let insert_types = server
+            .mock(
+                "POST",
+                format!("{tables}/cilens_pipeline_types/insertAll").as_str(),
+            )
+            .with_body("{}")
+            .create_async()
+            .await;
+        let bigquery = BigQuery {
+            api_url: server.url(),
+            project: "p".to_string(),
+            dataset: "ci".to_string(),
+            table_prefix: "cilens_".to_string(),
+            access_token: "token".to_string(),
+        };
+        let report = insights(vec![pipeline_type("MR", 1, 0, vec![job("lint", 30.0, 2)])]);
+
+        bigquery.export(&report).await.unwrap();
+
+        create.assert_async().await;
+        insert_jobs.assert_async().await;
+        insert_types.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_export_fails_on_insert_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let _tables = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_body("{}")
+            .create_async()
+            .await;
+        let _insert = server
+            .mock("POST", mockito::Matcher::Any)
+            .with_body(r#"{"insertErrors": [{"index": 0, "errors": [{"reason": "invalid"}]}]}"#)
+            .create_async()
+            .await;
+        let bigquery = BigQuery {
+            api_url: server.url(),
+            project: "p".to_string(),
+            dataset: "ci".to_string(),
+            table_prefix: "cilens_".to_string(),
+            access_token: "token".to_string(),
+        };
+        let report = insights(vec![pipeline_type("MR", 1, 0, vec![job("lint", 30.0, 2)])]);
+
+        let err = bigquery.export(&report).await.unwrap_err();
+
+        assert!(matches!(err, CILensError::Export(_)));
+    }
+}
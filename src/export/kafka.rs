@@ -0,0 +1,47 @@
+use clap::ValueEnum;
+
+use super::{avro, job_records};
+use crate::error::Result;
+use crate::insights::CIInsights;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum MessageFormat {
+    Json,
+    /// Avro binary in the Confluent wire format (needs a schema registry)
+    Avro,
+}
+
+/// Encodes one message per job record, keyed by [`super::JobRecord::key`]: as
+/// Avro when given the registry ID of [`avro::JOB_RECORD_SCHEMA`], as JSON otherwise.
+pub fn messages(
+    insights: &CIInsights,
+    avro_schema_id: Option<u32>,
+) -> Result<Vec<(String, Vec<u8>)>> {
+    job_records(insights)
+        .into_iter()
+        .map(|record| {
+            let payload = match avro_schema_id {
+                Some(schema_id) => avro::frame(schema_id, &avro::encode(&record)),
+                None => serde_json::to_vec(&record)?,
+            };
+            Ok((record.key(), payload))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    #[test]
+    fn test_messages_are_keyed_per_pipeline_type_and_job() {
+        let report = insights(vec![pipeline_type("MR", 1, 0, vec![job("lint", 30.0, 2)])]);
+
+        let messages = messages(&report, None).unwrap();
+        let record: serde_json::Value = serde_json::from_slice(&messages[0].1).unwrap();
+
+        assert_eq!(messages[0].0, "group/project/MR/lint");
+        assert_eq!(record["total_executions"], 2);
+    }
+}
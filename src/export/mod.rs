@@ -0,0 +1,94 @@
+#[cfg(feature = "kafka")]
+pub mod avro;
+pub mod bigquery;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+
+use serde::Serialize;
+
+use crate::insights::CIInsights;
+
+/// One row per job of each pipeline type, the shape exported to data platforms.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRecord {
+    pub project: String,
+    /// RFC 3339 timestamp of the report the row comes from
+    pub collected_at: String,
+    pub pipeline_type: String,
+    pub job: String,
+    pub avg_duration_seconds: f64,
+    pub avg_time_to_feedback_seconds: f64,
+    pub flakiness_rate: f64,
+    pub failure_rate: f64,
+    pub total_executions: i64,
+}
+
+impl JobRecord {
+    /// Identifies the job across reports, e.g. as a message key.
+    pub fn key(&self) -> String {
+        format!("{}/{}/{}", self.project, self.pipeline_type, self.job)
+    }
+}
+
+/// One row per pipeline type.
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineTypeRecord {
+    pub project: String,
+    pub collected_at: String,
+    pub pipeline_type: String,
+    pub total_pipelines: i64,
+    pub success_rate: f64,
+    pub avg_duration_seconds: f64,
+    pub avg_time_to_feedback_seconds: f64,
+    pub failed_pipeline_minutes: f64,
+}
+
+impl PipelineTypeRecord {
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.project, self.pipeline_type)
+    }
+}
+
+pub fn pipeline_type_records(insights: &CIInsights) -> Vec<PipelineTypeRecord> {
+    let collected_at = insights.collected_at.to_rfc3339();
+
+    insights
+        .pipeline_types
+        .iter()
+        .map(|pipeline_type| {
+            let metrics = &pipeline_type.metrics;
+            PipelineTypeRecord {
+                project: insights.project.clone(),
+                collected_at: collected_at.clone(),
+                pipeline_type: pipeline_type.label.clone(),
+                total_pipelines: i64::try_from(metrics.total_pipelines).unwrap_or(i64::MAX),
+                success_rate: metrics.success_rate,
+                avg_duration_seconds: metrics.avg_duration_seconds,
+                avg_time_to_feedback_seconds: metrics.avg_time_to_feedback_seconds,
+                failed_pipeline_minutes: metrics.failed_pipeline_minutes,
+            }
+        })
+        .collect()
+}
+
+pub fn job_records(insights: &CIInsights) -> Vec<JobRecord> {
+    let collected_at = insights.collected_at.to_rfc3339();
+
+    insights
+        .pipeline_types
+        .iter()
+        .flat_map(|pipeline_type| {
+            pipeline_type.metrics.jobs.iter().map(|job| JobRecord {
+                project: insights.project.clone(),
+                collected_at: collected_at.clone(),
+                pipeline_type: pipeline_type.label.clone(),
+                job: job.name.clone(),
+                avg_duration_seconds: job.avg_duration_seconds,
+                avg_time_to_feedback_seconds: job.avg_time_to_feedback_seconds,
+                flakiness_rate: job.flakiness_rate,
+                failure_rate: job.failure_rate,
+                total_executions: i64::try_from(job.total_executions).unwrap_or(i64::MAX),
+            })
+        })
+        .collect()
+}
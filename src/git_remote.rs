@@ -0,0 +1,123 @@
+use std::process::Command;
+
+use url::Url;
+
+/// Hosts of git forges `CILens` has no provider for
+const OTHER_FORGES: [&str; 3] = ["github.com", "bitbucket.org", "codeberg.org"];
+
+/// GitLab project a git remote points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remote {
+    /// Web URL of the instance, e.g. `https://gitlab.example.com`
+    pub base_url: String,
+    /// e.g. `group/subgroup/project`
+    pub project_path: String,
+}
+
+impl Remote {
+    /// Whether the remote is hosted on a forge other than GitLab.
+    pub fn is_other_forge(&self) -> bool {
+        OTHER_FORGES
+            .iter()
+            .any(|forge| self.base_url.ends_with(forge))
+    }
+}
+
+/// Parses HTTP(S), `ssh://` and scp-like (`git@host:group/project.git`)
+/// remote URLs. SSH remotes are assumed to serve the web UI over HTTPS on the
+/// default port.
+pub fn parse(remote_url: &str) -> Option<Remote> {
+    let remote_url = remote_url.trim();
+
+    let (base_url, path) = if remote_url.contains("://") {
+        let url = Url::parse(remote_url).ok()?;
+        let host = url.host_str()?;
+        let base_url = match (url.scheme(), url.port()) {
+            ("http" | "https", Some(port)) => format!("{}://{host}:{port}", url.scheme()),
+            ("http" | "https", None) => format!("{}://{host}", url.scheme()),
+            _ => format!("https://{host}"),
+        };
+        (base_url, url.path().to_string())
+    } else {
+        let (user_host, path) = remote_url.split_once(':')?;
+        let host = user_host.rsplit('@').next()?;
+        (format!("https://{host}"), path.to_string())
+    };
+
+    let project_path = path.trim_matches('/').trim_end_matches(".git");
+    // GitLab projects always live in a group or user namespace
+    if !project_path.contains('/') {
+        return None;
+    }
+
+    Some(Remote {
+        base_url,
+        project_path: project_path.to_string(),
+    })
+}
+
+/// The project the `origin` remote of the repository in the working
+/// directory points at, if there is one.
+pub fn detect() -> Option<Remote> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(base_url: &str, project_path: &str) -> Remote {
+        Remote {
+            base_url: base_url.to_string(),
+            project_path: project_path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_https_remote() {
+        assert_eq!(
+            parse("https://gitlab.com/group/sub/project.git\n"),
+            Some(remote("https://gitlab.com", "group/sub/project"))
+        );
+        assert_eq!(
+            parse("http://gitlab.local:8080/group/project"),
+            Some(remote("http://gitlab.local:8080", "group/project"))
+        );
+    }
+
+    #[test]
+    fn test_parse_ssh_remotes() {
+        assert_eq!(
+            parse("git@gitlab.example.com:team/backend.git"),
+            Some(remote("https://gitlab.example.com", "team/backend"))
+        );
+        assert_eq!(
+            parse("ssh://git@gitlab.example.com:2222/team/backend.git"),
+            Some(remote("https://gitlab.example.com", "team/backend"))
+        );
+    }
+
+    #[test]
+    fn test_other_forges_are_recognized() {
+        assert!(parse("git@github.com:org/repo.git")
+            .unwrap()
+            .is_other_forge());
+        assert!(!parse("git@gitlab.example.com:org/repo.git")
+            .unwrap()
+            .is_other_forge());
+    }
+
+    #[test]
+    fn test_parse_rejects_paths_without_namespace() {
+        assert_eq!(parse("https://gitlab.com/project.git"), None);
+        assert_eq!(parse("/srv/repos/project.git"), None);
+    }
+}
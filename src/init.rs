@@ -0,0 +1,262 @@
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use log::warn;
+
+use crate::error::{CILensError, Result};
+use crate::git_remote::{self, Remote};
+
+const DEFAULT_BASE_URL: &str = "https://gitlab.com";
+const DEFAULT_TOKEN_ENV: &str = "GITLAB_TOKEN";
+
+/// Settings a starter config is written with; unset ones are detected from
+/// the git remote or asked for.
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    pub base_url: Option<String>,
+    pub project_path: Option<String>,
+    pub token_env: Option<String>,
+    pub profile: Option<String>,
+    /// Accept detected and default values instead of asking
+    pub yes: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Settings {
+    profile: String,
+    base_url: String,
+    project_path: String,
+    token_env: String,
+}
+
+/// Writes a starter config with one profile to `path`, refusing to replace an
+/// existing file unless `force` is set. Returns the profile's name.
+pub fn run(path: &Path, options: InitOptions, force: bool) -> Result<String> {
+    if path.exists() && !force {
+        return Err(CILensError::Config(format!(
+            "{} already exists, pass --force to replace it",
+            path.display()
+        )));
+    }
+
+    let detected = git_remote::detect();
+    let settings = if options.yes || !std::io::stdin().is_terminal() {
+        resolve(options, detected.as_ref(), |_, default| {
+            Ok(default.to_string())
+        })?
+    } else {
+        let mut stdin = std::io::stdin().lock();
+        resolve(options, detected.as_ref(), |question, default| {
+            ask(&mut stdin, question, default)
+        })?
+    };
+
+    std::fs::write(path, render(&settings))?;
+    Ok(settings.profile)
+}
+
+/// Fills unset options from the remote and `answer`, which gets a question and
+/// the value it proposes.
+fn resolve(
+    options: InitOptions,
+    detected: Option<&Remote>,
+    mut answer: impl FnMut(&str, &str) -> Result<String>,
+) -> Result<Settings> {
+    if let Some(remote) = detected {
+        if remote.is_other_forge() {
+            warn!(
+                "The git remote points at {}, but CILens only supports GitLab",
+                remote.base_url
+            );
+        }
+    }
+
+    let base_url = match options.base_url {
+        Some(base_url) => base_url,
+        None => answer(
+            "GitLab URL",
+            detected.map_or(DEFAULT_BASE_URL, |remote| &remote.base_url),
+        )?,
+    };
+    let project_path = match options.project_path {
+        Some(project_path) => project_path,
+        None => answer(
+            "Project path",
+            detected.map_or("", |remote| &remote.project_path),
+        )?,
+    };
+    if project_path.is_empty() {
+        return Err(CILensError::Config(
+            "No git remote to detect the project from, pass --project-path".to_string(),
+        ));
+    }
+    let token_env = match options.token_env {
+        Some(token_env) => token_env,
+        None => answer("Environment variable holding the token", DEFAULT_TOKEN_ENV)?,
+    };
+    let profile = match options.profile {
+        Some(profile) => profile,
+        None => answer("Profile name", &default_profile_name(&project_path))?,
+    };
+
+    Ok(Settings {
+        profile,
+        base_url,
+        project_path,
+        token_env,
+    })
+}
+
+/// Last path segment of the project, usable as a bare TOML key.
+fn default_profile_name(project_path: &str) -> String {
+    project_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(project_path)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Prints `question` with its proposed value and reads the answer, keeping
+/// the proposal on an empty answer.
+fn ask(input: &mut impl BufRead, question: &str, default: &str) -> Result<String> {
+    let mut stderr = std::io::stderr().lock();
+    if default.is_empty() {
+        write!(stderr, "{question}: ")?;
+    } else {
+        write!(stderr, "{question} [{default}]: ")?;
+    }
+    stderr.flush()?;
+
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    let answer = line.trim();
+
+    Ok(if answer.is_empty() { default } else { answer }.to_string())
+}
+
+/// Asks a yes/no `question` on an interactive terminal, defaulting to yes.
+/// Without a terminal there is nobody to ask, so the answer is yes.
+#[cfg(feature = "gitlab")]
+pub fn confirm(question: &str) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(true);
+    }
+
+    let answer = ask(&mut std::io::stdin().lock(), question, "Y")?;
+    Ok(answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes"))
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn render(settings: &Settings) -> String {
+    format!(
+        r#"# CILens configuration. Collect with:
+#   cilens --profile {profile} gitlab
+# (or set CILENS_PROFILE={profile})
+
+[profile.{key}]
+base_url = {base_url}
+token_env = {token_env}  # tokens are only read from the environment
+project_path = {project_path}
+# ref = "main"
+# limit = 100
+# statuses = ["success", "failed"]
+
+# Attribute jobs to teams by glob pattern; the first matching team wins
+# [[teams]]
+# name = "platform"
+# jobs = ["build*", "deploy:*"]
+"#,
+        profile = settings.profile,
+        key = toml_key(&settings.profile),
+        base_url = toml_string(&settings.base_url),
+        token_env = toml_string(&settings.token_env),
+        project_path = toml_string(&settings.project_path),
+    )
+}
+
+fn toml_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn remote() -> Remote {
+        Remote {
+            base_url: "https://gitlab.example.com".to_string(),
+            project_path: "team/backend.api".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_proposes_detected_values() {
+        let settings = resolve(InitOptions::default(), Some(&remote()), |_, default| {
+            Ok(default.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(
+            settings,
+            Settings {
+                profile: "backend-api".to_string(),
+                base_url: "https://gitlab.example.com".to_string(),
+                project_path: "team/backend.api".to_string(),
+                token_env: DEFAULT_TOKEN_ENV.to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_resolve_requires_a_project_without_remote() {
+        let result = resolve(InitOptions::default(), None, |_, default| {
+            Ok(default.to_string())
+        });
+
+        assert!(matches!(result, Err(CILensError::Config(_))));
+    }
+
+    #[test]
+    fn test_ask_keeps_the_proposal_on_empty_answer() {
+        let mut input = "\ncustom\n".as_bytes();
+
+        assert_eq!(ask(&mut input, "Q", "proposed").unwrap(), "proposed");
+        assert_eq!(ask(&mut input, "Q", "proposed").unwrap(), "custom");
+    }
+
+    #[test]
+    fn test_rendered_config_parses_with_the_profile() {
+        let settings = Settings {
+            profile: "my team".to_string(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            project_path: "group/project".to_string(),
+            token_env: DEFAULT_TOKEN_ENV.to_string(),
+        };
+
+        let config: Config = toml::from_str(&render(&settings)).unwrap();
+        let profile = config.profile("my team").unwrap();
+
+        assert_eq!(profile.project_path.as_deref(), Some("group/project"));
+        assert_eq!(profile.token_env.as_deref(), Some(DEFAULT_TOKEN_ENV));
+    }
+}
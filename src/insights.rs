@@ -1,28 +1,866 @@
-use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "gitlab")]
+use std::fmt::Write;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CIInsights {
     pub provider: String,
     pub project: String,
     pub collected_at: DateTime<Utc>,
+    /// Creation time of the oldest analyzed pipeline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_pipeline_at: Option<DateTime<Utc>>,
+    /// Creation time of the newest analyzed pipeline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_pipeline_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watermark: Option<CollectionWatermark>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ReportMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Summary>,
     pub total_pipelines: usize,
     pub total_pipeline_types: usize,
     pub pipeline_types: Vec<PipelineType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_trains: Option<MergeTrainMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployments: Option<Vec<EnvironmentDeploymentMetrics>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverts: Option<RevertMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_comparison: Option<BranchComparison>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub teams: Option<Vec<TeamMetrics>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<ComponentMetrics>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingInfo>,
+    /// Jobs GitLab reported no duration for, timed with an estimate on the
+    /// critical path and left out of duration averages; absent when none were
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_job_durations: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub developer_wait: Option<DeveloperWait>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub releases: Option<ReleaseMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_changes: Option<Vec<ImageChange>>,
+    /// Absent when no ref changed the set of jobs it runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub composition_changes: Option<Vec<CompositionChange>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub users: Option<Vec<UserMetrics>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_usage: Option<ApiUsage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci_minutes: Option<CiMinutesUsage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currently_broken: Option<Vec<BrokenJob>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_logs: Option<Vec<JobLogMetrics>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commits: Option<CommitRetries>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lead_time_to_green: Option<LeadTimeToGreen>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedules: Option<Vec<ScheduleDrift>>,
+    /// Sections contributed by custom analyzers, keyed by analyzer name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sections: BTreeMap<String, Value>,
 }
 
+/// Headline numbers aggregated across all pipeline types.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PredecessorJob {
+pub struct Summary {
+    pub success_rate: f64,
+    /// Average duration of successful pipelines, weighted by pipeline count
+    pub avg_duration_seconds: f64,
+    /// Job executions analyzed, including retries
+    pub total_jobs: usize,
+    /// Days between the oldest and newest analyzed pipeline
+    pub window_days: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slowest_job: Option<JobHighlight>,
+    /// Absent when no job had a flaky retry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flakiest_job: Option<JobHighlight>,
+}
+
+/// A job singled out in the summary; `value` is in the unit of the field
+/// it stands for (seconds for `slowest_job`, percent for `flakiest_job`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobHighlight {
+    pub pipeline_type: String,
+    pub name: String,
+    pub value: f64,
+}
+
+impl Summary {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn of(insights: &CIInsights) -> Self {
+        let (weighted_duration, successful) =
+            insights
+                .pipeline_types
+                .iter()
+                .fold((0.0, 0), |(sum, count), pt| {
+                    let successful = pt.metrics.successful_pipelines.count;
+                    (
+                        sum + pt.metrics.avg_duration_seconds * successful as f64,
+                        count + successful,
+                    )
+                });
+
+        let jobs = || {
+            insights.pipeline_types.iter().flat_map(|pt| {
+                pt.metrics
+                    .jobs
+                    .iter()
+                    .map(move |job| (pt.label.as_str(), job))
+            })
+        };
+        let highlight = |(pipeline_type, job): (&str, &JobMetrics), value: f64| JobHighlight {
+            pipeline_type: pipeline_type.to_string(),
+            name: job.name.clone(),
+            value,
+        };
+
+        let slowest_job = jobs()
+            .max_by(|a, b| {
+                a.1.avg_duration_seconds
+                    .total_cmp(&b.1.avg_duration_seconds)
+            })
+            .map(|entry| highlight(entry, entry.1.avg_duration_seconds));
+        let flakiest_job = jobs()
+            .filter(|(_, job)| job.flakiness_rate > 0.0)
+            .max_by(|a, b| a.1.flakiness_rate.total_cmp(&b.1.flakiness_rate))
+            .map(|entry| highlight(entry, entry.1.flakiness_rate));
+
+        let window_days = match (insights.first_pipeline_at, insights.last_pipeline_at) {
+            (Some(first), Some(last)) => (last - first).num_seconds() as f64 / 86_400.0,
+            _ => 0.0,
+        };
+
+        Self {
+            success_rate: insights.success_rate(),
+            avg_duration_seconds: if successful == 0 {
+                0.0
+            } else {
+                weighted_duration / successful as f64
+            },
+            total_jobs: jobs().map(|(_, job)| job.total_executions).sum(),
+            window_days,
+            slowest_job,
+            flakiest_job,
+        }
+    }
+}
+
+/// GitLab API requests spent collecting a report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiUsage {
+    pub requests: usize,
+    pub graphql_requests: usize,
+    pub rest_requests: usize,
+    /// Sum of the complexity scores GitLab assigned to the GraphQL queries
+    pub graphql_complexity: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_requests: Option<usize>,
+}
+
+/// How many pipeline runs commits needed before one passed: a flakiness
+/// signal that also catches reruns of whole pipelines, which job retries miss.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitRetries {
+    pub total_commits: usize,
+    /// Commits with a successful pipeline
+    pub green_commits: usize,
+    /// Failed pipelines before the first successful one, plus that one,
+    /// averaged over green commits
+    pub avg_attempts_to_green: f64,
+    /// Green commits that needed more than one attempt, most attempts first
+    pub retried_commits: Vec<CommitAttempts>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitAttempts {
+    pub sha: String,
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    pub attempts_to_green: usize,
+    /// First successful pipeline of the commit
+    pub green_pipeline: String,
+}
+
+/// How long default-branch commits waited for CI to bless them: from the
+/// commit to the end of its first successful pipeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeadTimeToGreen {
+    pub default_branch: String,
+    /// Commits that went green
+    pub commits: usize,
+    pub avg_seconds: f64,
+    pub p50_seconds: f64,
+    pub p90_seconds: f64,
+    pub p95_seconds: f64,
+}
+
+/// How closely an active pipeline schedule kept to its cron expression over
+/// the analyzed window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleDrift {
+    pub id: u64,
+    pub description: String,
+    pub cron: String,
+    pub cron_timezone: String,
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    /// Times the cron expression fired in the window
+    pub expected_runs: usize,
+    /// Pipelines the schedule created in the window, including manual plays
+    pub actual_runs: usize,
+    /// Fire times no pipeline followed before the next one
+    pub missed_runs: usize,
+    /// From each fire time to the creation of the pipeline that ran it
+    pub avg_drift_seconds: f64,
+    pub max_drift_seconds: f64,
+}
+
+/// A job failing in every recent default-branch pipeline that ran it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenJob {
+    pub name: String,
+    /// Consecutive failing pipelines, up to the latest one
+    pub streak: usize,
+    pub broken_since: DateTime<Utc>,
+    /// Oldest pipeline of the streak, where the job first broke
+    pub first_broken_pipeline: String,
+}
+
+/// Reports of several projects collected in one run.
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Serialize)]
+pub struct CombinedInsights {
+    pub collected_at: DateTime<Utc>,
+    pub leaderboards: Leaderboards,
+    pub projects: Vec<CIInsights>,
+}
+
+/// The slowest and flakiest jobs across all projects of a combined report.
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize)]
+pub struct Leaderboards {
+    /// By average duration, longest first
+    pub slowest_jobs: Vec<LeaderboardEntry>,
+    /// By flakiness rate, flakiest first; jobs without flaky retries are left out
+    pub flakiest_jobs: Vec<LeaderboardEntry>,
+}
+
+/// A job of one project, its metrics weighted by executions across the
+/// project's pipeline types.
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize)]
+pub struct LeaderboardEntry {
+    pub project: String,
+    pub job: String,
+    pub avg_duration_seconds: f64,
+    pub flakiness_rate: f64,
+    pub total_executions: usize,
+}
+
+/// How a report was collected, so it describes itself and can be reproduced.
+/// The window it covers is in `first_pipeline_at` and `last_pipeline_at`, the
+/// requests it took in `api_usage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportMeta {
+    pub parameters: QueryParameters,
+    /// Seconds spent fetching from GitLab, before analysis
+    pub fetch_duration_seconds: f64,
+}
+
+/// Options that selected the analyzed pipelines, as passed on the command line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryParameters {
+    pub limit: usize,
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub ref_: Option<String>,
+    pub statuses: Vec<String>,
+    pub default_branch_only: bool,
+    /// `10%` or a pipeline count, when only a sample was analyzed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline_ids: Option<Vec<u64>>,
+    /// Kind of pipeline kept per commit SHA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedupe_by_sha: Option<String>,
+    pub min_type_percentage: u8,
+    pub min_executions: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_jobs: Option<usize>,
+}
+
+/// IDs of the oldest and newest analyzed pipelines. Pipelines created after
+/// `newest_pipeline_id` were not covered, however long collection took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionWatermark {
+    pub oldest_pipeline_id: u64,
+    pub newest_pipeline_id: u64,
+}
+
+/// Present when only a sample of the listed pipelines was analyzed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingInfo {
+    /// Pipelines listed in the analyzed window
+    pub population: usize,
+    /// Pipelines analyzed in depth
+    pub sampled: usize,
+    /// Half-width (percentage points) of the 95% confidence interval of the success rate
+    pub success_rate_margin_of_error: f64,
+}
+
+/// Time developers spent waiting on merge request pipelines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeveloperWait {
+    pub total_merge_request_pipelines: usize,
+    pub total_hours: f64,
+    pub avg_hours_per_week: f64,
+    pub weekly: Vec<WeeklyWait>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyWait {
+    /// Monday of the week
+    pub week_start: NaiveDate,
+    pub pipelines: usize,
+    pub hours: f64,
+}
+
+/// Health of tag-triggered (release) pipelines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseMetrics {
+    pub total_tags: usize,
+    pub total_pipelines: usize,
+    pub success_rate: f64,
+    pub avg_duration_seconds: f64,
+    /// Average time from a tag's first pipeline being created until a
+    /// pipeline for the tag finished successfully
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_time_to_release_seconds: Option<f64>,
+    /// Tags without a successful pipeline in the analyzed window
+    pub unreleased_tags: Vec<String>,
+}
+
+/// Pipelines triggered by one user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserMetrics {
+    pub username: String,
+    pub bot: bool,
+    pub total_pipelines: usize,
+    /// Share of all analyzed pipelines this user triggered
+    pub pipeline_share: f64,
+    pub failed_pipelines: usize,
+    pub failure_rate: f64,
+}
+
+/// A job switching container image, with how it performed before and after.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageChange {
+    pub job: String,
+    pub from_image: String,
+    pub to_image: String,
+    /// Start of the first execution on the new image
+    pub introduced_at: DateTime<Utc>,
+    pub executions_before: usize,
+    pub executions_after: usize,
+    /// Average duration of successful executions on the previous image
+    pub avg_duration_before_seconds: f64,
+    pub avg_duration_after_seconds: f64,
+    /// Change of the average duration; absent when either side had no successful execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_change_percentage: Option<f64>,
+    pub failure_rate_before: f64,
+    pub failure_rate_after: f64,
+    /// Human-readable description, e.g. "build: duration jumped 40% after image node:20 was introduced"
+    pub summary: String,
+}
+
+impl ImageChange {
+    /// Human-readable summary of the shift, as stored in `summary`.
+    #[cfg(feature = "gitlab")]
+    pub fn describe(&self) -> String {
+        let mut shifts = Vec::new();
+        if let Some(percentage) = self.duration_change_percentage {
+            let direction = if percentage >= 0.0 {
+                "jumped"
+            } else {
+                "dropped"
+            };
+            shifts.push(format!("duration {direction} {:.0}%", percentage.abs()));
+        }
+        let failure_delta = self.failure_rate_after - self.failure_rate_before;
+        if failure_delta.abs() >= 1.0 {
+            let direction = if failure_delta > 0.0 { "rose" } else { "fell" };
+            shifts.push(format!(
+                "failure rate {direction} from {:.0}% to {:.0}%",
+                self.failure_rate_before, self.failure_rate_after
+            ));
+        }
+        if shifts.is_empty() {
+            shifts.push("no notable shift".to_string());
+        }
+        format!(
+            "{}: {} after image {} was introduced",
+            self.job,
+            shifts.join(", "),
+            self.to_image
+        )
+    }
+}
+
+/// A change in the set of jobs a ref's pipelines run, typically a CI config
+/// refactor, with how pipeline durations compared on either side.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositionChange {
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    /// Creation of the first pipeline running the new set of jobs
+    pub changed_at: DateTime<Utc>,
+    pub pipeline: String,
+    pub jobs_before: usize,
+    pub jobs_after: usize,
+    pub added_jobs: Vec<String>,
+    pub removed_jobs: Vec<String>,
+    /// Average duration of successful pipelines running the previous set of jobs
+    pub avg_duration_before_seconds: f64,
+    pub avg_duration_after_seconds: f64,
+    /// Human-readable description, e.g. "main: 2 -> 3 jobs (added lint), duration jumped 40%"
+    pub summary: String,
+}
+
+impl CompositionChange {
+    /// Human-readable summary of the change, as stored in `summary`.
+    #[cfg(feature = "gitlab")]
+    pub fn describe(&self) -> String {
+        let mut jobs = Vec::new();
+        if !self.added_jobs.is_empty() {
+            jobs.push(format!("added {}", self.added_jobs.join(", ")));
+        }
+        if !self.removed_jobs.is_empty() {
+            jobs.push(format!("removed {}", self.removed_jobs.join(", ")));
+        }
+        let mut summary = format!(
+            "{}: {} -> {} jobs ({})",
+            self.ref_,
+            self.jobs_before,
+            self.jobs_after,
+            jobs.join("; ")
+        );
+        if self.avg_duration_before_seconds > 0.0 && self.avg_duration_after_seconds > 0.0 {
+            let percentage = (self.avg_duration_after_seconds - self.avg_duration_before_seconds)
+                / self.avg_duration_before_seconds
+                * 100.0;
+            if percentage.abs() >= 1.0 {
+                let direction = if percentage > 0.0 {
+                    "jumped"
+                } else {
+                    "dropped"
+                };
+                let _ = write!(summary, ", duration {direction} {:.0}%", percentage.abs());
+            }
+        }
+        summary
+    }
+}
+
+/// Jobs of all analyzed pipelines ranked together, without pipeline types
+/// (`--jobs-only`).
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRanking {
+    pub project: String,
+    pub collected_at: DateTime<Utc>,
+    pub total_pipelines: usize,
+    pub jobs: Vec<JobMetrics>,
+}
+
+/// Load and reliability of the runners that executed the analyzed jobs.
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunnerFleetReport {
+    pub project: String,
+    pub collected_at: DateTime<Utc>,
+    pub total_pipelines: usize,
+    pub total_jobs: usize,
+    pub runners: Vec<RunnerMetrics>,
+    /// p95 queue time each runner tag should stay within
+    pub queue_slo_seconds: u64,
+    pub queue_by_tag: Vec<TagQueueMetrics>,
+    pub concurrency_by_tag: Vec<TagConcurrency>,
+    pub hosting: HostingComparison,
+}
+
+/// Jobs run on shared (GitLab-hosted, `instance_type`) runners against jobs
+/// run on the project's or group's own runners.
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostingComparison {
+    pub shared: HostingMetrics,
+    pub self_hosted: HostingMetrics,
+    /// Jobs that ran on both kinds, so their durations compare like for like.
+    /// Largest self-hosted speedup first.
+    pub jobs: Vec<JobHostingComparison>,
+}
+
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostingMetrics {
+    pub total_jobs: usize,
+    pub avg_duration_seconds: f64,
+    pub avg_queued_seconds: f64,
+    pub p95_queued_seconds: f64,
+    pub failure_rate: f64,
+}
+
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobHostingComparison {
+    pub name: String,
+    pub shared: HostingMetrics,
+    pub self_hosted: HostingMetrics,
+    /// Self-hosted minus shared average duration; negative when self-hosted
+    /// runners are faster
+    pub duration_difference_seconds: f64,
+}
+
+/// How many jobs requesting one runner tag ran at the same time, weighted by
+/// how long each level lasted, over the time any of them was running.
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagConcurrency {
+    /// Null for jobs without tags
+    pub tag: Option<String>,
+    pub total_jobs: usize,
+    pub busy_minutes: f64,
+    pub peak_concurrency: usize,
+    pub avg_concurrency: f64,
+    /// Concurrency that covered 95% of the busy time
+    pub p95_concurrency: usize,
+    /// Runners (one job each) to keep idle: the average concurrency, rounded up
+    pub suggested_min_runners: usize,
+    /// Runners to scale up to: enough for the p95 concurrency, letting rarer
+    /// bursts queue
+    pub suggested_max_runners: usize,
+}
+
+/// What the logs of one job's recent attempts tell about where its time goes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobLogMetrics {
+    pub name: String,
+    pub logs_analyzed: usize,
+    /// Cache archives extracted, over all analyzed logs
+    pub cache_hits: usize,
+    /// Cache archives that could not be extracted (not found or broken)
+    pub cache_misses: usize,
+    /// Absent when no analyzed log restored a cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_hit_rate: Option<f64>,
+    /// Time spent in the runner's `restore_cache` section
+    pub avg_cache_restore_seconds: f64,
+    /// Average duration of attempts that extracted every cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_duration_on_hit_seconds: Option<f64>,
+    /// Average duration of attempts that missed a cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_duration_on_miss_seconds: Option<f64>,
+    /// Attempts missing the cache take at least twice as long as those hitting it
+    pub cache_misses_dominate: bool,
+    /// Time spent installing dependencies (`npm ci`, `pip install`, ...), over
+    /// the attempts that ran them in their own log section
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_dependency_install_seconds: Option<f64>,
+    /// Time per log section (`prepare_executor`, `get_sources`, `step_script`,
+    /// `upload_artifacts_on_success`, custom sections, ...), in log order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<SectionTiming>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionTiming {
     pub name: String,
+    /// Analyzed logs that had the section
+    pub executions: usize,
+    pub avg_seconds: f64,
+}
+
+/// Time jobs requesting one runner tag waited for a runner.
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagQueueMetrics {
+    /// Null for jobs without tags (picked up by runners running untagged jobs)
+    pub tag: Option<String>,
+    pub total_jobs: usize,
+    pub avg_queued_seconds: f64,
+    pub p95_queued_seconds: f64,
+    /// `p95_queued_seconds` exceeds the queue SLO: the runners for this tag
+    /// need more capacity
+    pub breaching_slo: bool,
+}
+
+#[cfg(feature = "gitlab")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunnerMetrics {
+    pub id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// `instance_type`, `group_type` or `project_type`; unknown for runners no
+    /// longer available to the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runner_type: Option<String>,
+    /// `online`, `offline`, `stale` or `never_contacted`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+    pub total_jobs: usize,
+    /// Share of all analyzed jobs this runner executed
+    pub job_share: f64,
+    pub busy_minutes: f64,
+    pub avg_queued_seconds: f64,
+    /// Share of the total time jobs spent queued that was spent by jobs this
+    /// runner eventually picked up
+    pub queue_share: f64,
+    pub failed_jobs: usize,
+    pub failure_rate: f64,
+    /// Failure rate minus the failure rate the same jobs have across the whole
+    /// fleet, in percentage points; high values point at a misbehaving runner
+    pub excess_failure_rate: f64,
+}
+
+/// Job metrics aggregated over the jobs mapped to a team in the config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMetrics {
+    pub team: String,
+    pub jobs: Vec<String>,
+    pub total_executions: usize,
+    pub total_duration_seconds: f64,
+    pub avg_duration_seconds: f64,
+    pub failed_executions: usize,
+    pub failure_rate: f64,
+    pub flaky_retries: usize,
+    pub flakiness_rate: f64,
+}
+
+/// Job metrics aggregated over the jobs mapped to a monorepo component.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentMetrics {
+    pub component: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub jobs: Vec<String>,
+    pub total_executions: usize,
+    pub total_duration_seconds: f64,
+    pub avg_duration_seconds: f64,
+    pub failure_rate: f64,
+    pub flakiness_rate: f64,
+    /// Time this component's jobs spend on pipeline critical paths, weighted by pipeline count
+    pub critical_path_seconds: f64,
+    /// Percentage of all critical path time owned by this component
+    pub critical_path_share: f64,
+}
+
+/// Default-branch health contrasted with health across all branches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchComparison {
+    pub default_branch: String,
+    pub default_branch_health: BranchHealth,
+    pub all_branches_health: BranchHealth,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchHealth {
+    pub total_pipelines: usize,
+    pub success_rate: f64,
     pub avg_duration_seconds: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentDeploymentMetrics {
+    pub environment: String,
+    pub total_deployments: usize,
+    pub successful_deployments: usize,
+    pub success_rate: f64,
+    pub deployments_per_day: f64,
+    pub avg_deploy_job_duration_seconds: f64,
+    pub rollbacks: usize,
+    pub rollback_rate: f64,
+}
+
+/// Shared runner minutes the project's namespace consumed this month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiMinutesUsage {
+    pub namespace: String,
+    /// First day of the reported month
+    pub month: NaiveDate,
+    pub used_minutes: u64,
+    /// Monthly plus purchased minutes, when GitLab discloses them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota_minutes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_minutes: Option<u64>,
+    /// Minutes used by the end of the month at the month-to-date burn rate
+    pub projected_minutes: u64,
+    /// Day the quota runs out at that rate, if before the month ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exhausted_on: Option<NaiveDate>,
+}
+
+/// Default-branch commits backing out earlier ones: with deployment
+/// rollbacks, the change failures of the analyzed window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevertMetrics {
+    pub default_branch: String,
+    /// Commits on the default branch since the oldest analyzed pipeline
+    pub total_commits: usize,
+    pub total_reverts: usize,
+    /// Share of commits that revert an earlier one
+    pub revert_rate: f64,
+    /// Reverts whose first pipeline followed a failed one on the branch
+    pub reverts_after_failure: usize,
+    /// Newest first
+    pub reverts: Vec<RevertCommit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevertCommit {
+    pub sha: String,
+    pub title: String,
+    /// From `git revert`'s "This reverts commit ..." trailer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverted_sha: Option<String>,
+    /// The branch's pipeline before the revert's first one failed: the
+    /// reverted change most likely broke the build
+    pub after_failed_pipeline: bool,
+    /// First pipeline of the revert on the branch, if it was analyzed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeTrainMetrics {
+    pub total_pipelines: usize,
+    pub success_rate: f64,
+    pub avg_duration_seconds: f64,
+    pub avg_time_in_train_seconds: f64,
+    pub failure_cascade_rate: f64,
+    pub by_queue_depth: Vec<QueueDepthMetrics>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueDepthMetrics {
+    pub queue_depth: usize,
+    pub pipelines: usize,
+    pub success_rate: f64,
+    pub avg_time_in_train_seconds: f64,
+}
+
+impl CIInsights {
+    /// Loads a JSON report written by a previous run, gzip/zstd compressed or not.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `path` cannot be read or does not hold a report.
+    pub fn from_file(path: &Path) -> crate::error::Result<Self> {
+        let content = crate::output::compress::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Stores an analyzer's section: built-in section names fill their typed
+    /// field, anything else lands in `sections`.
+    ///
+    /// # Errors
+    ///
+    /// Fails when a built-in section does not match its field's type.
+    #[cfg(feature = "gitlab")]
+    pub fn merge_section(&mut self, name: String, section: Value) -> crate::error::Result<()> {
+        match name.as_str() {
+            "merge_trains" => self.merge_trains = serde_json::from_value(section)?,
+            "developer_wait" => self.developer_wait = serde_json::from_value(section)?,
+            "releases" => self.releases = serde_json::from_value(section)?,
+            _ => {
+                self.sections.insert(name, section);
+            }
+        }
+        Ok(())
+    }
+
+    /// Success rate across all reported pipeline types, over the pipelines
+    /// that succeeded or failed.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn success_rate(&self) -> f64 {
+        let (successful, total) =
+            self.pipeline_types
+                .iter()
+                .fold((0, 0), |(successful, total), pt| {
+                    (
+                        successful + pt.metrics.successful_pipelines.count,
+                        total
+                            + pt.metrics.successful_pipelines.count
+                            + pt.metrics.failed_pipelines.count,
+                    )
+                });
+
+        if total == 0 {
+            return 0.0;
+        }
+        successful as f64 / total as f64 * 100.0
+    }
+
+    /// Average job duration by job name, weighted by executions across pipeline types.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn job_durations(&self) -> HashMap<&str, f64> {
+        let mut totals: HashMap<&str, (f64, usize)> = HashMap::new();
+        for pipeline_type in &self.pipeline_types {
+            for job in &pipeline_type.metrics.jobs {
+                let weight = job.total_executions.max(1);
+                let entry = totals.entry(job.name.as_str()).or_default();
+                entry.0 += job.avg_duration_seconds * weight as f64;
+                entry.1 += weight;
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|(name, (sum, count))| (name, sum / count as f64))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredecessorJob {
+    pub name: String,
+    pub avg_duration_seconds: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PipelineCountWithLinks {
     pub count: usize,
     pub links: Vec<String>,
 }
 
+impl PipelineCountWithLinks {
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobCountWithLinks {
     pub count: usize,
@@ -36,10 +874,55 @@ pub struct JobMetrics {
     pub avg_time_to_feedback_seconds: f64,
     pub predecessors: Vec<PredecessorJob>,
     pub flakiness_rate: f64,
+    /// Flakiness rate with each pipeline weighted by recency (exponential decay)
+    #[serde(default)]
+    pub flakiness_score: f64,
     pub flaky_retries: JobCountWithLinks,
     pub failed_executions: JobCountWithLinks,
     pub failure_rate: f64,
+    /// Failed executions caused by infrastructure or flakiness, as a percentage of executions
+    #[serde(default)]
+    pub transient_failure_rate: f64,
+    /// Failed executions caused by real breakage, as a percentage of executions
+    #[serde(default)]
+    pub persistent_failure_rate: f64,
     pub total_executions: usize,
+    /// Compute minutes spent in attempts that were later retried
+    #[serde(default)]
+    pub wasted_retry_minutes: f64,
+    /// Developer wait added by retries: time from the first attempt starting until the final one did
+    #[serde(default)]
+    pub retry_wait_minutes: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_histogram: Option<Vec<HistogramBucket>>,
+    /// Observed time from pipeline start until the job's final attempt started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_start_offset_seconds: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_started_at: Option<DateTime<Utc>>,
+    /// Breakdown of `parallel`/`matrix` variants grouped under this job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<VariantMetrics>>,
+    /// `needs` on jobs absent from the pipeline (optional or excluded by rules)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub absent_needs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantMetrics {
+    pub variant: String,
+    pub avg_duration_seconds: f64,
+    pub flakiness_rate: f64,
+    pub failure_rate: f64,
+    pub total_executions: usize,
+}
+
+/// Number of executions that took at most `le` seconds; `le` is null for the
+/// bucket counting every execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    pub le: Option<f64>,
+    pub count: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,8 +940,321 @@ pub struct TypeMetrics {
     pub total_pipelines: usize,
     pub successful_pipelines: PipelineCountWithLinks,
     pub failed_pipelines: PipelineCountWithLinks,
+    /// Only analyzed when `--statuses` includes them
+    #[serde(default, skip_serializing_if = "PipelineCountWithLinks::is_empty")]
+    pub canceled_pipelines: PipelineCountWithLinks,
+    #[serde(default, skip_serializing_if = "PipelineCountWithLinks::is_empty")]
+    pub skipped_pipelines: PipelineCountWithLinks,
+    /// Successful share of the pipelines that succeeded or failed
     pub success_rate: f64,
     pub avg_duration_seconds: f64,
     pub avg_time_to_feedback_seconds: f64,
+    /// Compute minutes consumed by pipelines that ultimately failed
+    #[serde(default)]
+    pub failed_pipeline_minutes: f64,
+    /// Minutes jobs of failed pipelines ran after the first job had already failed
+    #[serde(default)]
+    pub doomed_minutes: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sla: Option<SlaAttainment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_ref: Option<Vec<RefMetrics>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recommendations: Vec<Recommendation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_transfer: Option<ArtifactTransfer>,
     pub jobs: Vec<JobMetrics>,
 }
+
+/// Job time spent downloading and uploading artifacts, against the time
+/// spent on everything else, over the jobs whose logs were analyzed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactTransfer {
+    pub logs_analyzed: usize,
+    pub avg_download_seconds: f64,
+    pub avg_upload_seconds: f64,
+    pub avg_execution_seconds: f64,
+    /// Share of job time spent moving artifacts
+    pub transfer_percentage: f64,
+    /// Jobs that moved artifacts, most transfer time first
+    pub jobs: Vec<JobArtifactTransfer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobArtifactTransfer {
+    pub name: String,
+    pub avg_transfer_seconds: f64,
+    pub avg_execution_seconds: f64,
+    pub transfer_percentage: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationKind {
+    /// No job of the stage declares `needs`, so all of them wait for every
+    /// job of earlier stages
+    StageBarrier,
+    /// Jobs of the later of two adjacent stages never need a job of the
+    /// earlier one, yet wait for it through stage order
+    StageCollapse,
+    /// The job never failed yet lengthens the critical path
+    AlwaysGreen,
+    /// A `retry` setting for a flaky job
+    Retry,
+}
+
+/// A change to the pipeline configuration worth considering, with the
+/// wall-clock time it would save on average.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recommendation {
+    pub kind: RecommendationKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jobs: Vec<String>,
+    /// Job of an earlier stage the stage's jobs wait for last, for
+    /// `stage_barrier` recommendations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub straggler: Option<String>,
+    /// Executions the job passed, for `always_green` recommendations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executions: Option<usize>,
+    pub estimated_savings_seconds: f64,
+    /// Suggested `retry` value, for `retry` recommendations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<u8>,
+    /// Share of the job's attempts that pass, for `retry` recommendations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempt_success_rate: Option<f64>,
+    /// Share of the job's runs that pass with the suggested `retry`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_rate_with_retry: Option<f64>,
+    /// `--target-success-rate` the `retry` was chosen for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_success_rate: Option<f64>,
+    pub message: String,
+}
+
+impl Recommendation {
+    /// Human-readable explanation of the recommendation, as stored in `message`.
+    #[cfg(feature = "gitlab")]
+    pub fn describe(&self) -> String {
+        let savings = self.estimated_savings_seconds;
+        let stage = |index: usize| self.stages.get(index).map_or("", String::as_str);
+        let job = self.jobs.first().map_or("", String::as_str);
+
+        match self.kind {
+            RecommendationKind::StageBarrier => format!(
+                "No job in stage `{}` declares `needs`, so all of them wait for `{}`, which \
+                 finishes {savings:.0}s after the other jobs of earlier stages on average; jobs \
+                 that don't depend on it would start that much sooner with `needs`",
+                stage(0),
+                self.straggler.as_deref().unwrap_or_default()
+            ),
+            RecommendationKind::StageCollapse => {
+                let (earlier, later) = (stage(0), stage(1));
+                format!(
+                    "No job of stage `{later}` needs a job of `{earlier}`, yet those without \
+                     `needs` wait for it through stage order; merging the two stages (or \
+                     declaring `needs` in `{later}`) would let `{later}` finish \
+                     {savings:.0}s sooner on average"
+                )
+            }
+            RecommendationKind::AlwaysGreen => format!(
+                "`{job}` passed all {} executions but adds {savings:.0}s to the critical path \
+                 on average; running it only in merged results or scheduled pipelines would \
+                 report back that much sooner",
+                self.executions.unwrap_or_default()
+            ),
+            RecommendationKind::Retry => {
+                let rate = self.success_rate_with_retry.unwrap_or_default();
+                let target = self.target_success_rate.unwrap_or_default();
+                let outcome = if rate >= target {
+                    format!("would let it pass {rate:.1}% of its runs")
+                } else {
+                    format!("still only lets it pass {rate:.1}% of its runs; the job needs fixing")
+                };
+                format!(
+                    "`{job}` passes {:.1}% of its attempts; `retry: {}` {outcome} (target \
+                     {target}%)",
+                    self.attempt_success_rate.unwrap_or_default(),
+                    self.retry.unwrap_or_default()
+                )
+            }
+        }
+    }
+}
+
+/// Health of the pipelines of one ref pattern (`main`, `feature branches`, `tags`...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefMetrics {
+    pub ref_pattern: String,
+    #[serde(flatten)]
+    pub health: BranchHealth,
+}
+
+/// How often pipelines finish within the `--sla` target duration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaAttainment {
+    pub target_seconds: u64,
+    pub attainment_rate: f64,
+    pub trend: SlaTrend,
+    pub weekly: Vec<WeeklySlaAttainment>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlaTrend {
+    Improving,
+    Stable,
+    Worsening,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklySlaAttainment {
+    /// Monday of the week
+    pub week_start: NaiveDate,
+    pub pipelines: usize,
+    pub attainment_rate: f64,
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use super::*;
+
+    pub fn job(name: &str, avg_duration_seconds: f64, total_executions: usize) -> JobMetrics {
+        JobMetrics {
+            name: name.to_string(),
+            avg_duration_seconds,
+            avg_time_to_feedback_seconds: avg_duration_seconds,
+            predecessors: vec![],
+            flakiness_rate: 0.0,
+            flakiness_score: 0.0,
+            flaky_retries: JobCountWithLinks {
+                count: 0,
+                links: vec![],
+            },
+            failed_executions: JobCountWithLinks {
+                count: 0,
+                links: vec![],
+            },
+            failure_rate: 0.0,
+            transient_failure_rate: 0.0,
+            persistent_failure_rate: 0.0,
+            total_executions,
+            wasted_retry_minutes: 0.0,
+            retry_wait_minutes: 0.0,
+            duration_histogram: None,
+            avg_start_offset_seconds: None,
+            last_started_at: None,
+            variants: None,
+            absent_needs: vec![],
+        }
+    }
+
+    pub fn pipeline_type(
+        label: &str,
+        successful: usize,
+        failed: usize,
+        jobs: Vec<JobMetrics>,
+    ) -> PipelineType {
+        PipelineType {
+            label: label.to_string(),
+            stages: vec![],
+            ref_patterns: vec![],
+            sources: vec![],
+            metrics: TypeMetrics {
+                percentage: 100.0,
+                total_pipelines: successful + failed,
+                successful_pipelines: PipelineCountWithLinks {
+                    count: successful,
+                    links: vec![],
+                },
+                failed_pipelines: PipelineCountWithLinks {
+                    count: failed,
+                    links: vec![],
+                },
+                canceled_pipelines: PipelineCountWithLinks::default(),
+                skipped_pipelines: PipelineCountWithLinks::default(),
+                success_rate: 0.0,
+                avg_duration_seconds: 0.0,
+                avg_time_to_feedback_seconds: 0.0,
+                failed_pipeline_minutes: 0.0,
+                doomed_minutes: 0.0,
+                sla: None,
+                by_ref: None,
+                artifact_transfer: None,
+                recommendations: vec![],
+                jobs,
+            },
+        }
+    }
+
+    pub fn insights(pipeline_types: Vec<PipelineType>) -> CIInsights {
+        CIInsights {
+            provider: "GitLab".to_string(),
+            project: "group/project".to_string(),
+            collected_at: Utc::now(),
+            first_pipeline_at: None,
+            last_pipeline_at: None,
+            watermark: None,
+            meta: None,
+            summary: None,
+            total_pipelines: pipeline_types
+                .iter()
+                .map(|pt| pt.metrics.total_pipelines)
+                .sum(),
+            total_pipeline_types: pipeline_types.len(),
+            pipeline_types,
+            merge_trains: None,
+            deployments: None,
+            reverts: None,
+            branch_comparison: None,
+            teams: None,
+            components: None,
+            sampling: None,
+            estimated_job_durations: None,
+            developer_wait: None,
+            releases: None,
+            image_changes: None,
+            composition_changes: None,
+            users: None,
+            api_usage: None,
+            ci_minutes: None,
+            currently_broken: None,
+            job_logs: None,
+            commits: None,
+            lead_time_to_green: None,
+            schedules: None,
+            sections: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::{insights, job, pipeline_type};
+    use super::*;
+
+    #[test]
+    fn test_summary_aggregates_across_pipeline_types() {
+        let mut flaky = job("e2e", 300.0, 4);
+        flaky.flakiness_rate = 25.0;
+        let mut default = pipeline_type("Default", 3, 1, vec![job("lint", 30.0, 4), flaky]);
+        default.metrics.avg_duration_seconds = 100.0;
+        let mut release = pipeline_type("Release", 1, 0, vec![job("deploy", 60.0, 1)]);
+        release.metrics.avg_duration_seconds = 200.0;
+
+        let summary = Summary::of(&insights(vec![default, release]));
+
+        assert!((summary.success_rate - 80.0).abs() < 1e-9);
+        assert!((summary.avg_duration_seconds - 125.0).abs() < 1e-9);
+        assert_eq!(summary.total_jobs, 9);
+        assert_eq!(summary.slowest_job.unwrap().name, "e2e");
+        let flakiest = summary.flakiest_job.unwrap();
+        assert_eq!(
+            (flakiest.pipeline_type.as_str(), flakiest.name.as_str()),
+            ("Default", "e2e")
+        );
+    }
+}
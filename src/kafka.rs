@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use log::info;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+
+use crate::error::{CILensError, Result};
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Publishes `(key, payload)` messages to `topic`, returning once every message
+/// was acknowledged by the brokers.
+pub async fn publish(
+    brokers: &str,
+    topic: &str,
+    messages: impl IntoIterator<Item = (String, Vec<u8>)>,
+) -> Result<usize> {
+    let producer: FutureProducer = ClientConfig::new()
+        .set("bootstrap.servers", brokers)
+        .set(
+            "message.timeout.ms",
+            DELIVERY_TIMEOUT.as_millis().to_string(),
+        )
+        .create()
+        .map_err(|e| CILensError::Config(format!("Invalid Kafka configuration: {e}")))?;
+
+    let deliveries: Vec<_> = messages
+        .into_iter()
+        .map(|(key, payload)| {
+            let producer = producer.clone();
+            async move {
+                producer
+                    .send(
+                        FutureRecord::to(topic).key(&key).payload(&payload),
+                        DELIVERY_TIMEOUT,
+                    )
+                    .await
+                    .map_err(|(e, _)| {
+                        CILensError::Export(format!("Kafka delivery to {topic} failed: {e}"))
+                    })
+            }
+        })
+        .collect();
+
+    let published = futures::future::try_join_all(deliveries).await?.len();
+    info!("Published {published} messages to Kafka topic {topic}");
+
+    Ok(published)
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::insights::{CIInsights, LeaderboardEntry, Leaderboards};
+use crate::quarantine::flakiness_rates;
+
+/// Jobs listed per leaderboard.
+pub const LEADERBOARD_SIZE: usize = 20;
+
+fn entries(insights: &CIInsights) -> Vec<LeaderboardEntry> {
+    let flakiness = flakiness_rates(insights);
+    let mut executions: HashMap<&str, usize> = HashMap::new();
+    for job in insights
+        .pipeline_types
+        .iter()
+        .flat_map(|pt| &pt.metrics.jobs)
+    {
+        *executions.entry(job.name.as_str()).or_default() += job.total_executions;
+    }
+
+    insights
+        .job_durations()
+        .into_iter()
+        .map(|(name, avg_duration_seconds)| LeaderboardEntry {
+            project: insights.project.clone(),
+            job: name.to_string(),
+            avg_duration_seconds,
+            flakiness_rate: flakiness.get(name).copied().unwrap_or(0.0),
+            total_executions: executions.get(name).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Ranks the jobs of every project in `reports` by duration and by flakiness,
+/// keeping the `size` worst of each.
+pub fn leaderboards(reports: &[CIInsights], size: usize) -> Leaderboards {
+    let entries: Vec<LeaderboardEntry> = reports.iter().flat_map(entries).collect();
+
+    let mut slowest_jobs = entries.clone();
+    slowest_jobs.sort_by(|a, b| {
+        b.avg_duration_seconds
+            .total_cmp(&a.avg_duration_seconds)
+            .then_with(|| (&a.project, &a.job).cmp(&(&b.project, &b.job)))
+    });
+    slowest_jobs.truncate(size);
+
+    let mut flakiest_jobs: Vec<LeaderboardEntry> = entries
+        .into_iter()
+        .filter(|entry| entry.flakiness_rate > 0.0)
+        .collect();
+    flakiest_jobs.sort_by(|a, b| {
+        b.flakiness_rate
+            .total_cmp(&a.flakiness_rate)
+            .then_with(|| (&a.project, &a.job).cmp(&(&b.project, &b.job)))
+    });
+    flakiest_jobs.truncate(size);
+
+    Leaderboards {
+        slowest_jobs,
+        flakiest_jobs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    #[test]
+    fn test_leaderboards_rank_jobs_across_projects() {
+        let mut api = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![job("build", 600.0, 10), job("lint", 30.0, 10)],
+        )]);
+        api.project = "org/api".to_string();
+        api.pipeline_types[0].metrics.jobs[1].flaky_retries.count = 2;
+        let mut web = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![job("build", 900.0, 4)],
+        )]);
+        web.project = "org/web".to_string();
+        web.pipeline_types[0].metrics.jobs[0].flaky_retries.count = 1;
+
+        let boards = leaderboards(&[api, web], 2);
+
+        let slowest: Vec<(&str, &str)> = boards
+            .slowest_jobs
+            .iter()
+            .map(|e| (e.project.as_str(), e.job.as_str()))
+            .collect();
+        assert_eq!(slowest, vec![("org/web", "build"), ("org/api", "build")]);
+        let flakiest: Vec<(&str, f64)> = boards
+            .flakiest_jobs
+            .iter()
+            .map(|e| (e.project.as_str(), e.flakiness_rate))
+            .collect();
+        assert_eq!(flakiest, vec![("org/web", 25.0), ("org/api", 20.0)]);
+    }
+}
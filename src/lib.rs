@@ -0,0 +1,74 @@
+//! Collects CI/CD insights from GitLab pipelines.
+//!
+//! The `cilens` binary is the main interface. The library exposes the GitLab
+//! provider it is built on, so organizations can embed it, register their
+//! own [`Analyzer`]s without forking, run API requests through their own
+//! [`Middleware`], or process pipelines as their pages arrive with
+//! [`GitLabProvider::stream_pipelines`].
+
+#[cfg(feature = "gitlab")]
+mod attribution;
+#[cfg(feature = "gitlab")]
+mod auth;
+#[cfg(feature = "gitlab")]
+mod benchmark;
+mod cli;
+#[cfg(feature = "gitlab")]
+mod cloudevents;
+mod compare;
+mod config;
+mod digest;
+mod error;
+mod export;
+mod git_remote;
+mod init;
+mod insights;
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "gitlab")]
+mod leaderboard;
+mod lint_ci;
+#[cfg(feature = "gitlab")]
+mod loki;
+mod notify;
+mod output;
+mod providers;
+mod quarantine;
+#[cfg(feature = "gitlab")]
+mod redact;
+
+use std::process::ExitCode;
+
+use clap::{CommandFactory, FromArgMatches};
+use cli::Cli;
+use log::info;
+
+#[cfg(feature = "gitlab")]
+pub use auth::Token;
+pub use error::{CILensError, Result};
+pub use insights::CIInsights;
+#[cfg(feature = "gitlab")]
+pub use providers::{
+    AnalysisOptions, Analyzer, Api, CollectOptions, DedupePreference, GitLabJob, GitLabPipeline,
+    GitLabProvider, HttpOptions, JobSort, Middleware, Next, PipelineStatus, Sample,
+};
+
+/// Runs the `cilens` command line on the process arguments.
+pub async fn run() -> ExitCode {
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    cli.init_logging();
+    info!("Starting CILens - CI/CD Insights Tool");
+
+    let prepared = cli.apply_profile(&matches);
+    #[cfg(feature = "gitlab")]
+    let prepared = prepared.and_then(|()| cli.detect_project(&matches));
+    if let Err(err) = prepared {
+        return cli.report_error(&err);
+    }
+
+    match cli.execute().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => cli.report_error(&err),
+    }
+}
@@ -0,0 +1,545 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::error::{CILensError, Result};
+use crate::insights::CIInsights;
+
+const DEFAULT_STAGES: [&str; 5] = [".pre", "build", "test", "deploy", ".post"];
+
+/// Top-level keys of `.gitlab-ci.yml` that are not job definitions.
+const RESERVED_KEYWORDS: [&str; 12] = [
+    "default",
+    "include",
+    "stages",
+    "variables",
+    "workflow",
+    "image",
+    "services",
+    "cache",
+    "before_script",
+    "after_script",
+    "types",
+    "spec",
+];
+
+const MAX_SUGGESTIONS: usize = 10;
+
+#[derive(Debug, Clone)]
+struct Need {
+    job: String,
+    optional: bool,
+}
+
+#[derive(Debug, Clone)]
+struct CiJob {
+    name: String,
+    stage: String,
+    needs: Option<Vec<Need>>,
+    has_rules: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LintedJob {
+    pub name: String,
+    pub stage: String,
+    pub declares_needs: bool,
+    pub dependencies: Vec<String>,
+    pub duration_seconds: Option<f64>,
+    pub earliest_start_seconds: f64,
+    pub earliest_finish_seconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NeedsSuggestion {
+    pub job: String,
+    pub message: String,
+    pub potential_savings_seconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CiLintReport {
+    pub stages: Vec<String>,
+    pub jobs: Vec<LintedJob>,
+    pub critical_path: Vec<String>,
+    pub critical_path_seconds: f64,
+    pub suggestions: Vec<NeedsSuggestion>,
+    pub problems: Vec<String>,
+}
+
+fn as_str_list(value: &Value) -> Vec<String> {
+    value
+        .as_sequence()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_needs(value: &Value) -> Vec<Need> {
+    value
+        .as_sequence()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| match item {
+                    Value::String(job) => Some(Need {
+                        job: job.clone(),
+                        optional: false,
+                    }),
+                    Value::Mapping(need) => {
+                        // Cross-project/pipeline needs don't constrain this pipeline's DAG
+                        if need.contains_key("project") || need.contains_key("pipeline") {
+                            return None;
+                        }
+                        Some(Need {
+                            job: need.get("job")?.as_str()?.to_string(),
+                            optional: need
+                                .get("optional")
+                                .and_then(Value::as_bool)
+                                .unwrap_or(false),
+                        })
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Looks up `key` on a job, following `extends` templates (depth-first, last template wins).
+fn resolve_key<'a>(
+    config: &'a Mapping,
+    job: &'a Mapping,
+    key: &str,
+    depth: usize,
+) -> Option<&'a Value> {
+    if let Some(value) = job.get(key) {
+        return Some(value);
+    }
+    if depth > 10 {
+        return None;
+    }
+
+    let parents = match job.get("extends")? {
+        Value::String(parent) => vec![parent.clone()],
+        value => as_str_list(value),
+    };
+
+    parents.iter().rev().find_map(|parent| {
+        let parent = config.get(parent.as_str())?.as_mapping()?;
+        resolve_key(config, parent, key, depth + 1)
+    })
+}
+
+fn parse_config(content: &str) -> Result<(Vec<String>, Vec<CiJob>)> {
+    let config: Mapping = serde_yaml::from_str(content)
+        .map_err(|e| CILensError::Config(format!("Invalid CI config: {e}")))?;
+
+    let stages = config
+        .get("stages")
+        .map(as_str_list)
+        .filter(|stages| !stages.is_empty())
+        .unwrap_or_else(|| DEFAULT_STAGES.iter().map(ToString::to_string).collect());
+
+    let jobs = config
+        .iter()
+        .filter_map(|(key, value)| {
+            let name = key.as_str()?;
+            let definition = value.as_mapping()?;
+            if name.starts_with('.') || RESERVED_KEYWORDS.contains(&name) {
+                return None;
+            }
+
+            Some(CiJob {
+                name: name.to_string(),
+                stage: resolve_key(&config, definition, "stage", 0)
+                    .and_then(Value::as_str)
+                    .unwrap_or("test")
+                    .to_string(),
+                needs: resolve_key(&config, definition, "needs", 0).map(parse_needs),
+                has_rules: resolve_key(&config, definition, "rules", 0).is_some(),
+            })
+        })
+        .collect();
+
+    Ok((stages, jobs))
+}
+
+/// `image` may be a plain name or a mapping with a `name` key.
+#[cfg(feature = "gitlab")]
+fn image_name(value: &Value) -> Option<String> {
+    match value {
+        Value::String(name) => Some(name.clone()),
+        Value::Mapping(image) => image.get("name")?.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Resolves the container image of every job defined in `content`: its own
+/// (or inherited through `extends`) `image`, else `default:image`, else the
+/// deprecated top-level `image`. Jobs from `include`d files are not covered.
+#[cfg(feature = "gitlab")]
+pub fn job_images(content: &str) -> Result<HashMap<String, String>> {
+    let config: Mapping = serde_yaml::from_str(content)
+        .map_err(|e| CILensError::Config(format!("Invalid CI config: {e}")))?;
+
+    let fallback = config
+        .get("default")
+        .and_then(Value::as_mapping)
+        .and_then(|default| default.get("image"))
+        .or_else(|| config.get("image"))
+        .and_then(image_name);
+
+    Ok(config
+        .iter()
+        .filter_map(|(key, value)| {
+            let name = key.as_str()?;
+            let definition = value.as_mapping()?;
+            if name.starts_with('.') || RESERVED_KEYWORDS.contains(&name) {
+                return None;
+            }
+
+            let image = resolve_key(&config, definition, "image", 0)
+                .and_then(image_name)
+                .or_else(|| fallback.clone())?;
+            Some((name.to_string(), image))
+        })
+        .collect())
+}
+
+fn dependencies(job: &CiJob, jobs: &[CiJob], stage_index: &HashMap<&str, usize>) -> Vec<String> {
+    if let Some(needs) = &job.needs {
+        return needs.iter().map(|need| need.job.clone()).collect();
+    }
+
+    let current = stage_index.get(job.stage.as_str()).copied().unwrap_or(0);
+    jobs.iter()
+        .filter(|other| stage_index.get(other.stage.as_str()).copied().unwrap_or(0) < current)
+        .map(|other| other.name.clone())
+        .collect()
+}
+
+struct Schedule {
+    start: HashMap<String, f64>,
+    finish: HashMap<String, f64>,
+    slowest_dependency: HashMap<String, String>,
+}
+
+fn schedule(
+    order: &[String],
+    dependencies: &BTreeMap<String, Vec<String>>,
+    durations: &HashMap<&str, f64>,
+) -> Schedule {
+    let mut schedule = Schedule {
+        start: HashMap::new(),
+        finish: HashMap::new(),
+        slowest_dependency: HashMap::new(),
+    };
+
+    for name in order {
+        let slowest = dependencies[name]
+            .iter()
+            .filter_map(|dep| schedule.finish.get(dep).map(|&finish| (dep, finish)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let start = slowest.map_or(0.0, |(_, finish)| finish);
+        if let Some((dep, _)) = slowest {
+            schedule
+                .slowest_dependency
+                .insert(name.clone(), dep.clone());
+        }
+
+        let duration = durations.get(name.as_str()).copied().unwrap_or(0.0);
+        schedule.start.insert(name.clone(), start);
+        schedule.finish.insert(name.clone(), start + duration);
+    }
+
+    schedule
+}
+
+/// Orders jobs so that each one comes after its dependencies (Kahn's algorithm).
+/// Jobs that are part of a cycle are left out and reported as problems.
+fn topological_order(dependencies: &BTreeMap<String, Vec<String>>) -> Vec<String> {
+    let mut remaining: BTreeMap<&str, usize> = dependencies
+        .iter()
+        .map(|(name, deps)| {
+            let known = deps
+                .iter()
+                .filter(|d| dependencies.contains_key(*d))
+                .count();
+            (name.as_str(), known)
+        })
+        .collect();
+
+    let mut order = Vec::new();
+    while let Some(next) = remaining
+        .iter()
+        .find(|(_, &count)| count == 0)
+        .map(|(name, _)| (*name).to_string())
+    {
+        remaining.remove(next.as_str());
+        for (name, count) in &mut remaining {
+            let deps = &dependencies[*name];
+            *count -= deps.iter().filter(|d| **d == next).count();
+        }
+        order.push(next);
+    }
+
+    order
+}
+
+/// Parses a `.gitlab-ci.yml`, predicts its DAG and critical path, and suggests
+/// `needs` declarations using observed job durations from `insights`.
+pub fn lint(path: &Path, insights: Option<&CIInsights>) -> Result<CiLintReport> {
+    let content = std::fs::read_to_string(path)?;
+    analyze(&content, insights)
+}
+
+fn find_problems(jobs: &[CiJob], stage_index: &HashMap<&str, usize>) -> Vec<String> {
+    let known_jobs: HashSet<&str> = jobs.iter().map(|job| job.name.as_str()).collect();
+
+    let mut problems = Vec::new();
+    for job in jobs {
+        if !stage_index.contains_key(job.stage.as_str()) {
+            problems.push(format!(
+                "Job '{}' uses undefined stage '{}'",
+                job.name, job.stage
+            ));
+        }
+        for need in job.needs.iter().flatten() {
+            if !known_jobs.contains(need.job.as_str()) && !need.optional {
+                let hint = if job.has_rules {
+                    " (excluded by rules?)"
+                } else {
+                    ""
+                };
+                problems.push(format!(
+                    "Job '{}' needs unknown job '{}'{hint}; mark it `optional: true` if it is conditional",
+                    job.name, need.job
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+fn critical_path(timing: &Schedule) -> (Vec<String>, f64) {
+    let Some((last, &finish)) = timing.finish.iter().max_by(|a, b| a.1.total_cmp(b.1)) else {
+        return (vec![], 0.0);
+    };
+
+    let mut path: Vec<String> = std::iter::successors(Some(last.clone()), |current| {
+        timing.slowest_dependency.get(current).cloned()
+    })
+    .collect();
+    path.reverse();
+
+    (path, finish)
+}
+
+fn suggest_needs(jobs: &[CiJob], timing: &Schedule) -> Vec<NeedsSuggestion> {
+    let mut suggestions: Vec<NeedsSuggestion> = jobs
+        .iter()
+        .filter(|job| job.needs.is_none())
+        .filter_map(|job| {
+            let blocked = *timing.start.get(&job.name)?;
+            (blocked > 0.0).then(|| NeedsSuggestion {
+                job: job.name.clone(),
+                message: format!(
+                    "'{}' waits {blocked:.0}s for every job in earlier stages; declaring `needs` with only the jobs it uses could start it up to {blocked:.0}s earlier",
+                    job.name
+                ),
+                potential_savings_seconds: blocked,
+            })
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.potential_savings_seconds
+            .total_cmp(&a.potential_savings_seconds)
+    });
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+fn analyze(content: &str, insights: Option<&CIInsights>) -> Result<CiLintReport> {
+    let (stages, jobs) = parse_config(content)?;
+
+    let stage_index: HashMap<&str, usize> = stages
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.as_str(), i))
+        .collect();
+
+    let mut problems = find_problems(&jobs, &stage_index);
+
+    let known_jobs: HashSet<&str> = jobs.iter().map(|job| job.name.as_str()).collect();
+    let dependencies: BTreeMap<String, Vec<String>> = jobs
+        .iter()
+        .map(|job| {
+            let deps = dependencies(job, &jobs, &stage_index)
+                .into_iter()
+                .filter(|dep| known_jobs.contains(dep.as_str()))
+                .collect();
+            (job.name.clone(), deps)
+        })
+        .collect();
+
+    let order = topological_order(&dependencies);
+    if order.len() < dependencies.len() {
+        problems.push("The needs graph contains a cycle; some jobs were left out".to_string());
+    }
+
+    let durations = insights.map(CIInsights::job_durations).unwrap_or_default();
+    let timing = schedule(&order, &dependencies, &durations);
+    let (critical_path, critical_path_seconds) = critical_path(&timing);
+
+    let linted_jobs = order
+        .iter()
+        .filter_map(|name| {
+            let job = jobs.iter().find(|job| &job.name == name)?;
+            Some(LintedJob {
+                name: name.clone(),
+                stage: job.stage.clone(),
+                declares_needs: job.needs.is_some(),
+                dependencies: dependencies[name].clone(),
+                duration_seconds: durations.get(name.as_str()).copied(),
+                earliest_start_seconds: timing.start[name],
+                earliest_finish_seconds: timing.finish[name],
+            })
+        })
+        .collect();
+
+    Ok(CiLintReport {
+        stages,
+        jobs: linted_jobs,
+        critical_path,
+        critical_path_seconds,
+        suggestions: suggest_needs(&jobs, &timing),
+        problems,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    const CONFIG: &str = r"
+stages: [build, test, deploy]
+
+.test-template:
+  stage: test
+
+compile:
+  stage: build
+  script: make
+
+docs:
+  stage: build
+  script: make docs
+
+unit:
+  extends: .test-template
+  needs: [compile]
+  script: make test
+
+lint:
+  extends: .test-template
+  script: make lint
+
+deploy:
+  stage: deploy
+  needs:
+    - job: unit
+    - job: review-app
+      optional: true
+  script: make deploy
+";
+
+    fn report() -> CiLintReport {
+        let observed = insights(vec![pipeline_type(
+            "Default",
+            1,
+            0,
+            vec![
+                job("compile", 100.0, 1),
+                job("docs", 300.0, 1),
+                job("unit", 50.0, 1),
+                job("lint", 10.0, 1),
+                job("deploy", 20.0, 1),
+            ],
+        )]);
+        analyze(CONFIG, Some(&observed)).unwrap()
+    }
+
+    #[test]
+    fn test_analyze_resolves_extends_and_default_stage_barrier() {
+        let report = report();
+        let lint = report.jobs.iter().find(|j| j.name == "lint").unwrap();
+
+        assert_eq!(lint.stage, "test");
+        assert!(!lint.declares_needs);
+        assert_eq!(lint.dependencies, vec!["compile", "docs"]);
+        assert!((lint.earliest_start_seconds - 300.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_analyze_follows_needs_for_critical_path() {
+        let report = report();
+
+        assert_eq!(report.critical_path, vec!["docs", "lint"]);
+        assert!((report.critical_path_seconds - 310.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_analyze_suggests_needs_for_barrier_blocked_jobs() {
+        let report = report();
+
+        assert_eq!(report.suggestions.len(), 1);
+        assert_eq!(report.suggestions[0].job, "lint");
+    }
+
+    #[test]
+    fn test_analyze_ignores_missing_optional_needs() {
+        assert!(report().problems.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_reports_missing_required_needs() {
+        let config = "a:\n  script: x\n  needs: [ghost]\n";
+        let report = analyze(config, None).unwrap();
+
+        assert_eq!(report.problems.len(), 1);
+        assert!(report.problems[0].contains("ghost"));
+    }
+
+    #[cfg(feature = "gitlab")]
+    #[test]
+    fn test_job_images_follow_extends_and_defaults() {
+        let config = r"
+default:
+  image: alpine:3.19
+.node:
+  image:
+    name: node:20
+build:
+  extends: .node
+  script: npm ci
+lint:
+  script: make lint
+";
+        let images = job_images(config).unwrap();
+
+        assert_eq!(images["build"], "node:20");
+        assert_eq!(images["lint"], "alpine:3.19");
+        assert_eq!(images.len(), 2);
+    }
+}
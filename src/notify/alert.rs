@@ -0,0 +1,206 @@
+use std::fmt::Write;
+
+use reqwest::RequestBuilder;
+use serde_json::{json, Value};
+
+use super::{Channel, HealthSummary};
+use crate::output::human::Style;
+
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// Opsgenie limits alert messages to 130 characters.
+const OPSGENIE_MESSAGE_LIMIT: usize = 130;
+
+/// Alerts are deduplicated per project, so repeated breaches update one open incident.
+fn dedup_key(summary: &HealthSummary) -> String {
+    format!("cilens/{}", summary.project)
+}
+
+fn title(summary: &HealthSummary, condition: &str) -> String {
+    format!("CI health of {}: {condition}", summary.project)
+}
+
+/// Failed pipelines and flaky jobs, as `(href, text)` pairs.
+fn links(summary: &HealthSummary) -> Vec<(String, String)> {
+    let pipelines = summary
+        .failed_pipelines
+        .iter()
+        .map(|link| (link.clone(), "Failed pipeline".to_string()));
+    let jobs = summary.flakiest_jobs.iter().filter_map(|job| {
+        job.link
+            .as_ref()
+            .map(|link| (link.clone(), format!("Flaky job {}", job.name)))
+    });
+
+    pipelines.chain(jobs).collect()
+}
+
+fn details(summary: &HealthSummary, style: Style) -> Value {
+    json!({
+        "total_pipelines": summary.total_pipelines,
+        "success_rate": style.percentage(summary.success_rate),
+        "baseline_success_rate": summary.baseline_success_rate.map(|rate| style.percentage(rate)),
+        "regressions": summary
+            .regressions
+            .iter()
+            .map(|change| format!(
+                "{}: {} → {}",
+                change.name,
+                style.duration(change.baseline_seconds),
+                style.duration(change.current_seconds)
+            ))
+            .collect::<Vec<_>>(),
+        "flakiest_jobs": summary
+            .flakiest_jobs
+            .iter()
+            .map(|job| format!("{}: {} flaky", job.name, style.percentage(job.flakiness_rate)))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// `PagerDuty` Events API v2, triggering an incident on the service of `routing_key`.
+pub struct PagerDuty {
+    pub routing_key: String,
+    /// The violated health condition
+    pub condition: String,
+}
+
+impl Channel for PagerDuty {
+    fn name(&self) -> &'static str {
+        "PagerDuty"
+    }
+
+    fn request(
+        &self,
+        client: &reqwest::Client,
+        summary: &HealthSummary,
+        style: Style,
+    ) -> RequestBuilder {
+        client
+            .post(PAGERDUTY_EVENTS_URL)
+            .json(&self.payload(summary, style))
+    }
+}
+
+impl PagerDuty {
+    fn payload(&self, summary: &HealthSummary, style: Style) -> Value {
+        let links: Vec<Value> = links(summary)
+            .into_iter()
+            .map(|(href, text)| json!({ "href": href, "text": text }))
+            .collect();
+
+        json!({
+            "routing_key": self.routing_key,
+            "event_action": "trigger",
+            "dedup_key": dedup_key(summary),
+            "payload": {
+                "summary": title(summary, &self.condition),
+                "source": summary.project,
+                "severity": "warning",
+                "component": "ci",
+                "custom_details": details(summary, style),
+            },
+            "links": links,
+        })
+    }
+}
+
+/// Opsgenie Alert API; `api_url` selects the US or EU instance.
+pub struct Opsgenie {
+    pub api_url: String,
+    pub api_key: String,
+    /// The violated health condition
+    pub condition: String,
+}
+
+impl Channel for Opsgenie {
+    fn name(&self) -> &'static str {
+        "Opsgenie"
+    }
+
+    fn request(
+        &self,
+        client: &reqwest::Client,
+        summary: &HealthSummary,
+        style: Style,
+    ) -> RequestBuilder {
+        client
+            .post(format!("{}/v2/alerts", self.api_url.trim_end_matches('/')))
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("GenieKey {}", self.api_key),
+            )
+            .json(&self.payload(summary, style))
+    }
+}
+
+impl Opsgenie {
+    fn payload(&self, summary: &HealthSummary, style: Style) -> Value {
+        let mut description = format!("{}\n", self.condition);
+        for (href, text) in links(summary) {
+            let _ = write!(description, "\n{text}: {href}");
+        }
+
+        json!({
+            "message": title(summary, &self.condition)
+                .chars()
+                .take(OPSGENIE_MESSAGE_LIMIT)
+                .collect::<String>(),
+            "alias": dedup_key(summary),
+            "description": description,
+            "source": "cilens",
+            "priority": "P3",
+            "details": details(summary, style),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::fixtures::summary;
+
+    #[test]
+    fn test_pagerduty_payload_links_offending_pipelines_and_jobs() {
+        let summary = summary();
+        let alert = PagerDuty {
+            routing_key: "key".to_string(),
+            condition: summary.breach(Some(90.0)),
+        };
+
+        let payload = alert.payload(&summary, Style::default());
+
+        assert_eq!(payload["dedup_key"], "cilens/group/project");
+        assert_eq!(
+            payload["payload"]["summary"],
+            "CI health of group/project: Success rate 85.0% is below the 90.0% threshold"
+        );
+        assert_eq!(
+            payload["links"][0]["href"],
+            "https://gitlab.com/group/project/-/pipelines/7"
+        );
+        assert_eq!(payload["links"][1]["text"], "Flaky job e2e");
+    }
+
+    #[test]
+    fn test_opsgenie_message_is_truncated() {
+        let mut summary = summary();
+        summary.project = "a".repeat(200);
+        let alert = Opsgenie {
+            api_url: "https://api.opsgenie.com".to_string(),
+            api_key: "key".to_string(),
+            condition: summary.breach(None),
+        };
+
+        let payload = alert.payload(&summary, Style::default());
+
+        assert_eq!(
+            payload["message"].as_str().unwrap().chars().count(),
+            OPSGENIE_MESSAGE_LIMIT
+        );
+        assert!(payload["description"]
+            .as_str()
+            .unwrap()
+            .contains("Failed pipeline: https://gitlab.com/group/project/-/pipelines/7"));
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{CILensError, Result};
+use crate::insights::CIInsights;
+use crate::quarantine::flakiness_rates;
+
+/// Label put on every issue opened by cilens, so they can be found in Jira.
+const ISSUE_LABEL: &str = "cilens-flaky";
+
+/// Consecutive runs each job stayed above the flakiness threshold, and the
+/// issue opened for it. Persisted between runs with `--state`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FlakyJobState {
+    pub jobs: BTreeMap<String, TrackedJob>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TrackedJob {
+    pub consecutive_runs: u32,
+    pub issue: Option<String>,
+}
+
+impl FlakyJobState {
+    /// Reads the state of a previous run; a missing file is an empty state.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| CILensError::Config(format!("Invalid Jira state {}: {e}", path.display())))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Extends the streak of jobs above `threshold` and resets the ones at or
+    /// below it. Returns the jobs whose streak reached `consecutive_runs`.
+    pub fn update(
+        &mut self,
+        insights: &CIInsights,
+        threshold: f64,
+        consecutive_runs: u32,
+    ) -> Vec<String> {
+        let rates = flakiness_rates(insights);
+
+        // Jobs that didn't run in this window keep their streak: there's no
+        // evidence either way
+        self.jobs.retain(|name, _| {
+            rates
+                .get(name.as_str())
+                .is_none_or(|rate| *rate > threshold)
+        });
+
+        let mut chronic = Vec::new();
+        for (name, _) in rates.into_iter().filter(|(_, rate)| *rate > threshold) {
+            let job = self.jobs.entry(name.to_string()).or_default();
+            job.consecutive_runs += 1;
+            if job.consecutive_runs >= consecutive_runs {
+                chronic.push(name.to_string());
+            }
+        }
+        chronic
+    }
+}
+
+/// Flakiness, executions and the links of flaky retries and failures of `job`
+/// across pipeline types, as plain text for an issue description or comment.
+#[allow(clippy::cast_precision_loss)]
+fn describe(insights: &CIInsights, job: &str, consecutive_runs: u32) -> String {
+    let metrics: Vec<_> = insights
+        .pipeline_types
+        .iter()
+        .flat_map(|pt| &pt.metrics.jobs)
+        .filter(|metrics| metrics.name == job)
+        .collect();
+
+    let flaky: usize = metrics.iter().map(|m| m.flaky_retries.count).sum();
+    let executions: usize = metrics.iter().map(|m| m.total_executions).sum();
+
+    let mut text = format!(
+        "{job} in {} has been flaky for {consecutive_runs} consecutive cilens runs.\n\n\
+         Flaky retries: {flaky} of {executions} executions ({:.1}%)\n",
+        insights.project,
+        flaky as f64 / executions.max(1) as f64 * 100.0
+    );
+
+    for (title, links) in [
+        (
+            "Flaky retries",
+            metrics
+                .iter()
+                .flat_map(|m| &m.flaky_retries.links)
+                .collect::<Vec<_>>(),
+        ),
+        (
+            "Failed executions",
+            metrics
+                .iter()
+                .flat_map(|m| &m.failed_executions.links)
+                .collect::<Vec<_>>(),
+        ),
+    ] {
+        if links.is_empty() {
+            continue;
+        }
+        let _ = writeln!(text, "\n{title}:");
+        for link in links {
+            let _ = writeln!(text, "* {link}");
+        }
+    }
+
+    text
+}
+
+/// Jira Cloud/Server REST API (v2, plain-text descriptions).
+pub struct Jira {
+    pub url: String,
+    pub email: String,
+    pub api_token: String,
+    pub project_key: String,
+    pub issue_type: String,
+}
+
+impl Jira {
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/rest/api/2/{path}", self.url.trim_end_matches('/'))
+    }
+
+    async fn post(&self, path: &str, body: &Value) -> Result<Value> {
+        let response = reqwest::Client::new()
+            .post(self.endpoint(path))
+            .basic_auth(&self.email, Some(&self.api_token))
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    async fn create_issue(&self, job: &str, project: &str, description: &str) -> Result<String> {
+        let response = self
+            .post(
+                "issue",
+                &json!({
+                    "fields": {
+                        "project": { "key": self.project_key },
+                        "issuetype": { "name": self.issue_type },
+                        "summary": format!("Flaky CI job {job} in {project}"),
+                        "description": description,
+                        "labels": [ISSUE_LABEL],
+                    }
+                }),
+            )
+            .await?;
+
+        response["key"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| CILensError::Export("Jira did not return an issue key".to_string()))
+    }
+
+    async fn comment(&self, issue: &str, body: &str) -> Result<()> {
+        self.post(&format!("issue/{issue}/comment"), &json!({ "body": body }))
+            .await?;
+        Ok(())
+    }
+
+    /// Opens an issue for each chronic job without one, and comments the latest
+    /// numbers on the issues already open.
+    pub async fn sync(
+        &self,
+        insights: &CIInsights,
+        state: &mut FlakyJobState,
+        chronic: &[String],
+    ) -> Result<()> {
+        for name in chronic {
+            let job = state.jobs.entry(name.clone()).or_default();
+            let description = describe(insights, name, job.consecutive_runs);
+
+            if let Some(issue) = &job.issue {
+                self.comment(issue, &description).await?;
+                info!("Updated {issue} for flaky job {name}");
+            } else {
+                let issue = self
+                    .create_issue(name, &insights.project, &description)
+                    .await?;
+                info!("Opened {issue} for flaky job {name}");
+                job.issue = Some(issue);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    fn report(flaky: usize) -> CIInsights {
+        let mut lint = job("lint", 60.0, 10);
+        lint.flaky_retries.count = flaky;
+        lint.flaky_retries.links = vec!["https://gitlab.com/group/project/-/jobs/1".to_string()];
+        insights(vec![pipeline_type("MR", 10, 0, vec![lint])])
+    }
+
+    #[test]
+    fn test_update_reports_jobs_after_consecutive_runs_and_resets_recovered() {
+        let mut state = FlakyJobState::default();
+
+        assert!(state.update(&report(2), 5.0, 2).is_empty());
+        assert_eq!(state.update(&report(2), 5.0, 2), vec!["lint"]);
+        assert!(state.update(&report(0), 5.0, 2).is_empty());
+        assert!(!state.jobs.contains_key("lint"));
+    }
+
+    #[tokio::test]
+    async fn test_sync_opens_then_comments_on_the_issue() {
+        let mut server = mockito::Server::new_async().await;
+        let create = server
+            .mock("POST", "/rest/api/2/issue")
+            .with_body(r#"{"key": "CI-7"}"#)
+            .create_async()
+            .await;
+        let comment = server
+            .mock("POST", "/rest/api/2/issue/CI-7/comment")
+            .with_body("{}")
+            .create_async()
+            .await;
+        let jira = Jira {
+            url: server.url(),
+            email: "bot@example.com".to_string(),
+            api_token: "token".to_string(),
+            project_key: "CI".to_string(),
+            issue_type: "Bug".to_string(),
+        };
+        let report = report(2);
+        let mut state = FlakyJobState::default();
+        let chronic = state.update(&report, 5.0, 1);
+
+        jira.sync(&report, &mut state, &chronic).await.unwrap();
+        jira.sync(&report, &mut state, &chronic).await.unwrap();
+
+        create.assert_async().await;
+        comment.assert_async().await;
+        assert_eq!(state.jobs["lint"].issue.as_deref(), Some("CI-7"));
+    }
+}
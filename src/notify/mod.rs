@@ -0,0 +1,210 @@
+pub mod alert;
+pub mod jira;
+pub mod slack;
+pub mod teams;
+
+use log::info;
+use reqwest::RequestBuilder;
+
+use crate::compare::compare_jobs;
+use crate::error::Result;
+use crate::insights::CIInsights;
+use crate::output::human::Style;
+
+/// Average duration of a job in the baseline and the current report.
+#[derive(Debug, Clone)]
+pub struct JobChange {
+    pub name: String,
+    pub baseline_seconds: f64,
+    pub current_seconds: f64,
+}
+
+impl JobChange {
+    pub fn delta_percentage(&self) -> f64 {
+        if self.baseline_seconds > 0.0 {
+            (self.current_seconds - self.baseline_seconds) / self.baseline_seconds * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FlakyJob {
+    pub name: String,
+    pub flakiness_rate: f64,
+    pub link: Option<String>,
+}
+
+/// Channel-agnostic digest of a report that notifiers format for their medium.
+#[derive(Debug, Clone)]
+pub struct HealthSummary {
+    pub project: String,
+    pub total_pipelines: usize,
+    pub success_rate: f64,
+    pub baseline_success_rate: Option<f64>,
+    pub regressions: Vec<JobChange>,
+    pub improvements: Vec<JobChange>,
+    pub flakiest_jobs: Vec<FlakyJob>,
+    /// Links to the most recent failed pipelines
+    pub failed_pipelines: Vec<String>,
+}
+
+/// Jobs that got slower (or, with `slower == false`, faster), largest relative change first.
+pub fn find_changes(
+    insights: &CIInsights,
+    baseline: &CIInsights,
+    top: usize,
+    slower: bool,
+) -> Vec<JobChange> {
+    let mut changes: Vec<JobChange> = compare_jobs(baseline, insights)
+        .into_iter()
+        .filter_map(|delta| match (delta.base_seconds, delta.head_seconds) {
+            (Some(baseline_seconds), Some(current_seconds))
+                if (slower && current_seconds > baseline_seconds)
+                    || (!slower && current_seconds < baseline_seconds) =>
+            {
+                Some(JobChange {
+                    name: delta.name,
+                    baseline_seconds,
+                    current_seconds,
+                })
+            }
+            _ => None,
+        })
+        .collect();
+
+    changes.sort_by(|a, b| {
+        b.delta_percentage()
+            .abs()
+            .total_cmp(&a.delta_percentage().abs())
+    });
+    changes.truncate(top);
+    changes
+}
+
+fn find_flakiest_jobs(insights: &CIInsights, top: usize) -> Vec<FlakyJob> {
+    let mut flaky: Vec<FlakyJob> = insights
+        .pipeline_types
+        .iter()
+        .flat_map(|pt| &pt.metrics.jobs)
+        .filter(|job| job.flakiness_rate > 0.0)
+        .map(|job| FlakyJob {
+            name: job.name.clone(),
+            flakiness_rate: job.flakiness_rate,
+            link: job.flaky_retries.links.first().cloned(),
+        })
+        .collect();
+
+    flaky.sort_by(|a, b| b.flakiness_rate.total_cmp(&a.flakiness_rate));
+    // The same job can appear in several pipeline types; keep its worst rate
+    let mut seen = std::collections::HashSet::new();
+    flaky.retain(|job| seen.insert(job.name.clone()));
+    flaky.truncate(top);
+    flaky
+}
+
+impl HealthSummary {
+    pub fn new(insights: &CIInsights, baseline: Option<&CIInsights>, top: usize) -> Self {
+        Self {
+            project: insights.project.clone(),
+            total_pipelines: insights.total_pipelines,
+            success_rate: insights.success_rate(),
+            baseline_success_rate: baseline.map(CIInsights::success_rate),
+            regressions: baseline
+                .map(|baseline| find_changes(insights, baseline, top, true))
+                .unwrap_or_default(),
+            improvements: baseline
+                .map(|baseline| find_changes(insights, baseline, top, false))
+                .unwrap_or_default(),
+            flakiest_jobs: find_flakiest_jobs(insights, top),
+            failed_pipelines: insights
+                .pipeline_types
+                .iter()
+                .flat_map(|pt| &pt.metrics.failed_pipelines.links)
+                .take(top)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Describes the condition [`Self::is_degraded`] checked, for alerts.
+    pub fn breach(&self, min_success_rate: Option<f64>) -> String {
+        match (min_success_rate, self.baseline_success_rate) {
+            (Some(threshold), _) => format!(
+                "Success rate {:.1}% is below the {threshold:.1}% threshold",
+                self.success_rate
+            ),
+            (None, Some(baseline)) => format!(
+                "Success rate {:.1}% dropped below the baseline {baseline:.1}%",
+                self.success_rate
+            ),
+            (None, None) => format!("Success rate is {:.1}%", self.success_rate),
+        }
+    }
+
+    /// Whether health is below the threshold (or dropped below the baseline when
+    /// no threshold is given). Without either, every run notifies.
+    pub fn is_degraded(&self, min_success_rate: Option<f64>) -> bool {
+        match (min_success_rate, self.baseline_success_rate) {
+            (Some(threshold), _) => self.success_rate < threshold,
+            (None, Some(baseline)) => self.success_rate < baseline,
+            (None, None) => true,
+        }
+    }
+}
+
+/// A chat or alerting service that health summaries are delivered to.
+pub trait Channel {
+    fn name(&self) -> &'static str;
+
+    /// Request delivering `summary`, including the endpoint and any credentials.
+    fn request(
+        &self,
+        client: &reqwest::Client,
+        summary: &HealthSummary,
+        style: Style,
+    ) -> RequestBuilder;
+}
+
+pub async fn send(channel: &dyn Channel, summary: &HealthSummary, style: Style) -> Result<()> {
+    channel
+        .request(&reqwest::Client::new(), summary, style)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    info!(
+        "{} notification sent for {}",
+        channel.name(),
+        summary.project
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use super::*;
+
+    pub fn summary() -> HealthSummary {
+        HealthSummary {
+            project: "group/project".to_string(),
+            total_pipelines: 20,
+            success_rate: 85.0,
+            baseline_success_rate: Some(90.0),
+            regressions: vec![JobChange {
+                name: "build".to_string(),
+                baseline_seconds: 100.0,
+                current_seconds: 150.0,
+            }],
+            improvements: vec![],
+            flakiest_jobs: vec![FlakyJob {
+                name: "e2e".to_string(),
+                flakiness_rate: 12.5,
+                link: Some("https://gitlab.com/group/project/-/jobs/1".to_string()),
+            }],
+            failed_pipelines: vec!["https://gitlab.com/group/project/-/pipelines/7".to_string()],
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt::Write;
+
+use reqwest::RequestBuilder;
+use serde_json::{json, Value};
+
+use super::{Channel, HealthSummary};
+use crate::output::human::Style;
+
+/// Slack incoming webhook, formatted with `mrkdwn` blocks.
+pub struct Slack {
+    pub webhook_url: String,
+}
+
+impl Channel for Slack {
+    fn name(&self) -> &'static str {
+        "Slack"
+    }
+
+    fn request(
+        &self,
+        client: &reqwest::Client,
+        summary: &HealthSummary,
+        style: Style,
+    ) -> RequestBuilder {
+        client
+            .post(&self.webhook_url)
+            .json(&build_payload(summary, style))
+    }
+}
+
+fn format_message(summary: &HealthSummary, style: Style) -> String {
+    let mut text = format!(
+        "*CI health for `{}`* ({} pipelines)\n",
+        summary.project, summary.total_pipelines
+    );
+
+    let _ = write!(
+        text,
+        "Success rate: *{}*",
+        style.percentage(summary.success_rate)
+    );
+    if let Some(baseline) = summary.baseline_success_rate {
+        let _ = write!(
+            text,
+            " ({:+.1} pts vs baseline)",
+            summary.success_rate - baseline
+        );
+    }
+    text.push('\n');
+
+    for (title, changes) in [
+        ("Top regressions", &summary.regressions),
+        ("Top improvements", &summary.improvements),
+    ] {
+        if changes.is_empty() {
+            continue;
+        }
+        let _ = writeln!(text, "\n*{title}*");
+        for change in changes {
+            let _ = writeln!(
+                text,
+                "• `{}`: {} → {} ({})",
+                change.name,
+                style.duration(change.baseline_seconds),
+                style.duration(change.current_seconds),
+                style.percentage_change(change.delta_percentage())
+            );
+        }
+    }
+
+    if !summary.flakiest_jobs.is_empty() {
+        text.push_str("\n*Flakiest jobs*\n");
+        for job in &summary.flakiest_jobs {
+            let name = match &job.link {
+                Some(link) => format!("<{link}|{}>", job.name),
+                None => format!("`{}`", job.name),
+            };
+            let _ = writeln!(
+                text,
+                "• {name}: {} flaky",
+                style.percentage(job.flakiness_rate)
+            );
+        }
+    }
+
+    text
+}
+
+fn build_payload(summary: &HealthSummary, style: Style) -> Value {
+    let text = format_message(summary, style);
+    json!({
+        "text": format!(
+            "CI health for {}: {} success",
+            summary.project,
+            style.percentage(summary.success_rate)
+        ),
+        "blocks": [
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": text }
+            }
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::fixtures::summary;
+
+    #[test]
+    fn test_format_message_includes_all_sections() {
+        let text = format_message(&summary(), Style::default());
+
+        assert!(text.contains("Success rate: *85.0%* (-5.0 pts vs baseline)"));
+        assert!(text.contains("`build`: 100s → 150s (+50%)"));
+        assert!(text.contains("<https://gitlab.com/group/project/-/jobs/1|e2e>: 12.5% flaky"));
+    }
+
+    #[test]
+    fn test_format_message_in_human_style() {
+        let mut summary = summary();
+        summary.regressions[0].current_seconds = 724.0;
+
+        let text = format_message(
+            &summary,
+            Style {
+                human: true,
+                ..Style::default()
+            },
+        );
+
+        assert!(text.contains("Success rate: *85%*"));
+        assert!(text.contains("`build`: 1m 40s → 12m 4s (+624%)"));
+    }
+
+    #[test]
+    fn test_is_degraded_uses_threshold_before_baseline() {
+        let summary = summary();
+
+        assert!(summary.is_degraded(Some(90.0)));
+        assert!(!summary.is_degraded(Some(80.0)));
+        assert!(summary.is_degraded(None));
+    }
+}
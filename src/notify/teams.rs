@@ -0,0 +1,157 @@
+use std::fmt::Write;
+
+use reqwest::RequestBuilder;
+use serde_json::{json, Value};
+
+use super::{Channel, HealthSummary, JobChange};
+use crate::output::human::Style;
+
+/// Microsoft Teams incoming webhook (or Workflows "post to a channel" trigger),
+/// formatted as an Adaptive Card.
+pub struct Teams {
+    pub webhook_url: String,
+}
+
+impl Channel for Teams {
+    fn name(&self) -> &'static str {
+        "Teams"
+    }
+
+    fn request(
+        &self,
+        client: &reqwest::Client,
+        summary: &HealthSummary,
+        style: Style,
+    ) -> RequestBuilder {
+        client
+            .post(&self.webhook_url)
+            .json(&build_payload(summary, style))
+    }
+}
+
+fn build_payload(summary: &HealthSummary, style: Style) -> Value {
+    json!({
+        "type": "message",
+        "attachments": [
+            {
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": {
+                    "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                    "type": "AdaptiveCard",
+                    "version": "1.4",
+                    "body": card_body(summary, style),
+                }
+            }
+        ]
+    })
+}
+
+fn heading(text: &str) -> Value {
+    json!({
+        "type": "TextBlock",
+        "text": text,
+        "weight": "Bolder",
+        "spacing": "Medium",
+        "wrap": true,
+    })
+}
+
+fn changes_facts(changes: &[JobChange], style: Style) -> Value {
+    let facts: Vec<Value> = changes
+        .iter()
+        .map(|change| {
+            json!({
+                "title": change.name,
+                "value": format!(
+                    "{} → {} ({})",
+                    style.duration(change.baseline_seconds),
+                    style.duration(change.current_seconds),
+                    style.percentage_change(change.delta_percentage())
+                ),
+            })
+        })
+        .collect();
+
+    json!({ "type": "FactSet", "facts": facts })
+}
+
+fn card_body(summary: &HealthSummary, style: Style) -> Vec<Value> {
+    let mut success_rate = style.percentage(summary.success_rate);
+    if let Some(baseline) = summary.baseline_success_rate {
+        let _ = write!(
+            success_rate,
+            " ({:+.1} pts vs baseline)",
+            summary.success_rate - baseline
+        );
+    }
+
+    let mut body = vec![
+        json!({
+            "type": "TextBlock",
+            "text": format!("CI health for {}", summary.project),
+            "size": "Medium",
+            "weight": "Bolder",
+            "wrap": true,
+        }),
+        json!({
+            "type": "FactSet",
+            "facts": [
+                { "title": "Pipelines", "value": summary.total_pipelines.to_string() },
+                { "title": "Success rate", "value": success_rate },
+            ]
+        }),
+    ];
+
+    for (title, changes) in [
+        ("Top regressions", &summary.regressions),
+        ("Top improvements", &summary.improvements),
+    ] {
+        if !changes.is_empty() {
+            body.push(heading(title));
+            body.push(changes_facts(changes, style));
+        }
+    }
+
+    if !summary.flakiest_jobs.is_empty() {
+        body.push(heading("Flakiest jobs"));
+        for job in &summary.flakiest_jobs {
+            let name = match &job.link {
+                Some(link) => format!("[{}]({link})", job.name),
+                None => job.name.clone(),
+            };
+            body.push(json!({
+                "type": "TextBlock",
+                "text": format!("{name}: {} flaky", style.percentage(job.flakiness_rate)),
+                "spacing": "None",
+                "wrap": true,
+            }));
+        }
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::fixtures::summary;
+
+    #[test]
+    fn test_payload_is_an_adaptive_card_with_all_sections() {
+        let payload = build_payload(&summary(), Style::default());
+        let attachment = &payload["attachments"][0];
+        let body = &attachment["content"]["body"];
+
+        assert_eq!(
+            attachment["contentType"],
+            "application/vnd.microsoft.card.adaptive"
+        );
+        assert_eq!(body[0]["text"], "CI health for group/project");
+        assert_eq!(body[1]["facts"][1]["value"], "85.0% (-5.0 pts vs baseline)");
+        assert_eq!(body[3]["facts"][0]["value"], "100s → 150s (+50%)");
+        assert_eq!(
+            body[5]["text"],
+            "[e2e](https://gitlab.com/group/project/-/jobs/1): 12.5% flaky"
+        );
+    }
+}
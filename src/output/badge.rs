@@ -0,0 +1,87 @@
+use clap::ValueEnum;
+
+use super::human::Style;
+use crate::insights::{CIInsights, Summary};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum BadgeMetric {
+    SuccessRate,
+    Duration,
+}
+
+const GREEN: &str = "#4c1";
+const YELLOW: &str = "#dfb317";
+const RED: &str = "#e05d44";
+const BLUE: &str = "#007ec6";
+
+/// Approximate width of `text` in 11px Verdana, plus padding.
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 7 + 10
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders a flat, shields.io-style badge.
+fn render(label: &str, value: &str, color: &str) -> String {
+    let label_width = text_width(label);
+    let value_width = text_width(value);
+    let width = label_width + value_width;
+    let label_x = label_width / 2;
+    let value_x = label_width + value_width / 2;
+    let (label, value) = (escape(label), escape(value));
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {value}"><title>{label}: {value}</title><linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient><clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath><g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="#555"/><rect x="{label_width}" width="{value_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g><g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11"><text x="{label_x}" y="14">{label}</text><text x="{value_x}" y="14">{value}</text></g></svg>"##
+    )
+}
+
+/// Renders a badge for `metric` of `insights`.
+pub fn badge(insights: &CIInsights, metric: BadgeMetric) -> String {
+    let style = Style {
+        human: true,
+        ..Style::default()
+    };
+    let summary = Summary::of(insights);
+
+    match metric {
+        BadgeMetric::SuccessRate => {
+            let color = match summary.success_rate {
+                rate if rate >= 95.0 => GREEN,
+                rate if rate >= 80.0 => YELLOW,
+                _ => RED,
+            };
+            render(
+                "pipeline success",
+                &style.percentage(summary.success_rate),
+                color,
+            )
+        }
+        BadgeMetric::Duration => render(
+            "pipeline duration",
+            &style.duration(summary.avg_duration_seconds),
+            BLUE,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    #[test]
+    fn test_success_rate_badge_is_colored_by_rate() {
+        let report = insights(vec![pipeline_type("MR", 9, 1, vec![job("lint", 30.0, 10)])]);
+
+        let svg = badge(&report, BadgeMetric::SuccessRate);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("aria-label=\"pipeline success: 90%\""));
+        assert!(svg.contains(&format!("fill=\"{YELLOW}\"")));
+    }
+}
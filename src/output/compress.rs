@@ -0,0 +1,92 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use clap::ValueEnum;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression from a `.gz` or `.zst` file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Self::Gzip),
+            "zst" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Compresses `data` into `writer` as it is written, without buffering
+    /// the compressed output.
+    pub fn write_to<W: Write>(self, writer: W, data: &[u8]) -> Result<()> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(writer, flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()?;
+            }
+            Self::Zstd => {
+                let mut encoder = zstd::Encoder::new(writer, 0)?;
+                encoder.write_all(data)?;
+                encoder.finish()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_from<R: Read>(self, reader: R) -> Result<String> {
+        let mut content = String::new();
+        match self {
+            Self::Gzip => GzDecoder::new(reader).read_to_string(&mut content)?,
+            Self::Zstd => zstd::Decoder::new(reader)?.read_to_string(&mut content)?,
+        };
+        Ok(content)
+    }
+}
+
+/// Reads a file, transparently decompressing `.gz` and `.zst` files.
+pub fn read_to_string(path: &Path) -> Result<String> {
+    match Compression::from_path(path) {
+        Some(compression) => compression.read_from(std::fs::File::open(path)?),
+        None => Ok(std::fs::read_to_string(path)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path_detects_extension() {
+        assert_eq!(
+            Compression::from_path(Path::new("insights.json.gz")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("insights.json.zst")),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::from_path(Path::new("insights.json")), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let mut compressed = Vec::new();
+            compression
+                .write_to(&mut compressed, b"{\"project\":\"group/project\"}")
+                .unwrap();
+
+            let content = compression.read_from(compressed.as_slice()).unwrap();
+
+            assert_eq!(content, "{\"project\":\"group/project\"}");
+        }
+    }
+}
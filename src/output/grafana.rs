@@ -0,0 +1,269 @@
+use serde_json::{json, Value};
+
+use super::prometheus::{
+    Metric, JOB_DURATION, JOB_FAILURE_RATE, JOB_FLAKINESS_RATE, JOB_SET_CHANGE,
+    JOB_TIME_TO_FEEDBACK, PIPELINES_TOTAL, TYPE_DURATION, TYPE_SUCCESS_RATE, TYPE_TIME_TO_FEEDBACK,
+};
+
+const TOP_JOBS: usize = 10;
+
+fn selector(metric: &Metric) -> String {
+    format!(
+        "{}{{project=~\"$project\",pipeline_type=~\"$pipeline_type\"}}",
+        metric.name
+    )
+}
+
+type GridPos = (u32, u32, u32, u32);
+
+fn grid((x, y, w, h): GridPos) -> Value {
+    json!({ "x": x, "y": y, "w": w, "h": h })
+}
+
+fn stat_panel(id: u32, title: &str, expr: &str, unit: &str, position: GridPos) -> Value {
+    json!({
+        "id": id,
+        "type": "stat",
+        "title": title,
+        "datasource": { "type": "prometheus", "uid": "${datasource}" },
+        "gridPos": grid(position),
+        "fieldConfig": { "defaults": { "unit": unit }, "overrides": [] },
+        "options": { "reduceOptions": { "calcs": ["lastNotNull"] } },
+        "targets": [{ "refId": "A", "expr": expr }],
+    })
+}
+
+fn timeseries_panel(
+    id: u32,
+    title: &str,
+    expr: &str,
+    legend: &str,
+    unit: &str,
+    position: GridPos,
+) -> Value {
+    json!({
+        "id": id,
+        "type": "timeseries",
+        "title": title,
+        "datasource": { "type": "prometheus", "uid": "${datasource}" },
+        "gridPos": grid(position),
+        "fieldConfig": { "defaults": { "unit": unit }, "overrides": [] },
+        "targets": [{ "refId": "A", "expr": expr, "legendFormat": legend }],
+    })
+}
+
+fn bar_gauge_panel(id: u32, title: &str, expr: &str, unit: &str, position: GridPos) -> Value {
+    json!({
+        "id": id,
+        "type": "bargauge",
+        "title": title,
+        "datasource": { "type": "prometheus", "uid": "${datasource}" },
+        "gridPos": grid(position),
+        "fieldConfig": { "defaults": { "unit": unit }, "overrides": [] },
+        "options": { "displayMode": "gradient", "orientation": "horizontal" },
+        "targets": [{
+            "refId": "A",
+            "expr": expr,
+            "instant": true,
+            "legendFormat": "{{job}} ({{pipeline_type}})",
+        }],
+    })
+}
+
+fn query_variable(name: &str, label: &str, query: &str) -> Value {
+    json!({
+        "name": name,
+        "label": label,
+        "type": "query",
+        "datasource": { "type": "prometheus", "uid": "${datasource}" },
+        "query": query,
+        "refresh": 2,
+        "includeAll": true,
+        "multi": true,
+        "allValue": ".*",
+    })
+}
+
+fn templating() -> Value {
+    json!({
+        "list": [
+            {
+                "name": "datasource",
+                "label": "Data source",
+                "type": "datasource",
+                "query": "prometheus",
+            },
+            query_variable(
+                "project",
+                "Project",
+                &format!("label_values({}, project)", PIPELINES_TOTAL.name),
+            ),
+            query_variable(
+                "pipeline_type",
+                "Pipeline type",
+                &format!(
+                    "label_values({}{{project=~\"$project\"}}, pipeline_type)",
+                    TYPE_SUCCESS_RATE.name
+                ),
+            ),
+        ]
+    })
+}
+
+/// Marks CI config changes (jobs added or removed on a ref) on every time
+/// series, so duration jumps can be told apart from slower jobs.
+fn annotations() -> Value {
+    json!({
+        "list": [{
+            "name": "CI config changes",
+            "datasource": { "type": "prometheus", "uid": "${datasource}" },
+            "enable": true,
+            "iconColor": "orange",
+            // The metric's value is the change's timestamp, which Grafana wants in ms
+            "expr": format!("{}{{project=~\"$project\"}} * 1000", JOB_SET_CHANGE.name),
+            "useValueForTime": true,
+            "titleFormat": "Jobs changed on {{ref}}",
+            "textFormat": "{{summary}}",
+        }]
+    })
+}
+
+fn panels() -> Vec<Value> {
+    let top_jobs = |metric: &Metric| format!("topk({TOP_JOBS}, {})", selector(metric));
+
+    vec![
+        stat_panel(
+            1,
+            "Pipelines analyzed",
+            &format!("sum({}{{project=~\"$project\"}})", PIPELINES_TOTAL.name),
+            "none",
+            (0, 0, 8, 4),
+        ),
+        stat_panel(
+            2,
+            "Lowest success rate",
+            &format!("min({})", selector(&TYPE_SUCCESS_RATE)),
+            "percent",
+            (8, 0, 8, 4),
+        ),
+        stat_panel(
+            3,
+            "Slowest pipeline type",
+            &format!("max({})", selector(&TYPE_DURATION)),
+            "s",
+            (16, 0, 8, 4),
+        ),
+        timeseries_panel(
+            4,
+            "Success rate by pipeline type",
+            &selector(&TYPE_SUCCESS_RATE),
+            "{{pipeline_type}}",
+            "percent",
+            (0, 4, 12, 8),
+        ),
+        timeseries_panel(
+            5,
+            "Pipeline duration by type",
+            &selector(&TYPE_DURATION),
+            "{{pipeline_type}}",
+            "s",
+            (12, 4, 12, 8),
+        ),
+        timeseries_panel(
+            6,
+            "Time to first feedback by type",
+            &selector(&TYPE_TIME_TO_FEEDBACK),
+            "{{pipeline_type}}",
+            "s",
+            (0, 12, 12, 8),
+        ),
+        bar_gauge_panel(
+            7,
+            "Slowest jobs (time to feedback)",
+            &top_jobs(&JOB_TIME_TO_FEEDBACK),
+            "s",
+            (12, 12, 12, 8),
+        ),
+        bar_gauge_panel(
+            8,
+            "Longest running jobs",
+            &top_jobs(&JOB_DURATION),
+            "s",
+            (0, 20, 8, 8),
+        ),
+        bar_gauge_panel(
+            9,
+            "Flakiest jobs",
+            &top_jobs(&JOB_FLAKINESS_RATE),
+            "percent",
+            (8, 20, 8, 8),
+        ),
+        bar_gauge_panel(
+            10,
+            "Most failing jobs",
+            &top_jobs(&JOB_FAILURE_RATE),
+            "percent",
+            (16, 20, 8, 8),
+        ),
+    ]
+}
+
+/// Builds a ready-to-import Grafana dashboard over the metrics exported with
+/// `--format prometheus` / `--push-gateway`.
+pub fn dashboard(title: &str) -> Value {
+    json!({
+        "title": title,
+        "uid": "cilens",
+        "tags": ["cilens", "ci"],
+        "schemaVersion": 39,
+        "time": { "from": "now-30d", "to": "now" },
+        "templating": templating(),
+        "annotations": annotations(),
+        "panels": panels(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dashboard_panels_query_cilens_metrics() {
+        let dashboard = dashboard("CI");
+        let panels = dashboard["panels"].as_array().unwrap();
+
+        assert_eq!(dashboard["title"], "CI");
+        assert!(!panels.is_empty());
+        for panel in panels {
+            let expr = panel["targets"][0]["expr"].as_str().unwrap();
+            assert!(expr.contains("cilens_"), "unexpected query: {expr}");
+        }
+    }
+
+    #[test]
+    fn test_dashboard_annotates_job_set_changes() {
+        let dashboard = dashboard("CI");
+        let annotation = &dashboard["annotations"]["list"][0];
+
+        assert_eq!(annotation["useValueForTime"], true);
+        assert!(annotation["expr"]
+            .as_str()
+            .unwrap()
+            .starts_with("cilens_job_set_change_timestamp_seconds"));
+    }
+
+    #[test]
+    fn test_dashboard_panel_ids_are_unique() {
+        let dashboard = dashboard("CI");
+        let mut ids: Vec<u64> = dashboard["panels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|panel| panel["id"].as_u64().unwrap())
+            .collect();
+        let total = ids.len();
+        ids.dedup();
+
+        assert_eq!(ids.len(), total);
+    }
+}
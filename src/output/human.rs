@@ -0,0 +1,162 @@
+#[cfg(feature = "gitlab")]
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+/// How numbers are written in text outputs (Slack messages, merge request
+/// comments). JSON and Prometheus always carry raw numbers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Style {
+    /// `12m 4s` instead of `724s`, and percentages rounded to their magnitude
+    pub human: bool,
+    /// Timezone timestamps are written in
+    #[cfg_attr(not(feature = "gitlab"), allow(dead_code))]
+    pub timezone: Tz,
+}
+
+/// `724.0` -> `12m 4s`; units below the two most significant ones are dropped.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn human_duration(seconds: f64) -> String {
+    let total = seconds.abs().round() as u64;
+    let (days, hours, minutes, secs) = (
+        total / 86_400,
+        total % 86_400 / 3_600,
+        total % 3_600 / 60,
+        total % 60,
+    );
+
+    let parts: Vec<String> = [(days, "d"), (hours, "h"), (minutes, "m"), (secs, "s")]
+        .iter()
+        .skip_while(|(value, _)| *value == 0)
+        .take(2)
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect();
+
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Fewer decimals the larger the value: `85%`, `2.5%`, `0.25%`.
+fn human_percentage(value: f64) -> String {
+    let magnitude = value.abs();
+    let formatted = if magnitude >= 10.0 {
+        format!("{value:.0}")
+    } else if magnitude >= 1.0 {
+        format!("{value:.1}")
+    } else {
+        format!("{value:.2}")
+    };
+    let trimmed = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    format!("{trimmed}%")
+}
+
+impl Style {
+    pub fn duration(self, seconds: f64) -> String {
+        if self.human {
+            human_duration(seconds)
+        } else {
+            format!("{seconds:.0}s")
+        }
+    }
+
+    /// Duration change with an explicit sign, e.g. `+1m 5s`.
+    #[cfg(feature = "gitlab")]
+    pub fn duration_change(self, seconds: f64) -> String {
+        if self.human {
+            let sign = if seconds < 0.0 { '-' } else { '+' };
+            format!("{sign}{}", human_duration(seconds))
+        } else {
+            format!("{seconds:+.0}s")
+        }
+    }
+
+    pub fn percentage(self, value: f64) -> String {
+        if self.human {
+            human_percentage(value)
+        } else {
+            format!("{value:.1}%")
+        }
+    }
+
+    /// `2025-01-01 10:00 CET`, in the style's timezone.
+    #[cfg(feature = "gitlab")]
+    pub fn timestamp(self, time: DateTime<Utc>) -> String {
+        time.with_timezone(&self.timezone)
+            .format("%Y-%m-%d %H:%M %Z")
+            .to_string()
+    }
+
+    /// Relative change with an explicit sign, e.g. `+50%`.
+    pub fn percentage_change(self, value: f64) -> String {
+        if self.human {
+            let formatted = human_percentage(value);
+            if value < 0.0 {
+                formatted
+            } else {
+                format!("+{formatted}")
+            }
+        } else {
+            format!("{value:+.0}%")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HUMAN: Style = Style {
+        human: true,
+        timezone: Tz::UTC,
+    };
+
+    #[test]
+    fn test_human_durations_keep_two_units() {
+        assert_eq!(HUMAN.duration(724.0), "12m 4s");
+        assert_eq!(HUMAN.duration(45.0), "45s");
+        assert_eq!(HUMAN.duration(3_900.0), "1h 5m");
+        assert_eq!(HUMAN.duration(7_200.0), "2h");
+        assert_eq!(HUMAN.duration(0.2), "0s");
+        #[cfg(feature = "gitlab")]
+        assert_eq!(HUMAN.duration_change(-65.0), "-1m 5s");
+    }
+
+    #[test]
+    fn test_human_percentages_round_to_magnitude() {
+        assert_eq!(HUMAN.percentage(85.04), "85%");
+        assert_eq!(HUMAN.percentage(2.54), "2.5%");
+        assert_eq!(HUMAN.percentage(0.25), "0.25%");
+        assert_eq!(HUMAN.percentage(3.0), "3%");
+        assert_eq!(HUMAN.percentage_change(50.0), "+50%");
+    }
+
+    #[test]
+    fn test_raw_style_keeps_seconds() {
+        let raw = Style::default();
+
+        assert_eq!(raw.duration(724.0), "724s");
+        assert_eq!(raw.percentage(85.0), "85.0%");
+        #[cfg(feature = "gitlab")]
+        assert_eq!(raw.duration_change(-5.0), "-5s");
+    }
+
+    #[cfg(feature = "gitlab")]
+    #[test]
+    fn test_timestamps_follow_style_timezone() {
+        let time = "2025-01-01T10:00:00Z".parse().unwrap();
+        let berlin = Style {
+            timezone: Tz::Europe__Berlin,
+            ..Style::default()
+        };
+
+        assert_eq!(Style::default().timestamp(time), "2025-01-01 10:00 UTC");
+        assert_eq!(berlin.timestamp(time), "2025-01-01 11:00 CET");
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt::Write;
+
+use super::human::Style;
+use crate::compare::{compare_jobs, JobDelta};
+use crate::insights::CIInsights;
+
+/// Hidden marker identifying comments posted by cilens so they can be updated in place.
+pub const COMMENT_MARKER: &str = "<!-- cilens:ci-performance -->";
+
+const MAX_ROWS: usize = 20;
+
+fn format_seconds(seconds: Option<f64>, style: Style) -> String {
+    seconds.map_or_else(|| "—".to_string(), |s| style.duration(s))
+}
+
+fn format_delta(delta: &JobDelta, style: Style) -> String {
+    match (delta.delta_seconds(), delta.base_seconds) {
+        (Some(change), Some(base)) if base > 0.0 => format!(
+            "{} ({})",
+            style.duration_change(change),
+            style.percentage_change(change / base * 100.0)
+        ),
+        (Some(change), _) => style.duration_change(change),
+        (None, None) => "new".to_string(),
+        (None, Some(_)) => "removed".to_string(),
+    }
+}
+
+/// Renders a branch-vs-base job duration comparison as a merge request comment.
+pub fn render_comparison(
+    head_ref: &str,
+    base_ref: &str,
+    head: &CIInsights,
+    base: &CIInsights,
+    style: Style,
+) -> String {
+    let mut out = format!("{COMMENT_MARKER}\n### CI performance: `{head_ref}` vs `{base_ref}`\n\n");
+
+    let _ = writeln!(
+        out,
+        "Success rate: **{}** ({} pipelines) vs **{}** ({} pipelines)\n",
+        style.percentage(head.success_rate()),
+        head.total_pipelines,
+        style.percentage(base.success_rate()),
+        base.total_pipelines
+    );
+
+    let deltas = compare_jobs(base, head);
+    if deltas.is_empty() {
+        out.push_str("_No job data available for comparison._\n");
+        return out;
+    }
+
+    out.push_str("| Job | Base | This branch | Δ |\n|---|---:|---:|---:|\n");
+    for delta in deltas.iter().take(MAX_ROWS) {
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} | {} |",
+            delta.name,
+            format_seconds(delta.base_seconds, style),
+            format_seconds(delta.head_seconds, style),
+            format_delta(delta, style)
+        );
+    }
+
+    if deltas.len() > MAX_ROWS {
+        let _ = writeln!(
+            out,
+            "\n_{} more jobs with smaller changes not shown._",
+            deltas.len() - MAX_ROWS
+        );
+    }
+
+    out
+}
@@ -0,0 +1,17 @@
+pub mod badge;
+pub mod compress;
+#[cfg(feature = "gitlab")]
+pub mod grafana;
+pub mod human;
+#[cfg(feature = "gitlab")]
+pub mod markdown;
+#[cfg(feature = "gitlab")]
+pub mod pdf;
+#[cfg(feature = "gitlab")]
+pub mod prometheus;
+#[cfg(feature = "gitlab")]
+pub mod split;
+#[cfg(feature = "gitlab")]
+pub mod template;
+#[cfg(feature = "gitlab")]
+pub mod xlsx;
@@ -0,0 +1,373 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+use super::human::Style;
+use crate::insights::{CIInsights, Summary};
+
+/// A4 portrait, in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+/// Jobs listed per pipeline type; the rest are summarized in one line.
+const MAX_JOBS: usize = 15;
+
+/// Failure and flakiness rates (percent) at or above these are printed amber and red.
+const WARNING_RATE: f64 = 5.0;
+const CRITICAL_RATE: f64 = 10.0;
+
+const BLACK: (f32, f32, f32) = (0.0, 0.0, 0.0);
+const GREY: (f32, f32, f32) = (0.4, 0.4, 0.4);
+const AMBER: (f32, f32, f32) = (0.8, 0.5, 0.0);
+const RED: (f32, f32, f32) = (0.75, 0.0, 0.0);
+
+/// Left edge of each table column, after the wide first one.
+const COLUMNS: [f32; 6] = [MARGIN, 260.0, 320.0, 380.0, 440.0, 505.0];
+/// Characters that fit the first column at table font size.
+const FIRST_COLUMN_CHARS: usize = 40;
+
+/// Encodes `text` for the standard fonts' `WinAnsiEncoding`, which matches
+/// Latin-1 for printable characters; anything else becomes `?`.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match u8::try_from(u32::from(c)) {
+            Ok(byte) if (0x20..0x7f).contains(&byte) || byte >= 0xa0 => byte,
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        text.chars().take(max_chars - 3).collect::<String>() + "..."
+    }
+}
+
+fn rate_color(rate: f64) -> (f32, f32, f32) {
+    if rate >= CRITICAL_RATE {
+        RED
+    } else if rate >= WARNING_RATE {
+        AMBER
+    } else {
+        BLACK
+    }
+}
+
+/// Lays text out top to bottom, starting a new page when one fills up.
+struct Layout {
+    pages: Vec<Content>,
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Content::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn page(&mut self) -> &mut Content {
+        self.pages.last_mut().expect("layout always has a page")
+    }
+
+    /// Moves down by `height`, breaking the page first if it would not fit.
+    fn advance(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.pages.push(Content::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+        self.y -= height;
+    }
+
+    fn text(&mut self, x: f32, font: Name, size: f32, color: (f32, f32, f32), text: &str) {
+        let y = self.y;
+        let content = self.page();
+        content
+            .set_fill_rgb(color.0, color.1, color.2)
+            .begin_text()
+            .set_font(font, size)
+            .next_line(x, y)
+            .show(Str(&win_ansi(text)))
+            .end_text();
+    }
+
+    fn line(&mut self, font: Name, size: f32, color: (f32, f32, f32), text: &str) {
+        self.advance(size * 1.5);
+        self.text(MARGIN, font, size, color, text);
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.advance(10.0);
+        self.line(BOLD, 13.0, BLACK, text);
+        let y = self.y - 4.0;
+        self.page()
+            .set_stroke_rgb(0.7, 0.7, 0.7)
+            .set_line_width(0.5)
+            .move_to(MARGIN, y)
+            .line_to(PAGE_WIDTH - MARGIN, y)
+            .stroke();
+        self.advance(4.0);
+    }
+
+    /// One table row; `cells` pair each column's text with its color.
+    fn row(&mut self, font: Name, cells: &[(String, (f32, f32, f32))]) {
+        self.advance(13.0);
+        for (x, (text, color)) in COLUMNS.iter().zip(cells) {
+            self.text(*x, font, 9.0, *color, text);
+        }
+    }
+
+    fn header_row(&mut self, headers: [&str; 6]) {
+        let cells: Vec<_> = headers.iter().map(|h| (h.to_string(), GREY)).collect();
+        self.row(BOLD, &cells);
+    }
+}
+
+fn write_summary(layout: &mut Layout, insights: &CIInsights, summary: &Summary, style: Style) {
+    layout.heading("Summary");
+    layout.line(
+        REGULAR,
+        10.0,
+        BLACK,
+        &format!(
+            "Success rate: {}    Average pipeline duration: {}",
+            style.percentage(summary.success_rate),
+            style.duration(summary.avg_duration_seconds)
+        ),
+    );
+    layout.line(
+        REGULAR,
+        10.0,
+        BLACK,
+        &format!(
+            "{} pipelines in {} pipeline types, {} job executions",
+            insights.total_pipelines, insights.total_pipeline_types, summary.total_jobs
+        ),
+    );
+    if let Some(job) = &summary.slowest_job {
+        layout.line(
+            REGULAR,
+            10.0,
+            BLACK,
+            &format!(
+                "Slowest job: {} ({}), {} on average",
+                job.name,
+                job.pipeline_type,
+                style.duration(job.value)
+            ),
+        );
+    }
+    if let Some(job) = &summary.flakiest_job {
+        layout.line(
+            REGULAR,
+            10.0,
+            BLACK,
+            &format!(
+                "Flakiest job: {} ({}), {} of executions retried",
+                job.name,
+                job.pipeline_type,
+                style.percentage(job.value)
+            ),
+        );
+    }
+}
+
+fn write_pipeline_types(layout: &mut Layout, insights: &CIInsights, style: Style) {
+    layout.heading("Pipeline types");
+    layout.header_row([
+        "Pipeline type",
+        "Share",
+        "Pipelines",
+        "Success",
+        "Duration",
+        "Feedback",
+    ]);
+    for pipeline_type in &insights.pipeline_types {
+        let metrics = &pipeline_type.metrics;
+        layout.row(
+            REGULAR,
+            &[
+                (truncate(&pipeline_type.label, FIRST_COLUMN_CHARS), BLACK),
+                (style.percentage(metrics.percentage), BLACK),
+                (metrics.total_pipelines.to_string(), BLACK),
+                (
+                    style.percentage(metrics.success_rate),
+                    rate_color(100.0 - metrics.success_rate),
+                ),
+                (style.duration(metrics.avg_duration_seconds), BLACK),
+                (style.duration(metrics.avg_time_to_feedback_seconds), BLACK),
+            ],
+        );
+    }
+}
+
+fn write_jobs(layout: &mut Layout, insights: &CIInsights, style: Style) {
+    for pipeline_type in &insights.pipeline_types {
+        layout.heading(&format!("Jobs: {}", pipeline_type.label));
+        layout.header_row([
+            "Job",
+            "Executions",
+            "Failure",
+            "Flakiness",
+            "Duration",
+            "Feedback",
+        ]);
+
+        let jobs = &pipeline_type.metrics.jobs;
+        for job in jobs.iter().take(MAX_JOBS) {
+            layout.row(
+                REGULAR,
+                &[
+                    (truncate(&job.name, FIRST_COLUMN_CHARS), BLACK),
+                    (job.total_executions.to_string(), BLACK),
+                    (
+                        style.percentage(job.failure_rate),
+                        rate_color(job.failure_rate),
+                    ),
+                    (
+                        style.percentage(job.flakiness_rate),
+                        rate_color(job.flakiness_rate),
+                    ),
+                    (style.duration(job.avg_duration_seconds), BLACK),
+                    (style.duration(job.avg_time_to_feedback_seconds), BLACK),
+                ],
+            );
+        }
+        if jobs.len() > MAX_JOBS {
+            layout.line(
+                REGULAR,
+                9.0,
+                GREY,
+                &format!("... and {} more jobs", jobs.len() - MAX_JOBS),
+            );
+        }
+    }
+}
+
+/// Renders `insights` as a PDF health report: a summary, the pipeline types
+/// and the jobs of each type, with failure and flakiness rates from 5% in
+/// amber and from 10% in red. Uses the standard Helvetica fonts, so nothing
+/// is embedded.
+pub fn render(insights: &CIInsights, style: Style) -> Vec<u8> {
+    let mut layout = Layout::new();
+    layout.line(BOLD, 18.0, BLACK, "CI health report");
+    layout.line(REGULAR, 12.0, BLACK, &insights.project);
+    layout.line(
+        REGULAR,
+        9.0,
+        GREY,
+        &format!(
+            "{} report collected {}",
+            insights.provider,
+            style.timestamp(insights.collected_at)
+        ),
+    );
+
+    let summary = insights
+        .summary
+        .clone()
+        .unwrap_or_else(|| Summary::of(insights));
+    write_summary(&mut layout, insights, &summary, style);
+    write_pipeline_types(&mut layout, insights, style);
+    write_jobs(&mut layout, insights, style);
+
+    let mut pdf = Pdf::new();
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let regular_id = Ref::new(3);
+    let bold_id = Ref::new(4);
+    let info_id = Ref::new(5);
+    let mut next_id = 6;
+    let mut next_ref = || {
+        let id = Ref::new(next_id);
+        next_id += 1;
+        id
+    };
+
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.document_info(info_id)
+        .title(TextStr(&format!("CI health report: {}", insights.project)))
+        .producer(TextStr("cilens"));
+    pdf.type1_font(regular_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    let total_pages = layout.pages.len();
+    let mut page_ids = Vec::with_capacity(total_pages);
+    for (index, mut content) in layout.pages.into_iter().enumerate() {
+        content
+            .set_fill_rgb(GREY.0, GREY.1, GREY.2)
+            .begin_text()
+            .set_font(REGULAR, 8.0)
+            .next_line(MARGIN, MARGIN / 2.0)
+            .show(Str(
+                format!("Page {} of {total_pages}", index + 1).as_bytes()
+            ))
+            .end_text();
+
+        let page_id = next_ref();
+        let content_id = next_ref();
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(page_tree_id)
+            .contents(content_id);
+        page.resources()
+            .fonts()
+            .pair(REGULAR, regular_id)
+            .pair(BOLD, bold_id);
+        page.finish();
+        pdf.stream(content_id, &content.finish());
+        page_ids.push(page_id);
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pdf.pages(page_tree_id)
+        .kids(page_ids)
+        .count(total_pages as i32);
+
+    pdf.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    #[test]
+    fn test_win_ansi_replaces_unencodable_characters() {
+        assert_eq!(win_ansi("café ✓"), b"caf\xe9 ?");
+    }
+
+    #[test]
+    fn test_render_breaks_long_reports_into_pages() {
+        let jobs = (0..MAX_JOBS)
+            .map(|i| job(&format!("job-{i}"), 60.0, 5))
+            .collect::<Vec<_>>();
+        let types = (0..8)
+            .map(|i| pipeline_type(&format!("Type {i}"), 9, 1, jobs.clone()))
+            .collect();
+
+        let pdf = render(&insights(types), Style::default());
+        let text = String::from_utf8_lossy(&pdf);
+
+        let pages: usize = text
+            .split("/Count ")
+            .nth(1)
+            .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        assert!(text.starts_with("%PDF-"));
+        assert!(pages > 1);
+        assert!(text.contains(&format!("(Page {pages} of {pages})")));
+    }
+}
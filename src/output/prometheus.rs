@@ -0,0 +1,297 @@
+use std::fmt::Write;
+
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use log::info;
+use url::Url;
+
+use crate::error::{CILensError, Result};
+use crate::insights::CIInsights;
+
+pub struct Metric {
+    pub name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+}
+
+pub const PIPELINES_TOTAL: Metric = Metric {
+    name: "cilens_pipelines_total",
+    help: "Number of pipelines analyzed",
+    kind: "gauge",
+};
+pub const TYPE_PIPELINES_TOTAL: Metric = Metric {
+    name: "cilens_pipeline_type_pipelines_total",
+    help: "Number of pipelines in the pipeline type",
+    kind: "gauge",
+};
+pub const TYPE_SUCCESS_RATE: Metric = Metric {
+    name: "cilens_pipeline_type_success_rate",
+    help: "Percentage of successful pipelines in the pipeline type",
+    kind: "gauge",
+};
+pub const TYPE_DURATION: Metric = Metric {
+    name: "cilens_pipeline_type_duration_seconds",
+    help: "Average duration of successful pipelines in the pipeline type",
+    kind: "gauge",
+};
+pub const TYPE_TIME_TO_FEEDBACK: Metric = Metric {
+    name: "cilens_pipeline_type_time_to_feedback_seconds",
+    help: "Average time until the first job of the pipeline type finishes",
+    kind: "gauge",
+};
+pub const JOB_DURATION: Metric = Metric {
+    name: "cilens_job_duration_seconds",
+    help: "Average duration of the job",
+    kind: "gauge",
+};
+pub const JOB_TIME_TO_FEEDBACK: Metric = Metric {
+    name: "cilens_job_time_to_feedback_seconds",
+    help: "Average time from pipeline start until the job finishes",
+    kind: "gauge",
+};
+pub const JOB_FLAKINESS_RATE: Metric = Metric {
+    name: "cilens_job_flakiness_rate",
+    help: "Percentage of job executions that were flaky retries",
+    kind: "gauge",
+};
+pub const JOB_FAILURE_RATE: Metric = Metric {
+    name: "cilens_job_failure_rate",
+    help: "Percentage of job executions that failed without recovering",
+    kind: "gauge",
+};
+pub const JOB_EXECUTIONS_TOTAL: Metric = Metric {
+    name: "cilens_job_executions_total",
+    help: "Number of job executions, including retries",
+    kind: "gauge",
+};
+
+pub const JOB_SET_CHANGE: Metric = Metric {
+    name: "cilens_job_set_change_timestamp_seconds",
+    help: "When pipelines of the ref started running a different set of jobs",
+    kind: "gauge",
+};
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_labels(labels: &[(&str, &str)]) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(value)))
+        .collect();
+    labels.join(",")
+}
+
+#[derive(Default)]
+struct Exposition {
+    families: Vec<(&'static Metric, Vec<String>)>,
+}
+
+impl Exposition {
+    fn push(&mut self, metric: &'static Metric, labels: &[(&str, &str)], value: f64) {
+        let sample = format!("{}{{{}}} {value}", metric.name, format_labels(labels));
+        match self
+            .families
+            .iter_mut()
+            .find(|(m, _)| m.name == metric.name)
+        {
+            Some((_, samples)) => samples.push(sample),
+            None => self.families.push((metric, vec![sample])),
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        for (metric, samples) in self.families {
+            let _ = writeln!(out, "# HELP {} {}", metric.name, metric.help);
+            let _ = writeln!(out, "# TYPE {} {}", metric.name, metric.kind);
+            for sample in samples {
+                let _ = writeln!(out, "{sample}");
+            }
+        }
+        out
+    }
+}
+
+/// Renders insights in the Prometheus text exposition format.
+///
+/// Pipeline types are identified by their label plus their position in the
+/// report (`type_id`), since labels are not guaranteed to be unique.
+pub fn render(insights: &CIInsights) -> String {
+    render_all(std::slice::from_ref(insights))
+}
+
+/// Renders the reports of several projects as one exposition, so each metric
+/// family is declared once.
+pub fn render_all(reports: &[CIInsights]) -> String {
+    let mut exposition = Exposition::default();
+    for insights in reports {
+        push_insights(&mut exposition, insights);
+    }
+    exposition.finish()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn push_insights(exposition: &mut Exposition, insights: &CIInsights) {
+    let project = insights.project.as_str();
+
+    exposition.push(
+        &PIPELINES_TOTAL,
+        &[("project", project)],
+        insights.total_pipelines as f64,
+    );
+
+    for (index, pipeline_type) in insights.pipeline_types.iter().enumerate() {
+        let type_id = index.to_string();
+        let type_labels = [
+            ("project", project),
+            ("pipeline_type", pipeline_type.label.as_str()),
+            ("type_id", type_id.as_str()),
+        ];
+        let metrics = &pipeline_type.metrics;
+
+        exposition.push(
+            &TYPE_PIPELINES_TOTAL,
+            &type_labels,
+            metrics.total_pipelines as f64,
+        );
+        exposition.push(&TYPE_SUCCESS_RATE, &type_labels, metrics.success_rate);
+        exposition.push(&TYPE_DURATION, &type_labels, metrics.avg_duration_seconds);
+        exposition.push(
+            &TYPE_TIME_TO_FEEDBACK,
+            &type_labels,
+            metrics.avg_time_to_feedback_seconds,
+        );
+
+        for job in &metrics.jobs {
+            let job_labels = [
+                type_labels[0],
+                type_labels[1],
+                type_labels[2],
+                ("job", job.name.as_str()),
+            ];
+
+            exposition.push(&JOB_DURATION, &job_labels, job.avg_duration_seconds);
+            exposition.push(
+                &JOB_TIME_TO_FEEDBACK,
+                &job_labels,
+                job.avg_time_to_feedback_seconds,
+            );
+            exposition.push(&JOB_FLAKINESS_RATE, &job_labels, job.flakiness_rate);
+            exposition.push(&JOB_FAILURE_RATE, &job_labels, job.failure_rate);
+            exposition.push(
+                &JOB_EXECUTIONS_TOTAL,
+                &job_labels,
+                job.total_executions as f64,
+            );
+        }
+    }
+
+    for change in insights.composition_changes.iter().flatten() {
+        exposition.push(
+            &JOB_SET_CHANGE,
+            &[
+                ("project", project),
+                ("ref", change.ref_.as_str()),
+                ("pipeline", change.pipeline.as_str()),
+                ("summary", change.summary.as_str()),
+            ],
+            change.changed_at.timestamp() as f64,
+        );
+    }
+}
+
+/// Pushes rendered metrics to a Prometheus Pushgateway.
+///
+/// Metrics are grouped by `job` and `project`, so runs for different
+/// projects sharing a job name do not overwrite each other.
+pub async fn push_to_gateway(gateway: &str, job: &str, insights: &CIInsights) -> Result<()> {
+    let base = Url::parse(gateway)
+        .map_err(|e| CILensError::Config(format!("Invalid Pushgateway URL: {e}")))?;
+
+    let path = format!(
+        "metrics/job@base64/{}/project@base64/{}",
+        URL_SAFE.encode(job),
+        URL_SAFE.encode(&insights.project)
+    );
+
+    let url = base
+        .join(&path)
+        .map_err(|e| CILensError::Config(format!("Invalid Pushgateway URL: {e}")))?;
+
+    reqwest::Client::new()
+        .put(url)
+        .header(reqwest::header::CONTENT_TYPE, "text/plain; version=0.0.4")
+        .body(render(insights))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    info!("Metrics pushed to Pushgateway: {gateway}");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    fn sample_insights() -> CIInsights {
+        let mut lint = job("lint", 30.0, 4);
+        lint.failure_rate = 25.0;
+
+        let mut pipeline_type = pipeline_type("Development \"Pipeline\"", 3, 1, vec![lint]);
+        pipeline_type.metrics.success_rate = 75.0;
+        insights(vec![pipeline_type])
+    }
+
+    #[test]
+    fn test_render_emits_help_and_type_once_per_family() {
+        let output = render(&sample_insights());
+
+        assert_eq!(
+            output
+                .matches("# TYPE cilens_job_duration_seconds gauge")
+                .count(),
+            1
+        );
+        assert!(output.contains("cilens_pipelines_total{project=\"group/project\"} 4"));
+    }
+
+    #[test]
+    fn test_render_escapes_label_values() {
+        let output = render(&sample_insights());
+
+        assert!(output.contains(
+            "cilens_pipeline_type_success_rate{project=\"group/project\",pipeline_type=\"Development \\\"Pipeline\\\"\",type_id=\"0\"} 75"
+        ));
+    }
+
+    #[test]
+    fn test_render_all_declares_families_once_across_projects() {
+        let mut other = sample_insights();
+        other.project = "group/other".to_string();
+
+        let output = render_all(&[sample_insights(), other]);
+
+        assert_eq!(
+            output
+                .matches("# TYPE cilens_pipelines_total gauge")
+                .count(),
+            1
+        );
+        assert!(output.contains("cilens_pipelines_total{project=\"group/other\"} 4"));
+    }
+
+    #[test]
+    fn test_render_includes_job_labels() {
+        let output = render(&sample_insights());
+
+        assert!(output.contains("job=\"lint\"} 25"));
+    }
+}
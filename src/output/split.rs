@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use log::info;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::Result;
+use crate::insights::CIInsights;
+
+const INDEX_FILE: &str = "index.json";
+
+/// File name for a pipeline type; the position keeps names unique since
+/// labels are not.
+fn file_name(index: usize, label: &str) -> String {
+    let slug = label
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    format!("{index:02}-{slug}.json")
+}
+
+/// Splits a report into one document per pipeline type plus an index holding
+/// every other field, with `pipeline_types` replaced by a table of contents.
+fn split(insights: &CIInsights) -> Result<Vec<(String, Value)>> {
+    let mut files = Vec::with_capacity(insights.pipeline_types.len() + 1);
+    let mut contents = Vec::with_capacity(insights.pipeline_types.len());
+
+    for (index, pipeline_type) in insights.pipeline_types.iter().enumerate() {
+        let name = file_name(index, &pipeline_type.label);
+        contents.push(json!({
+            "label": pipeline_type.label,
+            "file": name,
+            "total_pipelines": pipeline_type.metrics.total_pipelines,
+            "success_rate": pipeline_type.metrics.success_rate,
+            "avg_duration_seconds": pipeline_type.metrics.avg_duration_seconds,
+        }));
+        files.push((name, serde_json::to_value(pipeline_type)?));
+    }
+
+    let mut index = serde_json::to_value(insights)?;
+    index["pipeline_types"] = Value::Array(contents);
+    files.insert(0, (INDEX_FILE.to_string(), index));
+
+    Ok(files)
+}
+
+fn to_string<T: Serialize>(value: &T, pretty: bool) -> Result<String> {
+    Ok(if pretty {
+        serde_json::to_string_pretty(value)?
+    } else {
+        serde_json::to_string(value)?
+    })
+}
+
+/// Writes the report to `dir` as `index.json` plus one JSON file per pipeline type.
+pub fn write(dir: &Path, insights: &CIInsights, pretty: bool) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    let files = split(insights)?;
+    for (name, value) in &files {
+        std::fs::write(dir.join(name), to_string(value, pretty)?)?;
+    }
+
+    info!("Wrote {} files to: {}", files.len(), dir.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, pipeline_type};
+
+    #[test]
+    fn test_split_writes_index_and_one_file_per_type() {
+        let report = insights(vec![
+            pipeline_type("Merge Request (main)", 3, 1, vec![]),
+            pipeline_type("Merge Request (main)", 1, 0, vec![]),
+        ]);
+
+        let files = split(&report).unwrap();
+        let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "index.json",
+                "00-merge-request-main.json",
+                "01-merge-request-main.json"
+            ]
+        );
+        assert_eq!(files[0].1["project"], "group/project");
+        assert_eq!(
+            files[0].1["pipeline_types"][1]["file"],
+            "01-merge-request-main.json"
+        );
+        assert_eq!(files[2].1["metrics"]["total_pipelines"], 1);
+    }
+}
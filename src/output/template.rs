@@ -0,0 +1,64 @@
+use std::error::Error as _;
+use std::path::Path;
+
+use serde::Serialize;
+use tera::{Context, Tera};
+
+use crate::error::{CILensError, Result};
+
+/// Renders a report through a user-supplied Tera template.
+///
+/// The report's JSON fields are the template's top-level variables, e.g.
+/// `{{ project }}` or `{% for type in pipeline_types %}`; a combined
+/// multi-project report exposes `projects` instead.
+pub fn render<T: Serialize>(path: &Path, report: &T) -> Result<String> {
+    let template = std::fs::read_to_string(path)?;
+    render_str(&template, report)
+        .map_err(|e| CILensError::Config(format!("Template {}: {e}", path.display())))
+}
+
+fn render_str<T: Serialize>(template: &str, report: &T) -> std::result::Result<String, String> {
+    let context = Context::from_serialize(report).map_err(|e| describe(&e))?;
+    Tera::one_off(template, &context, false).map_err(|e| describe(&e))
+}
+
+/// Tera keeps the actual cause (unknown variable, syntax error) in the source chain.
+fn describe(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_exposes_report_fields() {
+        let report = json!({
+            "project": "group/project",
+            "pipeline_types": [{"label": "Default"}, {"label": "Release"}],
+        });
+
+        let output = render_str(
+            "# {{ project }}\n{% for type in pipeline_types %}- {{ type.label }}\n{% endfor %}",
+            &report,
+        )
+        .unwrap();
+
+        assert_eq!(output, "# group/project\n- Default\n- Release\n");
+    }
+
+    #[test]
+    fn test_render_reports_unknown_variables() {
+        let error = render_str("{{ missing }}", &json!({})).unwrap_err();
+
+        assert!(error.contains("missing"));
+    }
+}
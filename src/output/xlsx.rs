@@ -0,0 +1,292 @@
+use std::collections::HashSet;
+
+use rust_xlsxwriter::{
+    ConditionalFormatCell, ConditionalFormatCellRule, Format, FormatBorder, Workbook, Worksheet,
+    XlsxError,
+};
+
+use crate::error::{CILensError, Result};
+use crate::insights::{CIInsights, PipelineType};
+
+const SUMMARY_SHEET: &str = "Summary";
+
+/// Failure and flakiness rates at or above these are highlighted amber and red.
+const WARNING_RATE: f64 = 0.05;
+const CRITICAL_RATE: f64 = 0.10;
+
+const SUMMARY_HEADERS: [&str; 9] = [
+    "Pipeline type",
+    "Share",
+    "Pipelines",
+    "Success rate",
+    "Failure rate",
+    "Flakiness rate",
+    "Avg duration (s)",
+    "Avg time to feedback (s)",
+    "Failed pipeline minutes",
+];
+
+const JOB_HEADERS: [&str; 7] = [
+    "Job",
+    "Executions",
+    "Failure rate",
+    "Flakiness rate",
+    "Avg duration (s)",
+    "Avg time to feedback (s)",
+    "Wasted retry minutes",
+];
+
+/// Header row of the summary table; the rows above hold the project and collection time.
+const SUMMARY_HEADER_ROW: u32 = 3;
+
+struct Formats {
+    header: Format,
+    percent: Format,
+    number: Format,
+    warning: Format,
+    critical: Format,
+}
+
+impl Formats {
+    fn new() -> Self {
+        Self {
+            header: Format::new()
+                .set_bold()
+                .set_background_color("D9E1F2")
+                .set_border_bottom(FormatBorder::Thin),
+            percent: Format::new().set_num_format("0.0%"),
+            number: Format::new().set_num_format("0.0"),
+            warning: Format::new()
+                .set_background_color("FFEB9C")
+                .set_font_color("9C5700"),
+            critical: Format::new()
+                .set_background_color("FFC7CE")
+                .set_font_color("9C0006"),
+        }
+    }
+}
+
+/// Flaky retries over executions across the jobs of `pipeline_type`, in percent.
+#[allow(clippy::cast_precision_loss)]
+fn flakiness_rate(pipeline_type: &PipelineType) -> f64 {
+    let (flaky, executions) =
+        pipeline_type
+            .metrics
+            .jobs
+            .iter()
+            .fold((0, 0), |(flaky, executions), job| {
+                (
+                    flaky + job.flaky_retries.count,
+                    executions + job.total_executions,
+                )
+            });
+
+    if executions == 0 {
+        0.0
+    } else {
+        flaky as f64 / executions as f64 * 100.0
+    }
+}
+
+/// Excel sheet names are at most 31 characters and cannot contain `[]:*?/\`;
+/// clashes get a numeric suffix.
+fn sheet_name(label: &str, taken: &mut HashSet<String>) -> String {
+    let base: String = label
+        .chars()
+        .map(|c| if "[]:*?/\\".contains(c) { '-' } else { c })
+        .collect::<String>()
+        .trim_matches('\'')
+        .chars()
+        .take(31)
+        .collect();
+    let base = if base.is_empty() {
+        "Pipeline type".to_string()
+    } else {
+        base
+    };
+
+    let mut name = base.clone();
+    let mut suffix = 2;
+    while taken.contains(&name.to_lowercase()) {
+        let tag = format!(" ({suffix})");
+        name = base.chars().take(31 - tag.len()).collect::<String>() + &tag;
+        suffix += 1;
+    }
+    taken.insert(name.to_lowercase());
+    name
+}
+
+/// Colors the rate cells of `col` (rows `first_row..=last_row`) amber or red.
+fn highlight_rates(
+    sheet: &mut Worksheet,
+    formats: &Formats,
+    first_row: u32,
+    last_row: u32,
+    col: u16,
+) -> std::result::Result<(), XlsxError> {
+    if last_row < first_row {
+        return Ok(());
+    }
+
+    let critical = ConditionalFormatCell::new()
+        .set_rule(ConditionalFormatCellRule::GreaterThanOrEqualTo(
+            CRITICAL_RATE,
+        ))
+        .set_format(&formats.critical);
+    let warning = ConditionalFormatCell::new()
+        .set_rule(ConditionalFormatCellRule::Between(
+            WARNING_RATE,
+            CRITICAL_RATE,
+        ))
+        .set_format(&formats.warning);
+    sheet.add_conditional_format(first_row, col, last_row, col, &critical)?;
+    sheet.add_conditional_format(first_row, col, last_row, col, &warning)?;
+
+    Ok(())
+}
+
+fn write_summary(
+    sheet: &mut Worksheet,
+    formats: &Formats,
+    insights: &CIInsights,
+) -> std::result::Result<(), XlsxError> {
+    sheet.set_name(SUMMARY_SHEET)?;
+    sheet.write_string_with_format(0, 0, "Project", &formats.header)?;
+    sheet.write_string(0, 1, &insights.project)?;
+    sheet.write_string_with_format(1, 0, "Collected at", &formats.header)?;
+    sheet.write_string(1, 1, insights.collected_at.to_rfc3339())?;
+
+    sheet.write_row_with_format(SUMMARY_HEADER_ROW, 0, SUMMARY_HEADERS, &formats.header)?;
+    let mut row = SUMMARY_HEADER_ROW;
+    for pipeline_type in &insights.pipeline_types {
+        row += 1;
+        let metrics = &pipeline_type.metrics;
+        #[allow(clippy::cast_precision_loss)]
+        let total_pipelines = metrics.total_pipelines as f64;
+        sheet.write_string(row, 0, &pipeline_type.label)?;
+        sheet.write_number_with_format(row, 1, metrics.percentage / 100.0, &formats.percent)?;
+        sheet.write_number(row, 2, total_pipelines)?;
+        sheet.write_number_with_format(row, 3, metrics.success_rate / 100.0, &formats.percent)?;
+        sheet.write_number_with_format(
+            row,
+            4,
+            (100.0 - metrics.success_rate) / 100.0,
+            &formats.percent,
+        )?;
+        sheet.write_number_with_format(
+            row,
+            5,
+            flakiness_rate(pipeline_type) / 100.0,
+            &formats.percent,
+        )?;
+        sheet.write_number_with_format(row, 6, metrics.avg_duration_seconds, &formats.number)?;
+        sheet.write_number_with_format(
+            row,
+            7,
+            metrics.avg_time_to_feedback_seconds,
+            &formats.number,
+        )?;
+        sheet.write_number_with_format(row, 8, metrics.failed_pipeline_minutes, &formats.number)?;
+    }
+
+    highlight_rates(sheet, formats, SUMMARY_HEADER_ROW + 1, row, 4)?;
+    highlight_rates(sheet, formats, SUMMARY_HEADER_ROW + 1, row, 5)?;
+    sheet.set_freeze_panes(SUMMARY_HEADER_ROW + 1, 1)?;
+    sheet.autofit();
+
+    Ok(())
+}
+
+fn write_pipeline_type(
+    sheet: &mut Worksheet,
+    formats: &Formats,
+    name: &str,
+    pipeline_type: &PipelineType,
+) -> std::result::Result<(), XlsxError> {
+    sheet.set_name(name)?;
+    sheet.write_row_with_format(0, 0, JOB_HEADERS, &formats.header)?;
+
+    let mut row = 0;
+    for job in &pipeline_type.metrics.jobs {
+        row += 1;
+        #[allow(clippy::cast_precision_loss)]
+        let total_executions = job.total_executions as f64;
+        sheet.write_string(row, 0, &job.name)?;
+        sheet.write_number(row, 1, total_executions)?;
+        sheet.write_number_with_format(row, 2, job.failure_rate / 100.0, &formats.percent)?;
+        sheet.write_number_with_format(row, 3, job.flakiness_rate / 100.0, &formats.percent)?;
+        sheet.write_number_with_format(row, 4, job.avg_duration_seconds, &formats.number)?;
+        sheet.write_number_with_format(
+            row,
+            5,
+            job.avg_time_to_feedback_seconds,
+            &formats.number,
+        )?;
+        sheet.write_number_with_format(row, 6, job.wasted_retry_minutes, &formats.number)?;
+    }
+
+    highlight_rates(sheet, formats, 1, row, 2)?;
+    highlight_rates(sheet, formats, 1, row, 3)?;
+    sheet.set_freeze_panes(1, 1)?;
+    sheet.autofit();
+
+    Ok(())
+}
+
+fn build(insights: &CIInsights) -> std::result::Result<Vec<u8>, XlsxError> {
+    let formats = Formats::new();
+    let mut workbook = Workbook::new();
+    write_summary(workbook.add_worksheet(), &formats, insights)?;
+
+    let mut taken = HashSet::from([SUMMARY_SHEET.to_lowercase()]);
+    for pipeline_type in &insights.pipeline_types {
+        let name = sheet_name(&pipeline_type.label, &mut taken);
+        write_pipeline_type(workbook.add_worksheet(), &formats, &name, pipeline_type)?;
+    }
+
+    workbook.save_to_buffer()
+}
+
+/// Renders `insights` as an Excel workbook: a summary sheet with one row per
+/// pipeline type, then one sheet per pipeline type listing its jobs. Failure
+/// and flakiness rates are highlighted from 5% (amber) and 10% (red).
+pub fn render(insights: &CIInsights) -> Result<Vec<u8>> {
+    build(insights).map_err(|e| CILensError::Export(format!("Could not build workbook: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+
+    #[test]
+    fn test_sheet_names_are_sanitized_and_unique() {
+        let mut taken = HashSet::from([SUMMARY_SHEET.to_lowercase()]);
+
+        assert_eq!(
+            sheet_name("MR: main/feature", &mut taken),
+            "MR- main-feature"
+        );
+        assert_eq!(sheet_name("summary", &mut taken), "summary (2)");
+        assert_eq!(
+            sheet_name("A very long pipeline type label indeed", &mut taken),
+            "A very long pipeline type label"
+        );
+        assert_eq!(
+            sheet_name("A very long pipeline type label indeed", &mut taken),
+            "A very long pipeline type l (2)"
+        );
+    }
+
+    #[test]
+    fn test_render_produces_a_zip_container() {
+        let report = insights(vec![
+            pipeline_type("MR", 9, 1, vec![job("lint", 30.0, 10)]),
+            pipeline_type("Main", 5, 0, vec![job("build", 120.0, 5)]),
+        ]);
+
+        let workbook = render(&report).unwrap();
+
+        assert_eq!(&workbook[..2], b"PK");
+    }
+}
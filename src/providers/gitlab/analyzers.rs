@@ -0,0 +1,122 @@
+use chrono_tz::Tz;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::types::GitLabPipeline;
+use crate::error::Result;
+
+/// An analysis over the collected pipelines that contributes one named
+/// section to the report.
+///
+/// Register custom analyzers with [`GitLabProvider::with_analyzer`]; their
+/// sections land in `CIInsights::sections`, keyed by `name()`. One named like
+/// a built-in section (`merge_trains`, `developer_wait`, `releases`) replaces
+/// it and fills its field instead. Pipeline types and the job metrics within
+/// them (flakiness, critical path) are the report itself rather than
+/// sections, so they are not analyzers.
+///
+/// [`GitLabProvider::with_analyzer`]: super::GitLabProvider::with_analyzer
+pub trait Analyzer: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// `None` leaves the section out, e.g. when no pipeline was relevant.
+    ///
+    /// # Errors
+    ///
+    /// An error fails the whole collection.
+    fn analyze(&self, pipelines: &[GitLabPipeline]) -> Result<Option<Value>>;
+}
+
+/// Wraps a built-in analysis returning a typed section.
+struct Builtin<F> {
+    name: &'static str,
+    analyze: F,
+}
+
+impl<F, T> Analyzer for Builtin<F>
+where
+    F: Fn(&[GitLabPipeline]) -> Option<T> + Send + Sync,
+    T: Serialize,
+{
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn analyze(&self, pipelines: &[GitLabPipeline]) -> Result<Option<Value>> {
+        Ok((self.analyze)(pipelines)
+            .map(|section| serde_json::to_value(section))
+            .transpose()?)
+    }
+}
+
+/// Analyzers every report runs, in report order, bucketing weekly trends in
+/// `timezone`.
+pub fn builtin(timezone: Tz) -> Vec<Box<dyn Analyzer>> {
+    vec![
+        Box::new(Builtin {
+            name: "merge_trains",
+            analyze: super::merge_trains::analyze_merge_trains,
+        }),
+        Box::new(Builtin {
+            name: "developer_wait",
+            analyze: move |pipelines: &[GitLabPipeline]| {
+                super::developer_wait::calculate_developer_wait(pipelines, timezone)
+            },
+        }),
+        Box::new(Builtin {
+            name: "releases",
+            analyze: super::releases::calculate_release_metrics,
+        }),
+    ]
+}
+
+/// Runs `analyzers` in order, returning the sections they produced.
+pub fn run(
+    analyzers: &[Box<dyn Analyzer>],
+    pipelines: &[GitLabPipeline],
+) -> Result<Vec<(String, Value)>> {
+    let mut sections = Vec::new();
+    for analyzer in analyzers {
+        if let Some(section) = analyzer.analyze(pipelines)? {
+            sections.push((analyzer.name().to_string(), section));
+        }
+    }
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+    use serde_json::json;
+
+    struct PipelineCount;
+
+    impl Analyzer for PipelineCount {
+        fn name(&self) -> &'static str {
+            "pipeline_count"
+        }
+
+        fn analyze(&self, pipelines: &[GitLabPipeline]) -> Result<Option<Value>> {
+            Ok(Some(json!({ "count": pipelines.len() })))
+        }
+    }
+
+    #[test]
+    fn test_run_collects_named_sections_and_skips_empty_ones() {
+        let mut analyzers = builtin(Tz::UTC);
+        analyzers.push(Box::new(PipelineCount));
+        let pipelines = vec![pipeline(
+            1,
+            "success",
+            "2025-01-01T10:00:00Z",
+            "2025-01-01T10:05:00Z",
+        )];
+
+        let sections = run(&analyzers, &pipelines).unwrap();
+        let names: Vec<&str> = sections.iter().map(|(name, _)| name.as_str()).collect();
+
+        assert!(!names.contains(&"merge_trains"));
+        assert_eq!(sections.last().unwrap().1, json!({ "count": 1 }));
+    }
+}
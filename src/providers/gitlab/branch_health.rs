@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use super::types::GitLabPipeline;
+use crate::insights::{BranchHealth, RefMetrics};
+
+const MAIN_BRANCHES: [&str; 2] = ["main", "master"];
+
+/// Buckets a pipeline's ref into main branches (by name), tags, merge
+/// requests and other (feature) branches.
+fn ref_pattern(pipeline: &GitLabPipeline) -> String {
+    if pipeline.ref_path.starts_with("refs/tags/") {
+        "tags".to_string()
+    } else if pipeline.ref_path.starts_with("refs/merge-requests/") {
+        "merge requests".to_string()
+    } else if MAIN_BRANCHES.contains(&pipeline.ref_.as_str()) {
+        pipeline.ref_.clone()
+    } else {
+        "feature branches".to_string()
+    }
+}
+
+/// Health of `pipelines` per ref pattern, most active pattern first.
+pub fn calculate_ref_metrics(pipelines: &[&GitLabPipeline]) -> Vec<RefMetrics> {
+    let mut by_pattern: BTreeMap<String, Vec<&GitLabPipeline>> = BTreeMap::new();
+    for pipeline in pipelines {
+        by_pattern
+            .entry(ref_pattern(pipeline))
+            .or_default()
+            .push(pipeline);
+    }
+
+    let mut metrics: Vec<RefMetrics> = by_pattern
+        .into_iter()
+        .map(|(ref_pattern, pipelines)| RefMetrics {
+            ref_pattern,
+            health: calculate_branch_health(pipelines),
+        })
+        .collect();
+
+    metrics.sort_by_key(|m| std::cmp::Reverse(m.health.total_pipelines));
+    metrics
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_branch_health<'a>(
+    pipelines: impl IntoIterator<Item = &'a GitLabPipeline>,
+) -> BranchHealth {
+    let pipelines: Vec<&GitLabPipeline> = pipelines.into_iter().collect();
+    let total_pipelines = pipelines.len();
+    if total_pipelines == 0 {
+        return BranchHealth {
+            total_pipelines,
+            success_rate: 0.0,
+            avg_duration_seconds: 0.0,
+        };
+    }
+
+    let successful = pipelines.iter().filter(|p| p.status == "success").count();
+    let failed = pipelines.iter().filter(|p| p.status == "failed").count();
+    let total_duration: usize = pipelines.iter().map(|p| p.duration).sum();
+
+    BranchHealth {
+        total_pipelines,
+        success_rate: successful as f64 / (successful + failed).max(1) as f64 * 100.0,
+        avg_duration_seconds: total_duration as f64 / total_pipelines as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+
+    #[test]
+    fn test_calculate_branch_health() {
+        let pipelines = vec![
+            pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:01:40Z"),
+            pipeline(2, "failed", "2025-01-01T11:00:00Z", "2025-01-01T11:05:00Z"),
+        ];
+
+        let health = calculate_branch_health(&pipelines);
+
+        assert_eq!(health.total_pipelines, 2);
+        assert!((health.success_rate - 50.0).abs() < f64::EPSILON);
+        assert!((health.avg_duration_seconds - 200.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_calculate_ref_metrics() {
+        let mut tag = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:01:00Z");
+        tag.ref_ = "v1.0".to_string();
+        tag.ref_path = "refs/tags/v1.0".to_string();
+        let mut feature = pipeline(2, "failed", "2025-01-01T11:00:00Z", "2025-01-01T11:01:00Z");
+        feature.ref_ = "fix-login".to_string();
+        feature.ref_path = "refs/heads/fix-login".to_string();
+        let main = pipeline(3, "success", "2025-01-01T12:00:00Z", "2025-01-01T12:01:00Z");
+        let main_again = pipeline(4, "success", "2025-01-01T13:00:00Z", "2025-01-01T13:01:00Z");
+
+        let metrics = calculate_ref_metrics(&[&tag, &feature, &main, &main_again]);
+
+        let patterns: Vec<&str> = metrics.iter().map(|m| m.ref_pattern.as_str()).collect();
+        assert_eq!(patterns, vec!["main", "feature branches", "tags"]);
+        assert!(metrics[1].health.success_rate.abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+
+use crate::insights::CiMinutesUsage;
+
+/// First day of the month of `date`.
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn days_in_month(month: NaiveDate) -> i64 {
+    let next = if month.month() == 12 {
+        NaiveDate::from_ymd_opt(month.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(month.year(), month.month() + 1, 1)
+    };
+    next.map_or(30, |next| (next - month).num_days())
+}
+
+/// Quota burn of a namespace as of `now`: minutes left, and minutes used by the
+/// end of the month if the month-to-date burn rate holds.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+pub fn calculate_ci_minutes_usage(
+    namespace: String,
+    used_minutes: u64,
+    quota_minutes: Option<u64>,
+    now: DateTime<Utc>,
+) -> CiMinutesUsage {
+    let month = month_start(now.date_naive());
+    let month_seconds = (days_in_month(month) * 86_400) as f64;
+    let elapsed_seconds = (now.naive_utc() - month.and_hms_opt(0, 0, 0).unwrap_or_default())
+        .num_seconds()
+        .max(1) as f64;
+    let minutes_per_second = used_minutes as f64 / elapsed_seconds;
+    let projected_minutes = (minutes_per_second * month_seconds).round() as u64;
+
+    let exhausted_on = quota_minutes
+        .filter(|quota| projected_minutes > *quota && minutes_per_second > 0.0)
+        .map(|quota| {
+            let seconds = (quota as f64 / minutes_per_second) as i64;
+            (month.and_hms_opt(0, 0, 0).unwrap_or_default() + Duration::seconds(seconds)).date()
+        });
+
+    CiMinutesUsage {
+        namespace,
+        month,
+        used_minutes,
+        quota_minutes,
+        remaining_minutes: quota_minutes.map(|quota| quota.saturating_sub(used_minutes)),
+        projected_minutes,
+        exhausted_on,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection_extrapolates_month_to_date_burn() {
+        // Ten of thirty days into June
+        let now = "2024-06-11T00:00:00Z".parse().unwrap();
+
+        let usage = calculate_ci_minutes_usage("group".to_string(), 1000, Some(2000), now);
+
+        assert_eq!(usage.month, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+        assert_eq!(usage.remaining_minutes, Some(1000));
+        assert_eq!(usage.projected_minutes, 3000);
+        assert_eq!(usage.exhausted_on, NaiveDate::from_ymd_opt(2024, 6, 21));
+    }
+
+    #[test]
+    fn test_no_exhaustion_within_quota() {
+        let now = "2024-12-16T12:00:00Z".parse().unwrap();
+
+        let usage = calculate_ci_minutes_usage("group".to_string(), 100, Some(10_000), now);
+
+        assert!(usage.exhausted_on.is_none());
+        assert_eq!(days_in_month(usage.month), 31);
+    }
+}
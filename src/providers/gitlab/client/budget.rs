@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use serde::Deserialize;
+
+use crate::error::{CILensError, Result};
+use crate::insights::ApiUsage;
+
+/// Counts the API requests of a client and enforces `--max-requests`.
+#[derive(Debug, Default)]
+pub struct RequestBudget {
+    max_requests: Option<usize>,
+    graphql_requests: AtomicUsize,
+    rest_requests: AtomicUsize,
+    graphql_complexity: AtomicU64,
+}
+
+/// Just the `queryComplexity` every query selects, read next to the typed data.
+#[derive(Deserialize)]
+pub struct ComplexityProbe {
+    data: Option<ComplexityData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComplexityData {
+    query_complexity: Option<QueryComplexity>,
+}
+
+#[derive(Deserialize)]
+struct QueryComplexity {
+    score: Option<u64>,
+}
+
+impl RequestBudget {
+    pub fn new(max_requests: Option<usize>) -> Self {
+        Self {
+            max_requests,
+            ..Self::default()
+        }
+    }
+
+    /// Accounts for one more request, failing once the budget is spent.
+    pub fn acquire(&self, graphql: bool) -> Result<()> {
+        let counter = if graphql {
+            &self.graphql_requests
+        } else {
+            &self.rest_requests
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        let used = self.requests();
+        match self.max_requests {
+            Some(max) if used > max => Err(CILensError::BudgetExceeded(format!(
+                "reached --max-requests {max}; raise it or lower --limit"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn record_complexity(&self, probe: &ComplexityProbe) {
+        let score = probe
+            .data
+            .as_ref()
+            .and_then(|data| data.query_complexity.as_ref())
+            .and_then(|complexity| complexity.score)
+            .unwrap_or(0);
+        self.graphql_complexity.fetch_add(score, Ordering::Relaxed);
+    }
+
+    fn requests(&self) -> usize {
+        self.graphql_requests.load(Ordering::Relaxed) + self.rest_requests.load(Ordering::Relaxed)
+    }
+
+    pub fn usage(&self) -> ApiUsage {
+        ApiUsage {
+            requests: self.requests(),
+            graphql_requests: self.graphql_requests.load(Ordering::Relaxed),
+            rest_requests: self.rest_requests.load(Ordering::Relaxed),
+            graphql_complexity: self.graphql_complexity.load(Ordering::Relaxed),
+            max_requests: self.max_requests,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_fails_past_the_budget() {
+        let budget = RequestBudget::new(Some(2));
+
+        assert!(budget.acquire(true).is_ok());
+        assert!(budget.acquire(false).is_ok());
+        assert!(matches!(
+            budget.acquire(true),
+            Err(CILensError::BudgetExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn test_record_complexity_sums_scores() {
+        let budget = RequestBudget::new(None);
+        let probe: ComplexityProbe =
+            serde_json::from_str(r#"{"data": {"queryComplexity": {"score": 42}}}"#).unwrap();
+
+        budget.record_complexity(&probe);
+        budget.record_complexity(&probe);
+
+        assert_eq!(budget.usage().graphql_complexity, 84);
+    }
+}
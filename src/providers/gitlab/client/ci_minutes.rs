@@ -0,0 +1,92 @@
+use chrono::NaiveDate;
+use graphql_client::GraphQLQuery;
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::{CILensError, Result};
+
+pub type NamespaceID = String;
+pub type Date = NaiveDate;
+#[allow(clippy::upper_case_acronyms)]
+pub type ISO8601Date = NaiveDate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/providers/gitlab/client/schema.json",
+    query_path = "src/providers/gitlab/client/ci_minutes.graphql",
+    response_derives = "Debug"
+)]
+pub struct FetchCiMinutesUsage;
+
+/// A namespace as returned by `GET /namespaces/:id`. The minutes limits are
+/// only visible on GitLab.com, to namespace owners.
+#[derive(Debug, Deserialize)]
+pub struct Namespace {
+    pub id: u64,
+    pub full_path: String,
+    #[serde(default)]
+    pub shared_runners_minutes_limit: Option<u64>,
+    #[serde(default)]
+    pub extra_shared_runners_minutes_limit: Option<u64>,
+}
+
+impl GitLabClient {
+    /// Fetches the namespace at `full_path` (a group or user namespace).
+    pub async fn fetch_namespace(&self, full_path: &str) -> Result<Namespace> {
+        let encoded: String = url::form_urlencoded::byte_serialize(full_path.as_bytes()).collect();
+        let url = self
+            .api_url
+            .join(&format!("namespaces/{encoded}"))
+            .map_err(|e| CILensError::Config(format!("Invalid REST API URL: {e}")))?;
+
+        let response = Self::check_response(self.send_rest(self.client.get(url)).await?)?;
+        Ok(response.json().await?)
+    }
+
+    /// Fetches the shared runner minutes `namespace_id` consumed in the month of `month`.
+    pub async fn fetch_ci_minutes_used(&self, namespace_id: u64, month: NaiveDate) -> Result<u64> {
+        let request_body = FetchCiMinutesUsage::build_query(fetch_ci_minutes_usage::Variables {
+            namespace_id: format!("gid://gitlab/Namespace/{namespace_id}"),
+            date: month,
+        });
+
+        let data: fetch_ci_minutes_usage::ResponseData = self.post_graphql(&request_body).await?;
+
+        let minutes = data
+            .ci_minutes_usage
+            .and_then(|usage| usage.nodes)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|node| node.month_iso8601 == Some(month))
+            .filter_map(|node| node.minutes)
+            .sum::<i64>();
+
+        Ok(u64::try_from(minutes).unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_ci_minutes_used_picks_requested_month() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .with_body(
+                r#"{"data": {"ciMinutesUsage": {"nodes": [
+                    {"monthIso8601": "2024-05-01", "minutes": 1200},
+                    {"monthIso8601": "2024-04-01", "minutes": 3000}
+                ]}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let month = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+
+        assert_eq!(client.fetch_ci_minutes_used(42, month).await.unwrap(), 1200);
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::Result;
+
+const PER_PAGE: &str = "100";
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct Commit {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+impl GitLabClient {
+    /// Fetches the commits of `ref_` committed after `since`, newest first.
+    pub async fn fetch_commits(
+        &self,
+        project_path: &str,
+        ref_: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Commit>> {
+        let url = self.project_api_url(project_path, "repository/commits")?;
+        let since = since.to_rfc3339();
+
+        let mut commits = Vec::new();
+        let mut page = 1;
+
+        while page <= MAX_PAGES {
+            let request = self.client.get(url.clone()).query(&[
+                ("ref_name", ref_),
+                ("since", since.as_str()),
+                ("per_page", PER_PAGE),
+                ("page", &page.to_string()),
+            ]);
+            let response = Self::check_response(self.send_rest(request).await?)?;
+
+            let has_next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| !v.is_empty());
+
+            let batch: Vec<Commit> = response.json().await?;
+            commits.extend(batch);
+
+            if !has_next_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(commits)
+    }
+}
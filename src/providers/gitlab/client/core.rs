@@ -1,21 +1,81 @@
-use reqwest::Client;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use log::warn;
+
+use reqwest::{Client, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use url::Url;
 
+use super::budget::{ComplexityProbe, RequestBudget};
+use super::introspection::SchemaSupport;
+use super::middleware::{Middleware, Next};
 use crate::auth::Token;
 use crate::error::{CILensError, Result};
 
+/// Timeouts and connection pooling of the HTTP client.
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    /// Whole request, from connecting until the response body is read
+    pub request_timeout: Duration,
+    pub connect_timeout: Duration,
+    /// Idle keep-alive connections kept open per host
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout: Duration,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_mins(1),
+            connect_timeout: Duration::from_secs(10),
+            pool_max_idle_per_host: 32,
+            pool_idle_timeout: Duration::from_secs(90),
+        }
+    }
+}
+
+impl HttpOptions {
+    fn build_client(&self) -> Result<Client> {
+        Client::builder()
+            .user_agent("CILens/0.1.0")
+            .timeout(self.request_timeout)
+            .connect_timeout(self.connect_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .build()
+            .map_err(|e| CILensError::Config(format!("Failed to create HTTP client: {e}")))
+    }
+}
+
+/// API pipelines and jobs are collected through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Api {
+    Graphql,
+    Rest,
+    /// GraphQL, switching to REST for the rest of the run if GraphQL is
+    /// disabled or restricted on the instance
+    Auto,
+}
+
 pub struct GitLabClient {
     pub client: Client,
     pub graphql_url: Url,
+    pub api_url: Url,
     pub token: Option<Token>,
+    pub budget: RequestBudget,
+    middleware: Vec<Arc<dyn Middleware>>,
+    api: Api,
+    /// What `Api::Auto` settled on after the first pipeline request
+    resolved_api: OnceLock<Api>,
+    pub(super) schema_support: tokio::sync::OnceCell<SchemaSupport>,
 }
 
 impl GitLabClient {
     pub fn new(base_url: &str, token: Option<Token>) -> Result<Self> {
-        let client = Client::builder()
-            .user_agent("CILens/0.1.0")
-            .build()
-            .map_err(|e| CILensError::Config(format!("Failed to create HTTP client: {e}")))?;
+        let client = HttpOptions::default().build_client()?;
 
         let base = Url::parse(base_url)
             .map_err(|e| CILensError::Config(format!("Invalid base URL: {e}")))?;
@@ -24,18 +84,172 @@ impl GitLabClient {
             .join("api/graphql")
             .map_err(|e| CILensError::Config(format!("Invalid GraphQL URL: {e}")))?;
 
+        let api_url = base
+            .join("api/v4/")
+            .map_err(|e| CILensError::Config(format!("Invalid REST API URL: {e}")))?;
+
         Ok(Self {
             client,
             graphql_url,
+            api_url,
             token,
+            budget: RequestBudget::default(),
+            middleware: Vec::new(),
+            api: Api::Graphql,
+            resolved_api: OnceLock::new(),
+            schema_support: tokio::sync::OnceCell::new(),
         })
     }
 
-    pub fn auth_request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    /// Replaces the HTTP client with one using `options`.
+    pub fn with_http_options(mut self, options: &HttpOptions) -> Result<Self> {
+        self.client = options.build_client()?;
+        Ok(self)
+    }
+
+    /// Caps the number of API requests this client may send.
+    #[must_use]
+    pub fn with_max_requests(mut self, max_requests: Option<usize>) -> Self {
+        self.budget = RequestBudget::new(max_requests);
+        self
+    }
+
+    /// Runs every request through `middleware`, after any added before it.
+    #[must_use]
+    pub fn with_middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Selects the API pipelines and jobs are collected through (GraphQL
+    /// unless told otherwise).
+    #[must_use]
+    pub fn with_api(mut self, api: Api) -> Self {
+        self.api = api;
+        self
+    }
+
+    /// Runs `graphql` or `rest` depending on the selected [`Api`].
+    ///
+    /// With `Api::Auto`, the first GraphQL failure that suggests GraphQL is
+    /// unavailable (a 401/403/404, GraphQL errors or a response that is not
+    /// GraphQL) switches every later call to REST; a first success pins
+    /// GraphQL, so pagination never changes API halfway.
+    pub(super) async fn with_api_fallback<T>(
+        &self,
+        graphql: impl Future<Output = Result<T>>,
+        rest: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        match self.resolved_api.get().copied().unwrap_or(self.api) {
+            Api::Graphql => graphql.await,
+            Api::Rest => rest.await,
+            Api::Auto => match graphql.await {
+                Err(
+                    e @ (CILensError::Auth(_)
+                    | CILensError::NotFound(_)
+                    | CILensError::GraphQL(_)
+                    | CILensError::Json(_)),
+                ) => {
+                    if self.resolved_api.set(Api::Rest).is_ok() {
+                        warn!("GraphQL request failed ({e}), falling back to the REST API");
+                    }
+                    rest.await
+                }
+                result => {
+                    if result.is_ok() {
+                        let _ = self.resolved_api.set(Api::Graphql);
+                    }
+                    result
+                }
+            },
+        }
+    }
+
+    fn with_token(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         if let Some(token) = &self.token {
             request.bearer_auth(token.as_str())
         } else {
             request
         }
     }
+
+    /// Authenticates and sends a request through the middleware chain.
+    async fn execute(&self, request: reqwest::RequestBuilder) -> Result<Response> {
+        let request = self.with_token(request).build()?;
+        Next::new(&self.client, &self.middleware).run(request).await
+    }
+
+    /// Sends a REST request, counting it against the request budget.
+    pub async fn send_rest(&self, request: reqwest::RequestBuilder) -> Result<Response> {
+        self.budget.acquire(false)?;
+        self.execute(request).await
+    }
+
+    /// Sends a GraphQL query and returns its data, turning GraphQL errors into
+    /// `CILensError::GraphQL`. Every query selects `queryComplexity { score }`
+    /// so the cost of the run can be reported.
+    pub async fn post_graphql<T: DeserializeOwned>(&self, body: &impl Serialize) -> Result<T> {
+        self.budget.acquire(true)?;
+
+        let request = self.client.post(self.graphql_url.clone()).json(body);
+        let response = Self::check_response(self.execute(request).await?)?;
+        let bytes = response.bytes().await?;
+
+        if let Ok(probe) = serde_json::from_slice::<ComplexityProbe>(&bytes) {
+            self.budget.record_complexity(&probe);
+        }
+
+        let response_body: graphql_client::Response<T> = serde_json::from_slice(&bytes)?;
+
+        if let Some(errors) = response_body.errors {
+            let error_messages: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
+            return Err(CILensError::GraphQL(error_messages.join(", ")));
+        }
+
+        response_body
+            .data
+            .ok_or_else(|| CILensError::GraphQL("GraphQL response contained no data".to_string()))
+    }
+
+    /// Builds a REST URL scoped to a project, e.g. `projects/group%2Fproject/{path}`,
+    /// or the project itself when `path` is empty.
+    pub fn project_api_url(&self, project_path: &str, path: &str) -> Result<Url> {
+        let encoded: String =
+            url::form_urlencoded::byte_serialize(project_path.as_bytes()).collect();
+        let project = format!("projects/{encoded}");
+        let relative = if path.is_empty() {
+            project
+        } else {
+            format!("{project}/{path}")
+        };
+        self.api_url
+            .join(&relative)
+            .map_err(|e| CILensError::Config(format!("Invalid REST API URL: {e}")))
+    }
+
+    /// Maps HTTP failure statuses onto the matching `CILensError` variant.
+    pub fn check_response(response: Response) -> Result<Response> {
+        let status = response.status();
+        let url = response.url().clone();
+
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(CILensError::Auth(format!(
+                "GitLab returned {status} for {url}"
+            ))),
+            StatusCode::NOT_FOUND => Err(CILensError::NotFound(format!(
+                "GitLab returned {status} for {url}"
+            ))),
+            StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .map_or_else(String::new, |v| format!(", retry after {v}s"));
+                Err(CILensError::RateLimited(format!(
+                    "GitLab returned {status} for {url}{retry_after}"
+                )))
+            }
+            _ => Ok(response.error_for_status()?),
+        }
+    }
 }
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::Result;
+
+const PER_PAGE: &str = "100";
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct Deployment {
+    pub sha: String,
+    pub created_at: DateTime<Utc>,
+    pub status: String,
+    pub environment: DeploymentEnvironment,
+    pub deployable: Option<Deployable>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeploymentEnvironment {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Deployable {
+    pub duration: Option<f64>,
+}
+
+impl GitLabClient {
+    /// Fetches deployments updated after `since`, oldest first.
+    pub async fn fetch_deployments(
+        &self,
+        project_path: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Deployment>> {
+        let url = self.project_api_url(project_path, "deployments")?;
+        let updated_after = since.to_rfc3339();
+
+        let mut deployments = Vec::new();
+        let mut page = 1;
+
+        while page <= MAX_PAGES {
+            let request = self.client.get(url.clone()).query(&[
+                ("updated_after", updated_after.as_str()),
+                ("order_by", "updated_at"),
+                ("sort", "asc"),
+                ("per_page", PER_PAGE),
+                ("page", &page.to_string()),
+            ]);
+            let response = Self::check_response(self.send_rest(request).await?)?;
+
+            let has_next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| !v.is_empty());
+
+            let batch: Vec<Deployment> = response.json().await?;
+            deployments.extend(batch);
+
+            if !has_next_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(deployments)
+    }
+}
@@ -0,0 +1,202 @@
+//! Record/replay of API responses, for offline reruns and reproducible bug
+//! reports (`--record-fixtures` / `--replay-fixtures`).
+//!
+//! Each response is stored as `<key>.json`, where the key hashes the method,
+//! URL and body of the request. Credentials are not part of the key and the
+//! `Authorization` header is never written.
+
+use std::path::{Path, PathBuf};
+
+use futures::future::BoxFuture;
+use log::debug;
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::middleware::{Middleware, Next};
+use crate::error::{CILensError, Result};
+
+/// Response headers worth keeping: pagination, rate limiting and validators.
+const KEPT_HEADERS: &[&str] = &[
+    "content-type",
+    "etag",
+    "last-modified",
+    "retry-after",
+    "x-next-page",
+    "x-total",
+];
+
+/// A response saved to disk.
+#[derive(Serialize, Deserialize)]
+pub struct Fixture {
+    method: String,
+    url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Fixture {
+    /// Reads `response` to the end, keeping its status, body and `KEPT_HEADERS`.
+    pub async fn capture(method: String, url: String, response: Response) -> Result<Self> {
+        let status = response.status().as_u16();
+        let headers = KEPT_HEADERS
+            .iter()
+            .filter_map(|&name| {
+                let value = response.headers().get(name)?.to_str().ok()?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect();
+        let body = response.text().await?;
+
+        Ok(Self {
+            method,
+            url,
+            status,
+            headers,
+            body,
+        })
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn into_response(self) -> Result<Response> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .body(self.body)
+            .map_err(|e| CILensError::Config(format!("Invalid fixture for {}: {e}", self.url)))?;
+        Ok(Response::from(response))
+    }
+}
+
+fn fixture_path(dir: &Path, request: &Request) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(request.method().as_str());
+    hasher.update(request.url().as_str());
+    if let Some(body) = request.body().and_then(reqwest::Body::as_bytes) {
+        hasher.update(body);
+    }
+    let digest = hasher.finalize();
+    let key = u64::from_be_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+    dir.join(format!("{key:016x}.json"))
+}
+
+/// Passes requests through and saves every response to `dir`.
+pub struct Recorder {
+    dir: PathBuf,
+}
+
+impl Recorder {
+    pub fn new(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+}
+
+impl Middleware for Recorder {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let path = fixture_path(&self.dir, &request);
+            let (method, url) = (request.method().to_string(), request.url().to_string());
+
+            let fixture = Fixture::capture(method, url, next.run(request).await?).await?;
+            fixture.write(&path)?;
+            debug!(
+                "Recorded {} {} to {}",
+                fixture.method,
+                fixture.url,
+                path.display()
+            );
+
+            fixture.into_response()
+        })
+    }
+}
+
+/// Answers requests from fixtures saved by [`Recorder`], never touching the network.
+pub struct Replayer {
+    dir: PathBuf,
+}
+
+impl Replayer {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+}
+
+impl Middleware for Replayer {
+    fn handle<'a>(&'a self, request: Request, _: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let path = fixture_path(&self.dir, &request);
+            if !path.exists() {
+                return Err(CILensError::NotFound(format!(
+                    "No recorded fixture for {} {} (expected {})",
+                    request.method(),
+                    request.url(),
+                    path.display()
+                )));
+            }
+            Fixture::read(&path)?.into_response()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::providers::gitlab::client::GitLabClient;
+
+    #[tokio::test]
+    async fn test_replay_returns_recorded_responses() {
+        let dir = std::env::temp_dir().join(format!("cilens-fixtures-{}", std::process::id()));
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/graphql")
+            .with_body(r#"{"data": {"project": {"id": "1"}}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let query = serde_json::json!({"query": "{ project { id } }"});
+
+        let recording = GitLabClient::new(&server.url(), None)
+            .unwrap()
+            .with_middleware(Arc::new(Recorder::new(&dir).unwrap()));
+        let recorded: serde_json::Value = recording.post_graphql(&query).await.unwrap();
+
+        let replaying = GitLabClient::new(&server.url(), None)
+            .unwrap()
+            .with_middleware(Arc::new(Replayer::new(&dir)));
+        let replayed: serde_json::Value = replaying.post_graphql(&query).await.unwrap();
+        let missing = replaying
+            .post_graphql::<serde_json::Value>(&serde_json::json!({"query": "{ other }"}))
+            .await;
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        mock.assert_async().await;
+        assert_eq!(recorded, replayed);
+        assert!(matches!(missing, Err(CILensError::NotFound(_))));
+    }
+}
@@ -0,0 +1,138 @@
+//! Conditional requests for REST GETs (`--http-cache`).
+//!
+//! Responses carrying an `ETag` or `Last-Modified` header are kept on disk;
+//! the next run sends them back as `If-None-Match` / `If-Modified-Since` and
+//! reuses the stored body when GitLab answers `304 Not Modified`, which does
+//! not count against GitLab's rate limits as a full download would.
+
+use std::path::{Path, PathBuf};
+
+use futures::future::BoxFuture;
+use log::debug;
+use reqwest::header::{HeaderValue, AUTHORIZATION, IF_MODIFIED_SINCE, IF_NONE_MATCH};
+use reqwest::{Method, Request, Response, StatusCode};
+use sha2::{Digest, Sha256};
+
+use super::fixtures::Fixture;
+use super::middleware::{Middleware, Next};
+use crate::error::Result;
+
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+impl HttpCache {
+    pub fn new(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Entries are per URL and credential, so tokens with different access
+    /// never share a cached body. Only a hash of the token is used.
+    fn entry_path(&self, request: &Request) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(request.url().as_str());
+        if let Some(authorization) = request.headers().get(AUTHORIZATION) {
+            hasher.update(authorization.as_bytes());
+        }
+        let digest = hasher.finalize();
+        let key = u64::from_be_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+        self.dir.join(format!("{key:016x}.json"))
+    }
+}
+
+impl Middleware for HttpCache {
+    fn handle<'a>(
+        &'a self,
+        mut request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            if request.method() != Method::GET {
+                return next.run(request).await;
+            }
+
+            let path = self.entry_path(&request);
+            let cached = Fixture::read(&path).ok();
+            if let Some(cached) = &cached {
+                let headers = request.headers_mut();
+                for (validator, header) in [
+                    ("etag", IF_NONE_MATCH),
+                    ("last-modified", IF_MODIFIED_SINCE),
+                ] {
+                    if let Some(value) = cached
+                        .header(validator)
+                        .and_then(|v| HeaderValue::from_str(v).ok())
+                    {
+                        headers.insert(header, value);
+                    }
+                }
+            }
+
+            let (method, url) = (request.method().to_string(), request.url().to_string());
+            let response = next.run(request).await?;
+
+            if response.status() == StatusCode::NOT_MODIFIED {
+                if let Some(cached) = cached {
+                    debug!("Not modified, reusing cached response for {url}");
+                    return cached.into_response();
+                }
+            }
+
+            let has_validator = response.headers().contains_key("etag")
+                || response.headers().contains_key("last-modified");
+            if !response.status().is_success() || !has_validator {
+                return Ok(response);
+            }
+
+            let fresh = Fixture::capture(method, url, response).await?;
+            fresh.write(&path)?;
+            fresh.into_response()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::providers::gitlab::client::GitLabClient;
+
+    #[tokio::test]
+    async fn test_not_modified_reuses_cached_body() {
+        let dir = std::env::temp_dir().join(format!("cilens-http-cache-{}", std::process::id()));
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/user")
+            .with_header("etag", "\"v1\"")
+            .with_body(r#"{"username": "jane"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/user")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None)
+            .unwrap()
+            .with_middleware(Arc::new(HttpCache::new(&dir).unwrap()));
+        let url = client.api_url.join("user").unwrap();
+        let first = client
+            .send_rest(client.client.get(url.clone()))
+            .await
+            .unwrap();
+        let first = first.text().await.unwrap();
+        let second = client.send_rest(client.client.get(url)).await.unwrap();
+
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(second.text().await.unwrap(), first);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+
+use log::{info, warn};
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::{CILensError, Result};
+
+/// Optional fields of the pipeline queries, which older GitLab versions do not
+/// expose: each is only selected when the instance's schema has it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct SchemaSupport {
+    pub merge_request_event_type: bool,
+    pub committed_at: bool,
+    pub queued_duration: bool,
+    pub failure_message: bool,
+    pub tags: bool,
+    pub downstream_pipeline: bool,
+}
+
+impl Default for SchemaSupport {
+    fn default() -> Self {
+        Self {
+            merge_request_event_type: true,
+            committed_at: true,
+            queued_duration: true,
+            failure_message: true,
+            tags: true,
+            downstream_pipeline: true,
+        }
+    }
+}
+
+const INTROSPECTION_QUERY: &str = r#"
+query {
+  pipeline: __type(name: "Pipeline") { fields(includeDeprecated: true) { name } }
+  job: __type(name: "CiJob") { fields(includeDeprecated: true) { name } }
+}"#;
+
+#[derive(Deserialize)]
+struct IntrospectionData {
+    pipeline: Option<IntrospectedType>,
+    job: Option<IntrospectedType>,
+}
+
+#[derive(Deserialize)]
+struct IntrospectedType {
+    fields: Vec<IntrospectedField>,
+}
+
+#[derive(Deserialize)]
+struct IntrospectedField {
+    name: String,
+}
+
+impl SchemaSupport {
+    /// Which optional fields the introspected types list, warning about each
+    /// missing one.
+    fn from_introspection(pipeline: IntrospectedType, job: IntrospectedType) -> Self {
+        let field_names = |introspected: IntrospectedType| -> HashSet<String> {
+            introspected
+                .fields
+                .into_iter()
+                .map(|field| field.name)
+                .collect()
+        };
+        let pipeline_fields = field_names(pipeline);
+        let job_fields = field_names(job);
+
+        let has = |type_fields: &HashSet<String>, field: &str, dependent: &str| {
+            let available = type_fields.contains(field);
+            if !available {
+                warn!("This GitLab version does not expose {field}; omitting {dependent}");
+            }
+            available
+        };
+
+        Self {
+            merge_request_event_type: has(
+                &pipeline_fields,
+                "mergeRequestEventType",
+                "merge train metrics",
+            ),
+            committed_at: has(&pipeline_fields, "committedAt", "lead time to green"),
+            queued_duration: has(&job_fields, "queuedDuration", "runner queue times"),
+            failure_message: has(
+                &job_fields,
+                "failureMessage",
+                "transient/persistent failure classification",
+            ),
+            tags: has(&job_fields, "tags", "runner tag breakdowns"),
+            downstream_pipeline: has(
+                &job_fields,
+                "downstreamPipeline",
+                "downstream pipeline durations on the critical path",
+            ),
+        }
+    }
+}
+
+impl GitLabClient {
+    /// Detects which optional pipeline and job fields the instance's GraphQL
+    /// schema exposes, introspecting it on the first call.
+    ///
+    /// Instances that disable introspection are assumed to expose them all.
+    pub(super) async fn schema_support(&self) -> SchemaSupport {
+        *self
+            .schema_support
+            .get_or_init(|| async {
+                match self.introspect().await {
+                    Ok(support) => support,
+                    Err(e) => {
+                        info!(
+                            "Could not introspect the GraphQL schema ({e}), \
+                             assuming every optional field is available"
+                        );
+                        SchemaSupport::default()
+                    }
+                }
+            })
+            .await
+    }
+
+    async fn introspect(&self) -> Result<SchemaSupport> {
+        let data: IntrospectionData = self
+            .post_graphql(&serde_json::json!({ "query": INTROSPECTION_QUERY }))
+            .await?;
+
+        match (data.pipeline, data.job) {
+            (Some(pipeline), Some(job)) => Ok(SchemaSupport::from_introspection(pipeline, job)),
+            _ => Err(CILensError::GraphQL(
+                "Schema does not describe the Pipeline and CiJob types".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_schema_support_omits_fields_the_schema_lacks() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .with_body(
+                r#"{"data": {
+                    "pipeline": {"fields": [{"name": "mergeRequestEventType"}, {"name": "committedAt"}]},
+                    "job": {"fields": [{"name": "tags"}, {"name": "failureMessage"}]}
+                }}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let support = client.schema_support().await;
+        // Introspected once per client
+        client.schema_support().await;
+
+        assert!(support.merge_request_event_type);
+        assert!(support.failure_message);
+        assert!(!support.queued_duration);
+        assert!(!support.downstream_pipeline);
+    }
+
+    #[tokio::test]
+    async fn test_schema_support_assumes_all_fields_without_introspection() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .with_body(r#"{"errors": [{"message": "introspection is disabled"}]}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+
+        assert_eq!(client.schema_support().await, SchemaSupport::default());
+    }
+}
@@ -0,0 +1,46 @@
+use super::core::GitLabClient;
+use crate::error::{CILensError, Result};
+
+impl GitLabClient {
+    /// Fetches the raw log of job `job_id` (numeric), or `None` when it has
+    /// none, e.g. because it was erased or expired.
+    pub async fn fetch_job_log(&self, project_path: &str, job_id: &str) -> Result<Option<String>> {
+        let url = self.project_api_url(project_path, &format!("jobs/{job_id}/trace"))?;
+
+        match Self::check_response(self.send_rest(self.client.get(url)).await?) {
+            Ok(response) => Ok(Some(response.text().await?)),
+            Err(CILensError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_job_log_treats_missing_logs_as_none() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/jobs/1/trace")
+            .with_body("Running with gitlab-runner 17.0.0\n")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/jobs/2/trace")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+
+        let log = client.fetch_job_log("group/project", "1").await.unwrap();
+        assert_eq!(log.as_deref(), Some("Running with gitlab-runner 17.0.0\n"));
+        assert!(client
+            .fetch_job_log("group/project", "2")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
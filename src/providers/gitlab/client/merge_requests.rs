@@ -0,0 +1,61 @@
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::Result;
+
+#[derive(Debug, Deserialize)]
+pub struct MergeRequest {
+    pub source_branch: String,
+    pub target_branch: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    id: u64,
+    body: String,
+}
+
+impl GitLabClient {
+    pub async fn fetch_merge_request(&self, project_path: &str, iid: u64) -> Result<MergeRequest> {
+        let url = self.project_api_url(project_path, &format!("merge_requests/{iid}"))?;
+        let response = self.send_rest(self.client.get(url)).await?;
+
+        Ok(Self::check_response(response)?.json().await?)
+    }
+
+    /// Creates a merge request note, or updates the existing one containing `marker`.
+    pub async fn upsert_merge_request_note(
+        &self,
+        project_path: &str,
+        iid: u64,
+        marker: &str,
+        body: &str,
+    ) -> Result<()> {
+        let notes_url =
+            self.project_api_url(project_path, &format!("merge_requests/{iid}/notes"))?;
+
+        let request = self
+            .client
+            .get(notes_url.clone())
+            .query(&[("per_page", "100"), ("sort", "desc")]);
+        let response = self.send_rest(request).await?;
+        let notes: Vec<Note> = Self::check_response(response)?.json().await?;
+
+        let payload = serde_json::json!({ "body": body });
+
+        let request = match notes.iter().find(|note| note.body.contains(marker)) {
+            Some(note) => {
+                let existing_url = self.project_api_url(
+                    project_path,
+                    &format!("merge_requests/{iid}/notes/{}", note.id),
+                )?;
+                self.client.put(existing_url).json(&payload)
+            }
+            None => self.client.post(notes_url).json(&payload),
+        };
+
+        Self::check_response(self.send_rest(request).await?)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Request, Response};
+
+use crate::error::Result;
+
+/// Hook around every request `GitLabClient` sends, e.g. to add headers,
+/// record metrics or answer requests without the network.
+///
+/// Middleware runs in registration order; call `next.run(request)` to pass
+/// the request on, or return a response without calling it to short-circuit.
+pub trait Middleware: Send + Sync {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>>;
+}
+
+/// The rest of the middleware chain, ending with the HTTP client.
+pub struct Next<'a> {
+    client: &'a Client,
+    middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(super) fn new(client: &'a Client, middleware: &'a [Arc<dyn Middleware>]) -> Self {
+        Self { client, middleware }
+    }
+
+    /// Passes `request` on to the next middleware, or sends it.
+    pub fn run(self, request: Request) -> BoxFuture<'a, Result<Response>> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => first.handle(request, Next::new(self.client, rest)),
+            None => Box::pin(async move { Ok(self.client.execute(request).await?) }),
+        }
+    }
+}
+
+/// Adds fixed headers to every request (see `--header`), e.g. credentials
+/// for a proxy in front of GitLab. They replace headers of the same name.
+pub struct Headers(HeaderMap);
+
+impl Headers {
+    pub fn new(headers: HeaderMap) -> Self {
+        Self(headers)
+    }
+}
+
+impl Middleware for Headers {
+    fn handle<'a>(
+        &'a self,
+        mut request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        request.headers_mut().extend(self.0.clone());
+        next.run(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::client::GitLabClient;
+    use reqwest::header::HeaderValue;
+
+    struct TraceHeader;
+
+    impl Middleware for TraceHeader {
+        fn handle<'a>(
+            &'a self,
+            mut request: Request,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, Result<Response>> {
+            request
+                .headers_mut()
+                .insert("x-trace-id", HeaderValue::from_static("abc"));
+            next.run(request)
+        }
+    }
+
+    struct Canned;
+
+    impl Middleware for Canned {
+        fn handle<'a>(&'a self, _: Request, _: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+            Box::pin(async {
+                Ok(Response::from(
+                    http::Response::builder()
+                        .body(r#"{"data": {"project": null}}"#)
+                        .unwrap(),
+                ))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_can_add_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v4/user")
+            .match_header("x-trace-id", "abc")
+            .with_body(r#"{"username": "jane"}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None)
+            .unwrap()
+            .with_middleware(Arc::new(TraceHeader));
+        client
+            .send_rest(client.client.get(client.api_url.join("user").unwrap()))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_headers_are_sent_with_every_request() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v4/user")
+            .match_header("x-proxy-token", "abc")
+            .with_body(r#"{"username": "jane"}"#)
+            .create_async()
+            .await;
+
+        let headers = HeaderMap::from_iter([(
+            reqwest::header::HeaderName::from_static("x-proxy-token"),
+            HeaderValue::from_static("abc"),
+        )]);
+        let client = GitLabClient::new(&server.url(), None)
+            .unwrap()
+            .with_middleware(Arc::new(Headers::new(headers)));
+        client
+            .send_rest(client.client.get(client.api_url.join("user").unwrap()))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_middleware_can_answer_without_the_network() {
+        let client = GitLabClient::new("http://127.0.0.1:9", None)
+            .unwrap()
+            .with_middleware(Arc::new(Canned));
+
+        let data: serde_json::Value = client.post_graphql(&serde_json::json!({})).await.unwrap();
+
+        assert_eq!(data, serde_json::json!({"project": null}));
+    }
+}
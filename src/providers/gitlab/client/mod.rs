@@ -1,4 +1,21 @@
+mod budget;
+mod ci_minutes;
+pub mod commits;
 mod core;
+pub mod deployments;
+pub mod fixtures;
+pub mod http_cache;
+mod introspection;
+mod job_logs;
+mod merge_requests;
+pub mod middleware;
 pub mod pipelines;
+mod project;
+mod rest_pipelines;
+pub mod runners;
+pub mod schedules;
+mod token;
 
-pub use core::GitLabClient;
+pub use core::{Api, GitLabClient, HttpOptions};
+pub use middleware::{Middleware, Next};
+pub use pipelines::PipelineStatus;
@@ -1,10 +1,17 @@
+use std::collections::HashSet;
+
 use graphql_client::GraphQLQuery;
+use log::debug;
+use serde::Deserialize;
 
 use super::core::GitLabClient;
 use crate::error::{CILensError, Result};
 
 pub type JobID = String;
 pub type CiPipelineID = String;
+pub type Time = chrono::DateTime<chrono::Utc>;
+pub type Duration = f64;
+pub type CiRunnerID = String;
 
 #[derive(GraphQLQuery)]
 #[graphql(
@@ -23,17 +30,145 @@ pub struct FetchPipelines;
 )]
 pub struct FetchPipelineJobs;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/providers/gitlab/client/schema.json",
+    query_path = "src/providers/gitlab/client/pipelines.graphql",
+    query_name = "FetchPipelineById",
+    response_derives = "Debug"
+)]
+pub struct FetchPipelineById;
+
+/// Response of `FetchPipelineById`, deserialized straight into the node type
+/// of `FetchPipelines` since both queries select the same pipeline fields.
+#[derive(Deserialize)]
+struct PipelineByIdData {
+    project: Option<PipelineByIdProject>,
+}
+
+#[derive(Deserialize)]
+struct PipelineByIdProject {
+    pipeline: Option<fetch_pipelines::FetchPipelinesProjectPipelinesNodes>,
+}
+
+/// Finished pipeline statuses that can be selected for analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PipelineStatus {
+    Success,
+    Failed,
+    Canceled,
+    Skipped,
+}
+
+impl PipelineStatus {
+    /// Statuses analyzed unless `--statuses` says otherwise.
+    pub const DEFAULT: [Self; 2] = [Self::Success, Self::Failed];
+
+    fn to_graphql(self) -> fetch_pipelines::PipelineStatusEnum {
+        match self {
+            Self::Success => fetch_pipelines::PipelineStatusEnum::SUCCESS,
+            Self::Failed => fetch_pipelines::PipelineStatusEnum::FAILED,
+            Self::Canceled => fetch_pipelines::PipelineStatusEnum::CANCELED,
+            Self::Skipped => fetch_pipelines::PipelineStatusEnum::SKIPPED,
+        }
+    }
+}
+
+/// One page of pipelines and the cursor of the next page, if there is one.
+pub type PipelinePage = (
+    Vec<fetch_pipelines::FetchPipelinesProjectPipelinesNodes>,
+    Option<String>,
+);
+
 impl GitLabClient {
-    async fn fetch_pipelines_with_status(
+    /// Fetches up to one page (capped at `limit`) of pipelines, newest first,
+    /// starting after `cursor`.
+    pub async fn fetch_pipelines_page(
         &self,
         project_path: &str,
         limit: usize,
+        cursor: Option<String>,
         ref_: Option<&str>,
         status: Option<fetch_pipelines::PipelineStatusEnum>,
-    ) -> Result<Vec<fetch_pipelines::FetchPipelinesProjectPipelinesNodes>> {
+    ) -> Result<PipelinePage> {
+        self.with_api_fallback(
+            self.fetch_pipelines_page_graphql(
+                project_path,
+                limit,
+                cursor.clone(),
+                ref_,
+                status.clone(),
+            ),
+            self.fetch_pipelines_page_rest(
+                project_path,
+                limit,
+                cursor.clone(),
+                ref_,
+                status.clone(),
+            ),
+        )
+        .await
+    }
+
+    async fn fetch_pipelines_page_graphql(
+        &self,
+        project_path: &str,
+        limit: usize,
+        cursor: Option<String>,
+        ref_: Option<&str>,
+        status: Option<fetch_pipelines::PipelineStatusEnum>,
+    ) -> Result<PipelinePage> {
         const PAGE_SIZE: i64 = 50;
 
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let fetch_count = std::cmp::min(limit, PAGE_SIZE as usize) as i64;
+
+        let support = self.schema_support().await;
+        let variables = fetch_pipelines::Variables {
+            project_path: project_path.to_string(),
+            first: fetch_count,
+            after: cursor,
+            ref_: ref_.map(std::string::ToString::to_string),
+            status,
+            with_merge_request_event_type: support.merge_request_event_type,
+            with_committed_at: support.committed_at,
+        };
+
+        let request_body = FetchPipelines::build_query(variables);
+
+        let data: fetch_pipelines::ResponseData = self.post_graphql(&request_body).await?;
+
+        let project = data
+            .project
+            .ok_or_else(|| CILensError::NotFound(format!("Project '{project_path}' not found")))?;
+
+        let pipelines = project.pipelines.ok_or_else(|| {
+            CILensError::NotFound(format!(
+                "No pipeline data available for project '{project_path}'"
+            ))
+        })?;
+
+        let next_cursor = if pipelines.page_info.has_next_page {
+            pipelines.page_info.end_cursor
+        } else {
+            None
+        };
+
+        Ok((
+            pipelines.nodes.into_iter().flatten().flatten().collect(),
+            next_cursor,
+        ))
+    }
+
+    async fn fetch_pipelines_with_status(
+        &self,
+        project_path: &str,
+        limit: usize,
+        ref_: Option<&str>,
+        status: Option<fetch_pipelines::PipelineStatusEnum>,
+    ) -> Result<Vec<fetch_pipelines::FetchPipelinesProjectPipelinesNodes>> {
         let mut all_pipelines = Vec::new();
+        let mut seen = HashSet::new();
         let mut cursor: Option<String> = None;
 
         loop {
@@ -42,59 +177,25 @@ impl GitLabClient {
                 break;
             }
 
-            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-            let fetch_count = std::cmp::min(remaining, PAGE_SIZE as usize) as i64;
-
-            let variables = fetch_pipelines::Variables {
-                project_path: project_path.to_string(),
-                first: fetch_count,
-                after: cursor.clone(),
-                ref_: ref_.map(std::string::ToString::to_string),
-                status: status.clone(),
-            };
-
-            let request_body = FetchPipelines::build_query(variables);
-
-            let request = self
-                .client
-                .post(self.graphql_url.clone())
-                .json(&request_body);
-            let request = self.auth_request(request);
-
-            let response = request.send().await?;
-            let response_body: graphql_client::Response<fetch_pipelines::ResponseData> =
-                response.json().await?;
-
-            if let Some(errors) = response_body.errors {
-                let error_messages: Vec<String> =
-                    errors.iter().map(|e| e.message.clone()).collect();
-                let joined_errors = error_messages.join(", ");
-                return Err(CILensError::Config(format!(
-                    "GraphQL errors: {joined_errors}"
-                )));
+            let (nodes, next_cursor) = self
+                .fetch_pipelines_page(project_path, remaining, cursor, ref_, status.clone())
+                .await?;
+
+            // Pipelines created mid-collection shift later pages, repeating
+            // pipelines already fetched
+            let listed = nodes.len();
+            let before = all_pipelines.len();
+            all_pipelines.extend(
+                nodes
+                    .into_iter()
+                    .filter(|node| seen.insert(node.id.clone())),
+            );
+            let repeated = listed - (all_pipelines.len() - before);
+            if repeated > 0 {
+                debug!("Skipped {repeated} pipelines repeated by a shifted page");
             }
 
-            let data = response_body.data.ok_or_else(|| {
-                CILensError::Config("GraphQL response contained no data".to_string())
-            })?;
-
-            let project = data.project.ok_or_else(|| {
-                CILensError::Config(format!("Project '{project_path}' not found"))
-            })?;
-
-            let pipelines = project.pipelines.ok_or_else(|| {
-                CILensError::Config(format!(
-                    "No pipeline data available for project '{project_path}'"
-                ))
-            })?;
-
-            all_pipelines.extend(pipelines.nodes.into_iter().flatten().flatten());
-
-            if !pipelines.page_info.has_next_page || all_pipelines.len() >= limit {
-                break;
-            }
-
-            cursor = pipelines.page_info.end_cursor;
+            cursor = next_cursor;
 
             if cursor.is_none() {
                 break;
@@ -106,45 +207,60 @@ impl GitLabClient {
         Ok(all_pipelines)
     }
 
+    /// Fetches up to `limit` pipelines with one of `statuses`, newest first.
+    /// The limit is split evenly between the statuses (the first ones taking
+    /// the remainder), which are fetched in parallel.
     pub async fn fetch_pipelines(
         &self,
         project_path: &str,
         limit: usize,
         ref_: Option<&str>,
+        statuses: &[PipelineStatus],
     ) -> Result<Vec<fetch_pipelines::FetchPipelinesProjectPipelinesNodes>> {
-        // Fetch SUCCESS and FAILED pipelines in parallel
-        let half_limit = limit / 2;
-
-        let (success_result, failed_result) = tokio::join!(
-            self.fetch_pipelines_with_status(
-                project_path,
-                half_limit,
-                ref_,
-                Some(fetch_pipelines::PipelineStatusEnum::SUCCESS),
-            ),
-            self.fetch_pipelines_with_status(
-                project_path,
-                half_limit,
-                ref_,
-                Some(fetch_pipelines::PipelineStatusEnum::FAILED),
-            ),
-        );
-
-        let mut all_pipelines = success_result?;
-        all_pipelines.extend(failed_result?);
-
-        // Note: Pipelines are already sorted by creation time in GitLab's response
+        let buckets = statuses.len().max(1);
+        let (share, remainder) = (limit / buckets, limit % buckets);
+
+        let per_status =
+            futures::future::try_join_all(statuses.iter().enumerate().map(|(i, status)| {
+                let share = share + usize::from(i < remainder);
+                self.fetch_pipelines_with_status(
+                    project_path,
+                    share,
+                    ref_,
+                    Some(status.to_graphql()),
+                )
+            }))
+            .await?;
+
+        let mut all_pipelines: Vec<_> = per_status.into_iter().flatten().collect();
+
+        // Each status is sorted on its own, so interleave them by creation time
+        all_pipelines.sort_by_key(|pipeline| std::cmp::Reverse(pipeline.created_at));
         all_pipelines.truncate(limit);
 
         Ok(all_pipelines)
     }
 
+    /// Fetches every job of a pipeline, including retried attempts.
     pub async fn fetch_pipeline_jobs(
         &self,
         project_path: &str,
         pipeline_id: &str,
+    ) -> Result<Vec<fetch_pipeline_jobs::FetchPipelineJobsProjectPipelineJobsNodes>> {
+        self.with_api_fallback(
+            self.fetch_pipeline_jobs_graphql(project_path, pipeline_id),
+            self.fetch_pipeline_jobs_rest(project_path, pipeline_id),
+        )
+        .await
+    }
+
+    async fn fetch_pipeline_jobs_graphql(
+        &self,
+        project_path: &str,
+        pipeline_id: &str,
     ) -> Result<Vec<fetch_pipeline_jobs::FetchPipelineJobsProjectPipelineJobsNodes>> {
         const PAGE_SIZE: i64 = 50;
+        let support = self.schema_support().await;
         let mut all_jobs = Vec::new();
         let mut cursor: Option<String> = None;
 
@@ -154,43 +270,26 @@ impl GitLabClient {
                 pipeline_id: pipeline_id.to_string(),
                 first: PAGE_SIZE,
                 after: cursor.clone(),
+                with_queued_duration: support.queued_duration,
+                with_failure_message: support.failure_message,
+                with_tags: support.tags,
+                with_downstream_pipeline: support.downstream_pipeline,
             };
 
             let request_body = FetchPipelineJobs::build_query(variables);
 
-            let request = self
-                .client
-                .post(self.graphql_url.clone())
-                .json(&request_body);
-            let request = self.auth_request(request);
-
-            let response = request.send().await?;
-            let response_body: graphql_client::Response<fetch_pipeline_jobs::ResponseData> =
-                response.json().await?;
-
-            if let Some(errors) = response_body.errors {
-                let error_messages: Vec<String> =
-                    errors.iter().map(|e| e.message.clone()).collect();
-                let joined_errors = error_messages.join(", ");
-                return Err(CILensError::Config(format!(
-                    "GraphQL errors: {joined_errors}"
-                )));
-            }
-
-            let data = response_body.data.ok_or_else(|| {
-                CILensError::Config("GraphQL response contained no data".to_string())
-            })?;
+            let data: fetch_pipeline_jobs::ResponseData = self.post_graphql(&request_body).await?;
 
             let project = data.project.ok_or_else(|| {
-                CILensError::Config(format!("Project '{project_path}' not found"))
+                CILensError::NotFound(format!("Project '{project_path}' not found"))
             })?;
 
             let pipeline = project.pipeline.ok_or_else(|| {
-                CILensError::Config(format!("Pipeline '{pipeline_id}' not found"))
+                CILensError::NotFound(format!("Pipeline '{pipeline_id}' not found"))
             })?;
 
             let jobs = pipeline.jobs.ok_or_else(|| {
-                CILensError::Config(format!(
+                CILensError::NotFound(format!(
                     "No job data available for pipeline '{pipeline_id}'"
                 ))
             })?;
@@ -210,4 +309,287 @@ impl GitLabClient {
 
         Ok(all_jobs)
     }
+
+    /// Fetches specific pipelines by their numeric ID (as shown in pipeline URLs).
+    pub async fn fetch_pipelines_by_id(
+        &self,
+        project_path: &str,
+        pipeline_ids: &[u64],
+    ) -> Result<Vec<fetch_pipelines::FetchPipelinesProjectPipelinesNodes>> {
+        let futures = pipeline_ids
+            .iter()
+            .map(|&id| self.fetch_pipeline_by_id(project_path, id));
+
+        futures::future::try_join_all(futures).await
+    }
+
+    async fn fetch_pipeline_by_id(
+        &self,
+        project_path: &str,
+        pipeline_id: u64,
+    ) -> Result<fetch_pipelines::FetchPipelinesProjectPipelinesNodes> {
+        self.with_api_fallback(
+            self.fetch_pipeline_by_id_graphql(project_path, pipeline_id),
+            self.fetch_pipeline_by_id_rest(project_path, pipeline_id),
+        )
+        .await
+    }
+
+    async fn fetch_pipeline_by_id_graphql(
+        &self,
+        project_path: &str,
+        pipeline_id: u64,
+    ) -> Result<fetch_pipelines::FetchPipelinesProjectPipelinesNodes> {
+        let support = self.schema_support().await;
+        let request_body = FetchPipelineById::build_query(fetch_pipeline_by_id::Variables {
+            project_path: project_path.to_string(),
+            pipeline_id: format!("gid://gitlab/Ci::Pipeline/{pipeline_id}"),
+            with_merge_request_event_type: support.merge_request_event_type,
+            with_committed_at: support.committed_at,
+        });
+
+        let data: PipelineByIdData = self.post_graphql(&request_body).await?;
+
+        let project = data
+            .project
+            .ok_or_else(|| CILensError::NotFound(format!("Project '{project_path}' not found")))?;
+
+        project.pipeline.ok_or_else(|| {
+            CILensError::NotFound(format!(
+                "Pipeline {pipeline_id} not found in project '{project_path}'"
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_pipelines_page_returns_next_cursor() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .with_body(
+                r#"{"data": {"project": {"pipelines": {
+                    "pageInfo": {"hasNextPage": true, "endCursor": "abc"},
+                    "nodes": []
+                }}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let (nodes, cursor) = client
+            .fetch_pipelines_page("group/project", 10, None, None, None)
+            .await
+            .unwrap();
+
+        assert!(nodes.is_empty());
+        assert_eq!(cursor.as_deref(), Some("abc"));
+    }
+
+    fn pipeline_node_json(id: u64) -> String {
+        format!(
+            r#"{{"id": "gid://gitlab/Ci::Pipeline/{id}", "ref": "main", "refPath": null,
+                "sha": null, "source": "push", "status": "SUCCESS", "duration": 60,
+                "createdAt": "2025-01-01T10:{id:02}:00Z", "startedAt": null,
+                "finishedAt": null, "user": null, "stages": null}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pipelines_skips_pipelines_repeated_by_shifted_pages() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .match_body(mockito::Matcher::Regex(r#""after":null"#.to_string()))
+            .with_body(format!(
+                r#"{{"data": {{"project": {{"pipelines": {{
+                    "pageInfo": {{"hasNextPage": true, "endCursor": "abc"}},
+                    "nodes": [{}, {}]
+                }}}}}}}}"#,
+                pipeline_node_json(3),
+                pipeline_node_json(2)
+            ))
+            .create_async()
+            .await;
+        // A pipeline created in between pushed pipeline 2 onto the next page
+        server
+            .mock("POST", "/api/graphql")
+            .match_body(mockito::Matcher::Regex(r#""after":"abc""#.to_string()))
+            .with_body(format!(
+                r#"{{"data": {{"project": {{"pipelines": {{
+                    "pageInfo": {{"hasNextPage": false, "endCursor": null}},
+                    "nodes": [{}, {}]
+                }}}}}}}}"#,
+                pipeline_node_json(2),
+                pipeline_node_json(1)
+            ))
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let pipelines = client
+            .fetch_pipelines("group/project", 10, None, &[PipelineStatus::Success])
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = pipelines.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "gid://gitlab/Ci::Pipeline/3",
+                "gid://gitlab/Ci::Pipeline/2",
+                "gid://gitlab/Ci::Pipeline/1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pipelines_splits_the_limit_between_statuses() {
+        let mut server = mockito::Server::new_async().await;
+        for (status, first, ids) in [("SUCCESS", 2, [4, 1].as_slice()), ("FAILED", 1, &[3])] {
+            let nodes: Vec<String> = ids.iter().map(|&id| pipeline_node_json(id)).collect();
+            server
+                .mock("POST", "/api/graphql")
+                .match_body(mockito::Matcher::AllOf(vec![
+                    mockito::Matcher::Regex(format!(r#""status":"{status}""#)),
+                    mockito::Matcher::Regex(format!(r#""first":{first}[,}}]"#)),
+                ]))
+                .with_body(format!(
+                    r#"{{"data": {{"project": {{"pipelines": {{
+                        "pageInfo": {{"hasNextPage": false, "endCursor": null}},
+                        "nodes": [{}]
+                    }}}}}}}}"#,
+                    nodes.join(", ")
+                ))
+                .create_async()
+                .await;
+        }
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let pipelines = client
+            .fetch_pipelines(
+                "group/project",
+                3,
+                None,
+                &[PipelineStatus::Success, PipelineStatus::Failed],
+            )
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = pipelines.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "gid://gitlab/Ci::Pipeline/4",
+                "gid://gitlab/Ci::Pipeline/3",
+                "gid://gitlab/Ci::Pipeline/1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pipeline_jobs_skips_fields_the_schema_lacks() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .match_body(mockito::Matcher::Regex("__type".to_string()))
+            .with_body(
+                r#"{"data": {
+                    "pipeline": {"fields": [{"name": "committedAt"}]},
+                    "job": {"fields": [{"name": "tags"}, {"name": "failureMessage"}]}
+                }}"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/api/graphql")
+            .match_body(mockito::Matcher::Regex(
+                r#""withQueuedDuration":false"#.to_string(),
+            ))
+            .with_body(
+                r#"{"data": {"project": {"pipeline": {"jobs": {
+                    "pageInfo": {"hasNextPage": false, "endCursor": null},
+                    "nodes": [{
+                        "id": "gid://gitlab/Ci::Build/1",
+                        "name": "test",
+                        "status": "SUCCESS",
+                        "duration": 30,
+                        "startedAt": null,
+                        "finishedAt": null,
+                        "retried": false,
+                        "failureMessage": null,
+                        "tags": [],
+                        "runner": null,
+                        "stage": {"name": "test"},
+                        "needs": null
+                    }]
+                }}}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let jobs = client
+            .fetch_pipeline_jobs("group/project", "gid://gitlab/Ci::Pipeline/123")
+            .await
+            .unwrap();
+
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].queued_duration, None);
+        assert_eq!(jobs[0].downstream_pipeline, None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pipelines_by_id() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .with_body(
+                r#"{"data": {"project": {"pipeline": {
+                    "id": "gid://gitlab/Ci::Pipeline/123",
+                    "ref": "main",
+                    "refPath": "refs/heads/main",
+                    "sha": "abc123",
+                    "source": "push",
+                    "status": "SUCCESS",
+                    "mergeRequestEventType": null,
+                    "duration": 60,
+                    "createdAt": "2025-01-01T10:00:00Z",
+                    "startedAt": "2025-01-01T10:00:00Z",
+                    "finishedAt": "2025-01-01T10:01:00Z",
+                    "user": {"username": "release-bot", "bot": true},
+                    "stages": {"nodes": [{"name": "test"}]}
+                }}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let pipelines = client
+            .fetch_pipelines_by_id("group/project", &[123])
+            .await
+            .unwrap();
+
+        assert_eq!(pipelines.len(), 1);
+        assert_eq!(pipelines[0].id, "gid://gitlab/Ci::Pipeline/123");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pipelines_by_id_missing_pipeline() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .with_body(r#"{"data": {"project": {"pipeline": null}}}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let result = client.fetch_pipelines_by_id("group/project", &[404]).await;
+
+        assert!(matches!(result, Err(CILensError::NotFound(_))));
+    }
 }
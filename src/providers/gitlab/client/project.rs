@@ -0,0 +1,160 @@
+use graphql_client::GraphQLQuery;
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::{CILensError, Result};
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/providers/gitlab/client/schema.json",
+    query_path = "src/providers/gitlab/client/project.graphql",
+    response_derives = "Debug"
+)]
+pub struct FetchDefaultBranch;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/providers/gitlab/client/schema.json",
+    query_path = "src/providers/gitlab/client/project.graphql",
+    query_name = "FetchCiConfigPath",
+    response_derives = "Debug"
+)]
+pub struct FetchCiConfigPath;
+
+#[derive(Deserialize)]
+struct RestProject {
+    default_branch: Option<String>,
+}
+
+impl GitLabClient {
+    /// Looks up the project's default branch (the repository root ref).
+    pub async fn fetch_default_branch(&self, project_path: &str) -> Result<String> {
+        self.with_api_fallback(
+            self.fetch_default_branch_graphql(project_path),
+            self.fetch_default_branch_rest(project_path),
+        )
+        .await
+    }
+
+    async fn fetch_default_branch_graphql(&self, project_path: &str) -> Result<String> {
+        let request_body = FetchDefaultBranch::build_query(fetch_default_branch::Variables {
+            project_path: project_path.to_string(),
+        });
+
+        let data: fetch_default_branch::ResponseData = self.post_graphql(&request_body).await?;
+
+        let project = data
+            .project
+            .ok_or_else(|| CILensError::NotFound(format!("Project '{project_path}' not found")))?;
+
+        project
+            .repository
+            .and_then(|repository| repository.root_ref)
+            .ok_or_else(|| {
+                CILensError::NotFound(format!(
+                    "Project '{project_path}' has no default branch (empty repository?)"
+                ))
+            })
+    }
+
+    async fn fetch_default_branch_rest(&self, project_path: &str) -> Result<String> {
+        let url = self.project_api_url(project_path, "")?;
+        let response = Self::check_response(self.send_rest(self.client.get(url)).await?)?;
+        let project: RestProject = response.json().await?;
+
+        project.default_branch.ok_or_else(|| {
+            CILensError::NotFound(format!(
+                "Project '{project_path}' has no default branch (empty repository?)"
+            ))
+        })
+    }
+
+    /// Looks up the path of the project's CI config, `.gitlab-ci.yml` unless customized.
+    pub async fn fetch_ci_config_path(&self, project_path: &str) -> Result<String> {
+        let request_body = FetchCiConfigPath::build_query(fetch_ci_config_path::Variables {
+            project_path: project_path.to_string(),
+        });
+
+        let data: fetch_ci_config_path::ResponseData = self.post_graphql(&request_body).await?;
+
+        data.project
+            .map(|project| project.ci_config_path_or_default)
+            .ok_or_else(|| CILensError::NotFound(format!("Project '{project_path}' not found")))
+    }
+
+    /// Fetches the raw content of `path` as of `ref_`, or `None` when the file
+    /// does not exist there.
+    pub async fn fetch_file(
+        &self,
+        project_path: &str,
+        path: &str,
+        ref_: &str,
+    ) -> Result<Option<String>> {
+        let encoded: String = url::form_urlencoded::byte_serialize(path.as_bytes()).collect();
+        let url = self.project_api_url(project_path, &format!("repository/files/{encoded}/raw"))?;
+
+        let request = self.client.get(url).query(&[("ref", ref_)]);
+        match Self::check_response(self.send_rest(request).await?) {
+            Ok(response) => Ok(Some(response.text().await?)),
+            Err(CILensError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_default_branch() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .with_body(r#"{"data": {"project": {"repository": {"rootRef": "trunk"}}}}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let branch = client.fetch_default_branch("group/project").await.unwrap();
+
+        assert_eq!(branch, "trunk");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_file_returns_none_when_missing() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock(
+                "GET",
+                "/api/v4/projects/group%2Fproject/repository/files/ci%2Fmain.yml/raw",
+            )
+            .match_query(mockito::Matcher::UrlEncoded("ref".into(), "abc".into()))
+            .with_body("build:\n  script: make\n")
+            .create_async()
+            .await;
+        server
+            .mock(
+                "GET",
+                "/api/v4/projects/group%2Fproject/repository/files/ci%2Fmain.yml/raw",
+            )
+            .match_query(mockito::Matcher::UrlEncoded("ref".into(), "def".into()))
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+
+        let found = client
+            .fetch_file("group/project", "ci/main.yml", "abc")
+            .await
+            .unwrap();
+        let missing = client
+            .fetch_file("group/project", "ci/main.yml", "def")
+            .await
+            .unwrap();
+
+        assert_eq!(found.as_deref(), Some("build:\n  script: make\n"));
+        assert!(missing.is_none());
+    }
+}
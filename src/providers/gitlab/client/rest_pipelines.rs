@@ -0,0 +1,488 @@
+//! REST counterparts of the pipeline queries, for instances with GraphQL
+//! disabled or restricted (see [`Api`](super::Api)).
+//!
+//! Responses are mapped onto the GraphQL node types so the provider handles
+//! both APIs alike. REST reports neither job `needs` (jobs fall back to stage
+//! ordering), a pipeline's stages nor downstream pipeline durations.
+
+use std::collections::HashMap;
+
+use reqwest::header::{HeaderMap, LINK};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+
+use super::core::GitLabClient;
+use super::pipelines::{fetch_pipeline_jobs, fetch_pipelines, PipelinePage, Time};
+use crate::error::{CILensError, Result};
+
+const PER_PAGE: usize = 100;
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct RestPipelineSummary {
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestPipeline {
+    id: u64,
+    #[serde(rename = "ref")]
+    ref_: Option<String>,
+    #[serde(default)]
+    tag: bool,
+    sha: Option<String>,
+    source: Option<String>,
+    status: String,
+    duration: Option<i64>,
+    committed_at: Option<Time>,
+    created_at: Time,
+    started_at: Option<Time>,
+    finished_at: Option<Time>,
+    user: Option<RestUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestUser {
+    username: String,
+    #[serde(default)]
+    bot: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestJob {
+    id: u64,
+    name: String,
+    stage: String,
+    status: String,
+    duration: Option<f64>,
+    queued_duration: Option<f64>,
+    started_at: Option<Time>,
+    finished_at: Option<Time>,
+    #[serde(default)]
+    tag_list: Vec<String>,
+    failure_reason: Option<String>,
+    runner: Option<RestRunner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestRunner {
+    id: u64,
+    runner_type: Option<String>,
+}
+
+/// Parses a GraphQL enum value from its REST spelling, e.g. `instance_type`.
+fn graphql_enum<T: DeserializeOwned>(value: &str) -> Result<T> {
+    Ok(serde_json::from_value(serde_json::Value::String(
+        value.to_uppercase(),
+    ))?)
+}
+
+impl RestPipeline {
+    fn into_node(self) -> Result<fetch_pipelines::FetchPipelinesProjectPipelinesNodes> {
+        let ref_path = self.ref_.as_ref().map(|ref_| {
+            if ref_.starts_with("refs/") {
+                ref_.clone()
+            } else if self.tag {
+                format!("refs/tags/{ref_}")
+            } else {
+                format!("refs/heads/{ref_}")
+            }
+        });
+
+        Ok(fetch_pipelines::FetchPipelinesProjectPipelinesNodes {
+            id: format!("gid://gitlab/Ci::Pipeline/{}", self.id),
+            ref_: self.ref_,
+            ref_path,
+            sha: self.sha,
+            source: self.source,
+            status: graphql_enum(&self.status)?,
+            merge_request_event_type: None,
+            duration: self.duration,
+            committed_at: self.committed_at,
+            created_at: self.created_at,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            user: self.user.map(
+                |user| fetch_pipelines::FetchPipelinesProjectPipelinesNodesUser {
+                    username: user.username,
+                    bot: user.bot,
+                },
+            ),
+            stages: None,
+        })
+    }
+}
+
+impl RestJob {
+    fn into_node(
+        self,
+        retried: bool,
+    ) -> Result<fetch_pipeline_jobs::FetchPipelineJobsProjectPipelineJobsNodes> {
+        let runner = self
+            .runner
+            .map(|runner| -> Result<_> {
+                Ok(
+                    fetch_pipeline_jobs::FetchPipelineJobsProjectPipelineJobsNodesRunner {
+                        id: format!("gid://gitlab/Ci::Runner/{}", runner.id),
+                        runner_type: graphql_enum(
+                            runner.runner_type.as_deref().unwrap_or_default(),
+                        )?,
+                    },
+                )
+            })
+            .transpose()?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(
+            fetch_pipeline_jobs::FetchPipelineJobsProjectPipelineJobsNodes {
+                id: Some(format!("gid://gitlab/Ci::Build/{}", self.id)),
+                name: Some(self.name),
+                status: Some(graphql_enum(&self.status)?),
+                duration: self.duration.map(|duration| duration.round() as i64),
+                started_at: self.started_at,
+                finished_at: self.finished_at,
+                retried: Some(retried),
+                // Worded like GraphQL's failure messages, e.g. "runner system failure"
+                failure_message: self.failure_reason.map(|reason| reason.replace('_', " ")),
+                queued_duration: self.queued_duration,
+                tags: Some(self.tag_list),
+                runner,
+                stage: Some(
+                    fetch_pipeline_jobs::FetchPipelineJobsProjectPipelineJobsNodesStage {
+                        name: Some(self.stage),
+                    },
+                ),
+                needs: None,
+                downstream_pipeline: None,
+            },
+        )
+    }
+}
+
+/// Numeric ID of a `gid://gitlab/Ci::Pipeline/123` global ID.
+fn numeric_pipeline_id(pipeline_id: &str) -> Result<u64> {
+    pipeline_id
+        .rsplit('/')
+        .next()
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| CILensError::Config(format!("Invalid pipeline ID '{pipeline_id}'")))
+}
+
+/// The `rel="next"` URL of a `Link` header, which GitLab sends with both
+/// keyset and offset pagination.
+fn next_page_link(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(LINK)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .find_map(|link| {
+            let (url, params) = link.split_once(';')?;
+            params
+                .split(';')
+                .any(|param| param.trim() == r#"rel="next""#)
+                .then(|| {
+                    url.trim()
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_string()
+                })
+        })
+}
+
+impl GitLabClient {
+    /// REST counterpart of `fetch_pipelines_page`. Pipelines are listed with
+    /// keyset pagination, which GitLab neither caps nor slows down at depth
+    /// like offset pages; the cursor is the URL of the next page.
+    pub(super) async fn fetch_pipelines_page_rest(
+        &self,
+        project_path: &str,
+        limit: usize,
+        cursor: Option<String>,
+        ref_: Option<&str>,
+        status: Option<fetch_pipelines::PipelineStatusEnum>,
+    ) -> Result<PipelinePage> {
+        let request = if let Some(next_page) = cursor {
+            let url = Url::parse(&next_page)
+                .map_err(|e| CILensError::Config(format!("Invalid next page URL: {e}")))?;
+            // The token must not leave the instance, whatever the Link header says
+            if url.origin() != self.api_url.origin() {
+                return Err(CILensError::Config(format!(
+                    "Next page {url} is not on the GitLab instance"
+                )));
+            }
+            self.client.get(url)
+        } else {
+            let mut query = vec![
+                ("pagination", "keyset".to_string()),
+                ("order_by", "id".to_string()),
+                ("sort", "desc".to_string()),
+                ("per_page", limit.min(PER_PAGE).to_string()),
+            ];
+            if let Some(ref_) = ref_ {
+                query.push(("ref", ref_.to_string()));
+            }
+            if let Some(status) = status {
+                query.push(("status", format!("{status:?}").to_lowercase()));
+            }
+            self.client
+                .get(self.project_api_url(project_path, "pipelines")?)
+                .query(&query)
+        };
+        let response = Self::check_response(self.send_rest(request).await?)?;
+
+        let next_page = next_page_link(response.headers());
+
+        // Listed pipelines lack durations and users, so fetch each one
+        let summaries: Vec<RestPipelineSummary> = response.json().await?;
+        let nodes = futures::future::try_join_all(
+            summaries
+                .into_iter()
+                .map(|summary| self.fetch_pipeline_by_id_rest(project_path, summary.id)),
+        )
+        .await?;
+
+        Ok((nodes, next_page))
+    }
+
+    /// REST counterpart of `fetch_pipeline_by_id`.
+    pub(super) async fn fetch_pipeline_by_id_rest(
+        &self,
+        project_path: &str,
+        pipeline_id: u64,
+    ) -> Result<fetch_pipelines::FetchPipelinesProjectPipelinesNodes> {
+        let url = self.project_api_url(project_path, &format!("pipelines/{pipeline_id}"))?;
+        let response = Self::check_response(self.send_rest(self.client.get(url)).await?)?;
+
+        response.json::<RestPipeline>().await?.into_node()
+    }
+
+    /// REST counterpart of `fetch_pipeline_jobs`: the pipeline's jobs and
+    /// trigger (bridge) jobs, with every attempt but the last of a job marked
+    /// as retried.
+    pub(super) async fn fetch_pipeline_jobs_rest(
+        &self,
+        project_path: &str,
+        pipeline_id: &str,
+    ) -> Result<Vec<fetch_pipeline_jobs::FetchPipelineJobsProjectPipelineJobsNodes>> {
+        let pipeline_id = numeric_pipeline_id(pipeline_id)?;
+
+        let mut jobs = self
+            .fetch_rest_jobs(
+                project_path,
+                &format!("pipelines/{pipeline_id}/jobs"),
+                &[("include_retried", "true")],
+            )
+            .await?;
+        jobs.extend(
+            self.fetch_rest_jobs(
+                project_path,
+                &format!("pipelines/{pipeline_id}/bridges"),
+                &[],
+            )
+            .await?,
+        );
+
+        let mut last_attempts: HashMap<&str, u64> = HashMap::new();
+        for job in &jobs {
+            let last = last_attempts.entry(&job.name).or_default();
+            *last = (*last).max(job.id);
+        }
+        let retried: Vec<bool> = jobs
+            .iter()
+            .map(|job| last_attempts[job.name.as_str()] != job.id)
+            .collect();
+
+        jobs.into_iter()
+            .zip(retried)
+            .map(|(job, retried)| job.into_node(retried))
+            .collect()
+    }
+
+    async fn fetch_rest_jobs(
+        &self,
+        project_path: &str,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Vec<RestJob>> {
+        let url = self.project_api_url(project_path, path)?;
+
+        let mut jobs = Vec::new();
+        let mut page = 1;
+
+        while page <= MAX_PAGES {
+            let request = self.client.get(url.clone()).query(params).query(&[
+                ("per_page", PER_PAGE.to_string()),
+                ("page", page.to_string()),
+            ]);
+            let response = Self::check_response(self.send_rest(request).await?)?;
+
+            let has_next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| !v.is_empty());
+
+            let batch: Vec<RestJob> = response.json().await?;
+            jobs.extend(batch);
+
+            if !has_next_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(jobs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Api;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_pipelines_page_falls_back_to_rest() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .with_status(404)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/pipelines")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"[{"id": 123}]"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/pipelines/123")
+            .with_body(
+                r#"{
+                    "id": 123,
+                    "ref": "main",
+                    "tag": false,
+                    "sha": "abc123",
+                    "source": "push",
+                    "status": "success",
+                    "duration": 60,
+                    "created_at": "2025-01-01T10:00:00Z",
+                    "started_at": "2025-01-01T10:00:00Z",
+                    "finished_at": "2025-01-01T10:01:00Z",
+                    "user": {"username": "alice"}
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None)
+            .unwrap()
+            .with_api(Api::Auto);
+        let (nodes, cursor) = client
+            .fetch_pipelines_page("group/project", 10, None, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].id, "gid://gitlab/Ci::Pipeline/123");
+        assert_eq!(nodes[0].ref_path.as_deref(), Some("refs/heads/main"));
+        assert_eq!(
+            nodes[0].status,
+            fetch_pipelines::PipelineStatusEnum::SUCCESS
+        );
+        assert!(cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pipelines_page_rest_follows_keyset_links() {
+        let mut server = mockito::Server::new_async().await;
+        let next_page = format!(
+            "{}/api/v4/projects/group%2Fproject/pipelines?cursor=abc&pagination=keyset",
+            server.url()
+        );
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/pipelines")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("pagination".into(), "keyset".into()),
+                mockito::Matcher::UrlEncoded("order_by".into(), "id".into()),
+                mockito::Matcher::UrlEncoded("sort".into(), "desc".into()),
+            ]))
+            .with_header(
+                "link",
+                &format!(
+                    r#"<{next_page}>; rel="next", <{}>; rel="first""#,
+                    server.url()
+                ),
+            )
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None)
+            .unwrap()
+            .with_api(Api::Rest);
+        let (nodes, cursor) = client
+            .fetch_pipelines_page("group/project", 10, None, None, None)
+            .await
+            .unwrap();
+
+        assert!(nodes.is_empty());
+        assert_eq!(cursor.as_deref(), Some(next_page.as_str()));
+
+        let foreign = client
+            .fetch_pipelines_page(
+                "group/project",
+                10,
+                Some("https://evil.example.com/pipelines".to_string()),
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(foreign, Err(CILensError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pipeline_jobs_rest_marks_earlier_attempts_retried() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/pipelines/123/jobs")
+            .match_query(mockito::Matcher::Any)
+            .with_body(
+                r#"[
+                    {"id": 2, "name": "test", "stage": "test", "status": "success",
+                     "duration": 30.4, "runner": {"id": 7, "runner_type": "instance_type"}},
+                    {"id": 1, "name": "test", "stage": "test", "status": "failed",
+                     "duration": 12.0, "failure_reason": "runner_system_failure"}
+                ]"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock(
+                "GET",
+                "/api/v4/projects/group%2Fproject/pipelines/123/bridges",
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None)
+            .unwrap()
+            .with_api(Api::Rest);
+        let jobs = client
+            .fetch_pipeline_jobs("group/project", "gid://gitlab/Ci::Pipeline/123")
+            .await
+            .unwrap();
+
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].retried, Some(false));
+        assert_eq!(jobs[0].duration, Some(30));
+        assert_eq!(jobs[1].retried, Some(true));
+        assert_eq!(
+            jobs[1].failure_message.as_deref(),
+            Some("runner system failure")
+        );
+    }
+}
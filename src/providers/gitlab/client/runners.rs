@@ -0,0 +1,88 @@
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::Result;
+
+const PER_PAGE: &str = "100";
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct Runner {
+    pub id: u64,
+    pub description: Option<String>,
+    /// `online`, `offline`, `stale` or `never_contacted`
+    pub status: String,
+    #[serde(default)]
+    pub paused: bool,
+    /// `instance_type`, `group_type` or `project_type`
+    #[serde(rename = "runner_type")]
+    pub kind: String,
+}
+
+impl GitLabClient {
+    /// Fetches the runners available to the project, including shared and group runners.
+    pub async fn fetch_project_runners(&self, project_path: &str) -> Result<Vec<Runner>> {
+        let url = self.project_api_url(project_path, "runners")?;
+
+        let mut runners = Vec::new();
+        let mut page = 1;
+
+        while page <= MAX_PAGES {
+            let request = self
+                .client
+                .get(url.clone())
+                .query(&[("per_page", PER_PAGE), ("page", &page.to_string())]);
+            let response = Self::check_response(self.send_rest(request).await?)?;
+
+            let has_next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| !v.is_empty());
+
+            let batch: Vec<Runner> = response.json().await?;
+            runners.extend(batch);
+
+            if !has_next_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(runners)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_project_runners_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/runners")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+            .with_header("x-next-page", "2")
+            .with_body(
+                r#"[{"id": 1, "description": "docker-1", "status": "online", "paused": false, "runner_type": "project_type"}]"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/runners")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_body(
+                r#"[{"id": 2, "description": null, "status": "offline", "runner_type": "instance_type"}]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(&server.url(), None).unwrap();
+        let runners = client.fetch_project_runners("group/project").await.unwrap();
+
+        assert_eq!(runners.len(), 2);
+        assert_eq!(runners[1].status, "offline");
+        assert!(!runners[1].paused);
+    }
+}
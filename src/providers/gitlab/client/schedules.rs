@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::Result;
+
+const PER_PAGE: &str = "100";
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct PipelineSchedule {
+    pub id: u64,
+    #[serde(default)]
+    pub description: String,
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    pub cron: String,
+    pub cron_timezone: String,
+    pub active: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SchedulePipeline {
+    pub created_at: DateTime<Utc>,
+}
+
+impl GitLabClient {
+    /// Fetches the project's pipeline schedules.
+    pub async fn fetch_pipeline_schedules(
+        &self,
+        project_path: &str,
+    ) -> Result<Vec<PipelineSchedule>> {
+        let url = self.project_api_url(project_path, "pipeline_schedules")?;
+
+        let mut schedules = Vec::new();
+        let mut page = 1;
+
+        while page <= MAX_PAGES {
+            let request = self
+                .client
+                .get(url.clone())
+                .query(&[("per_page", PER_PAGE), ("page", &page.to_string())]);
+            let response = Self::check_response(self.send_rest(request).await?)?;
+
+            let has_next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| !v.is_empty());
+
+            let batch: Vec<PipelineSchedule> = response.json().await?;
+            schedules.extend(batch);
+
+            if !has_next_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(schedules)
+    }
+
+    /// Fetches the pipelines schedule `schedule_id` created after `since`,
+    /// newest first.
+    pub async fn fetch_schedule_pipelines(
+        &self,
+        project_path: &str,
+        schedule_id: u64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<SchedulePipeline>> {
+        let url = self.project_api_url(
+            project_path,
+            &format!("pipeline_schedules/{schedule_id}/pipelines"),
+        )?;
+
+        let mut pipelines = Vec::new();
+        let mut page = 1;
+
+        while page <= MAX_PAGES {
+            let request = self.client.get(url.clone()).query(&[
+                ("sort", "desc"),
+                ("per_page", PER_PAGE),
+                ("page", &page.to_string()),
+            ]);
+            let response = Self::check_response(self.send_rest(request).await?)?;
+
+            let has_next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| !v.is_empty());
+
+            let batch: Vec<SchedulePipeline> = response.json().await?;
+            let reached_since = batch.iter().any(|p| p.created_at < since);
+            pipelines.extend(batch.into_iter().filter(|p| p.created_at >= since));
+
+            if !has_next_page || reached_since {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(pipelines)
+    }
+}
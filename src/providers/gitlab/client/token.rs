@@ -0,0 +1,170 @@
+use log::{debug, warn};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::core::GitLabClient;
+use crate::error::{CILensError, Result};
+
+const REQUIRED_SCOPES: [&str; 2] = ["read_api", "api"];
+
+#[derive(Debug, Deserialize)]
+struct CurrentUser {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenInfo {
+    #[serde(default)]
+    scopes: Vec<String>,
+    #[serde(default)]
+    active: Option<bool>,
+}
+
+impl GitLabClient {
+    /// Verifies the configured token before any collection starts.
+    ///
+    /// Checks that GitLab accepts the token and, when the token-info endpoint
+    /// is available, that it carries the `read_api` (or `api`) scope.
+    pub async fn validate_token(&self) -> Result<()> {
+        if self.token.is_none() {
+            return Ok(());
+        }
+
+        let user_url = self
+            .api_url
+            .join("user")
+            .map_err(|e| CILensError::Config(format!("Invalid REST API URL: {e}")))?;
+
+        let response = self.send_rest(self.client.get(user_url)).await?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED => {
+                return Err(CILensError::Auth(
+                    "GitLab rejected the token (expired, revoked or invalid)".to_string(),
+                ));
+            }
+            StatusCode::FORBIDDEN => {
+                return Err(CILensError::Auth(
+                    "Token is not allowed to access the GitLab API (missing read_api scope?)"
+                        .to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        let user: CurrentUser = Self::check_response(response)?.json().await?;
+        debug!("Authenticated as {}", user.username);
+
+        let token_url = self
+            .api_url
+            .join("personal_access_tokens/self")
+            .map_err(|e| CILensError::Config(format!("Invalid REST API URL: {e}")))?;
+
+        let response = self.send_rest(self.client.get(token_url)).await?;
+
+        // Only personal, project and group access tokens can introspect themselves
+        if !response.status().is_success() {
+            warn!(
+                "Could not inspect token scopes (HTTP {}), skipping scope check",
+                response.status()
+            );
+            return Ok(());
+        }
+
+        let info: TokenInfo = response.json().await?;
+
+        if info.active == Some(false) {
+            return Err(CILensError::Auth("Token is no longer active".to_string()));
+        }
+
+        if !info
+            .scopes
+            .iter()
+            .any(|scope| REQUIRED_SCOPES.contains(&scope.as_str()))
+        {
+            return Err(CILensError::Auth(format!(
+                "Token is missing the read_api scope (has: {})",
+                info.scopes.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Token;
+
+    fn client_for(server: &mockito::Server) -> GitLabClient {
+        GitLabClient::new(&server.url(), Some(Token::from("glpat-test"))).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_accepts_read_api_scope() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/user")
+            .with_body(r#"{"username": "alice"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/personal_access_tokens/self")
+            .with_body(r#"{"scopes": ["read_api"], "active": true}"#)
+            .create_async()
+            .await;
+
+        assert!(client_for(&server).validate_token().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_rejects_invalid_token() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/user")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let result = client_for(&server).validate_token().await;
+
+        assert!(matches!(result, Err(CILensError::Auth(_))));
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_rejects_missing_scope() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/user")
+            .with_body(r#"{"username": "alice"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/personal_access_tokens/self")
+            .with_body(r#"{"scopes": ["read_repository"], "active": true}"#)
+            .create_async()
+            .await;
+
+        let result = client_for(&server).validate_token().await;
+
+        assert!(matches!(result, Err(CILensError::Auth(_))));
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_skips_scope_check_when_unavailable() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/user")
+            .with_body(r#"{"username": "alice"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/personal_access_tokens/self")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        assert!(client_for(&server).validate_token().await.is_ok());
+    }
+}
@@ -0,0 +1,76 @@
+use serde_json::json;
+
+use super::types::GitLabPipeline;
+use super::url_utils::pipeline_id_to_url;
+use crate::cloudevents::{CloudEvent, PIPELINE_COLLECTED};
+
+/// One event per collected pipeline. IDs derive from the pipeline ID and
+/// status, so re-collecting a finished pipeline yields a duplicate consumers can drop.
+pub fn pipeline_events(
+    pipelines: &[GitLabPipeline],
+    base_url: &str,
+    project_path: &str,
+) -> Vec<CloudEvent> {
+    pipelines
+        .iter()
+        .map(|pipeline| {
+            let url = pipeline_id_to_url(base_url, project_path, &pipeline.id);
+            let jobs: Vec<_> = pipeline
+                .jobs
+                .iter()
+                .filter(|job| !job.retried)
+                .map(|job| {
+                    json!({
+                        "name": job.full_name(),
+                        "stage": job.stage,
+                        "status": job.status,
+                        "duration_seconds": job.duration,
+                    })
+                })
+                .collect();
+
+            CloudEvent::new(
+                PIPELINE_COLLECTED,
+                project_path,
+                url.clone(),
+                format!("{}:{}", pipeline.id, pipeline.status),
+                pipeline.finished_at.unwrap_or(pipeline.created_at),
+                json!({
+                    "url": url,
+                    "ref": pipeline.ref_,
+                    "sha": pipeline.sha,
+                    "source": pipeline.source,
+                    "status": pipeline.status,
+                    "duration_seconds": pipeline.duration,
+                    "created_at": pipeline.created_at,
+                    "finished_at": pipeline.finished_at,
+                    "jobs": jobs,
+                }),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline};
+
+    #[test]
+    fn test_pipeline_events_carry_final_job_attempts() {
+        let mut pipeline = pipeline(42, "failed", "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z");
+        let mut retried = job(1, "rspec", "test", 30.0, "FAILED");
+        retried.retried = true;
+        pipeline.jobs = vec![retried, job(2, "rspec", "test", 40.0, "SUCCESS")];
+
+        let events = pipeline_events(&[pipeline], "https://gitlab.com", "group/project");
+
+        assert_eq!(events[0].type_, PIPELINE_COLLECTED);
+        assert_eq!(
+            events[0].subject,
+            "https://gitlab.com/group/project/-/pipelines/42"
+        );
+        assert_eq!(events[0].data["jobs"].as_array().unwrap().len(), 1);
+        assert_eq!(events[0].data["jobs"][0]["status"], "SUCCESS");
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use super::types::GitLabPipeline;
+use super::url_utils::pipeline_id_to_url;
+use crate::insights::{CommitAttempts, CommitRetries};
+
+/// How many pipelines each commit took to go green: its failed pipelines
+/// before the first successful one, plus that one. Commits never seen green
+/// don't count towards the average; commits that needed more than one
+/// attempt are listed, most attempts first.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_commit_retries(
+    pipelines: &[GitLabPipeline],
+    base_url: &str,
+    project_path: &str,
+) -> CommitRetries {
+    let mut by_sha: HashMap<&str, Vec<&GitLabPipeline>> = HashMap::new();
+    for pipeline in pipelines.iter().filter(|p| !p.sha.is_empty()) {
+        by_sha.entry(&pipeline.sha).or_default().push(pipeline);
+    }
+    let total_commits = by_sha.len();
+
+    let mut green: Vec<CommitAttempts> = by_sha
+        .into_iter()
+        .filter_map(|(sha, mut commit_pipelines)| {
+            commit_pipelines.sort_by_key(|p| p.created_at);
+            let green_index = commit_pipelines
+                .iter()
+                .position(|p| p.status == "success")?;
+            let failed = commit_pipelines[..green_index]
+                .iter()
+                .filter(|p| p.status == "failed")
+                .count();
+            let green_pipeline = commit_pipelines[green_index];
+
+            Some(CommitAttempts {
+                sha: sha.to_string(),
+                ref_: green_pipeline.ref_.clone(),
+                attempts_to_green: failed + 1,
+                green_pipeline: pipeline_id_to_url(base_url, project_path, &green_pipeline.id),
+            })
+        })
+        .collect();
+
+    let green_commits = green.len();
+    let avg_attempts_to_green = if green.is_empty() {
+        0.0
+    } else {
+        green.iter().map(|c| c.attempts_to_green).sum::<usize>() as f64 / green.len() as f64
+    };
+
+    green.retain(|c| c.attempts_to_green > 1);
+    green.sort_by(|a, b| {
+        b.attempts_to_green
+            .cmp(&a.attempts_to_green)
+            .then_with(|| a.sha.cmp(&b.sha))
+    });
+
+    CommitRetries {
+        total_commits,
+        green_commits,
+        avg_attempts_to_green,
+        retried_commits: green,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+
+    #[test]
+    fn test_commit_retries_count_failed_runs_before_green() {
+        let run = |id: u64, sha: &str, status: &str, hour: u32| {
+            let mut pipeline = pipeline(
+                id,
+                status,
+                &format!("2025-01-01T{hour:02}:00:00Z"),
+                &format!("2025-01-01T{hour:02}:10:00Z"),
+            );
+            pipeline.sha = sha.to_string();
+            pipeline
+        };
+        let pipelines = vec![
+            run(1, "aaa", "failed", 1),
+            run(2, "aaa", "canceled", 2),
+            run(3, "aaa", "failed", 3),
+            run(4, "aaa", "success", 4),
+            run(5, "aaa", "failed", 5),
+            run(6, "bbb", "success", 1),
+            run(7, "ccc", "failed", 1),
+        ];
+
+        let retries = calculate_commit_retries(&pipelines, "https://gitlab.com", "group/project");
+
+        assert_eq!(retries.total_commits, 3);
+        assert_eq!(retries.green_commits, 2);
+        assert!((retries.avg_attempts_to_green - 2.0).abs() < f64::EPSILON);
+        assert_eq!(retries.retried_commits.len(), 1);
+        assert_eq!(retries.retried_commits[0].attempts_to_green, 3);
+        assert_eq!(
+            retries.retried_commits[0].green_pipeline,
+            "https://gitlab.com/group/project/-/pipelines/4"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::types::GitLabPipeline;
+use super::url_utils::pipeline_id_to_url;
+use crate::insights::CompositionChange;
+
+/// Consecutive pipelines of a ref that ran the same set of jobs.
+struct Run<'a> {
+    jobs: BTreeSet<&'a str>,
+    pipelines: Vec<&'a GitLabPipeline>,
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn avg_successful_duration(pipelines: &[&GitLabPipeline]) -> f64 {
+    let durations: Vec<f64> = pipelines
+        .iter()
+        .filter(|p| p.status == "success")
+        .map(|p| p.duration as f64)
+        .collect();
+    if durations.is_empty() {
+        0.0
+    } else {
+        durations.iter().sum::<f64>() / durations.len() as f64
+    }
+}
+
+/// Finds where the set of jobs a ref's pipelines run changed, as when a CI
+/// config refactor adds, drops or renames jobs, changing the pipeline type's
+/// signature. A set counts once two consecutive pipelines of the ref ran it,
+/// so jobs `rules` include only now and then do not register as changes.
+/// Oldest change first.
+pub fn calculate_composition_changes(
+    pipelines: &[GitLabPipeline],
+    base_url: &str,
+    project_path: &str,
+) -> Vec<CompositionChange> {
+    let mut by_ref: BTreeMap<&str, Vec<&GitLabPipeline>> = BTreeMap::new();
+    for pipeline in pipelines.iter().filter(|p| !p.jobs.is_empty()) {
+        by_ref.entry(&pipeline.ref_).or_default().push(pipeline);
+    }
+
+    let mut changes = Vec::new();
+    for (ref_, mut ref_pipelines) in by_ref {
+        ref_pipelines.sort_by_key(|p| p.created_at);
+
+        let mut runs: Vec<Run> = Vec::new();
+        for pipeline in ref_pipelines {
+            let jobs: BTreeSet<&str> = pipeline.jobs.iter().map(|j| j.name.as_str()).collect();
+            match runs.last_mut() {
+                Some(run) if run.jobs == jobs => run.pipelines.push(pipeline),
+                _ => runs.push(Run {
+                    jobs,
+                    pipelines: vec![pipeline],
+                }),
+            }
+        }
+
+        let stable: Vec<&Run> = runs.iter().filter(|run| run.pipelines.len() >= 2).collect();
+        for pair in stable.windows(2) {
+            let (before, after) = (pair[0], pair[1]);
+            if before.jobs == after.jobs {
+                continue;
+            }
+
+            let first = after.pipelines[0];
+            let mut change = CompositionChange {
+                ref_: ref_.to_string(),
+                changed_at: first.created_at,
+                pipeline: pipeline_id_to_url(base_url, project_path, &first.id),
+                jobs_before: before.jobs.len(),
+                jobs_after: after.jobs.len(),
+                added_jobs: after
+                    .jobs
+                    .difference(&before.jobs)
+                    .map(ToString::to_string)
+                    .collect(),
+                removed_jobs: before
+                    .jobs
+                    .difference(&after.jobs)
+                    .map(ToString::to_string)
+                    .collect(),
+                avg_duration_before_seconds: avg_successful_duration(&before.pipelines),
+                avg_duration_after_seconds: avg_successful_duration(&after.pipelines),
+                summary: String::new(),
+            };
+            change.summary = change.describe();
+            changes.push(change);
+        }
+    }
+
+    changes.sort_by_key(|change| change.changed_at);
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline};
+
+    fn run(id: u64, day: u32, jobs: &[&str], finished: &str) -> GitLabPipeline {
+        let mut pipeline = pipeline(
+            id,
+            "success",
+            &format!("2025-01-{day:02}T10:00:00Z"),
+            &format!("2025-01-{day:02}T{finished}Z"),
+        );
+        pipeline.jobs = jobs
+            .iter()
+            .enumerate()
+            .map(|(i, name)| job(id * 10 + i as u64, name, "test", 60.0, "SUCCESS"))
+            .collect();
+        pipeline
+    }
+
+    #[test]
+    fn test_composition_change_needs_the_new_job_set_to_stick() {
+        let pipelines = vec![
+            run(1, 1, &["build", "test"], "10:05:00"),
+            run(2, 2, &["build", "test"], "10:05:00"),
+            // `rules` added docs once; not a config change
+            run(3, 3, &["build", "docs", "test"], "10:06:00"),
+            run(4, 4, &["build", "test"], "10:05:00"),
+            run(5, 5, &["build", "lint", "unit"], "10:08:00"),
+            run(6, 6, &["build", "lint", "unit"], "10:08:00"),
+        ];
+
+        let changes = calculate_composition_changes(&pipelines, "https://gitlab.com", "g/p");
+
+        assert_eq!(changes.len(), 1);
+        let change = &changes[0];
+        assert_eq!(change.added_jobs, vec!["lint", "unit"]);
+        assert_eq!(change.removed_jobs, vec!["test"]);
+        assert_eq!(change.pipeline, "https://gitlab.com/g/p/-/pipelines/5");
+        assert!((change.avg_duration_before_seconds - 300.0).abs() < f64::EPSILON);
+        assert!((change.avg_duration_after_seconds - 480.0).abs() < f64::EPSILON);
+        assert_eq!(
+            change.summary,
+            "main: 2 -> 3 jobs (added lint, unit; removed test), duration jumped 60%"
+        );
+    }
+}
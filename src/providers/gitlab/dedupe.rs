@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use super::types::GitLabPipeline;
+
+/// Which pipeline to keep when several ran for the same commit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DedupePreference {
+    /// Merge request pipelines, which are the ones gating merges
+    #[default]
+    MergeRequest,
+    /// Branch (and tag) pipelines
+    Branch,
+}
+
+fn is_merge_request(pipeline: &GitLabPipeline) -> bool {
+    pipeline.ref_path.starts_with("refs/merge-requests/")
+}
+
+/// Keeps one pipeline per commit SHA: the latest one of the preferred kind,
+/// or the latest one overall when none is of that kind. Pipelines without a
+/// SHA are kept as is, and the original order is preserved.
+pub fn dedupe_by_sha(
+    pipelines: Vec<GitLabPipeline>,
+    preference: DedupePreference,
+) -> Vec<GitLabPipeline> {
+    let preferred = |pipeline: &GitLabPipeline| {
+        is_merge_request(pipeline) == (preference == DedupePreference::MergeRequest)
+    };
+
+    let mut keep: HashMap<&str, usize> = HashMap::new();
+    for (index, pipeline) in pipelines.iter().enumerate() {
+        if pipeline.sha.is_empty() {
+            continue;
+        }
+        keep.entry(pipeline.sha.as_str())
+            .and_modify(|kept| {
+                let current = &pipelines[*kept];
+                let better = match (preferred(pipeline), preferred(current)) {
+                    (true, false) => true,
+                    (false, true) => false,
+                    _ => pipeline.created_at > current.created_at,
+                };
+                if better {
+                    *kept = index;
+                }
+            })
+            .or_insert(index);
+    }
+
+    let keep: Vec<bool> = pipelines
+        .iter()
+        .enumerate()
+        .map(|(index, p)| p.sha.is_empty() || keep.get(p.sha.as_str()) == Some(&index))
+        .collect();
+
+    pipelines
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(pipeline, keep)| keep.then_some(pipeline))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+
+    fn commit_pipeline(
+        id: u64,
+        sha: &str,
+        merge_request: bool,
+        created_at: &str,
+    ) -> GitLabPipeline {
+        let mut pipeline = pipeline(id, "success", created_at, "2025-01-02T00:00:00Z");
+        pipeline.sha = sha.to_string();
+        if merge_request {
+            pipeline.ref_path = format!("refs/merge-requests/{id}/head");
+        }
+        pipeline
+    }
+
+    fn ids(pipelines: &[GitLabPipeline]) -> Vec<&str> {
+        pipelines.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    fn sample() -> Vec<GitLabPipeline> {
+        vec![
+            commit_pipeline(1, "aaa", false, "2025-01-01T10:00:00Z"),
+            commit_pipeline(2, "aaa", true, "2025-01-01T10:00:05Z"),
+            commit_pipeline(3, "bbb", false, "2025-01-01T11:00:00Z"),
+            commit_pipeline(4, "bbb", false, "2025-01-01T12:00:00Z"),
+            commit_pipeline(5, "", false, "2025-01-01T13:00:00Z"),
+        ]
+    }
+
+    #[test]
+    fn test_dedupe_prefers_merge_request_pipelines() {
+        let pipelines = dedupe_by_sha(sample(), DedupePreference::MergeRequest);
+
+        assert_eq!(
+            ids(&pipelines),
+            vec![
+                "gid://gitlab/Ci::Pipeline/2",
+                "gid://gitlab/Ci::Pipeline/4",
+                "gid://gitlab/Ci::Pipeline/5",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedupe_prefers_branch_pipelines() {
+        let pipelines = dedupe_by_sha(sample(), DedupePreference::Branch);
+
+        assert_eq!(
+            ids(&pipelines),
+            vec![
+                "gid://gitlab/Ci::Pipeline/1",
+                "gid://gitlab/Ci::Pipeline/4",
+                "gid://gitlab/Ci::Pipeline/5",
+            ]
+        );
+    }
+}
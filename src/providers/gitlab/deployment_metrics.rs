@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, HashSet};
+
+use super::client::deployments::Deployment;
+use super::stats::{mean, rate};
+use crate::insights::EnvironmentDeploymentMetrics;
+
+/// Counts successful deployments that redeploy a commit already deployed
+/// earlier (but not immediately before) to the same environment.
+fn count_rollbacks(deployments: &[&Deployment]) -> usize {
+    let mut deployed: HashSet<&str> = HashSet::new();
+    let mut previous: Option<&str> = None;
+    let mut rollbacks = 0;
+
+    for deployment in deployments.iter().filter(|d| d.status == "success") {
+        let sha = deployment.sha.as_str();
+        if previous != Some(sha) && deployed.contains(sha) {
+            rollbacks += 1;
+        }
+        deployed.insert(sha);
+        previous = Some(sha);
+    }
+
+    rollbacks
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn environment_metrics(
+    environment: String,
+    mut deployments: Vec<&Deployment>,
+) -> EnvironmentDeploymentMetrics {
+    deployments.sort_by_key(|d| d.created_at);
+
+    let successful: Vec<&Deployment> = deployments
+        .iter()
+        .filter(|d| d.status == "success")
+        .copied()
+        .collect();
+
+    let avg_deploy_job_duration_seconds = mean(
+        successful
+            .iter()
+            .filter_map(|d| d.deployable.as_ref()?.duration),
+    );
+
+    // At least one day so a burst of same-day deploys doesn't read as infinite frequency
+    let window_days = match (deployments.first(), deployments.last()) {
+        (Some(first), Some(last)) => {
+            ((last.created_at - first.created_at).num_seconds() as f64 / 86_400.0).max(1.0)
+        }
+        _ => 1.0,
+    };
+
+    let rollbacks = count_rollbacks(&deployments);
+
+    EnvironmentDeploymentMetrics {
+        environment,
+        total_deployments: deployments.len(),
+        successful_deployments: successful.len(),
+        success_rate: rate(successful.len(), deployments.len()),
+        deployments_per_day: successful.len() as f64 / window_days,
+        avg_deploy_job_duration_seconds,
+        rollbacks,
+        rollback_rate: rate(rollbacks, successful.len()),
+    }
+}
+
+pub fn calculate_deployment_metrics(
+    deployments: &[Deployment],
+) -> Vec<EnvironmentDeploymentMetrics> {
+    let mut by_environment: BTreeMap<&str, Vec<&Deployment>> = BTreeMap::new();
+    for deployment in deployments {
+        by_environment
+            .entry(deployment.environment.name.as_str())
+            .or_default()
+            .push(deployment);
+    }
+
+    let mut metrics: Vec<EnvironmentDeploymentMetrics> = by_environment
+        .into_iter()
+        .map(|(environment, deployments)| environment_metrics(environment.to_string(), deployments))
+        .collect();
+
+    metrics.sort_by_key(|m| std::cmp::Reverse(m.total_deployments));
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::client::deployments::{Deployable, DeploymentEnvironment};
+    use crate::providers::gitlab::types::fixtures::time;
+
+    fn deployment(environment: &str, sha: &str, created_at: &str, status: &str) -> Deployment {
+        Deployment {
+            sha: sha.to_string(),
+            created_at: time(created_at),
+            status: status.to_string(),
+            environment: DeploymentEnvironment {
+                name: environment.to_string(),
+            },
+            deployable: Some(Deployable {
+                duration: Some(60.0),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_redeploying_an_older_sha_counts_as_rollback() {
+        let deployments = vec![
+            deployment("production", "a", "2025-01-01T10:00:00Z", "success"),
+            deployment("production", "b", "2025-01-02T10:00:00Z", "success"),
+            deployment("production", "a", "2025-01-03T10:00:00Z", "success"),
+            deployment("staging", "a", "2025-01-01T10:00:00Z", "success"),
+            deployment("staging", "a", "2025-01-01T11:00:00Z", "success"),
+        ];
+
+        let metrics = calculate_deployment_metrics(&deployments);
+        let production = metrics
+            .iter()
+            .find(|m| m.environment == "production")
+            .unwrap();
+        let staging = metrics.iter().find(|m| m.environment == "staging").unwrap();
+
+        assert_eq!(production.rollbacks, 1);
+        // Retrying the same sha back-to-back is a redeploy, not a rollback
+        assert_eq!(staging.rollbacks, 0);
+    }
+
+    #[test]
+    fn test_deployment_frequency_and_success_rate() {
+        let deployments = vec![
+            deployment("production", "a", "2025-01-01T00:00:00Z", "success"),
+            deployment("production", "b", "2025-01-02T00:00:00Z", "failed"),
+            deployment("production", "c", "2025-01-05T00:00:00Z", "success"),
+        ];
+
+        let metrics = calculate_deployment_metrics(&deployments);
+
+        assert_eq!(metrics[0].total_deployments, 3);
+        assert!((metrics[0].deployments_per_day - 0.5).abs() < f64::EPSILON);
+        assert!((metrics[0].success_rate - 200.0 / 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+
+use super::types::GitLabPipeline;
+use crate::insights::{DeveloperWait, WeeklyWait};
+
+const MERGE_REQUEST_SOURCE: &str = "merge_request_event";
+
+/// Seconds until the developer has the full picture: the latest job feedback
+/// on the pipeline's critical path, or the pipeline duration when it has no jobs.
+#[allow(clippy::cast_precision_loss)]
+fn wait_seconds(pipeline: &GitLabPipeline) -> f64 {
+    super::job_analysis::calculate_job_metrics(pipeline)
+        .iter()
+        .map(|job| job.avg_time_to_feedback_seconds)
+        .max_by(f64::total_cmp)
+        .unwrap_or(pipeline.duration as f64)
+}
+
+/// Monday of the week `time` falls in, in `timezone`.
+pub fn week_start(time: DateTime<Utc>, timezone: Tz) -> NaiveDate {
+    let date = time.with_timezone(&timezone).date_naive();
+    date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+/// Sums how long developers waited on merge request pipelines, per ISO week
+/// of `timezone`.
+pub fn calculate_developer_wait(
+    pipelines: &[GitLabPipeline],
+    timezone: Tz,
+) -> Option<DeveloperWait> {
+    let mut weeks: BTreeMap<NaiveDate, (usize, f64)> = BTreeMap::new();
+
+    for pipeline in pipelines
+        .iter()
+        .filter(|p| p.source == MERGE_REQUEST_SOURCE)
+    {
+        let week = weeks
+            .entry(week_start(pipeline.created_at, timezone))
+            .or_default();
+        week.0 += 1;
+        week.1 += wait_seconds(pipeline) / 3600.0;
+    }
+
+    if weeks.is_empty() {
+        return None;
+    }
+
+    let weekly: Vec<WeeklyWait> = weeks
+        .into_iter()
+        .map(|(week_start, (pipelines, hours))| WeeklyWait {
+            week_start,
+            pipelines,
+            hours,
+        })
+        .collect();
+
+    let total_hours: f64 = weekly.iter().map(|w| w.hours).sum();
+    #[allow(clippy::cast_precision_loss)]
+    let avg_hours_per_week = total_hours / weekly.len() as f64;
+
+    Some(DeveloperWait {
+        total_merge_request_pipelines: weekly.iter().map(|w| w.pipelines).sum(),
+        total_hours,
+        avg_hours_per_week,
+        weekly,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+
+    fn mr_pipeline(id: u64, created_at: &str, finished_at: &str) -> GitLabPipeline {
+        let mut pipeline = pipeline(id, "success", created_at, finished_at);
+        pipeline.source = MERGE_REQUEST_SOURCE.to_string();
+        pipeline
+    }
+
+    #[test]
+    fn test_developer_wait_groups_merge_request_pipelines_by_week() {
+        let pipelines = vec![
+            // Monday and Sunday of the same week
+            mr_pipeline(1, "2025-01-06T10:00:00Z", "2025-01-06T11:00:00Z"),
+            mr_pipeline(2, "2025-01-12T10:00:00Z", "2025-01-12T10:30:00Z"),
+            mr_pipeline(3, "2025-01-13T10:00:00Z", "2025-01-13T12:00:00Z"),
+            // Branch pipelines don't keep anyone waiting on review
+            pipeline(4, "success", "2025-01-06T10:00:00Z", "2025-01-06T20:00:00Z"),
+        ];
+
+        let wait = calculate_developer_wait(&pipelines, Tz::UTC).unwrap();
+
+        assert_eq!(wait.total_merge_request_pipelines, 3);
+        assert_eq!(wait.weekly.len(), 2);
+        assert_eq!(wait.weekly[0].week_start.to_string(), "2025-01-06");
+        assert!((wait.weekly[0].hours - 1.5).abs() < f64::EPSILON);
+        assert!((wait.avg_hours_per_week - 1.75).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_developer_wait_is_none_without_merge_request_pipelines() {
+        let pipelines = vec![pipeline(
+            1,
+            "success",
+            "2025-01-06T10:00:00Z",
+            "2025-01-06T11:00:00Z",
+        )];
+
+        assert!(calculate_developer_wait(&pipelines, Tz::UTC).is_none());
+    }
+
+    #[test]
+    fn test_developer_wait_weeks_start_on_monday_in_timezone() {
+        // Late Sunday in UTC is already Monday in Tokyo
+        let pipelines = vec![mr_pipeline(
+            1,
+            "2025-01-12T20:00:00Z",
+            "2025-01-12T21:00:00Z",
+        )];
+
+        let utc = calculate_developer_wait(&pipelines, Tz::UTC).unwrap();
+        let tokyo = calculate_developer_wait(&pipelines, Tz::Asia__Tokyo).unwrap();
+
+        assert_eq!(utc.weekly[0].week_start.to_string(), "2025-01-06");
+        assert_eq!(tokyo.weekly[0].week_start.to_string(), "2025-01-13");
+    }
+}
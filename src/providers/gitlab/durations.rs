@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use super::types::GitLabPipeline;
+
+/// Gives every job GitLab reported no duration for an `estimated_duration`,
+/// so it keeps its place in DAG timing: the time between its start and
+/// finish when both are known, otherwise the average reported duration of the
+/// same job across `pipelines` (0 when it never reported one). Returns how
+/// many durations were estimated.
+#[allow(clippy::cast_precision_loss)]
+pub fn estimate_missing_durations(pipelines: &mut [GitLabPipeline]) -> usize {
+    let mut known: HashMap<String, (f64, usize)> = HashMap::new();
+    for job in pipelines.iter().flat_map(|p| &p.jobs) {
+        if let Some(duration) = job.duration {
+            let entry = known.entry(job.name.clone()).or_default();
+            entry.0 += duration;
+            entry.1 += 1;
+        }
+    }
+
+    let mut estimated = 0;
+    for job in pipelines
+        .iter_mut()
+        .flat_map(|p| &mut p.jobs)
+        .filter(|job| job.duration.is_none())
+    {
+        let elapsed = job
+            .started_at
+            .zip(job.finished_at)
+            .map(|(started_at, finished_at)| {
+                (finished_at - started_at).num_milliseconds() as f64 / 1000.0
+            });
+        let average = known
+            .get(&job.name)
+            .map(|(total, count)| total / *count as f64);
+
+        job.estimated_duration = Some(elapsed.or(average).unwrap_or(0.0));
+        estimated += 1;
+    }
+
+    estimated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline, time};
+
+    #[test]
+    fn test_missing_durations_are_estimated_from_timestamps_then_other_runs() {
+        let mut timed = job(2, "test", "test", 0.0, "CANCELED");
+        timed.duration = None;
+        timed.started_at = Some(time("2025-01-02T10:00:00Z"));
+        timed.finished_at = Some(time("2025-01-02T10:01:30Z"));
+        let mut untimed = job(3, "build", "build", 0.0, "CANCELED");
+        untimed.duration = None;
+
+        let mut first = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z");
+        first.jobs = vec![job(1, "build", "build", 40.0, "SUCCESS")];
+        let mut second = pipeline(
+            2,
+            "canceled",
+            "2025-01-02T10:00:00Z",
+            "2025-01-02T10:05:00Z",
+        );
+        second.jobs = vec![untimed, timed];
+        let mut pipelines = [first, second];
+
+        let estimated = estimate_missing_durations(&mut pipelines);
+
+        assert_eq!(estimated, 2);
+        assert!((pipelines[1].jobs[0].dag_duration() - 40.0).abs() < f64::EPSILON);
+        assert!((pipelines[1].jobs[1].dag_duration() - 90.0).abs() < f64::EPSILON);
+        assert_eq!(pipelines[0].jobs[0].estimated_duration, None);
+    }
+}
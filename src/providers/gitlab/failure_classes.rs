@@ -0,0 +1,177 @@
+use std::collections::{HashMap, HashSet};
+
+use super::types::{GitLabJob, GitLabPipeline};
+
+/// Wording of GitLab's failure messages for infrastructure problems, which
+/// usually go away on a retry.
+const INFRASTRUCTURE_FAILURES: [&str; 6] = [
+    "runner system failure",
+    "api failure",
+    "timeout failure",
+    "got stuck",
+    "scheduler failed",
+    "integrity problem",
+];
+const SCRIPT_FAILURE: &str = "script failure";
+
+/// Failed attempts of one job, split by cause.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FailureCounts {
+    pub executions: usize,
+    /// Infrastructure failures and failures a rerun of the same code passed
+    pub transient: usize,
+    /// Failures that stuck: script failures, or failures repeated by the next run
+    pub persistent: usize,
+}
+
+/// `Some(true)` for infrastructure failures, `Some(false)` for script failures,
+/// `None` when GitLab gave no (recognized) reason.
+fn infrastructure_failure(job: &GitLabJob) -> Option<bool> {
+    let message = job.failure_message.as_deref()?.to_lowercase();
+    if INFRASTRUCTURE_FAILURES.iter().any(|m| message.contains(m)) {
+        Some(true)
+    } else if message.contains(SCRIPT_FAILURE) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Whether each job failed again in the next pipeline of the same ref that ran
+/// it, keyed by pipeline ID and job name.
+fn next_run_failed<'a>(pipelines: &[&'a GitLabPipeline]) -> HashMap<(&'a str, &'a str), bool> {
+    let mut by_ref: HashMap<&str, Vec<&GitLabPipeline>> = HashMap::new();
+    for pipeline in pipelines {
+        by_ref.entry(&pipeline.ref_).or_default().push(pipeline);
+    }
+
+    let mut next_failed = HashMap::new();
+    for mut ref_pipelines in by_ref.into_values() {
+        ref_pipelines.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+
+        let mut later: HashMap<&str, bool> = HashMap::new();
+        for pipeline in ref_pipelines {
+            let finals: Vec<&GitLabJob> = pipeline.jobs.iter().filter(|j| !j.retried).collect();
+            for job in &finals {
+                if let Some(&failed) = later.get(job.name.as_str()) {
+                    next_failed.insert((pipeline.id.as_str(), job.name.as_str()), failed);
+                }
+            }
+            for job in finals {
+                later.insert(&job.name, job.status == "FAILED");
+            }
+        }
+    }
+
+    next_failed
+}
+
+/// Splits each job's failed attempts into transient and persistent ones:
+///
+/// - infrastructure failures (runner, API, timeout, scheduler) are transient;
+/// - so are attempts a retry passed, and final failures another pipeline for
+///   the same commit passed;
+/// - remaining script failures are persistent;
+/// - failures without a recognized reason are persistent when the job failed
+///   again in the next pipeline of the same ref (a streak), transient otherwise.
+pub fn classify_failures<'a>(pipelines: &[&'a GitLabPipeline]) -> HashMap<&'a str, FailureCounts> {
+    let passed_commits: HashSet<(&str, &str)> = pipelines
+        .iter()
+        .flat_map(|p| {
+            p.jobs
+                .iter()
+                .filter(|j| !j.retried && j.status == "SUCCESS")
+                .map(|j| (p.sha.as_str(), j.name.as_str()))
+        })
+        .collect();
+    let next_failed = next_run_failed(pipelines);
+
+    let mut counts: HashMap<&str, FailureCounts> = HashMap::new();
+    for pipeline in pipelines {
+        for job in &pipeline.jobs {
+            let entry = counts.entry(job.name.as_str()).or_default();
+            entry.executions += 1;
+            if job.status != "FAILED" {
+                continue;
+            }
+
+            let passed_on_retry = pipeline.jobs.iter().any(|other| {
+                !other.retried
+                    && other.name == job.name
+                    && other.variant == job.variant
+                    && other.status == "SUCCESS"
+            });
+            let transient = match infrastructure_failure(job) {
+                Some(true) => true,
+                _ if passed_on_retry => true,
+                _ if passed_commits.contains(&(pipeline.sha.as_str(), job.name.as_str())) => true,
+                Some(false) => false,
+                None => !next_failed
+                    .get(&(pipeline.id.as_str(), job.name.as_str()))
+                    .copied()
+                    .unwrap_or(true),
+            };
+
+            if transient {
+                entry.transient += 1;
+            } else {
+                entry.persistent += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline};
+
+    fn failed(id: u64, name: &str, message: Option<&str>) -> GitLabJob {
+        let mut job = job(id, name, "test", 60.0, "FAILED");
+        job.failure_message = message.map(ToString::to_string);
+        job
+    }
+
+    #[test]
+    fn test_classify_failures_combines_reason_retries_and_streaks() {
+        let script = "There has been a script failure. Check the job log for more information";
+        let runner = "There has been a runner system failure, please try again";
+
+        let mut first = pipeline(1, "failed", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        let mut retried = failed(1, "e2e", None);
+        retried.retried = true;
+        first.jobs = vec![
+            retried,
+            job(2, "e2e", "test", 60.0, "SUCCESS"),
+            failed(3, "unit", Some(script)),
+            failed(4, "lint", None),
+            failed(5, "deploy", Some(runner)),
+        ];
+        let mut second = pipeline(2, "failed", "2025-01-02T10:00:00Z", "2025-01-02T10:10:00Z");
+        second.jobs = vec![
+            job(6, "e2e", "test", 60.0, "SUCCESS"),
+            failed(7, "unit", Some(script)),
+            failed(8, "lint", None),
+            job(9, "deploy", "test", 60.0, "SUCCESS"),
+        ];
+
+        let counts = classify_failures(&[&first, &second]);
+
+        assert_eq!((counts["e2e"].transient, counts["e2e"].persistent), (1, 0));
+        assert_eq!(
+            (counts["unit"].transient, counts["unit"].persistent),
+            (0, 2)
+        );
+        assert_eq!(
+            (counts["lint"].transient, counts["lint"].persistent),
+            (0, 2)
+        );
+        assert_eq!(
+            (counts["deploy"].transient, counts["deploy"].persistent),
+            (1, 0)
+        );
+        assert_eq!(counts["e2e"].executions, 3);
+    }
+}
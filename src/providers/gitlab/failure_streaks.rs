@@ -0,0 +1,103 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::job_analysis::final_attempts;
+use super::types::GitLabPipeline;
+use super::url_utils::pipeline_id_to_url;
+use crate::insights::BrokenJob;
+
+/// Jobs whose final attempt failed in each of the last `min_streak` (or more)
+/// pipelines of `default_branch` that ran them. Pipelines a job did not run in
+/// neither extend nor break its streak. Longest streaks first.
+pub fn broken_jobs(
+    pipelines: &[GitLabPipeline],
+    default_branch: &str,
+    min_streak: usize,
+    base_url: &str,
+    project_path: &str,
+) -> Vec<BrokenJob> {
+    let mut branch_pipelines: Vec<&GitLabPipeline> = pipelines
+        .iter()
+        .filter(|p| p.ref_ == default_branch && p.merge_request_event_type.is_none())
+        .collect();
+    branch_pipelines.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+
+    let outcomes: Vec<HashMap<&str, bool>> = branch_pipelines
+        .iter()
+        .map(|pipeline| {
+            final_attempts(pipeline)
+                .into_iter()
+                .map(|(name, job)| (name, job.status == "FAILED"))
+                .collect()
+        })
+        .collect();
+    let names: BTreeSet<&str> = outcomes.iter().flat_map(|o| o.keys().copied()).collect();
+
+    let mut broken: Vec<BrokenJob> = names
+        .into_iter()
+        .filter_map(|name| {
+            let streak: Vec<&GitLabPipeline> = branch_pipelines
+                .iter()
+                .zip(&outcomes)
+                .filter_map(|(pipeline, outcome)| {
+                    outcome.get(name).map(|&failed| (pipeline, failed))
+                })
+                .take_while(|&(_, failed)| failed)
+                .map(|(pipeline, _)| *pipeline)
+                .collect();
+            let first_broken = streak.last()?;
+
+            (streak.len() >= min_streak).then(|| BrokenJob {
+                name: name.to_string(),
+                streak: streak.len(),
+                broken_since: first_broken.created_at,
+                first_broken_pipeline: pipeline_id_to_url(base_url, project_path, &first_broken.id),
+            })
+        })
+        .collect();
+
+    broken.sort_by(|a, b| b.streak.cmp(&a.streak).then_with(|| a.name.cmp(&b.name)));
+    broken
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline};
+
+    #[test]
+    fn test_broken_jobs_count_the_current_failure_streak() {
+        let run = |id: u64, day: u32, e2e: &str| {
+            let created_at = format!("2025-01-{day:02}T10:00:00Z");
+            let finished_at = format!("2025-01-{day:02}T10:10:00Z");
+            let mut pipeline = pipeline(id, "failed", &created_at, &finished_at);
+            pipeline.jobs = vec![
+                job(id * 10, "e2e", "test", 60.0, e2e),
+                job(id * 10 + 1, "lint", "test", 10.0, "SUCCESS"),
+            ];
+            pipeline
+        };
+        let mut feature = run(5, 5, "SUCCESS");
+        feature.ref_ = "feature".to_string();
+        let mut without_e2e = run(4, 4, "FAILED");
+        without_e2e.jobs.remove(0);
+        let pipelines = vec![
+            run(1, 1, "SUCCESS"),
+            run(2, 2, "FAILED"),
+            run(3, 3, "FAILED"),
+            without_e2e,
+            feature,
+            run(6, 6, "FAILED"),
+        ];
+
+        let broken = broken_jobs(&pipelines, "main", 3, "https://gitlab.com", "group/project");
+
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].name, "e2e");
+        assert_eq!(broken[0].streak, 3);
+        assert_eq!(
+            broken[0].first_broken_pipeline,
+            "https://gitlab.com/group/project/-/pipelines/2"
+        );
+        assert!(broken_jobs(&pipelines, "main", 4, "", "").is_empty());
+    }
+}
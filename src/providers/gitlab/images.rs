@@ -0,0 +1,167 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+
+use super::stats::{mean, rate};
+use super::types::{GitLabJob, GitLabPipeline};
+use crate::insights::ImageChange;
+
+/// Job images per job name, per commit SHA.
+pub type ImagesBySha = HashMap<String, HashMap<String, String>>;
+
+struct Execution<'a> {
+    at: DateTime<Utc>,
+    image: &'a str,
+    job: &'a GitLabJob,
+}
+
+fn summarize(executions: &[&Execution]) -> (f64, f64) {
+    let avg_duration = mean(
+        executions
+            .iter()
+            .filter(|e| e.job.status == "SUCCESS")
+            .filter_map(|e| e.job.duration),
+    );
+    let failed = executions
+        .iter()
+        .filter(|e| e.job.status == "FAILED")
+        .count();
+    (avg_duration, rate(failed, executions.len()))
+}
+
+/// Finds every point where a job switched image and compares how its
+/// executions on the previous image fared against those on the new one.
+///
+/// Jobs without a known image for a pipeline's commit are skipped for that
+/// pipeline. Changes are ordered by the size of the duration shift.
+pub fn calculate_image_changes(
+    pipelines: &[GitLabPipeline],
+    images: &ImagesBySha,
+) -> Vec<ImageChange> {
+    let mut by_job: BTreeMap<&str, Vec<Execution>> = BTreeMap::new();
+    for pipeline in pipelines {
+        let Some(job_images) = images.get(&pipeline.sha) else {
+            continue;
+        };
+        for job in &pipeline.jobs {
+            // Config keys are the names shown in GitLab, so matrix variants share their parent's image
+            if let Some(image) = job_images.get(&job.name) {
+                by_job
+                    .entry(job.name.as_str())
+                    .or_default()
+                    .push(Execution {
+                        at: job.started_at.unwrap_or(pipeline.created_at),
+                        image,
+                        job,
+                    });
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (name, mut executions) in by_job {
+        executions.sort_by_key(|e| e.at);
+
+        let mut seen = Vec::new();
+        for pair in executions.windows(2) {
+            let (previous, current) = (&pair[0], &pair[1]);
+            if previous.image == current.image || seen.contains(&(previous.image, current.image)) {
+                continue;
+            }
+            seen.push((previous.image, current.image));
+
+            let before: Vec<&Execution> = executions
+                .iter()
+                .filter(|e| e.image == previous.image && e.at < current.at)
+                .collect();
+            let after: Vec<&Execution> = executions
+                .iter()
+                .filter(|e| e.image == current.image && e.at >= current.at)
+                .collect();
+
+            let (duration_before, failure_rate_before) = summarize(&before);
+            let (duration_after, failure_rate_after) = summarize(&after);
+
+            let mut change = ImageChange {
+                job: name.to_string(),
+                from_image: previous.image.to_string(),
+                to_image: current.image.to_string(),
+                introduced_at: current.at,
+                executions_before: before.len(),
+                executions_after: after.len(),
+                avg_duration_before_seconds: duration_before,
+                avg_duration_after_seconds: duration_after,
+                duration_change_percentage: (duration_before > 0.0 && duration_after > 0.0)
+                    .then(|| (duration_after - duration_before) / duration_before * 100.0),
+                failure_rate_before,
+                failure_rate_after,
+                summary: String::new(),
+            };
+            change.summary = change.describe();
+            changes.push(change);
+        }
+    }
+
+    changes.sort_by(|a, b| {
+        let shift = |c: &ImageChange| c.duration_change_percentage.map_or(0.0, f64::abs);
+        shift(b).total_cmp(&shift(a))
+    });
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline};
+
+    fn build_pipeline(id: u64, sha: &str, created_at: &str, duration: f64) -> GitLabPipeline {
+        let mut pipeline = pipeline(id, "success", created_at, "2025-01-10T00:00:00Z");
+        pipeline.sha = sha.to_string();
+        pipeline.jobs = vec![job(id, "build", "build", duration, "SUCCESS")];
+        pipeline
+    }
+
+    fn images(entries: &[(&str, &str)]) -> ImagesBySha {
+        entries
+            .iter()
+            .map(|(sha, image)| {
+                let jobs = HashMap::from([("build".to_string(), (*image).to_string())]);
+                ((*sha).to_string(), jobs)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_image_change_reports_duration_shift() {
+        let pipelines = vec![
+            build_pipeline(1, "a", "2025-01-01T10:00:00Z", 100.0),
+            build_pipeline(2, "a", "2025-01-02T10:00:00Z", 100.0),
+            build_pipeline(3, "b", "2025-01-03T10:00:00Z", 140.0),
+            build_pipeline(4, "c", "2025-01-04T10:00:00Z", 140.0),
+        ];
+        let images = images(&[("a", "node:18"), ("b", "node:20"), ("c", "node:20")]);
+
+        let changes = calculate_image_changes(&pipelines, &images);
+
+        assert_eq!(changes.len(), 1);
+        let change = &changes[0];
+        assert_eq!(change.from_image, "node:18");
+        assert_eq!(change.executions_before, 2);
+        assert_eq!(change.executions_after, 2);
+        assert!((change.duration_change_percentage.unwrap() - 40.0).abs() < 1e-9);
+        assert_eq!(
+            change.summary,
+            "build: duration jumped 40% after image node:20 was introduced"
+        );
+    }
+
+    #[test]
+    fn test_jobs_without_known_image_are_skipped() {
+        let pipelines = vec![
+            build_pipeline(1, "a", "2025-01-01T10:00:00Z", 100.0),
+            build_pipeline(2, "unknown", "2025-01-02T10:00:00Z", 100.0),
+        ];
+
+        assert!(calculate_image_changes(&pipelines, &images(&[("a", "node:18")])).is_empty());
+    }
+}
@@ -3,20 +3,89 @@ use std::collections::HashMap;
 use super::types::{GitLabJob, GitLabPipeline};
 use crate::insights::{JobCountWithLinks, JobMetrics, PredecessorJob};
 
-pub fn calculate_job_metrics(pipeline: &GitLabPipeline) -> Vec<JobMetrics> {
-    if pipeline.jobs.is_empty() {
-        return vec![];
-    }
-
-    let job_map: HashMap<&str, &GitLabJob> =
-        pipeline.jobs.iter().map(|j| (j.name.as_str(), j)).collect();
+/// Final attempts only; of several parallel/matrix variants the slowest one
+/// decides when dependents can start.
+pub fn final_attempts(pipeline: &GitLabPipeline) -> HashMap<&str, &GitLabJob> {
+    pipeline
+        .jobs
+        .iter()
+        .filter(|j| !j.retried)
+        .fold(HashMap::new(), |mut map, job| {
+            map.entry(job.name.as_str())
+                .and_modify(|current: &mut &GitLabJob| {
+                    if job.dag_duration() > current.dag_duration() {
+                        *current = job;
+                    }
+                })
+                .or_insert(job);
+            map
+        })
+}
 
-    let stage_index: HashMap<&str, usize> = pipeline
+pub fn stage_index(pipeline: &GitLabPipeline) -> HashMap<&str, usize> {
+    pipeline
         .stages
         .iter()
         .enumerate()
         .map(|(i, s)| (s.as_str(), i))
-        .collect();
+        .collect()
+}
+
+/// Seconds from pipeline start until each job would finish if it started as
+/// soon as its dependencies did.
+pub fn finish_times(pipeline: &GitLabPipeline) -> HashMap<&str, f64> {
+    all_finish_times(&final_attempts(pipeline), &stage_index(pipeline))
+}
+
+/// Seconds from pipeline start until its last job would finish without
+/// `skipped`, as if `rules` excluded it (jobs needing it no longer wait).
+pub fn critical_path_without(pipeline: &GitLabPipeline, skipped: &str) -> f64 {
+    let mut job_map = final_attempts(pipeline);
+    job_map.remove(skipped);
+
+    all_finish_times(&job_map, &stage_index(pipeline))
+        .into_values()
+        .fold(0.0, f64::max)
+}
+
+fn all_finish_times<'a>(
+    job_map: &HashMap<&'a str, &'a GitLabJob>,
+    stage_index: &HashMap<&str, usize>,
+) -> HashMap<&'a str, f64> {
+    let mut finish_times = HashMap::new();
+    let mut predecessors = HashMap::new();
+
+    for &job_name in job_map.keys() {
+        calculate_finish_time(
+            job_name,
+            job_map,
+            stage_index,
+            &mut finish_times,
+            &mut predecessors,
+        );
+    }
+
+    finish_times
+}
+
+/// Jobs each job waits for, as inferred by [`get_dependencies`].
+pub fn dependencies(pipeline: &GitLabPipeline) -> HashMap<&str, Vec<&str>> {
+    let job_map = final_attempts(pipeline);
+    let stage_index = stage_index(pipeline);
+
+    job_map
+        .iter()
+        .map(|(&name, job)| (name, get_dependencies(job, &job_map, &stage_index)))
+        .collect()
+}
+
+pub fn calculate_job_metrics(pipeline: &GitLabPipeline) -> Vec<JobMetrics> {
+    if pipeline.jobs.is_empty() {
+        return vec![];
+    }
+
+    let job_map = final_attempts(pipeline);
+    let stage_index = stage_index(pipeline);
 
     let mut finish_times = HashMap::new();
     let mut predecessors = HashMap::new();
@@ -34,9 +103,10 @@ pub fn calculate_job_metrics(pipeline: &GitLabPipeline) -> Vec<JobMetrics> {
     let mut metrics: Vec<JobMetrics> = job_map
         .iter()
         .map(|(&name, job)| {
-            let avg_duration_seconds = job.duration;
+            let avg_duration_seconds = job.dag_duration();
             let avg_time_to_feedback_seconds = *finish_times.get(name).unwrap_or(&0.0);
             let predecessor_list = build_predecessor_list(name, &predecessors, &job_map);
+            let absent_needs = absent_needs(job, &job_map);
 
             JobMetrics {
                 name: name.to_string(),
@@ -44,6 +114,7 @@ pub fn calculate_job_metrics(pipeline: &GitLabPipeline) -> Vec<JobMetrics> {
                 avg_time_to_feedback_seconds,
                 predecessors: predecessor_list,
                 flakiness_rate: 0.0,
+                flakiness_score: 0.0,
                 flaky_retries: JobCountWithLinks {
                     count: 0,
                     links: vec![],
@@ -53,7 +124,16 @@ pub fn calculate_job_metrics(pipeline: &GitLabPipeline) -> Vec<JobMetrics> {
                     links: vec![],
                 },
                 failure_rate: 0.0,
+                transient_failure_rate: 0.0,
+                persistent_failure_rate: 0.0,
                 total_executions: 0,
+                wasted_retry_minutes: 0.0,
+                retry_wait_minutes: 0.0,
+                duration_histogram: None,
+                avg_start_offset_seconds: None,
+                last_started_at: None,
+                variants: None,
+                absent_needs,
             }
         })
         .collect();
@@ -79,7 +159,7 @@ fn build_predecessor_list(
     .filter_map(|name| {
         job_map.get(name).map(|job| PredecessorJob {
             name: name.to_string(),
-            avg_duration_seconds: job.duration,
+            avg_duration_seconds: job.path_duration(),
         })
     })
     .collect::<Vec<_>>()
@@ -107,8 +187,8 @@ fn calculate_finish_time<'a>(
     let deps = get_dependencies(job, job_map, stage_index);
 
     if deps.is_empty() {
-        finish_times.insert(job_name, job.duration);
-        return job.duration;
+        finish_times.insert(job_name, job.path_duration());
+        return job.path_duration();
     }
 
     let (slowest_dep, slowest_time) = deps
@@ -120,7 +200,7 @@ fn calculate_finish_time<'a>(
         .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
         .unwrap_or(("", 0.0));
 
-    let finish_time = slowest_time + job.duration;
+    let finish_time = slowest_time + job.path_duration();
     finish_times.insert(job_name, finish_time);
 
     if slowest_time > 0.0 {
@@ -138,8 +218,12 @@ fn get_dependencies<'a>(
     match &job.needs {
         // needs = Some([]) -> no dependencies, starts immediately
         Some(needs) if needs.is_empty() => vec![],
-        // needs = Some([...]) -> explicit dependencies
-        Some(needs) => needs.iter().map(String::as_str).collect(),
+        // needs = Some([...]) -> explicit dependencies that made it into the pipeline
+        Some(needs) => needs
+            .iter()
+            .map(String::as_str)
+            .filter(|need| job_map.contains_key(need))
+            .collect(),
         // needs = None -> depends on all jobs in previous stages
         None => {
             let current_stage = stage_index.get(job.stage.as_str()).copied().unwrap_or(0);
@@ -153,3 +237,56 @@ fn get_dependencies<'a>(
         }
     }
 }
+
+/// Needs pointing at jobs that are not part of the pipeline. GitLab refuses to
+/// create a pipeline with a missing required need, so these are `optional`
+/// needs (or needs on jobs excluded by `rules`) and the job did not wait for them.
+fn absent_needs(job: &GitLabJob, job_map: &HashMap<&str, &GitLabJob>) -> Vec<String> {
+    job.needs
+        .iter()
+        .flatten()
+        .filter(|need| !job_map.contains_key(need.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline};
+
+    #[test]
+    fn test_absent_needs_do_not_delay_or_appear_as_predecessors() {
+        let mut test = job(2, "test", "test", 30.0, "SUCCESS");
+        test.needs = Some(vec!["build".to_string(), "docs".to_string()]);
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z");
+        pipeline.stages = vec!["build".to_string(), "test".to_string()];
+        pipeline.jobs = vec![job(1, "build", "build", 60.0, "SUCCESS"), test];
+
+        let metrics = calculate_job_metrics(&pipeline);
+        let test = metrics.iter().find(|m| m.name == "test").unwrap();
+
+        assert!((test.avg_time_to_feedback_seconds - 90.0).abs() < f64::EPSILON);
+        assert_eq!(test.predecessors.len(), 1);
+        assert_eq!(test.absent_needs, vec!["docs"]);
+    }
+
+    #[test]
+    fn test_trigger_jobs_hold_up_dependents_for_their_downstream_pipeline() {
+        let mut trigger = job(1, "deploy-docs", "deploy", 2.0, "SUCCESS");
+        trigger.downstream_duration = Some(300.0);
+        let mut smoke = job(2, "smoke", "verify", 30.0, "SUCCESS");
+        smoke.needs = Some(vec!["deploy-docs".to_string()]);
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:06:00Z");
+        pipeline.stages = vec!["deploy".to_string(), "verify".to_string()];
+        pipeline.jobs = vec![trigger, smoke];
+
+        let metrics = calculate_job_metrics(&pipeline);
+        let smoke = metrics.iter().find(|m| m.name == "smoke").unwrap();
+        let trigger = metrics.iter().find(|m| m.name == "deploy-docs").unwrap();
+
+        assert!((smoke.avg_time_to_feedback_seconds - 330.0).abs() < f64::EPSILON);
+        assert!((smoke.predecessors[0].avg_duration_seconds - 300.0).abs() < f64::EPSILON);
+        assert!((trigger.avg_duration_seconds - 2.0).abs() < f64::EPSILON);
+    }
+}
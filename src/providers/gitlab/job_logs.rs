@@ -0,0 +1,540 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
+
+use super::stats::{mean, percentage};
+use super::types::{GitLabJob, GitLabPipeline};
+use crate::insights::{ArtifactTransfer, JobArtifactTransfer, JobLogMetrics, SectionTiming};
+
+/// Logs downloaded per job name, newest attempts first; logs are large, so
+/// the recent ones stand in for the rest.
+pub const MAX_LOGS_PER_JOB: usize = 10;
+
+/// Jobs that missing their cache makes at least this many times slower are
+/// flagged.
+const MISS_SLOWDOWN: f64 = 2.0;
+
+/// Runner section wrapping cache extraction.
+const RESTORE_CACHE: &str = "restore_cache";
+
+/// Runner sections moving artifacts from earlier jobs in, and this job's out.
+const DOWNLOAD_ARTIFACTS: &str = "download_artifacts";
+const UPLOAD_ARTIFACTS: [&str; 2] = ["upload_artifacts_on_success", "upload_artifacts_on_failure"];
+
+/// Runner lines after extracting a cache archive, or failing to.
+const CACHE_HIT: &str = "Successfully extracted cache";
+const CACHE_MISS: &str = "Failed to extract cache";
+
+const SECTION_START: &str = "section_start:";
+const SECTION_END: &str = "section_end:";
+
+/// Script commands that install dependencies, matched on their leading words.
+const DEPENDENCY_INSTALLS: [&[&str]; 17] = [
+    &["npm", "ci"],
+    &["npm", "install"],
+    &["npm", "i"],
+    &["yarn", "install"],
+    &["pnpm", "install"],
+    &["pip", "install"],
+    &["pip3", "install"],
+    &["python", "-m", "pip", "install"],
+    &["python3", "-m", "pip", "install"],
+    &["poetry", "install"],
+    &["pipenv", "install"],
+    &["bundle", "install"],
+    &["composer", "install"],
+    &["go", "mod", "download"],
+    &["cargo", "fetch"],
+    &["mvn", "dependency:go-offline"],
+    &["dotnet", "restore"],
+];
+
+/// A `section_start`/`section_end` pair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub seconds: f64,
+    /// Byte range of the log between the two markers
+    span: Range<usize>,
+}
+
+/// What one job log tells about where the job's time went.
+#[derive(Debug, Default)]
+pub struct JobLog {
+    /// Sections in the order they started
+    pub sections: Vec<Section>,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    /// Time spent in sections that only ran dependency installs (see
+    /// `DEPENDENCY_INSTALLS`); absent when no install ran in such a section
+    pub dependency_install_seconds: Option<f64>,
+}
+
+/// Timestamp and name of each section marker of kind `prefix`, e.g. from
+/// `section_start:1700000000:restore_cache\r\x1b[0K`. Names end at their
+/// options (`[collapsed=true]`) or the carriage return.
+fn markers<'a>(log: &'a str, prefix: &str) -> Vec<(usize, i64, &'a str)> {
+    log.match_indices(prefix)
+        .filter_map(|(offset, _)| {
+            let rest = &log[offset + prefix.len()..];
+            let (timestamp, rest) = rest.split_once(':')?;
+            let end = rest
+                .find(|c: char| c == '[' || c.is_control())
+                .unwrap_or(rest.len());
+            Some((offset, timestamp.parse().ok()?, &rest[..end]))
+        })
+        .collect()
+}
+
+fn sections(log: &str) -> Vec<Section> {
+    let mut events: Vec<(usize, i64, &str, bool)> = markers(log, SECTION_START)
+        .into_iter()
+        .map(|(offset, time, name)| (offset, time, name, true))
+        .chain(
+            markers(log, SECTION_END)
+                .into_iter()
+                .map(|(offset, time, name)| (offset, time, name, false)),
+        )
+        .collect();
+    events.sort_unstable_by_key(|&(offset, ..)| offset);
+
+    let mut open: HashMap<&str, (usize, i64)> = HashMap::new();
+    let mut sections = Vec::new();
+    for (offset, time, name, start) in events {
+        if start {
+            open.insert(name, (offset, time));
+        } else if let Some((started_at, started)) = open.remove(name) {
+            #[allow(clippy::cast_precision_loss)]
+            sections.push(Section {
+                name: name.to_string(),
+                seconds: (time - started).max(0) as f64,
+                span: started_at..offset,
+            });
+        }
+    }
+    sections.sort_by_key(|section| section.span.start);
+    sections
+}
+
+/// Removes ANSI escape sequences (`ESC [ ... letter`) the runner colors output with.
+fn strip_ansi(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                chars.by_ref().find(char::is_ascii_alphabetic);
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+/// Offset of each script command the runner echoed (`$ npm ci`), and whether
+/// it installs dependencies. Only the text after a line's last carriage
+/// return is shown, so that's where the command is looked for.
+fn commands(log: &str) -> Vec<(usize, bool)> {
+    let mut commands = Vec::new();
+    let mut line_start = 0;
+    for line in log.split_inclusive('\n') {
+        let shown_from = line.rfind('\r').map_or(0, |i| i + 1);
+        let shown = strip_ansi(&line[shown_from..]);
+        if let Some(command) = shown.trim_start().strip_prefix("$ ") {
+            let words: Vec<&str> = command.split_whitespace().collect();
+            let installs = DEPENDENCY_INSTALLS
+                .iter()
+                .any(|install| words.starts_with(install));
+            commands.push((line_start + shown_from, installs));
+        }
+        line_start += line.len();
+    }
+    commands
+}
+
+/// Seconds spent in the innermost sections around dependency installs that
+/// ran nothing else, or `None` when no install ran in such a section.
+fn dependency_install_seconds(sections: &[Section], commands: &[(usize, bool)]) -> Option<f64> {
+    let innermost = |offset: usize| {
+        sections
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.span.contains(&offset))
+            .max_by_key(|(_, s)| s.span.start)
+            .map(|(index, _)| index)
+    };
+
+    let install_sections: BTreeSet<usize> = commands
+        .iter()
+        .filter(|(_, installs)| *installs)
+        .filter_map(|&(offset, _)| innermost(offset))
+        .filter(|&index| {
+            commands
+                .iter()
+                .filter(|(offset, _)| sections[index].span.contains(offset))
+                .all(|(_, installs)| *installs)
+        })
+        .collect();
+
+    (!install_sections.is_empty()).then(|| {
+        install_sections
+            .into_iter()
+            .map(|index| sections[index].seconds)
+            .sum()
+    })
+}
+
+impl JobLog {
+    pub fn parse(log: &str) -> Self {
+        let sections = sections(log);
+        let dependency_install_seconds = dependency_install_seconds(&sections, &commands(log));
+
+        Self {
+            sections,
+            cache_hits: log.matches(CACHE_HIT).count(),
+            cache_misses: log.matches(CACHE_MISS).count(),
+            dependency_install_seconds,
+        }
+    }
+
+    fn artifact_seconds(&self) -> (f64, f64) {
+        (
+            self.section_seconds(DOWNLOAD_ARTIFACTS),
+            UPLOAD_ARTIFACTS
+                .iter()
+                .map(|name| self.section_seconds(name))
+                .sum(),
+        )
+    }
+
+    /// Seconds spent in sections called `name`.
+    pub fn section_seconds(&self, name: &str) -> f64 {
+        self.sections
+            .iter()
+            .filter(|section| section.name == name)
+            .map(|section| section.seconds)
+            .sum()
+    }
+}
+
+/// The finished attempts whose logs are worth downloading: the latest
+/// `MAX_LOGS_PER_JOB` of each job.
+pub fn jobs_to_analyze(pipelines: &[GitLabPipeline]) -> Vec<&GitLabJob> {
+    let mut by_name: BTreeMap<&str, Vec<(&GitLabPipeline, &GitLabJob)>> = BTreeMap::new();
+    for pipeline in pipelines {
+        for job in &pipeline.jobs {
+            if job.status == "SUCCESS" || job.status == "FAILED" {
+                by_name.entry(&job.name).or_default().push((pipeline, job));
+            }
+        }
+    }
+
+    by_name
+        .into_values()
+        .flat_map(|mut attempts| {
+            attempts.sort_by_key(|(pipeline, job)| {
+                std::cmp::Reverse(job.started_at.unwrap_or(pipeline.created_at))
+            });
+            attempts
+                .into_iter()
+                .take(MAX_LOGS_PER_JOB)
+                .map(|(_, job)| job)
+        })
+        .collect()
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| mean(values.iter().copied()))
+}
+
+/// Average time per section over the logs that have it, in the order the
+/// sections first appear. Repeated sections of one log add up.
+fn section_timings(logs: &[&JobLog]) -> Vec<SectionTiming> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut seconds: HashMap<&str, Vec<f64>> = HashMap::new();
+    for log in logs {
+        let mut seen = BTreeSet::new();
+        for section in &log.sections {
+            if !seen.insert(section.name.as_str()) {
+                continue;
+            }
+            let durations = seconds.entry(&section.name).or_insert_with(|| {
+                order.push(&section.name);
+                Vec::new()
+            });
+            durations.push(log.section_seconds(&section.name));
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|name| SectionTiming {
+            name: name.to_string(),
+            executions: seconds[name].len(),
+            avg_seconds: average(&seconds[name]).unwrap_or(0.0),
+        })
+        .collect()
+}
+
+/// Section timings, cache effectiveness and dependency install time of each
+/// job over its parsed `logs` (keyed by job ID).
+/// An attempt that failed to extract any cache counts as a miss. Jobs whose
+/// misses dominate their runtime first, then by time spent restoring caches.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_job_log_metrics(
+    pipelines: &[GitLabPipeline],
+    logs: &HashMap<String, JobLog>,
+) -> Vec<JobLogMetrics> {
+    let mut by_name: BTreeMap<&str, Vec<(&GitLabJob, &JobLog)>> = BTreeMap::new();
+    for job in pipelines.iter().flat_map(|p| &p.jobs) {
+        if let Some(log) = logs.get(&job.id) {
+            by_name.entry(&job.name).or_default().push((job, log));
+        }
+    }
+
+    let mut metrics: Vec<JobLogMetrics> = by_name
+        .into_iter()
+        .map(|(name, attempts)| {
+            let cache_hits = attempts.iter().map(|(_, log)| log.cache_hits).sum();
+            let cache_misses = attempts.iter().map(|(_, log)| log.cache_misses).sum();
+            let durations = |missed: bool| -> Vec<f64> {
+                attempts
+                    .iter()
+                    .filter(|(_, log)| log.cache_hits + log.cache_misses > 0)
+                    .filter(|(_, log)| (log.cache_misses > 0) == missed)
+                    .filter_map(|(job, _)| job.duration)
+                    .collect()
+            };
+            let restore: Vec<f64> = attempts
+                .iter()
+                .map(|(_, log)| log.section_seconds(RESTORE_CACHE))
+                .collect();
+            let installs: Vec<f64> = attempts
+                .iter()
+                .filter_map(|(_, log)| log.dependency_install_seconds)
+                .collect();
+            let avg_duration_on_hit_seconds = average(&durations(false));
+            let avg_duration_on_miss_seconds = average(&durations(true));
+
+            JobLogMetrics {
+                name: name.to_string(),
+                logs_analyzed: attempts.len(),
+                cache_hits,
+                cache_misses,
+                cache_hit_rate: (cache_hits + cache_misses > 0)
+                    .then(|| cache_hits as f64 / (cache_hits + cache_misses) as f64 * 100.0),
+                avg_cache_restore_seconds: average(&restore).unwrap_or(0.0),
+                avg_duration_on_hit_seconds,
+                avg_duration_on_miss_seconds,
+                cache_misses_dominate: matches!(
+                    (avg_duration_on_hit_seconds, avg_duration_on_miss_seconds),
+                    (Some(hit), Some(miss)) if miss >= hit * MISS_SLOWDOWN
+                ),
+                avg_dependency_install_seconds: average(&installs),
+                sections: section_timings(
+                    &attempts.iter().map(|(_, log)| *log).collect::<Vec<_>>(),
+                ),
+            }
+        })
+        .collect();
+
+    metrics.sort_by(|a, b| {
+        b.cache_misses_dominate
+            .cmp(&a.cache_misses_dominate)
+            .then_with(|| {
+                b.avg_cache_restore_seconds
+                    .total_cmp(&a.avg_cache_restore_seconds)
+            })
+    });
+    metrics
+}
+
+/// Splits the duration of the jobs of `pipelines` that have a parsed log into
+/// artifact download, artifact upload and everything else.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_artifact_transfer(
+    pipelines: &[&GitLabPipeline],
+    logs: &HashMap<String, JobLog>,
+) -> ArtifactTransfer {
+    // (duration, download, upload) of each analyzed attempt, per job name
+    let mut by_name: BTreeMap<&str, Vec<(f64, f64, f64)>> = BTreeMap::new();
+    for job in pipelines.iter().flat_map(|p| &p.jobs) {
+        if let (Some(log), Some(duration)) = (logs.get(&job.id), job.duration) {
+            let (download, upload) = log.artifact_seconds();
+            by_name
+                .entry(&job.name)
+                .or_default()
+                .push((duration, download, upload));
+        }
+    }
+
+    let all: Vec<(f64, f64, f64)> = by_name.values().flatten().copied().collect();
+    let total = |pick: fn(&(f64, f64, f64)) -> f64, attempts: &[(f64, f64, f64)]| {
+        attempts.iter().map(pick).sum::<f64>()
+    };
+    let execution = |attempts: &[(f64, f64, f64)]| {
+        attempts
+            .iter()
+            .map(|(duration, download, upload)| (duration - download - upload).max(0.0))
+            .sum::<f64>()
+    };
+    let per_attempt = |seconds: f64, attempts: usize| {
+        if attempts == 0 {
+            0.0
+        } else {
+            seconds / attempts as f64
+        }
+    };
+
+    let mut jobs: Vec<JobArtifactTransfer> = by_name
+        .iter()
+        .filter_map(|(name, attempts)| {
+            let transfer = total(|a| a.1, attempts) + total(|a| a.2, attempts);
+            (transfer > 0.0).then(|| JobArtifactTransfer {
+                name: (*name).to_string(),
+                avg_transfer_seconds: per_attempt(transfer, attempts.len()),
+                avg_execution_seconds: per_attempt(execution(attempts), attempts.len()),
+                transfer_percentage: percentage(transfer, total(|a| a.0, attempts)),
+            })
+        })
+        .collect();
+    jobs.sort_by(|a, b| b.avg_transfer_seconds.total_cmp(&a.avg_transfer_seconds));
+
+    let download = total(|a| a.1, &all);
+    let upload = total(|a| a.2, &all);
+    ArtifactTransfer {
+        logs_analyzed: all.len(),
+        avg_download_seconds: per_attempt(download, all.len()),
+        avg_upload_seconds: per_attempt(upload, all.len()),
+        avg_execution_seconds: per_attempt(execution(&all), all.len()),
+        transfer_percentage: percentage(download + upload, total(|a| a.0, &all)),
+        jobs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline};
+
+    fn log(restore_seconds: i64, hit: bool) -> String {
+        let outcome = if hit {
+            CACHE_HIT
+        } else {
+            "WARNING: file does not exist\nFailed to extract cache"
+        };
+        format!(
+            "\x1b[0Ksection_start:1700000000:restore_cache[collapsed=true]\r\x1b[0K\
+             \x1b[0K\x1b[36;1mRestoring cache\x1b[0;m\n\
+             Checking cache for default-1...\n{outcome}\n\
+             \x1b[0Ksection_end:{}:restore_cache\r\x1b[0K\n",
+            1_700_000_000 + restore_seconds
+        )
+    }
+
+    #[test]
+    fn test_parse_reads_sections_and_cache_outcomes() {
+        let parsed = JobLog::parse(&log(12, false));
+
+        let sections: Vec<(&str, f64)> = parsed
+            .sections
+            .iter()
+            .map(|s| (s.name.as_str(), s.seconds))
+            .collect();
+        assert_eq!(sections, vec![("restore_cache", 12.0)]);
+        assert_eq!((parsed.cache_hits, parsed.cache_misses), (0, 1));
+    }
+
+    #[test]
+    fn test_parse_times_sections_that_only_install_dependencies() {
+        let step = |n: i64, start: i64, end: i64, command: &str| {
+            format!(
+                "section_start:{start}:section_script_step_{n}[hide_duration=true]\r\x1b[0K                 \x1b[32;1m$ {command}\x1b[0;m\noutput\n                 section_end:{end}:section_script_step_{n}\r\x1b[0K\n"
+            )
+        };
+        let log = format!(
+            "section_start:100:step_script\r\x1b[0K{}{}{}\x1b[32;1m$ pip install -r requirements.txt\x1b[0;m\n             section_end:400:step_script\r\x1b[0K\n",
+            step(0, 100, 160, "npm ci"),
+            step(1, 160, 170, "npm install --prefix tools"),
+            step(2, 170, 300, "npm test"),
+        );
+
+        let parsed = JobLog::parse(&log);
+
+        // The pip install shares `step_script` with other commands, so it can't be timed
+        assert_eq!(parsed.dependency_install_seconds, Some(70.0));
+        let names: Vec<&str> = parsed.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names[..2], ["step_script", "section_script_step_0"]);
+        assert_eq!(JobLog::parse("$ make\n").dependency_install_seconds, None);
+    }
+
+    #[test]
+    fn test_job_log_metrics_flag_jobs_slowed_down_by_cache_misses() {
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:30:00Z");
+        pipeline.jobs = vec![
+            job(1, "install", "build", 100.0, "SUCCESS"),
+            job(2, "install", "build", 120.0, "SUCCESS"),
+            job(3, "install", "build", 600.0, "SUCCESS"),
+            job(4, "lint", "test", 30.0, "SUCCESS"),
+        ];
+        let logs: HashMap<String, JobLog> = [(1, 5, true), (2, 7, true), (3, 3, false)]
+            .into_iter()
+            .map(|(id, seconds, hit)| {
+                (
+                    pipeline.jobs[id - 1].id.clone(),
+                    JobLog::parse(&log(seconds, hit)),
+                )
+            })
+            .chain(std::iter::once((
+                pipeline.jobs[3].id.clone(),
+                JobLog::default(),
+            )))
+            .collect();
+
+        let metrics = calculate_job_log_metrics(&[pipeline], &logs);
+
+        assert_eq!(metrics[0].name, "install");
+        assert!(metrics[0].cache_misses_dominate);
+        assert_eq!((metrics[0].cache_hits, metrics[0].cache_misses), (2, 1));
+        assert!((metrics[0].avg_cache_restore_seconds - 5.0).abs() < f64::EPSILON);
+        assert_eq!(metrics[0].sections.len(), 1);
+        assert_eq!(metrics[0].sections[0].executions, 3);
+        assert!(metrics[1].sections.is_empty());
+        assert_eq!(metrics[0].avg_duration_on_hit_seconds, Some(110.0));
+        assert_eq!(metrics[1].cache_hit_rate, None);
+    }
+
+    #[test]
+    fn test_artifact_transfer_splits_job_time() {
+        let section = |name: &str, seconds: i64| {
+            format!(
+                "section_start:1000:{name}\r\x1b[0K\nsection_end:{}:{name}\r\x1b[0K\n",
+                1000 + seconds
+            )
+        };
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:30:00Z");
+        pipeline.jobs = vec![
+            job(1, "build", "build", 100.0, "SUCCESS"),
+            job(2, "e2e", "test", 300.0, "SUCCESS"),
+            job(3, "lint", "test", 100.0, "SUCCESS"),
+        ];
+        let logs: HashMap<String, JobLog> = [
+            section("upload_artifacts_on_success", 20),
+            section("download_artifacts", 40) + &section("upload_artifacts_on_failure", 20),
+            String::new(),
+        ]
+        .iter()
+        .zip(&pipeline.jobs)
+        .map(|(log, job)| (job.id.clone(), JobLog::parse(log)))
+        .collect();
+
+        let transfer = calculate_artifact_transfer(&[&pipeline], &logs);
+
+        assert_eq!(transfer.logs_analyzed, 3);
+        assert!((transfer.transfer_percentage - 16.0).abs() < 1e-9);
+        assert!((transfer.avg_download_seconds - 40.0 / 3.0).abs() < 1e-9);
+        let names: Vec<&str> = transfer.jobs.iter().map(|j| j.name.as_str()).collect();
+        assert_eq!(names, ["e2e", "build"]);
+        assert!((transfer.jobs[0].avg_execution_seconds - 240.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use super::stats::percentile;
+use super::types::GitLabPipeline;
+use crate::insights::LeadTimeToGreen;
+
+/// Time from each default-branch commit to the end of its first successful
+/// pipeline there. Commits without a successful pipeline, or without a
+/// commit time, are left out.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_lead_time_to_green(
+    pipelines: &[GitLabPipeline],
+    default_branch: &str,
+) -> LeadTimeToGreen {
+    let mut first_green: HashMap<&str, &GitLabPipeline> = HashMap::new();
+    for pipeline in pipelines.iter().filter(|p| {
+        p.ref_ == default_branch
+            && p.merge_request_event_type.is_none()
+            && p.status == "success"
+            && p.finished_at.is_some()
+    }) {
+        first_green
+            .entry(&pipeline.sha)
+            .and_modify(|green| {
+                if pipeline.finished_at < green.finished_at {
+                    *green = pipeline;
+                }
+            })
+            .or_insert(pipeline);
+    }
+
+    let mut lead_times: Vec<f64> = first_green
+        .values()
+        .filter_map(|pipeline| {
+            let lead_time = pipeline.finished_at? - pipeline.committed_at?;
+            (lead_time.num_seconds() >= 0).then(|| lead_time.num_seconds() as f64)
+        })
+        .collect();
+    lead_times.sort_by(f64::total_cmp);
+
+    LeadTimeToGreen {
+        default_branch: default_branch.to_string(),
+        commits: lead_times.len(),
+        avg_seconds: if lead_times.is_empty() {
+            0.0
+        } else {
+            lead_times.iter().sum::<f64>() / lead_times.len() as f64
+        },
+        p50_seconds: percentile(&lead_times, 50.0),
+        p90_seconds: percentile(&lead_times, 90.0),
+        p95_seconds: percentile(&lead_times, 95.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{pipeline, time};
+
+    #[test]
+    fn test_lead_time_runs_from_commit_to_first_green_pipeline() {
+        let run = |id: u64, sha: &str, status: &str, finished_at: &str| {
+            let mut pipeline = pipeline(id, status, "2025-01-01T10:00:00Z", finished_at);
+            pipeline.sha = sha.to_string();
+            pipeline.committed_at = Some(time("2025-01-01T09:00:00Z"));
+            pipeline
+        };
+        let mut feature = run(5, "ccc", "success", "2025-01-01T10:10:00Z");
+        feature.ref_ = "feature".to_string();
+        let pipelines = vec![
+            run(1, "aaa", "failed", "2025-01-01T10:10:00Z"),
+            run(2, "aaa", "success", "2025-01-01T11:00:00Z"),
+            run(3, "aaa", "success", "2025-01-01T12:00:00Z"),
+            run(4, "bbb", "success", "2025-01-01T10:00:00Z"),
+            feature,
+        ];
+
+        let lead_time = calculate_lead_time_to_green(&pipelines, "main");
+
+        assert_eq!(lead_time.commits, 2);
+        assert!((lead_time.avg_seconds - 5400.0).abs() < f64::EPSILON);
+        assert!((lead_time.p50_seconds - 3600.0).abs() < f64::EPSILON);
+        assert!((lead_time.p95_seconds - 7200.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,61 @@
+/// Splits a job name generated by `parallel:` or `parallel:matrix:` into its
+/// base name and variant, e.g. `rspec 2/5` -> (`rspec`, `2/5`) and
+/// `deploy: [aws, eu-west-1]` -> (`deploy`, `[aws, eu-west-1]`).
+pub fn split_job_name(name: &str) -> (String, Option<String>) {
+    if let Some((base, variant)) = split_parallel(name).or_else(|| split_matrix(name)) {
+        return (base.to_string(), Some(variant.to_string()));
+    }
+    (name.to_string(), None)
+}
+
+fn split_parallel(name: &str) -> Option<(&str, &str)> {
+    let (base, variant) = name.rsplit_once(' ')?;
+    let (index, total) = variant.split_once('/')?;
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    (is_number(index) && is_number(total) && !base.is_empty()).then_some((base, variant))
+}
+
+fn split_matrix(name: &str) -> Option<(&str, &str)> {
+    if !name.ends_with(']') {
+        return None;
+    }
+    let start = name.rfind(": [")?;
+    let base = &name[..start];
+
+    (!base.is_empty()).then_some((base, &name[start + 2..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_job_name() {
+        assert_eq!(
+            split_job_name("rspec 2/5"),
+            ("rspec".to_string(), Some("2/5".to_string()))
+        );
+        assert_eq!(
+            split_job_name("deploy: [aws, eu-west-1]"),
+            ("deploy".to_string(), Some("[aws, eu-west-1]".to_string()))
+        );
+        assert_eq!(
+            split_job_name("build 2024"),
+            ("build 2024".to_string(), None)
+        );
+        assert_eq!(split_job_name("lint"), ("lint".to_string(), None));
+    }
+
+    #[test]
+    fn test_full_name_round_trips() {
+        use crate::providers::gitlab::types::fixtures::job;
+
+        for name in ["rspec 2/5", "deploy: [aws, eu-west-1]", "lint"] {
+            let (base, variant) = split_job_name(name);
+            let mut job = job(1, &base, "test", 1.0, "SUCCESS");
+            job.variant = variant;
+            assert_eq!(job.full_name(), name);
+        }
+    }
+}
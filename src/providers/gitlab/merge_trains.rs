@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use super::stats::{mean, rate};
+use super::types::GitLabPipeline;
+use crate::insights::{MergeTrainMetrics, QueueDepthMetrics};
+
+struct TrainPipeline<'a> {
+    pipeline: &'a GitLabPipeline,
+    enqueued_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+}
+
+impl TrainPipeline<'_> {
+    #[allow(clippy::cast_precision_loss)]
+    fn time_in_train_seconds(&self) -> f64 {
+        (self.finished_at - self.enqueued_at).num_seconds() as f64
+    }
+
+    fn succeeded(&self) -> bool {
+        self.pipeline.status == "success"
+    }
+}
+
+/// Number of train pipelines still running when `train` was enqueued.
+fn queue_depth(train: &TrainPipeline, all: &[TrainPipeline]) -> usize {
+    all.iter()
+        .filter(|other| {
+            other.enqueued_at < train.enqueued_at && other.finished_at > train.enqueued_at
+        })
+        .count()
+}
+
+/// A failure cascades when a car enqueued behind it (while it was still
+/// running) failed too, since that car was built on top of the broken one.
+fn cascaded(failed: &TrainPipeline, all: &[TrainPipeline]) -> bool {
+    all.iter().any(|other| {
+        !other.succeeded()
+            && other.enqueued_at > failed.enqueued_at
+            && other.enqueued_at < failed.finished_at
+    })
+}
+
+/// Summarizes merge train pipelines; `None` when the project doesn't use merge trains.
+pub fn analyze_merge_trains(pipelines: &[GitLabPipeline]) -> Option<MergeTrainMetrics> {
+    let mut trains: Vec<TrainPipeline> = pipelines
+        .iter()
+        .filter(|p| p.merge_request_event_type.as_deref() == Some("merge_train"))
+        .filter_map(|pipeline| {
+            Some(TrainPipeline {
+                pipeline,
+                enqueued_at: pipeline.created_at,
+                finished_at: pipeline.finished_at?,
+            })
+        })
+        .collect();
+
+    if trains.is_empty() {
+        return None;
+    }
+    trains.sort_by_key(|train| train.enqueued_at);
+
+    let successful = trains.iter().filter(|t| t.succeeded()).count();
+    let failed: Vec<&TrainPipeline> = trains.iter().filter(|t| !t.succeeded()).collect();
+    let cascades = failed.iter().filter(|f| cascaded(f, &trains)).count();
+
+    let mut by_depth: BTreeMap<usize, Vec<&TrainPipeline>> = BTreeMap::new();
+    for train in &trains {
+        by_depth
+            .entry(queue_depth(train, &trains))
+            .or_default()
+            .push(train);
+    }
+
+    let by_queue_depth = by_depth
+        .into_iter()
+        .map(|(queue_depth, group)| QueueDepthMetrics {
+            queue_depth,
+            pipelines: group.len(),
+            success_rate: rate(group.iter().filter(|t| t.succeeded()).count(), group.len()),
+            avg_time_in_train_seconds: mean(group.iter().map(|t| t.time_in_train_seconds())),
+        })
+        .collect();
+
+    #[allow(clippy::cast_precision_loss)]
+    Some(MergeTrainMetrics {
+        total_pipelines: trains.len(),
+        success_rate: rate(successful, trains.len()),
+        avg_duration_seconds: mean(trains.iter().map(|t| t.pipeline.duration as f64)),
+        avg_time_in_train_seconds: mean(trains.iter().map(TrainPipeline::time_in_train_seconds)),
+        failure_cascade_rate: rate(cascades, failed.len()),
+        by_queue_depth,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+
+    fn train(id: u64, status: &str, created_at: &str, finished_at: &str) -> GitLabPipeline {
+        let mut pipeline = pipeline(id, status, created_at, finished_at);
+        pipeline.merge_request_event_type = Some("merge_train".to_string());
+        pipeline
+    }
+
+    #[test]
+    fn test_analyze_merge_trains_none_without_train_pipelines() {
+        let pipelines = vec![pipeline(
+            1,
+            "success",
+            "2025-01-01T10:00:00Z",
+            "2025-01-01T10:10:00Z",
+        )];
+
+        assert!(analyze_merge_trains(&pipelines).is_none());
+    }
+
+    #[test]
+    fn test_analyze_merge_trains_detects_cascades_and_queue_depth() {
+        let pipelines = vec![
+            train(1, "failed", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z"),
+            train(2, "failed", "2025-01-01T10:02:00Z", "2025-01-01T10:12:00Z"),
+            train(3, "success", "2025-01-01T11:00:00Z", "2025-01-01T11:10:00Z"),
+        ];
+
+        let metrics = analyze_merge_trains(&pipelines).unwrap();
+
+        assert_eq!(metrics.total_pipelines, 3);
+        // Only the first failure had a failing car enqueued behind it
+        assert!((metrics.failure_cascade_rate - 50.0).abs() < f64::EPSILON);
+        assert_eq!(metrics.by_queue_depth.len(), 2);
+        assert_eq!(metrics.by_queue_depth[1].queue_depth, 1);
+        assert_eq!(metrics.by_queue_depth[1].pipelines, 1);
+        assert!((metrics.avg_time_in_train_seconds - 600.0).abs() < f64::EPSILON);
+    }
+}
@@ -1,9 +1,45 @@
+mod analyzers;
+mod branch_health;
+mod ci_minutes;
 mod client;
+mod cloudevents;
+mod commits;
+mod composition;
+mod dedupe;
+mod deployment_metrics;
+mod developer_wait;
+mod durations;
+mod failure_classes;
+mod failure_streaks;
+mod images;
 mod job_analysis;
+mod job_logs;
+mod lead_time;
+mod matrix;
+mod merge_trains;
+mod otlp;
 mod pipeline_types;
 mod provider;
+mod recommendations;
+mod releases;
+mod reverts;
+mod runners;
+mod sampling;
+mod schedules;
+mod sla;
+mod stats;
 mod type_metrics;
 mod types;
 mod url_utils;
+mod users;
+#[cfg(feature = "wasm-plugins")]
+mod wasm;
+mod waste;
 
-pub use provider::GitLabProvider;
+pub use analyzers::Analyzer;
+pub use client::{Api, HttpOptions, Middleware, Next, PipelineStatus};
+pub use dedupe::DedupePreference;
+pub use pipeline_types::{AnalysisOptions, JobSort};
+pub use provider::{CollectOptions, GitLabProvider};
+pub use sampling::Sample;
+pub use types::{GitLabJob, GitLabPipeline};
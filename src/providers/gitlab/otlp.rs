@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde_json::{json, Value};
+use url::Url;
+
+use super::types::{GitLabJob, GitLabPipeline};
+use super::url_utils::{job_id_to_url, pipeline_id_to_url};
+use crate::error::{CILensError, Result};
+
+const BATCH_SIZE: usize = 20;
+
+// OTLP span status codes
+const STATUS_OK: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+
+fn numeric_id(gid: &str) -> u64 {
+    gid.rsplit('/')
+        .next()
+        .and_then(|id| id.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Trace IDs derive from the pipeline ID so re-exporting a pipeline is idempotent.
+fn trace_id(pipeline: &GitLabPipeline) -> String {
+    format!("{:032x}", numeric_id(&pipeline.id))
+}
+
+/// The root span sets the high bit so it never collides with a job span ID.
+fn root_span_id(pipeline: &GitLabPipeline) -> String {
+    format!("{:016x}", numeric_id(&pipeline.id) | (1 << 63))
+}
+
+fn span_id(job: &GitLabJob) -> String {
+    format!("{:016x}", numeric_id(&job.id))
+}
+
+fn unix_nanos(time: DateTime<Utc>) -> String {
+    time.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn pipeline_span(pipeline: &GitLabPipeline, base_url: &str, project_path: &str) -> Option<Value> {
+    let start = pipeline.started_at.unwrap_or(pipeline.created_at);
+    let end = pipeline.finished_at?;
+    let status = if pipeline.status == "success" {
+        STATUS_OK
+    } else {
+        STATUS_ERROR
+    };
+
+    Some(json!({
+        "traceId": trace_id(pipeline),
+        "spanId": root_span_id(pipeline),
+        "name": format!("pipeline {}", pipeline.ref_),
+        "kind": 1,
+        "startTimeUnixNano": unix_nanos(start),
+        "endTimeUnixNano": unix_nanos(end),
+        "attributes": [
+            attribute("cicd.pipeline.id", &numeric_id(&pipeline.id).to_string()),
+            attribute("cicd.pipeline.ref", &pipeline.ref_),
+            attribute("cicd.pipeline.source", &pipeline.source),
+            attribute("cicd.pipeline.status", &pipeline.status),
+            attribute("url.full", &pipeline_id_to_url(base_url, project_path, &pipeline.id)),
+        ],
+        "status": { "code": status },
+    }))
+}
+
+fn job_span(
+    pipeline: &GitLabPipeline,
+    job: &GitLabJob,
+    base_url: &str,
+    project_path: &str,
+) -> Option<Value> {
+    let (start, end) = (job.started_at?, job.finished_at?);
+    let status = if job.status == "SUCCESS" {
+        STATUS_OK
+    } else {
+        STATUS_ERROR
+    };
+
+    Some(json!({
+        "traceId": trace_id(pipeline),
+        "spanId": span_id(job),
+        "parentSpanId": root_span_id(pipeline),
+        "name": job.full_name(),
+        "kind": 1,
+        "startTimeUnixNano": unix_nanos(start),
+        "endTimeUnixNano": unix_nanos(end),
+        "attributes": [
+            attribute("cicd.pipeline.task.name", &job.full_name()),
+            attribute("cicd.pipeline.task.stage", &job.stage),
+            attribute("cicd.pipeline.task.status", &job.status),
+            attribute("cicd.pipeline.task.retried", &job.retried.to_string()),
+            attribute("url.full", &job_id_to_url(base_url, project_path, &job.id)),
+        ],
+        "status": { "code": status },
+    }))
+}
+
+fn build_payload(pipelines: &[GitLabPipeline], base_url: &str, project_path: &str) -> Value {
+    let spans: Vec<Value> = pipelines
+        .iter()
+        .filter_map(|pipeline| {
+            let root = pipeline_span(pipeline, base_url, project_path)?;
+            let jobs = pipeline
+                .jobs
+                .iter()
+                .filter_map(|job| job_span(pipeline, job, base_url, project_path));
+            Some(std::iter::once(root).chain(jobs).collect::<Vec<_>>())
+        })
+        .flatten()
+        .collect();
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    attribute("service.name", "gitlab-ci"),
+                    attribute("vcs.repository.name", project_path),
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "cilens", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }]
+        }]
+    })
+}
+
+/// Exports pipelines as OTLP traces (one trace per pipeline, one span per job)
+/// to an OTLP/HTTP collector, e.g. `http://localhost:4318`.
+pub async fn export_traces(
+    endpoint: &str,
+    pipelines: &[GitLabPipeline],
+    base_url: &str,
+    project_path: &str,
+) -> Result<()> {
+    let url = Url::parse(endpoint)
+        .and_then(|base| base.join("v1/traces"))
+        .map_err(|e| CILensError::Config(format!("Invalid OTLP endpoint: {e}")))?;
+
+    let client = reqwest::Client::new();
+    let unfinished = pipelines.iter().filter(|p| p.finished_at.is_none()).count();
+    if unfinished > 0 {
+        warn!("Skipping {unfinished} pipelines without a finish time in trace export");
+    }
+
+    for batch in pipelines.chunks(BATCH_SIZE) {
+        client
+            .post(url.clone())
+            .json(&build_payload(batch, base_url, project_path))
+            .send()
+            .await?
+            .error_for_status()?;
+    }
+
+    info!(
+        "Exported {} pipelines as traces to {url}",
+        pipelines.len() - unfinished
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures;
+
+    fn pipeline() -> GitLabPipeline {
+        let mut pipeline =
+            fixtures::pipeline(42, "failed", "2025-01-01T10:00:00Z", "2025-01-01T10:02:00Z");
+
+        let mut rspec = fixtures::job(7, "rspec", "test", 60.0, "FAILED");
+        rspec.started_at = Some(fixtures::time("2025-01-01T10:00:00Z"));
+        rspec.finished_at = Some(fixtures::time("2025-01-01T10:01:00Z"));
+        let deploy = fixtures::job(8, "deploy", "deploy", 0.0, "SKIPPED");
+
+        pipeline.jobs = vec![rspec, deploy];
+        pipeline
+    }
+
+    #[test]
+    fn test_build_payload_links_job_spans_to_pipeline_root() {
+        let payload = build_payload(&[pipeline()], "https://gitlab.com", "group/project");
+        let spans = payload["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+
+        // The job that never started has no span
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0]["traceId"], format!("{:032x}", 42));
+        assert_eq!(spans[1]["parentSpanId"], spans[0]["spanId"]);
+        assert_eq!(spans[1]["traceId"], spans[0]["traceId"]);
+        assert_eq!(spans[1]["startTimeUnixNano"], "1735725600000000000");
+        assert_eq!(spans[0]["status"]["code"], STATUS_ERROR);
+    }
+}
@@ -1,8 +1,58 @@
 use std::collections::{BTreeSet, HashMap};
 
+use chrono_tz::Tz;
+
+use super::job_logs::JobLog;
 use super::types::GitLabPipeline;
 use crate::insights::PipelineType;
 
+/// Ordering of the jobs reported for each pipeline type (highest first).
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum JobSort {
+    /// Time from pipeline start until the job reports back
+    #[default]
+    Feedback,
+    Duration,
+    FailureRate,
+    Flakiness,
+    Executions,
+}
+
+/// Controls how collected pipelines are grouped and summarized.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisOptions {
+    /// Pipeline types below this share of all pipelines are dropped (0-100)
+    pub min_type_percentage: u8,
+    /// Target pipeline duration to report SLA attainment against
+    pub sla_seconds: Option<u64>,
+    /// Upper bounds (seconds) of the job duration histogram buckets
+    pub histogram_buckets: Option<Vec<f64>>,
+    /// Only report this many jobs per pipeline type (all when unset)
+    pub top_jobs: Option<usize>,
+    /// Always keep this many of the flakiest jobs, even past `top_jobs`
+    pub top_flaky: usize,
+    /// Hide jobs that executed fewer times than this
+    pub min_executions: usize,
+    pub sort_jobs: JobSort,
+    /// Report per-variant metrics for `parallel`/`matrix` jobs
+    pub matrix_variants: bool,
+    /// Break type metrics down per ref pattern
+    pub by_ref: bool,
+    /// Half-life (seconds) of a pipeline's weight in `flakiness_score`; 0
+    /// weighs every pipeline the same
+    pub flakiness_half_life: u64,
+    /// Jobs that passed at least this many executions without a failure are
+    /// candidates to run less often (0 disables)
+    pub always_green_executions: usize,
+    /// Share of runs (percent) flaky jobs should pass with their retry
+    /// settings (0 disables)
+    pub target_success_rate: f64,
+    /// Labels pinned in the config, keyed by job signature
+    pub type_names: HashMap<Vec<String>, String>,
+    /// Timezone weekly trends are bucketed in
+    pub timezone: Tz,
+}
+
 fn extract_job_signature(pipeline: &GitLabPipeline) -> Vec<String> {
     pipeline
         .jobs
@@ -15,7 +65,8 @@ fn extract_job_signature(pipeline: &GitLabPipeline) -> Vec<String> {
 
 pub fn group_pipeline_types(
     pipelines: &[GitLabPipeline],
-    min_type_percentage: u8,
+    options: &AnalysisOptions,
+    job_logs: Option<&HashMap<String, JobLog>>,
     base_url: &str,
     project_path: &str,
 ) -> Vec<PipelineType> {
@@ -34,14 +85,16 @@ pub fn group_pipeline_types(
                 &job_names,
                 &cluster_pipelines,
                 total_pipelines,
+                options,
+                job_logs,
                 base_url,
                 project_path,
             )
         })
-        .filter(|pt| pt.metrics.percentage >= f64::from(min_type_percentage))
+        .filter(|pt| pt.metrics.percentage >= f64::from(options.min_type_percentage))
         .collect();
 
-    pipeline_types.sort_by(|a, b| b.metrics.total_pipelines.cmp(&a.metrics.total_pipelines));
+    pipeline_types.sort_by_key(|pt| std::cmp::Reverse(pt.metrics.total_pipelines));
     pipeline_types
 }
 
@@ -49,6 +102,8 @@ fn create_pipeline_type(
     job_names: &[String],
     pipelines: &[&GitLabPipeline],
     total_pipelines: usize,
+    options: &AnalysisOptions,
+    job_logs: Option<&HashMap<String, JobLog>>,
     base_url: &str,
     project_path: &str,
 ) -> PipelineType {
@@ -56,27 +111,31 @@ fn create_pipeline_type(
     #[allow(clippy::cast_precision_loss)]
     let percentage = (count as f64 / total_pipelines.max(1) as f64) * 100.0;
 
-    // Generate label from job names
-    let label = if job_names.iter().any(|j| j.to_lowercase().contains("prod")) {
-        "Production Pipeline".to_string()
-    } else if job_names.iter().any(|j| {
-        let lower = j.to_lowercase();
-        lower.contains("staging")
-            || lower.contains("dev")
-            || lower.contains("test")
-            || lower.contains("qa")
-    }) {
-        "Development Pipeline".to_string()
-    } else {
-        "Unknown Pipeline".to_string()
-    };
+    let label = options
+        .type_names
+        .get(job_names)
+        .cloned()
+        .unwrap_or_else(|| heuristic_label(job_names));
 
     // Extract common characteristics
     let (stages, ref_patterns, sources) = extract_characteristics(pipelines);
 
     // Calculate metrics
-    let metrics =
-        super::type_metrics::calculate_type_metrics(pipelines, percentage, base_url, project_path);
+    let mut metrics = super::type_metrics::calculate_type_metrics(
+        pipelines,
+        percentage,
+        options,
+        base_url,
+        project_path,
+    );
+    metrics.sla = options
+        .sla_seconds
+        .map(|target| super::sla::calculate_sla(pipelines, target, options.timezone));
+    metrics.by_ref = options
+        .by_ref
+        .then(|| super::branch_health::calculate_ref_metrics(pipelines));
+    metrics.artifact_transfer =
+        job_logs.map(|logs| super::job_logs::calculate_artifact_transfer(pipelines, logs));
 
     PipelineType {
         label,
@@ -87,6 +146,23 @@ fn create_pipeline_type(
     }
 }
 
+/// Generates a label from job names.
+fn heuristic_label(job_names: &[String]) -> String {
+    if job_names.iter().any(|j| j.to_lowercase().contains("prod")) {
+        "Production Pipeline".to_string()
+    } else if job_names.iter().any(|j| {
+        let lower = j.to_lowercase();
+        lower.contains("staging")
+            || lower.contains("dev")
+            || lower.contains("test")
+            || lower.contains("qa")
+    }) {
+        "Development Pipeline".to_string()
+    } else {
+        "Unknown Pipeline".to_string()
+    }
+}
+
 fn extract_characteristics(
     pipelines: &[&GitLabPipeline],
 ) -> (Vec<String>, Vec<String>, Vec<String>) {
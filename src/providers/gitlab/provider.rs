@@ -1,41 +1,419 @@
-use chrono::Utc;
+use std::cmp::{max, min};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use futures::future;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use log::{info, warn};
+use reqwest::header::HeaderMap;
 
 use crate::auth::Token;
+use crate::cloudevents::Sink;
 use crate::error::Result;
-use crate::insights::CIInsights;
+use crate::insights::{
+    ApiUsage, BranchComparison, BrokenJob, CIInsights, CiMinutesUsage, CollectionWatermark,
+    EnvironmentDeploymentMetrics, ImageChange, JobRanking, LeadTimeToGreen, QueryParameters,
+    ReportMeta, RevertMetrics, RunnerFleetReport, SamplingInfo, ScheduleDrift, Summary,
+};
+use crate::lint_ci;
+use crate::output::human::Style;
+use crate::output::markdown;
+use crate::providers::gitlab::client::middleware::Headers;
 use crate::providers::gitlab::client::pipelines::{fetch_pipeline_jobs, fetch_pipelines};
-use crate::providers::gitlab::client::GitLabClient;
+use crate::providers::gitlab::client::{
+    fixtures, http_cache, Api, GitLabClient, HttpOptions, Middleware, PipelineStatus,
+};
+
+use super::analyzers::Analyzer;
+use super::branch_health::calculate_branch_health;
+use super::ci_minutes::{calculate_ci_minutes_usage, month_start};
+use super::dedupe::{dedupe_by_sha, DedupePreference};
+use super::images::ImagesBySha;
+use super::job_logs::JobLog;
+use super::matrix::split_job_name;
+use super::pipeline_types::AnalysisOptions;
+use super::sampling::{sample_by_recency, sampling_info, Sample};
+use super::types::{GitLabJob, GitLabPipeline, GitLabUser};
+use super::url_utils::extract_numeric_id;
+
+/// Stages in the order their first job was created, which follows the
+/// pipeline's stage order since GitLab creates jobs stage by stage.
+fn stage_order_from_jobs(jobs: &[GitLabJob]) -> Vec<String> {
+    let mut first_job_ids: HashMap<&str, u64> = HashMap::new();
+    for job in jobs {
+        let id = extract_numeric_id(&job.id).parse().unwrap_or(u64::MAX);
+        let first = first_job_ids.entry(&job.stage).or_insert(id);
+        *first = (*first).min(id);
+    }
+
+    let mut stages: Vec<(&str, u64)> = first_job_ids.into_iter().collect();
+    stages.sort_by_key(|&(stage, id)| (id, stage));
+    stages
+        .into_iter()
+        .map(|(stage, _)| stage.to_string())
+        .collect()
+}
+
+/// Range of pipeline IDs `pipelines` covers, `None` when there are none.
+fn collection_watermark(pipelines: &[GitLabPipeline]) -> Option<CollectionWatermark> {
+    let ids: Vec<u64> = pipelines
+        .iter()
+        .filter_map(|pipeline| extract_numeric_id(&pipeline.id).parse().ok())
+        .collect();
+
+    Some(CollectionWatermark {
+        oldest_pipeline_id: *ids.iter().min()?,
+        newest_pipeline_id: *ids.iter().max()?,
+    })
+}
 
-use super::types::{GitLabJob, GitLabPipeline};
+/// Creation times of the oldest and newest of `pipelines`, `None` when there
+/// are none.
+fn creation_span(pipelines: &[GitLabPipeline]) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    pipelines
+        .iter()
+        .map(|pipeline| pipeline.created_at)
+        .fold(None, |span, created_at| match span {
+            Some((first, last)) => Some((min(first, created_at), max(last, created_at))),
+            None => Some((created_at, created_at)),
+        })
+}
+
+/// How the report was collected, logging the API requests it took.
+fn report_meta(
+    options: &CollectOptions,
+    api_usage: &ApiUsage,
+    fetch_duration: Duration,
+) -> ReportMeta {
+    info!(
+        "Used {} GitLab API requests ({} GraphQL with complexity {}, {} REST)",
+        api_usage.requests,
+        api_usage.graphql_requests,
+        api_usage.graphql_complexity,
+        api_usage.rest_requests
+    );
+
+    ReportMeta {
+        parameters: options.query_parameters(),
+        fetch_duration_seconds: fetch_duration.as_secs_f64(),
+    }
+}
+
+/// Parameters controlling which pipelines are collected and what is done with them.
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct CollectOptions {
+    pub limit: usize,
+    pub ref_: Option<String>,
+    /// Pipeline statuses to fetch and analyze ([`PipelineStatus::DEFAULT`] when empty)
+    pub statuses: Vec<PipelineStatus>,
+    /// OTLP/HTTP collector to export pipelines to as traces
+    pub otlp_endpoint: Option<String>,
+    /// Sink to emit a `CloudEvent` per collected pipeline to
+    pub cloudevents_sink: Option<Sink>,
+    /// Also collect deployments over the analyzed window
+    pub include_deployments: bool,
+    /// Also report the namespace's CI minutes usage
+    pub include_ci_minutes: bool,
+    /// Quota to project against when GitLab does not disclose the namespace's
+    pub ci_minutes_quota: Option<u64>,
+    /// Restrict analysis to the project's default branch (overrides `ref_`)
+    pub default_branch_only: bool,
+    /// Contrast default-branch health with all-branch health
+    pub compare_default_branch: bool,
+    /// Only analyze a sample of the listed pipelines
+    pub sample: Option<Sample>,
+    /// Analyze exactly these pipelines (numeric IDs) instead of the latest `limit`
+    pub pipeline_ids: Option<Vec<u64>>,
+    /// Break pipelines down by triggering user
+    pub by_user: bool,
+    /// Correlate job container image changes with duration/failure shifts
+    pub include_images: bool,
+    /// Report jobs failing in at least this many consecutive default-branch pipelines
+    pub broken_streak: Option<usize>,
+    /// Measure the time from default-branch commits to their first green pipeline
+    pub lead_time: bool,
+    /// Compare scheduled pipelines against their schedules' cron expressions
+    pub include_schedules: bool,
+    /// Download recent job logs to measure cache effectiveness and dependency
+    /// install time
+    pub analyze_logs: bool,
+    /// Keep a single pipeline per commit SHA, preferring this kind
+    pub dedupe_by_sha: Option<DedupePreference>,
+    pub analysis: AnalysisOptions,
+}
+
+impl CollectOptions {
+    fn statuses(&self) -> &[PipelineStatus] {
+        if self.statuses.is_empty() {
+            &PipelineStatus::DEFAULT
+        } else {
+            &self.statuses
+        }
+    }
+
+    /// The options, spelled like the command-line values that set them.
+    fn query_parameters(&self) -> QueryParameters {
+        fn value_name(value: &impl ValueEnum) -> String {
+            value
+                .to_possible_value()
+                .map(|value| value.get_name().to_string())
+                .unwrap_or_default()
+        }
+
+        QueryParameters {
+            limit: self.limit,
+            ref_: self.ref_.clone(),
+            statuses: self.statuses().iter().map(value_name).collect(),
+            default_branch_only: self.default_branch_only,
+            sample: self.sample.map(|sample| match sample {
+                Sample::Percentage(percentage) => format!("{percentage}%"),
+                Sample::Count(count) => count.to_string(),
+            }),
+            pipeline_ids: self.pipeline_ids.clone(),
+            dedupe_by_sha: self.dedupe_by_sha.as_ref().map(value_name),
+            min_type_percentage: self.analysis.min_type_percentage,
+            min_executions: self.analysis.min_executions,
+            top_jobs: self.analysis.top_jobs,
+        }
+    }
+}
 
 pub struct GitLabProvider {
     pub client: GitLabClient,
     pub project_path: String,
+    analyzers: Vec<Box<dyn Analyzer>>,
 }
 
 impl GitLabProvider {
+    /// Collects `project_path` from the GitLab instance at `base_url`.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `base_url` is not a valid URL.
     pub fn new(base_url: &str, project_path: String, token: Option<Token>) -> Result<Self> {
         let client = GitLabClient::new(base_url, token)?;
 
         Ok(Self {
             client,
             project_path,
+            analyzers: super::analyzers::builtin(Tz::UTC),
         })
     }
 
+    /// Caps the GitLab API requests spent on this project (see `--max-requests`).
+    #[must_use]
+    pub fn with_max_requests(mut self, max_requests: Option<usize>) -> Self {
+        self.client = self.client.with_max_requests(max_requests);
+        self
+    }
+
+    /// Collects pipelines and jobs through `api` (see `--api`).
+    #[must_use]
+    pub fn with_api(mut self, api: Api) -> Self {
+        self.client = self.client.with_api(api);
+        self
+    }
+
+    /// Applies `--request-timeout`, `--connect-timeout` and pool sizing.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the HTTP client cannot be rebuilt with `options`.
+    pub fn with_http_options(mut self, options: &HttpOptions) -> Result<Self> {
+        self.client = self.client.with_http_options(options)?;
+        Ok(self)
+    }
+
+    /// Revalidates REST responses cached in `dir` instead of downloading them
+    /// again (see `--http-cache`).
+    ///
+    /// # Errors
+    ///
+    /// Fails when `dir` cannot be created.
+    pub fn with_http_cache(self, dir: &Path) -> Result<Self> {
+        Ok(self.with_middleware(Arc::new(http_cache::HttpCache::new(dir)?)))
+    }
+
+    /// Saves every API response to `dir` (see `--record-fixtures`).
+    ///
+    /// # Errors
+    ///
+    /// Fails when `dir` cannot be created.
+    pub fn with_recording(self, dir: &Path) -> Result<Self> {
+        Ok(self.with_middleware(Arc::new(fixtures::Recorder::new(dir)?)))
+    }
+
+    /// Answers every API request from responses saved in `dir` (see `--replay-fixtures`).
+    #[must_use]
+    pub fn with_replay(self, dir: &Path) -> Self {
+        self.with_middleware(Arc::new(fixtures::Replayer::new(dir)))
+    }
+
+    /// Sends `headers` with every API request (see `--header`).
+    #[must_use]
+    pub fn with_headers(self, headers: HeaderMap) -> Self {
+        self.with_middleware(Arc::new(Headers::new(headers)))
+    }
+
+    /// Runs every API request through `middleware`, after any added before
+    /// it, e.g. to record request metrics or answer requests in tests.
+    #[must_use]
+    pub fn with_middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.client = self.client.with_middleware(middleware);
+        self
+    }
+
+    /// Buckets the built-in sections' weekly trends in `timezone` instead of
+    /// UTC (see `--timezone`). Replaces built-ins overridden with
+    /// [`with_analyzer`](Self::with_analyzer), so call it first.
+    #[must_use]
+    pub fn with_timezone(self, timezone: Tz) -> Self {
+        super::analyzers::builtin(timezone)
+            .into_iter()
+            .fold(self, Self::with_analyzer)
+    }
+
+    /// Adds a custom analyzer whose section is merged into every report.
+    ///
+    /// An analyzer named like a built-in section (e.g. `releases`) replaces it.
+    #[must_use]
+    pub fn with_analyzer(mut self, analyzer: Box<dyn Analyzer>) -> Self {
+        self.analyzers
+            .retain(|existing| existing.name() != analyzer.name());
+        self.analyzers.push(analyzer);
+        self
+    }
+
+    /// Registers the WebAssembly analyzers found in `dir` (see `--plugins-dir`).
+    ///
+    /// # Errors
+    ///
+    /// Fails when `dir` cannot be read or a plugin does not compile.
+    #[cfg(feature = "wasm-plugins")]
+    pub fn with_plugins_dir(self, dir: &std::path::Path) -> Result<Self> {
+        Ok(super::wasm::load_plugins(dir)?
+            .into_iter()
+            .fold(self, |provider, plugin| {
+                provider.with_analyzer(Box::new(plugin))
+            }))
+    }
+
     async fn fetch_pipelines(
         &self,
         limit: usize,
         ref_: Option<&str>,
+        statuses: &[PipelineStatus],
     ) -> Result<Vec<GitLabPipeline>> {
         info!("Fetching up to {limit} pipelines...");
 
         let pipeline_nodes = self
             .client
-            .fetch_pipelines(&self.project_path, limit, ref_)
+            .fetch_pipelines(&self.project_path, limit, ref_, statuses)
+            .await?;
+
+        self.process_pipeline_nodes(pipeline_nodes).await
+    }
+
+    /// Streams up to `limit` finished pipelines, newest first, with their jobs.
+    ///
+    /// Pipelines are yielded as soon as their page and jobs are fetched, so
+    /// consumers can process them without waiting for the whole window.
+    pub fn stream_pipelines<'a>(
+        &'a self,
+        limit: usize,
+        ref_: Option<&'a str>,
+    ) -> impl Stream<Item = Result<GitLabPipeline>> + 'a {
+        const JOB_FETCH_CONCURRENCY: usize = 10;
+
+        // `None` once the last page was fetched
+        let first_page: Option<Option<String>> = Some(None);
+
+        stream::try_unfold(
+            (first_page, HashSet::new()),
+            move |(cursor, mut seen): (Option<Option<String>>, HashSet<String>)| async move {
+                let Some(cursor) = cursor.filter(|_| seen.len() < limit) else {
+                    return Result::<_>::Ok(None);
+                };
+                let (nodes, next_cursor) = self
+                    .client
+                    .fetch_pipelines_page(
+                        &self.project_path,
+                        limit - seen.len(),
+                        cursor,
+                        ref_,
+                        None,
+                    )
+                    .await?;
+                // Skip pipelines a page shifted by newly created ones repeats
+                let nodes: Vec<_> = nodes
+                    .into_iter()
+                    .filter(|node| seen.insert(node.id.clone()))
+                    .collect();
+                Ok(Some((nodes, (next_cursor.map(Some), seen))))
+            },
+        )
+        .map_ok(move |nodes| {
+            stream::iter(nodes)
+                .map(move |node| self.transform_pipeline_with_jobs(node))
+                .buffered(JOB_FETCH_CONCURRENCY)
+        })
+        .try_flatten()
+        .try_filter_map(|pipeline| future::ready(Ok(pipeline)))
+    }
+
+    /// Lists up to `limit` pipelines but only fetches jobs for a recency-weighted sample of them.
+    async fn fetch_sampled_pipelines(
+        &self,
+        limit: usize,
+        ref_: Option<&str>,
+        statuses: &[PipelineStatus],
+        sample: Sample,
+    ) -> Result<(Vec<GitLabPipeline>, SamplingInfo)> {
+        info!("Listing up to {limit} pipelines to sample from...");
+
+        let pipeline_nodes = self
+            .client
+            .fetch_pipelines(&self.project_path, limit, ref_, statuses)
             .await?;
 
+        let population = pipeline_nodes.len();
+        let size = sample.size(population);
+        info!("Sampling {size} of {population} pipelines");
+
+        let sampled_nodes = sample_by_recency(
+            pipeline_nodes,
+            |node| node.created_at,
+            size,
+            &mut rand::rng(),
+        );
+
+        let pipelines = self.process_pipeline_nodes(sampled_nodes).await?;
+        let sampling = sampling_info(population, &pipelines);
+
+        Ok((pipelines, sampling))
+    }
+
+    async fn fetch_pipelines_by_id(&self, pipeline_ids: &[u64]) -> Result<Vec<GitLabPipeline>> {
+        info!("Fetching {} selected pipelines...", pipeline_ids.len());
+
+        let pipeline_nodes = self
+            .client
+            .fetch_pipelines_by_id(&self.project_path, pipeline_ids)
+            .await?;
+
+        self.process_pipeline_nodes(pipeline_nodes).await
+    }
+
+    async fn process_pipeline_nodes(
+        &self,
+        pipeline_nodes: Vec<fetch_pipelines::FetchPipelinesProjectPipelinesNodes>,
+    ) -> Result<Vec<GitLabPipeline>> {
         info!(
             "Fetching jobs for {} pipelines in parallel...",
             pipeline_nodes.len()
@@ -80,10 +458,10 @@ impl GitLabProvider {
 
         let jobs = Self::transform_job_nodes(job_nodes);
 
-        // Extract stage order from pipeline metadata
-        let stages = node
-            .stages
-            .map(|stages_conn| {
+        // Extract stage order from pipeline metadata; the REST API has none
+        let stages = node.stages.map_or_else(
+            || stage_order_from_jobs(&jobs),
+            |stages_conn| {
                 stages_conn
                     .nodes
                     .into_iter()
@@ -91,15 +469,28 @@ impl GitLabProvider {
                     .flatten()
                     .filter_map(|stage| stage.name)
                     .collect()
-            })
-            .unwrap_or_default();
+            },
+        );
 
         Ok(Some(GitLabPipeline {
             id: node.id,
             ref_: node.ref_.unwrap_or_default(),
+            ref_path: node.ref_path.unwrap_or_default(),
+            sha: node.sha.unwrap_or_default(),
+            user: node.user.map(|user| GitLabUser {
+                username: user.username,
+                bot: user.bot,
+            }),
             source: node.source.unwrap_or_default(),
             status: format!("{:?}", node.status).to_lowercase(),
+            merge_request_event_type: node
+                .merge_request_event_type
+                .map(|event_type| format!("{event_type:?}").to_lowercase()),
             duration,
+            committed_at: node.committed_at,
+            created_at: node.created_at,
+            started_at: node.started_at,
+            finished_at: node.finished_at,
             stages,
             jobs,
         }))
@@ -111,17 +502,38 @@ impl GitLabProvider {
         job_nodes
             .into_iter()
             .map(|job_node| {
+                // parallel/matrix variants are analyzed as one job
+                let (name, variant) = split_job_name(&job_node.name.unwrap_or_default());
+
                 #[allow(clippy::cast_precision_loss)]
                 GitLabJob {
                     id: job_node.id.unwrap_or_default(),
-                    name: job_node.name.unwrap_or_default(),
+                    name,
+                    variant,
                     stage: job_node.stage.and_then(|s| s.name).unwrap_or_default(),
-                    duration: job_node.duration.unwrap_or(0) as f64,
+                    duration: job_node.duration.map(|duration| duration as f64),
+                    estimated_duration: None,
                     status: job_node
                         .status
                         .map(|s| format!("{s:?}"))
                         .unwrap_or_default(),
+                    started_at: job_node.started_at,
+                    finished_at: job_node.finished_at,
                     retried: job_node.retried.unwrap_or(false),
+                    runner_id: job_node
+                        .runner
+                        .as_ref()
+                        .and_then(|runner| runner.id.rsplit('/').next()?.parse().ok()),
+                    runner_type: job_node
+                        .runner
+                        .map(|runner| format!("{:?}", runner.runner_type).to_lowercase()),
+                    queued_duration: job_node.queued_duration,
+                    tags: job_node.tags.unwrap_or_default(),
+                    failure_message: job_node.failure_message,
+                    downstream_duration: job_node
+                        .downstream_pipeline
+                        .and_then(|downstream| downstream.duration)
+                        .map(|duration| duration as f64),
                     needs: job_node.needs.map(|needs_conn| {
                         needs_conn
                             .nodes
@@ -129,6 +541,9 @@ impl GitLabProvider {
                             .flatten()
                             .flatten()
                             .filter_map(|need| need.name)
+                            .map(|need| split_job_name(&need).0)
+                            .collect::<BTreeSet<_>>()
+                            .into_iter()
                             .collect()
                     }),
                 }
@@ -136,40 +551,685 @@ impl GitLabProvider {
             .collect()
     }
 
-    pub async fn collect_insights(
+    /// Fetches the pipelines `options` select, with their jobs.
+    async fn collect_pipelines(
         &self,
-        limit: usize,
-        ref_: Option<&str>,
-        min_type_percentage: u8,
-    ) -> Result<CIInsights> {
+        options: &CollectOptions,
+    ) -> Result<(Vec<GitLabPipeline>, Option<SamplingInfo>)> {
+        let ref_ = if options.default_branch_only {
+            let default_branch = self.client.fetch_default_branch(&self.project_path).await?;
+            info!("Restricting analysis to default branch: {default_branch}");
+            Some(default_branch)
+        } else {
+            options.ref_.clone()
+        };
+
+        let (pipelines, sampling) = match (&options.pipeline_ids, options.sample) {
+            (Some(pipeline_ids), _) => (self.fetch_pipelines_by_id(pipeline_ids).await?, None),
+            (None, Some(sample)) => {
+                let (pipelines, sampling) = self
+                    .fetch_sampled_pipelines(
+                        options.limit,
+                        ref_.as_deref(),
+                        options.statuses(),
+                        sample,
+                    )
+                    .await?;
+                (pipelines, Some(sampling))
+            }
+            (None, None) => (
+                self.fetch_pipelines(options.limit, ref_.as_deref(), options.statuses())
+                    .await?,
+                None,
+            ),
+        };
+
+        let mut pipelines = match options.dedupe_by_sha {
+            Some(preference) => {
+                let listed = pipelines.len();
+                let pipelines = dedupe_by_sha(pipelines, preference);
+                info!(
+                    "Dropped {} pipelines that ran for an already analyzed commit",
+                    listed - pipelines.len()
+                );
+                pipelines
+            }
+            None => pipelines,
+        };
+
+        if pipelines.is_empty() {
+            warn!("No pipelines found for project: {}", self.project_path);
+        }
+
+        let estimated = super::durations::estimate_missing_durations(&mut pipelines);
+        if estimated > 0 {
+            info!("Estimated the duration of {estimated} jobs GitLab reported none for");
+        }
+
+        Ok((pipelines, sampling))
+    }
+
+    /// Instance URL, e.g. `https://gitlab.com` for `https://gitlab.com/api/graphql`.
+    fn base_url(&self) -> String {
+        self.client.graphql_url.origin().ascii_serialization()
+    }
+
+    /// Collects the pipelines `options` select and analyzes them into a report.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the token is rejected, an API request fails or an analyzer
+    /// returns an error.
+    pub async fn collect_insights(&self, options: &CollectOptions) -> Result<CIInsights> {
         info!(
             "Starting insights collection for project: {}",
             self.project_path
         );
 
-        let pipelines = self.fetch_pipelines(limit, ref_).await?;
+        let started = Instant::now();
+        self.client.validate_token().await?;
 
-        if pipelines.is_empty() {
-            warn!("No pipelines found for project: {}", self.project_path);
-        }
+        let (pipelines, sampling) = self.collect_pipelines(options).await?;
+
+        let base_url = self.base_url();
+
+        self.export_pipelines(&pipelines, options, &base_url)
+            .await?;
 
-        // Extract base URL from graphql_url (e.g., https://gitlab.com/api/graphql -> https://gitlab.com)
-        let base_url = self.client.graphql_url.origin().ascii_serialization();
+        let job_logs = if options.analyze_logs {
+            Some(self.collect_job_logs(&pipelines).await?)
+        } else {
+            None
+        };
 
         let pipeline_types = super::pipeline_types::group_pipeline_types(
             &pipelines,
-            min_type_percentage,
+            &options.analysis,
+            job_logs.as_ref(),
             &base_url,
             &self.project_path,
         );
 
-        Ok(CIInsights {
+        let (deployments, reverts) = if options.include_deployments {
+            let (deployments, reverts) = self.collect_deployments(&pipelines, &base_url).await?;
+            (Some(deployments), Some(reverts))
+        } else {
+            (None, None)
+        };
+
+        let schedules = if options.include_schedules {
+            Some(self.collect_schedule_drift(&pipelines).await?)
+        } else {
+            None
+        };
+
+        let image_changes = if options.include_images {
+            Some(self.collect_image_changes(&pipelines).await?)
+        } else {
+            None
+        };
+
+        let ci_minutes = if options.include_ci_minutes {
+            Some(self.collect_ci_minutes(options.ci_minutes_quota).await?)
+        } else {
+            None
+        };
+
+        let branch_comparison = if options.compare_default_branch {
+            Some(
+                self.compare_default_branch(&pipelines, options.limit, options.statuses())
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        let (currently_broken, lead_time_to_green) = self
+            .collect_default_branch_health(&pipelines, options, &base_url)
+            .await?;
+
+        let fetch_duration = started.elapsed();
+        let api_usage = self.client.budget.usage();
+        let span = creation_span(&pipelines);
+
+        let mut insights = CIInsights {
             provider: "GitLab".to_string(),
             project: self.project_path.clone(),
             collected_at: Utc::now(),
+            first_pipeline_at: span.map(|(first, _)| first),
+            last_pipeline_at: span.map(|(_, last)| last),
+            watermark: collection_watermark(&pipelines),
+            meta: Some(report_meta(options, &api_usage, fetch_duration)),
+            summary: None,
             total_pipelines: pipelines.len(),
             total_pipeline_types: pipeline_types.len(),
             pipeline_types,
+            merge_trains: None,
+            deployments,
+            reverts,
+            branch_comparison,
+            teams: None,
+            components: None,
+            sampling,
+            estimated_job_durations: None,
+            developer_wait: None,
+            releases: None,
+            image_changes,
+            composition_changes: None,
+            users: None,
+            api_usage: Some(api_usage),
+            ci_minutes,
+            currently_broken,
+            lead_time_to_green,
+            schedules,
+            job_logs: None,
+            commits: None,
+            sections: BTreeMap::new(),
+        };
+        self.add_pipeline_sections(&mut insights, &pipelines, options, job_logs.as_ref())?;
+        insights.summary = Some(Summary::of(&insights));
+
+        Ok(insights)
+    }
+
+    /// Fills in the sections computed from the collected pipelines alone,
+    /// including those of the registered analyzers.
+    fn add_pipeline_sections(
+        &self,
+        insights: &mut CIInsights,
+        pipelines: &[GitLabPipeline],
+        options: &CollectOptions,
+        job_logs: Option<&HashMap<String, JobLog>>,
+    ) -> Result<()> {
+        let base_url = self.base_url();
+        insights.estimated_job_durations = Some(
+            pipelines
+                .iter()
+                .flat_map(|p| &p.jobs)
+                .filter(|job| job.estimated_duration.is_some())
+                .count(),
+        )
+        .filter(|&estimated| estimated > 0);
+        insights.composition_changes = Some(super::composition::calculate_composition_changes(
+            pipelines,
+            &base_url,
+            &self.project_path,
+        ))
+        .filter(|changes| !changes.is_empty());
+        insights.users = options
+            .by_user
+            .then(|| super::users::calculate_user_metrics(pipelines));
+        insights.job_logs =
+            job_logs.map(|logs| super::job_logs::calculate_job_log_metrics(pipelines, logs));
+        insights.commits = Some(super::commits::calculate_commit_retries(
+            pipelines,
+            &base_url,
+            &self.project_path,
+        ));
+
+        for (name, section) in super::analyzers::run(&self.analyzers, pipelines)? {
+            insights.merge_section(name, section)?;
+        }
+        Ok(())
+    }
+
+    /// Ranks the jobs of the pipelines `options` select, without clustering them
+    /// into pipeline types or collecting any optional sections.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the token is rejected or an API request fails.
+    pub async fn collect_job_ranking(&self, options: &CollectOptions) -> Result<JobRanking> {
+        info!("Starting job ranking for project: {}", self.project_path);
+
+        self.client.validate_token().await?;
+
+        let (pipelines, _) = self.collect_pipelines(options).await?;
+        let base_url = self.base_url();
+
+        Ok(JobRanking {
+            project: self.project_path.clone(),
+            collected_at: Utc::now(),
+            total_pipelines: pipelines.len(),
+            jobs: super::type_metrics::calculate_job_ranking(
+                &pipelines,
+                &options.analysis,
+                &base_url,
+                &self.project_path,
+            ),
         })
     }
+
+    /// Reports load and reliability per runner over the pipelines `options`
+    /// select, with queueing and concurrency per runner tag and shared runners
+    /// compared against self-hosted ones.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the token is rejected or an API request fails.
+    pub async fn collect_runner_report(
+        &self,
+        options: &CollectOptions,
+        queue_slo_seconds: u64,
+    ) -> Result<RunnerFleetReport> {
+        info!(
+            "Starting runner fleet report for project: {}",
+            self.project_path
+        );
+
+        self.client.validate_token().await?;
+
+        let (pipelines, _) = self.collect_pipelines(options).await?;
+        let runners = self
+            .client
+            .fetch_project_runners(&self.project_path)
+            .await?;
+        let metrics = super::runners::calculate_runner_metrics(&pipelines, &runners);
+
+        Ok(RunnerFleetReport {
+            project: self.project_path.clone(),
+            collected_at: Utc::now(),
+            total_pipelines: pipelines.len(),
+            total_jobs: pipelines
+                .iter()
+                .flat_map(|p| &p.jobs)
+                .filter(|j| j.runner_id.is_some())
+                .count(),
+            runners: metrics,
+            queue_slo_seconds,
+            queue_by_tag: super::runners::calculate_tag_queue_metrics(
+                &pipelines,
+                queue_slo_seconds,
+            ),
+            concurrency_by_tag: super::runners::calculate_tag_concurrency(&pipelines),
+            hosting: super::runners::calculate_hosting_comparison(&pipelines),
+        })
+    }
+
+    async fn compare_default_branch(
+        &self,
+        all_pipelines: &[GitLabPipeline],
+        limit: usize,
+        statuses: &[PipelineStatus],
+    ) -> Result<BranchComparison> {
+        let default_branch = self.client.fetch_default_branch(&self.project_path).await?;
+
+        info!("Fetching default branch ({default_branch}) pipelines for comparison...");
+
+        let default_pipelines = self
+            .fetch_pipelines(limit, Some(&default_branch), statuses)
+            .await?;
+
+        Ok(BranchComparison {
+            default_branch,
+            default_branch_health: calculate_branch_health(&default_pipelines),
+            all_branches_health: calculate_branch_health(all_pipelines),
+        })
+    }
+
+    /// Deployment metrics per environment, and the reverts on the default
+    /// branch over the same window.
+    async fn collect_deployments(
+        &self,
+        pipelines: &[GitLabPipeline],
+        base_url: &str,
+    ) -> Result<(Vec<EnvironmentDeploymentMetrics>, RevertMetrics)> {
+        // Cover the same window as the analyzed pipelines
+        let since = pipelines
+            .iter()
+            .map(|p| p.created_at)
+            .min()
+            .unwrap_or_else(|| Utc::now() - chrono::Duration::days(30));
+
+        info!("Fetching deployments since {since}...");
+
+        let deployments = self
+            .client
+            .fetch_deployments(&self.project_path, since)
+            .await?;
+
+        info!("Processed {} deployments", deployments.len());
+
+        let default_branch = self.client.fetch_default_branch(&self.project_path).await?;
+        let commits = self
+            .client
+            .fetch_commits(&self.project_path, &default_branch, since)
+            .await?;
+
+        Ok((
+            super::deployment_metrics::calculate_deployment_metrics(&deployments),
+            super::reverts::calculate_revert_metrics(
+                pipelines,
+                &commits,
+                &default_branch,
+                base_url,
+                &self.project_path,
+            ),
+        ))
+    }
+
+    /// Drift and missed runs of each active pipeline schedule over the
+    /// analyzed window.
+    async fn collect_schedule_drift(
+        &self,
+        pipelines: &[GitLabPipeline],
+    ) -> Result<Vec<ScheduleDrift>> {
+        let until = Utc::now();
+        let since = pipelines
+            .iter()
+            .map(|p| p.created_at)
+            .min()
+            .unwrap_or_else(|| until - chrono::Duration::days(30));
+
+        let schedules = self
+            .client
+            .fetch_pipeline_schedules(&self.project_path)
+            .await?;
+        info!(
+            "Comparing {} pipeline schedules with their runs since {since}...",
+            schedules.len()
+        );
+
+        let mut drift = Vec::new();
+        for schedule in schedules.iter().filter(|s| s.active) {
+            let schedule_pipelines = self
+                .client
+                .fetch_schedule_pipelines(&self.project_path, schedule.id, since)
+                .await?;
+            drift.extend(super::schedules::calculate_schedule_drift(
+                schedule,
+                &schedule_pipelines,
+                since,
+                until,
+            ));
+        }
+
+        Ok(drift)
+    }
+
+    /// Fetches this month's CI minutes of the project's namespace.
+    async fn collect_ci_minutes(&self, quota_override: Option<u64>) -> Result<CiMinutesUsage> {
+        let namespace_path = self
+            .project_path
+            .rsplit_once('/')
+            .map_or(self.project_path.as_str(), |(namespace, _)| namespace);
+        let namespace = self.client.fetch_namespace(namespace_path).await?;
+
+        let now = Utc::now();
+        let used_minutes = self
+            .client
+            .fetch_ci_minutes_used(namespace.id, month_start(now.date_naive()))
+            .await?;
+        let quota_minutes = quota_override.or_else(|| {
+            namespace
+                .shared_runners_minutes_limit
+                .map(|limit| limit + namespace.extra_shared_runners_minutes_limit.unwrap_or(0))
+        });
+
+        let usage =
+            calculate_ci_minutes_usage(namespace.full_path, used_minutes, quota_minutes, now);
+        info!(
+            "Namespace {} used {} CI minutes this month (projected {})",
+            usage.namespace, usage.used_minutes, usage.projected_minutes
+        );
+        Ok(usage)
+    }
+
+    /// Reads the CI config at every analyzed commit to learn each job's image.
+    /// Sends collected pipelines to the OTLP collector and `CloudEvents` sink
+    /// `options` name, if any.
+    async fn export_pipelines(
+        &self,
+        pipelines: &[GitLabPipeline],
+        options: &CollectOptions,
+        base_url: &str,
+    ) -> Result<()> {
+        if let Some(endpoint) = &options.otlp_endpoint {
+            super::otlp::export_traces(endpoint, pipelines, base_url, &self.project_path).await?;
+        }
+
+        if let Some(sink) = &options.cloudevents_sink {
+            let events =
+                super::cloudevents::pipeline_events(pipelines, base_url, &self.project_path);
+            crate::cloudevents::emit(sink, &events).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Jobs failing in at least `--broken-streak` consecutive pipelines of the
+    /// default branch, and its lead time to green (`--lead-time`), as
+    /// `options` ask for them.
+    async fn collect_default_branch_health(
+        &self,
+        pipelines: &[GitLabPipeline],
+        options: &CollectOptions,
+        base_url: &str,
+    ) -> Result<(Option<Vec<BrokenJob>>, Option<LeadTimeToGreen>)> {
+        if options.broken_streak.is_none() && !options.lead_time {
+            return Ok((None, None));
+        }
+
+        let default_branch = self.client.fetch_default_branch(&self.project_path).await?;
+        let broken_jobs = options.broken_streak.map(|min_streak| {
+            super::failure_streaks::broken_jobs(
+                pipelines,
+                &default_branch,
+                min_streak,
+                base_url,
+                &self.project_path,
+            )
+        });
+        let lead_time = options
+            .lead_time
+            .then(|| super::lead_time::calculate_lead_time_to_green(pipelines, &default_branch));
+
+        Ok((broken_jobs, lead_time))
+    }
+
+    /// Downloads and parses the logs of the latest attempts of each job, keyed
+    /// by job ID. Jobs without a log (erased or expired) are left out.
+    async fn collect_job_logs(
+        &self,
+        pipelines: &[GitLabPipeline],
+    ) -> Result<HashMap<String, JobLog>> {
+        const LOG_FETCH_CONCURRENCY: usize = 10;
+
+        let jobs = super::job_logs::jobs_to_analyze(pipelines);
+        info!(
+            "Fetching the logs of {} jobs (up to {} per job name)...",
+            jobs.len(),
+            super::job_logs::MAX_LOGS_PER_JOB
+        );
+
+        stream::iter(jobs)
+            .map(|job| async move {
+                let log = self
+                    .client
+                    .fetch_job_log(&self.project_path, extract_numeric_id(&job.id))
+                    .await?;
+                Result::Ok(log.map(|log| (job.id.clone(), JobLog::parse(&log))))
+            })
+            .buffer_unordered(LOG_FETCH_CONCURRENCY)
+            .try_filter_map(|parsed| future::ready(Ok(parsed)))
+            .try_collect()
+            .await
+    }
+
+    async fn collect_image_changes(
+        &self,
+        pipelines: &[GitLabPipeline],
+    ) -> Result<Vec<ImageChange>> {
+        let config_path = self.client.fetch_ci_config_path(&self.project_path).await?;
+        // External configs (`path@group/project`, URLs) live outside this repository
+        if config_path.contains('@') || config_path.contains("://") {
+            warn!(
+                "CI config '{config_path}' is not stored in the project; skipping image analysis"
+            );
+            return Ok(vec![]);
+        }
+
+        let shas: BTreeSet<&str> = pipelines
+            .iter()
+            .map(|p| p.sha.as_str())
+            .filter(|sha| !sha.is_empty())
+            .collect();
+        info!("Fetching {config_path} at {} commits...", shas.len());
+
+        let configs = futures::future::join_all(shas.iter().map(|sha| {
+            self.client
+                .fetch_file(&self.project_path, &config_path, sha)
+        }))
+        .await;
+
+        let mut images = ImagesBySha::new();
+        for (sha, content) in shas.into_iter().zip(configs) {
+            let Some(content) = content? else { continue };
+            match lint_ci::job_images(&content) {
+                Ok(job_images) => {
+                    images.insert(sha.to_string(), job_images);
+                }
+                Err(e) => warn!("Skipping CI config at {sha}: {e}"),
+            }
+        }
+
+        Ok(super::images::calculate_image_changes(pipelines, &images))
+    }
+
+    /// Compares the merge request's source branch against its target branch and
+    /// posts (or updates) the result as a merge request comment.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the merge request is not found or an API request fails.
+    pub async fn comment_on_merge_request(
+        &self,
+        iid: u64,
+        options: &CollectOptions,
+        style: Style,
+    ) -> Result<()> {
+        let merge_request = self
+            .client
+            .fetch_merge_request(&self.project_path, iid)
+            .await?;
+
+        info!(
+            "Comparing {} against {} for merge request !{iid}",
+            merge_request.source_branch, merge_request.target_branch
+        );
+
+        let head_options = CollectOptions {
+            ref_: Some(merge_request.source_branch.clone()),
+            default_branch_only: false,
+            compare_default_branch: false,
+            ..options.clone()
+        };
+        let base_options = CollectOptions {
+            ref_: Some(merge_request.target_branch.clone()),
+            default_branch_only: false,
+            compare_default_branch: false,
+            ..options.clone()
+        };
+
+        let (head, base) = tokio::try_join!(
+            self.collect_insights(&head_options),
+            self.collect_insights(&base_options),
+        )?;
+
+        let body = markdown::render_comparison(
+            &merge_request.source_branch,
+            &merge_request.target_branch,
+            &head,
+            &base,
+            style,
+        );
+
+        self.client
+            .upsert_merge_request_note(&self.project_path, iid, markdown::COMMENT_MARKER, &body)
+            .await?;
+
+        info!("Posted CI performance comment on merge request !{iid}");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipelines_page_json(ids: &[u64], end_cursor: Option<&str>) -> String {
+        let nodes: Vec<String> = ids
+            .iter()
+            .map(|id| {
+                format!(
+                    r#"{{"id": "gid://gitlab/Ci::Pipeline/{id}", "ref": "main", "refPath": null,
+                        "sha": null, "source": "push", "status": "SUCCESS", "duration": 60,
+                        "createdAt": "2025-01-01T10:{id:02}:00Z", "startedAt": null,
+                        "finishedAt": null, "user": null, "stages": null}}"#
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"data": {{"project": {{"pipelines": {{
+                "pageInfo": {{"hasNextPage": {}, "endCursor": {}}},
+                "nodes": [{}]
+            }}}}}}}}"#,
+            end_cursor.is_some(),
+            end_cursor.map_or("null".to_string(), |cursor| format!("\"{cursor}\"")),
+            nodes.join(", ")
+        )
+    }
+
+    #[tokio::test]
+    async fn test_stream_pipelines_yields_each_page_with_its_jobs() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/graphql")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(r#""operationName":"FetchPipelines""#.to_string()),
+                mockito::Matcher::Regex(r#""after":null"#.to_string()),
+            ]))
+            .with_body(pipelines_page_json(&[3, 2], Some("abc")))
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/api/graphql")
+            .match_body(mockito::Matcher::Regex(r#""after":"abc""#.to_string()))
+            .with_body(pipelines_page_json(&[1], None))
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/api/graphql")
+            .match_body(mockito::Matcher::Regex(
+                r#""operationName":"FetchPipelineJobs""#.to_string(),
+            ))
+            .with_body(
+                r#"{"data": {"project": {"pipeline": {"jobs": {
+                    "pageInfo": {"hasNextPage": false, "endCursor": null},
+                    "nodes": []
+                }}}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = GitLabProvider::new(&server.url(), "group/project".to_string(), None)
+            .unwrap()
+            .with_api(Api::Graphql);
+        let pipelines: Vec<GitLabPipeline> = provider
+            .stream_pipelines(10, None)
+            .try_collect()
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = pipelines.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "gid://gitlab/Ci::Pipeline/3",
+                "gid://gitlab/Ci::Pipeline/2",
+                "gid://gitlab/Ci::Pipeline/1"
+            ]
+        );
+    }
 }
@@ -0,0 +1,465 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::job_analysis::{
+    critical_path_without, dependencies, final_attempts, finish_times, stage_index,
+};
+use super::pipeline_types::AnalysisOptions;
+use super::types::GitLabPipeline;
+use crate::insights::{Recommendation, RecommendationKind};
+
+/// Savings below this are within duration noise and not worth a config change.
+const MIN_SAVINGS_SECONDS: f64 = 30.0;
+
+#[derive(Default)]
+struct BarrierStats<'a> {
+    declares_needs: bool,
+    jobs: BTreeSet<&'a str>,
+    savings: Vec<f64>,
+    stragglers: HashMap<&'a str, usize>,
+}
+
+/// Stages in which no job declares `needs`. Their jobs all wait for the last
+/// job of the earlier stages; with `needs`, the ones not depending on that
+/// straggler would start when the rest finished, so the estimate is the gap
+/// between the last two earlier jobs to finish.
+fn stage_barriers(pipelines: &[&GitLabPipeline]) -> Vec<Recommendation> {
+    let mut stats: BTreeMap<&str, BarrierStats> = BTreeMap::new();
+
+    for pipeline in pipelines {
+        let jobs = final_attempts(pipeline);
+        let stage_index = stage_index(pipeline);
+        let finish_times = finish_times(pipeline);
+
+        for (index, stage) in pipeline.stages.iter().enumerate().skip(1) {
+            let stage_jobs: Vec<_> = jobs.values().filter(|j| j.stage == *stage).collect();
+            if stage_jobs.is_empty() {
+                continue;
+            }
+
+            let entry = stats.entry(stage.as_str()).or_default();
+            entry.declares_needs |= stage_jobs.iter().any(|j| j.needs.is_some());
+            entry
+                .jobs
+                .extend(stage_jobs.iter().map(|j| j.name.as_str()));
+
+            let mut earlier: Vec<(&str, f64)> = jobs
+                .iter()
+                .filter(|(_, j)| stage_index.get(j.stage.as_str()).copied().unwrap_or(0) < index)
+                .map(|(&name, _)| (name, finish_times.get(name).copied().unwrap_or(0.0)))
+                .collect();
+            earlier.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            let savings = match earlier.as_slice() {
+                [(straggler, last), (_, next), ..] => {
+                    *entry.stragglers.entry(straggler).or_default() += 1;
+                    last - next
+                }
+                _ => 0.0,
+            };
+            entry.savings.push(savings);
+        }
+    }
+
+    stats
+        .into_iter()
+        .filter(|(_, stats)| !stats.declares_needs)
+        .filter_map(|(stage, stats)| {
+            #[allow(clippy::cast_precision_loss)]
+            let avg_savings = stats.savings.iter().sum::<f64>() / stats.savings.len() as f64;
+            if avg_savings < MIN_SAVINGS_SECONDS {
+                return None;
+            }
+
+            let straggler = stats
+                .stragglers
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(&name, _)| name)?;
+
+            Some(Recommendation {
+                kind: RecommendationKind::StageBarrier,
+                stages: vec![stage.to_string()],
+                jobs: stats.jobs.iter().map(ToString::to_string).collect(),
+                straggler: Some(straggler.to_string()),
+                executions: None,
+                estimated_savings_seconds: avg_savings,
+                retry: None,
+                attempt_success_rate: None,
+                success_rate_with_retry: None,
+                target_success_rate: None,
+                message: String::new(),
+            })
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct CollapseStats<'a> {
+    interdependent: bool,
+    declares_needs: bool,
+    jobs: BTreeSet<&'a str>,
+    savings: Vec<f64>,
+}
+
+/// Adjacent stages where the jobs of the later stage that declare `needs` never
+/// name a job of the earlier one, while the rest wait for it through stage
+/// order. Stages without any `needs` give no evidence either way and are left
+/// to [`stage_barriers`]. The estimate is how much sooner the later stage would
+/// finish if its jobs without `needs` only waited for the stages before both.
+fn stage_collapses(pipelines: &[&GitLabPipeline]) -> Vec<Recommendation> {
+    let mut stats: BTreeMap<(&str, &str), CollapseStats> = BTreeMap::new();
+
+    for pipeline in pipelines {
+        let jobs = final_attempts(pipeline);
+        let dependencies = dependencies(pipeline);
+        let finish_times = finish_times(pipeline);
+        let finish = |name: &str| finish_times.get(name).copied().unwrap_or(0.0);
+        let stages: Vec<&str> = pipeline
+            .stages
+            .iter()
+            .map(String::as_str)
+            .filter(|stage| jobs.values().any(|j| j.stage == *stage))
+            .collect();
+
+        for (index, pair) in stages.windows(2).enumerate() {
+            let (earlier, later) = (pair[0], pair[1]);
+            let later_jobs: Vec<_> = jobs.values().filter(|j| j.stage == later).collect();
+            let entry = stats.entry((earlier, later)).or_default();
+
+            entry.declares_needs |= later_jobs.iter().any(|j| j.needs.is_some());
+            entry.interdependent |= later_jobs
+                .iter()
+                .filter(|j| j.needs.is_some())
+                .flat_map(|j| &dependencies[j.name.as_str()])
+                .any(|dep| jobs[dep].stage == earlier);
+            entry.jobs.extend(
+                later_jobs
+                    .iter()
+                    .filter(|j| j.needs.is_none())
+                    .map(|j| j.name.as_str()),
+            );
+
+            let before = jobs
+                .values()
+                .filter(|j| stages[..index].contains(&j.stage.as_str()))
+                .map(|j| finish(&j.name))
+                .fold(0.0, f64::max);
+            let current = later_jobs
+                .iter()
+                .map(|j| finish(&j.name))
+                .fold(0.0, f64::max);
+            let collapsed = later_jobs
+                .iter()
+                .map(|j| {
+                    if j.needs.is_none() {
+                        before + j.path_duration()
+                    } else {
+                        finish(&j.name)
+                    }
+                })
+                .fold(0.0, f64::max);
+            entry.savings.push(current - collapsed);
+        }
+    }
+
+    stats
+        .into_iter()
+        .filter(|(_, stats)| {
+            stats.declares_needs && !stats.interdependent && !stats.jobs.is_empty()
+        })
+        .filter_map(|((earlier, later), stats)| {
+            #[allow(clippy::cast_precision_loss)]
+            let avg_savings = stats.savings.iter().sum::<f64>() / stats.savings.len() as f64;
+            (avg_savings >= MIN_SAVINGS_SECONDS).then(|| Recommendation {
+                kind: RecommendationKind::StageCollapse,
+                stages: vec![earlier.to_string(), later.to_string()],
+                jobs: stats.jobs.iter().map(ToString::to_string).collect(),
+                straggler: None,
+                executions: None,
+                estimated_savings_seconds: avg_savings,
+                retry: None,
+                attempt_success_rate: None,
+                success_rate_with_retry: None,
+                target_success_rate: None,
+                message: String::new(),
+            })
+        })
+        .collect()
+}
+
+/// Jobs that passed every one of at least `min_executions` executions, yet
+/// lengthen the critical path of successful pipelines. The estimate is how much
+/// sooner those pipelines would have finished without the job.
+fn always_green_jobs(
+    pipelines: &[&GitLabPipeline],
+    successful: &[&GitLabPipeline],
+    min_executions: usize,
+) -> Vec<Recommendation> {
+    if min_executions == 0 || successful.is_empty() {
+        return vec![];
+    }
+
+    let mut executions: BTreeMap<&str, (usize, bool)> = BTreeMap::new();
+    for job in pipelines.iter().flat_map(|p| &p.jobs) {
+        if job.status == "SUCCESS" || job.status == "FAILED" {
+            let entry = executions.entry(job.name.as_str()).or_default();
+            entry.0 += 1;
+            entry.1 |= job.status == "FAILED";
+        }
+    }
+
+    executions
+        .into_iter()
+        .filter(|&(_, (count, failed))| count >= min_executions && !failed)
+        .filter_map(|(name, (count, _))| {
+            let savings: f64 = successful
+                .iter()
+                .map(|pipeline| {
+                    let critical_path = finish_times(pipeline).into_values().fold(0.0, f64::max);
+                    critical_path - critical_path_without(pipeline, name)
+                })
+                .sum();
+            #[allow(clippy::cast_precision_loss)]
+            let avg_savings = savings / successful.len() as f64;
+
+            (avg_savings >= MIN_SAVINGS_SECONDS).then(|| Recommendation {
+                kind: RecommendationKind::AlwaysGreen,
+                stages: vec![],
+                jobs: vec![name.to_string()],
+                straggler: None,
+                executions: Some(count),
+                estimated_savings_seconds: avg_savings,
+                retry: None,
+                attempt_success_rate: None,
+                success_rate_with_retry: None,
+                target_success_rate: None,
+                message: String::new(),
+            })
+        })
+        .collect()
+}
+
+/// GitLab accepts `retry` values up to 2.
+const MAX_RETRY: u8 = 2;
+
+/// Chance a job with per-attempt success rate `attempt_rate` eventually passes
+/// when retried up to `retry` times, assuming independent attempts.
+fn success_with_retry(attempt_rate: f64, retry: u8) -> f64 {
+    1.0 - (1.0 - attempt_rate).powi(i32::from(retry) + 1)
+}
+
+/// Flaky jobs (retried, then passed) that pass less than `target_rate` percent
+/// of their runs, with the smallest `retry` that lets them pass at least that
+/// share. The per-attempt success rate counts every attempt of the job;
+/// the estimate is the pipeline time developers would no longer spend rerunning
+/// failed pipelines, per pipeline.
+fn retry_policies(
+    pipelines: &[&GitLabPipeline],
+    successful: &[&GitLabPipeline],
+    target_rate: f64,
+) -> Vec<Recommendation> {
+    if target_rate <= 0.0 {
+        return vec![];
+    }
+    let target = target_rate / 100.0;
+
+    // (passed attempts, failed attempts, seen flaky)
+    let mut attempts: BTreeMap<&str, (usize, usize, bool)> = BTreeMap::new();
+    for pipeline in pipelines {
+        for job in &pipeline.jobs {
+            let entry = attempts.entry(job.name.as_str()).or_default();
+            match job.status.as_str() {
+                "SUCCESS" => entry.0 += 1,
+                "FAILED" => entry.1 += 1,
+                _ => {}
+            }
+        }
+        for (name, job) in final_attempts(pipeline) {
+            let retried = pipeline.jobs.iter().any(|j| j.retried && j.name == name);
+            if retried && job.status == "SUCCESS" {
+                attempts.entry(name).or_default().2 = true;
+            }
+        }
+    }
+
+    let durations = if successful.is_empty() {
+        pipelines
+    } else {
+        successful
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let avg_duration =
+        durations.iter().map(|p| p.duration as f64).sum::<f64>() / durations.len().max(1) as f64;
+
+    attempts
+        .into_iter()
+        .filter(|&(_, (passed, failed, flaky))| flaky && passed + failed > 0)
+        .filter_map(|(name, (passed, failed, _))| {
+            #[allow(clippy::cast_precision_loss)]
+            let attempt_rate = passed as f64 / (passed + failed) as f64;
+            let without_retry = success_with_retry(attempt_rate, 0);
+            if without_retry >= target {
+                return None;
+            }
+
+            let (retry, rate) = (1..=MAX_RETRY)
+                .map(|retry| (retry, success_with_retry(attempt_rate, retry)))
+                .find(|&(_, rate)| rate >= target)
+                .unwrap_or((MAX_RETRY, success_with_retry(attempt_rate, MAX_RETRY)));
+
+            Some(Recommendation {
+                kind: RecommendationKind::Retry,
+                stages: vec![],
+                jobs: vec![name.to_string()],
+                straggler: None,
+                executions: None,
+                estimated_savings_seconds: (rate - without_retry) * avg_duration,
+                retry: Some(retry),
+                attempt_success_rate: Some(attempt_rate * 100.0),
+                success_rate_with_retry: Some(rate * 100.0),
+                target_success_rate: Some(target_rate),
+                message: String::new(),
+            })
+        })
+        .collect()
+}
+
+/// Configuration changes that would shorten the pipelines of one type, largest
+/// savings first. Timings come from the successful pipelines.
+pub fn recommend(pipelines: &[&GitLabPipeline], options: &AnalysisOptions) -> Vec<Recommendation> {
+    let successful: Vec<&GitLabPipeline> = pipelines
+        .iter()
+        .filter(|p| p.status == "success")
+        .copied()
+        .collect();
+
+    let mut recommendations = stage_barriers(&successful);
+    recommendations.extend(stage_collapses(&successful));
+    recommendations.extend(always_green_jobs(
+        pipelines,
+        &successful,
+        options.always_green_executions,
+    ));
+    recommendations.extend(retry_policies(
+        pipelines,
+        &successful,
+        options.target_success_rate,
+    ));
+    recommendations.sort_by(|a, b| {
+        b.estimated_savings_seconds
+            .total_cmp(&a.estimated_savings_seconds)
+    });
+    for recommendation in &mut recommendations {
+        recommendation.message = recommendation.describe();
+    }
+    recommendations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline};
+
+    #[test]
+    fn test_stage_barriers_estimate_wait_on_the_straggler() {
+        let mut deploy = job(5, "deploy", "deploy", 20.0, "SUCCESS");
+        deploy.needs = Some(vec!["unit".to_string()]);
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        pipeline.stages = vec!["build".into(), "test".into(), "deploy".into()];
+        pipeline.jobs = vec![
+            job(1, "build-a", "build", 60.0, "SUCCESS"),
+            job(2, "build-b", "build", 300.0, "SUCCESS"),
+            job(3, "unit", "test", 30.0, "SUCCESS"),
+            job(4, "lint", "test", 10.0, "SUCCESS"),
+            deploy,
+        ];
+
+        let recommendations = recommend(&[&pipeline], &AnalysisOptions::default());
+
+        assert_eq!(recommendations.len(), 1);
+        assert_eq!(recommendations[0].kind, RecommendationKind::StageBarrier);
+        assert_eq!(recommendations[0].stages, vec!["test"]);
+        assert_eq!(recommendations[0].jobs, vec!["lint", "unit"]);
+        assert!((recommendations[0].estimated_savings_seconds - 240.0).abs() < f64::EPSILON);
+        assert!(recommendations[0].message.contains("`build-b`"));
+    }
+
+    #[test]
+    fn test_stage_collapses_skip_stages_with_declared_needs() {
+        let mut unit = job(2, "unit", "test", 60.0, "SUCCESS");
+        unit.needs = Some(vec!["compile".to_string()]);
+        let mut api_docs = job(4, "api-docs", "docs", 10.0, "SUCCESS");
+        api_docs.needs = Some(vec!["compile".to_string()]);
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        pipeline.stages = vec!["build".into(), "test".into(), "docs".into()];
+        pipeline.jobs = vec![
+            job(1, "compile", "build", 300.0, "SUCCESS"),
+            unit,
+            job(3, "pages", "docs", 20.0, "SUCCESS"),
+            api_docs,
+        ];
+
+        let collapses = stage_collapses(&[&pipeline]);
+
+        assert_eq!(collapses.len(), 1);
+        assert_eq!(collapses[0].stages, vec!["test", "docs"]);
+        assert_eq!(collapses[0].jobs, vec!["pages"]);
+        assert!((collapses[0].estimated_savings_seconds - 60.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_always_green_jobs_on_the_critical_path() {
+        let pipelines: Vec<GitLabPipeline> = (0..3)
+            .map(|i| {
+                let mut e2e = job(i * 3 + 3, "e2e", "test", 600.0, "SUCCESS");
+                e2e.needs = Some(vec!["build".to_string()]);
+                let mut pipeline =
+                    pipeline(i, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:20:00Z");
+                pipeline.stages = vec!["build".into(), "test".into()];
+                pipeline.jobs = vec![
+                    job(i * 3 + 1, "build", "build", 120.0, "SUCCESS"),
+                    job(
+                        i * 3 + 2,
+                        "unit",
+                        "test",
+                        60.0,
+                        if i == 0 { "FAILED" } else { "SUCCESS" },
+                    ),
+                    e2e,
+                ];
+                pipeline
+            })
+            .collect();
+        let pipelines: Vec<&GitLabPipeline> = pipelines.iter().collect();
+
+        let recommendations = always_green_jobs(&pipelines, &pipelines, 3);
+
+        let jobs: Vec<&str> = recommendations.iter().map(|r| r.jobs[0].as_str()).collect();
+        assert_eq!(jobs, vec!["build", "e2e"]);
+        assert!((recommendations[1].estimated_savings_seconds - 540.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_retry_policies_pick_the_smallest_sufficient_retry() {
+        // 4 of 5 attempts pass: 80% without retry, 96% with one, 99.2% with two
+        let mut first = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        let mut flaky = job(1, "e2e", "test", 60.0, "FAILED");
+        flaky.retried = true;
+        first.jobs = vec![flaky, job(2, "e2e", "test", 60.0, "SUCCESS")];
+        let mut second = pipeline(2, "success", "2025-01-01T11:00:00Z", "2025-01-01T11:10:00Z");
+        second.jobs = vec![job(3, "e2e", "test", 60.0, "SUCCESS")];
+        let mut third = pipeline(3, "success", "2025-01-01T12:00:00Z", "2025-01-01T12:10:00Z");
+        third.jobs = vec![job(4, "e2e", "test", 60.0, "SUCCESS")];
+        let mut fourth = pipeline(4, "success", "2025-01-01T13:00:00Z", "2025-01-01T13:10:00Z");
+        fourth.jobs = vec![job(5, "e2e", "test", 60.0, "SUCCESS")];
+        let pipelines = [&first, &second, &third, &fourth];
+
+        let at_95 = retry_policies(&pipelines, &pipelines, 95.0);
+        let at_99 = retry_policies(&pipelines, &pipelines, 99.0);
+
+        assert_eq!(at_95[0].retry, Some(1));
+        assert!((at_95[0].estimated_savings_seconds - 0.16 * 600.0).abs() < 1e-9);
+        assert_eq!(at_99[0].retry, Some(2));
+        assert!(retry_policies(&pipelines, &pipelines, 80.0).is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use super::branch_health::calculate_branch_health;
+use super::types::GitLabPipeline;
+use crate::insights::ReleaseMetrics;
+
+const TAG_PREFIX: &str = "refs/tags/";
+
+/// Release pipeline health over tag-triggered pipelines.
+///
+/// GitLab does not expose tag creation times through GraphQL, so the first
+/// pipeline created for a tag stands in for the tag push: a tag pipeline is
+/// created the moment the tag is pushed.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_release_metrics(pipelines: &[GitLabPipeline]) -> Option<ReleaseMetrics> {
+    let mut by_tag: BTreeMap<&str, Vec<&GitLabPipeline>> = BTreeMap::new();
+    for pipeline in pipelines {
+        if let Some(tag) = pipeline.ref_path.strip_prefix(TAG_PREFIX) {
+            by_tag.entry(tag).or_default().push(pipeline);
+        }
+    }
+
+    if by_tag.is_empty() {
+        return None;
+    }
+
+    let mut times_to_release = Vec::new();
+    let mut unreleased_tags = Vec::new();
+
+    for (tag, pipelines) in &by_tag {
+        let tagged_at = pipelines.iter().map(|p| p.created_at).min();
+        let released_at = pipelines
+            .iter()
+            .filter(|p| p.status == "success")
+            .filter_map(|p| p.finished_at)
+            .min();
+
+        match (tagged_at, released_at) {
+            (Some(tagged_at), Some(released_at)) => {
+                times_to_release.push((released_at - tagged_at).num_seconds() as f64);
+            }
+            _ => unreleased_tags.push((*tag).to_string()),
+        }
+    }
+
+    let health = calculate_branch_health(by_tag.values().flatten().copied());
+    let avg_time_to_release_seconds = (!times_to_release.is_empty())
+        .then(|| times_to_release.iter().sum::<f64>() / times_to_release.len() as f64);
+
+    Some(ReleaseMetrics {
+        total_tags: by_tag.len(),
+        total_pipelines: health.total_pipelines,
+        success_rate: health.success_rate,
+        avg_duration_seconds: health.avg_duration_seconds,
+        avg_time_to_release_seconds,
+        unreleased_tags,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+
+    fn tag_pipeline(
+        id: u64,
+        tag: &str,
+        status: &str,
+        created_at: &str,
+        finished_at: &str,
+    ) -> GitLabPipeline {
+        let mut pipeline = pipeline(id, status, created_at, finished_at);
+        pipeline.ref_ = tag.to_string();
+        pipeline.ref_path = format!("{TAG_PREFIX}{tag}");
+        pipeline
+    }
+
+    #[test]
+    fn test_release_metrics_measure_time_from_tag_to_first_successful_pipeline() {
+        let pipelines = vec![
+            pipeline(1, "success", "2025-01-01T09:00:00Z", "2025-01-01T09:10:00Z"),
+            // v1.0 fails first, then a retry pipeline succeeds
+            tag_pipeline(
+                2,
+                "v1.0",
+                "failed",
+                "2025-01-01T10:00:00Z",
+                "2025-01-01T10:05:00Z",
+            ),
+            tag_pipeline(
+                3,
+                "v1.0",
+                "success",
+                "2025-01-01T10:20:00Z",
+                "2025-01-01T10:30:00Z",
+            ),
+            tag_pipeline(
+                4,
+                "v1.1",
+                "success",
+                "2025-01-02T10:00:00Z",
+                "2025-01-02T10:10:00Z",
+            ),
+            tag_pipeline(
+                5,
+                "v1.2",
+                "failed",
+                "2025-01-03T10:00:00Z",
+                "2025-01-03T10:02:00Z",
+            ),
+        ];
+
+        let metrics = calculate_release_metrics(&pipelines).unwrap();
+
+        assert_eq!(metrics.total_tags, 3);
+        assert_eq!(metrics.total_pipelines, 4);
+        assert!((metrics.success_rate - 50.0).abs() < f64::EPSILON);
+        // (30min + 10min) / 2
+        assert!((metrics.avg_time_to_release_seconds.unwrap() - 1200.0).abs() < f64::EPSILON);
+        assert_eq!(metrics.unreleased_tags, vec!["v1.2"]);
+    }
+
+    #[test]
+    fn test_release_metrics_absent_without_tag_pipelines() {
+        let pipelines = vec![pipeline(
+            1,
+            "success",
+            "2025-01-01T09:00:00Z",
+            "2025-01-01T09:10:00Z",
+        )];
+
+        assert!(calculate_release_metrics(&pipelines).is_none());
+    }
+}
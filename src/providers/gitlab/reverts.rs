@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use super::client::commits::Commit;
+use super::stats::rate;
+use super::types::GitLabPipeline;
+use super::url_utils::pipeline_id_to_url;
+use crate::insights::{RevertCommit, RevertMetrics};
+
+/// Trailer `git revert` adds to the message of the commits it creates.
+const REVERT_TRAILER: &str = "This reverts commit ";
+
+/// Whether `commit` backs out an earlier one: `git revert`'s trailer, or a
+/// title starting with "Revert" as GitLab's revert button and most manual
+/// reverts word it.
+fn is_revert(commit: &Commit) -> bool {
+    commit.message.contains(REVERT_TRAILER)
+        || commit
+            .title
+            .get(..6)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("revert"))
+}
+
+fn reverted_sha(commit: &Commit) -> Option<String> {
+    let (_, rest) = commit.message.split_once(REVERT_TRAILER)?;
+    let sha: String = rest.chars().take_while(char::is_ascii_hexdigit).collect();
+    (!sha.is_empty()).then_some(sha)
+}
+
+/// Reverts among the `commits` of `default_branch`, and whether the first
+/// pipeline of each followed a failed pipeline on the branch - a change
+/// backed out because it broke the build. Newest first, like `commits`.
+pub fn calculate_revert_metrics(
+    pipelines: &[GitLabPipeline],
+    commits: &[Commit],
+    default_branch: &str,
+    base_url: &str,
+    project_path: &str,
+) -> RevertMetrics {
+    let mut branch_pipelines: Vec<&GitLabPipeline> = pipelines
+        .iter()
+        .filter(|p| p.ref_ == default_branch && p.merge_request_event_type.is_none())
+        .collect();
+    branch_pipelines.sort_by_key(|p| p.created_at);
+    let mut first_pipeline: HashMap<&str, usize> = HashMap::new();
+    for (index, pipeline) in branch_pipelines.iter().enumerate() {
+        first_pipeline.entry(&pipeline.sha).or_insert(index);
+    }
+
+    let reverts: Vec<RevertCommit> = commits
+        .iter()
+        .filter(|commit| is_revert(commit))
+        .map(|commit| {
+            let index = first_pipeline.get(commit.id.as_str()).copied();
+            RevertCommit {
+                sha: commit.id.clone(),
+                title: commit.title.clone(),
+                reverted_sha: reverted_sha(commit),
+                after_failed_pipeline: index
+                    .and_then(|index| index.checked_sub(1))
+                    .is_some_and(|previous| branch_pipelines[previous].status == "failed"),
+                pipeline: index.map(|index| {
+                    pipeline_id_to_url(base_url, project_path, &branch_pipelines[index].id)
+                }),
+            }
+        })
+        .collect();
+
+    RevertMetrics {
+        default_branch: default_branch.to_string(),
+        total_commits: commits.len(),
+        total_reverts: reverts.len(),
+        revert_rate: rate(reverts.len(), commits.len()),
+        reverts_after_failure: reverts.iter().filter(|r| r.after_failed_pipeline).count(),
+        reverts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+
+    fn commit(sha: &str, title: &str, message: &str) -> Commit {
+        Commit {
+            id: sha.to_string(),
+            title: title.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_revert_metrics_flag_reverts_following_failures() {
+        let run = |id: u64, sha: &str, status: &str, hour: u32| {
+            let mut pipeline = pipeline(
+                id,
+                status,
+                &format!("2025-01-01T{hour:02}:00:00Z"),
+                &format!("2025-01-01T{hour:02}:10:00Z"),
+            );
+            pipeline.sha = sha.to_string();
+            pipeline
+        };
+        let pipelines = vec![
+            run(1, "a1", "success", 1),
+            run(2, "b2", "failed", 2),
+            run(3, "c3", "success", 3),
+            run(4, "d4", "success", 4),
+        ];
+        let commits = vec![
+            commit("d4", "revert: drop flaky cache", "revert: drop flaky cache"),
+            commit(
+                "c3",
+                "Revert \"Add feature\"",
+                "Revert \"Add feature\"\n\nThis reverts commit b2b2b2.\n",
+            ),
+            commit("b2", "Add feature", "Add feature"),
+            commit("a1", "Reverse the list order", "Reverse the list order"),
+        ];
+
+        let reverts = calculate_revert_metrics(&pipelines, &commits, "main", "", "");
+
+        assert_eq!(reverts.total_reverts, 2);
+        assert!((reverts.revert_rate - 50.0).abs() < f64::EPSILON);
+        assert_eq!(reverts.reverts_after_failure, 1);
+        assert_eq!(reverts.reverts[1].reverted_sha.as_deref(), Some("b2b2b2"));
+        assert!(reverts.reverts[1].after_failed_pipeline);
+        assert!(!reverts.reverts[0].after_failed_pipeline);
+    }
+}
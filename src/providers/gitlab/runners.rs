@@ -0,0 +1,429 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+
+use super::client::runners::Runner;
+use super::stats::{percentage, percentile};
+use super::types::{GitLabJob, GitLabPipeline};
+use crate::insights::{
+    HostingComparison, HostingMetrics, JobHostingComparison, RunnerMetrics, TagConcurrency,
+    TagQueueMetrics,
+};
+
+const FAILED: &str = "FAILED";
+
+/// Per-runner load over every job attempt (retries included) of `pipelines`.
+///
+/// Runners available to the project that ran nothing are reported too, so
+/// idle capacity shows up; runners that ran jobs but are no longer available
+/// are reported without status.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_runner_metrics(
+    pipelines: &[GitLabPipeline],
+    runners: &[Runner],
+) -> Vec<RunnerMetrics> {
+    let jobs: Vec<&GitLabJob> = pipelines
+        .iter()
+        .flat_map(|p| &p.jobs)
+        .filter(|j| j.runner_id.is_some())
+        .collect();
+
+    let mut by_runner: BTreeMap<u64, Vec<&GitLabJob>> =
+        runners.iter().map(|r| (r.id, Vec::new())).collect();
+    for job in &jobs {
+        if let Some(runner_id) = job.runner_id {
+            by_runner.entry(runner_id).or_default().push(job);
+        }
+    }
+
+    // Fleet-wide failure rate per job name, to tell runner trouble from job trouble
+    let mut by_name: HashMap<&str, (usize, usize)> = HashMap::new();
+    for job in &jobs {
+        let entry = by_name.entry(job.name.as_str()).or_default();
+        entry.0 += 1;
+        entry.1 += usize::from(job.status == FAILED);
+    }
+
+    let total_queued: f64 = jobs.iter().filter_map(|j| j.queued_duration).sum();
+    let runner_info: HashMap<u64, &Runner> = runners.iter().map(|r| (r.id, r)).collect();
+
+    let mut metrics: Vec<RunnerMetrics> = by_runner
+        .into_iter()
+        .map(|(id, runner_jobs)| {
+            let total_jobs = runner_jobs.len();
+            let failed_jobs = runner_jobs.iter().filter(|j| j.status == FAILED).count();
+            let queued: f64 = runner_jobs.iter().filter_map(|j| j.queued_duration).sum();
+            let expected_failures: f64 = runner_jobs
+                .iter()
+                .map(|j| {
+                    let (executions, failures) = by_name[j.name.as_str()];
+                    failures as f64 / executions as f64
+                })
+                .sum();
+            let info = runner_info.get(&id);
+
+            RunnerMetrics {
+                id,
+                description: info.and_then(|r| r.description.clone()),
+                runner_type: info.map(|r| r.kind.clone()),
+                status: info.map(|r| r.status.clone()),
+                paused: info.map(|r| r.paused),
+                total_jobs,
+                job_share: percentage(total_jobs as f64, jobs.len() as f64),
+                busy_minutes: runner_jobs.iter().filter_map(|j| j.duration).sum::<f64>() / 60.0,
+                avg_queued_seconds: if total_jobs == 0 {
+                    0.0
+                } else {
+                    queued / total_jobs as f64
+                },
+                queue_share: percentage(queued, total_queued),
+                failed_jobs,
+                failure_rate: percentage(failed_jobs as f64, total_jobs as f64),
+                excess_failure_rate: percentage(
+                    failed_jobs as f64 - expected_failures,
+                    total_jobs as f64,
+                ),
+            }
+        })
+        .collect();
+
+    metrics.sort_by_key(|m| std::cmp::Reverse(m.total_jobs));
+    metrics
+}
+
+/// Queue times of every job attempt with a known queue time, grouped by each
+/// runner tag it requested; p95 above `slo_seconds` flags the tag. Worst first.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_tag_queue_metrics(
+    pipelines: &[GitLabPipeline],
+    slo_seconds: u64,
+) -> Vec<TagQueueMetrics> {
+    let mut metrics: Vec<TagQueueMetrics> = jobs_by_tag(pipelines)
+        .into_iter()
+        .filter_map(|(tag, jobs)| {
+            let mut queued: Vec<f64> = jobs.iter().filter_map(|j| j.queued_duration).collect();
+            if queued.is_empty() {
+                return None;
+            }
+            queued.sort_by(f64::total_cmp);
+            let p95_queued_seconds = percentile(&queued, 95.0);
+            Some(TagQueueMetrics {
+                tag: tag.map(ToString::to_string),
+                total_jobs: queued.len(),
+                avg_queued_seconds: queued.iter().sum::<f64>() / queued.len() as f64,
+                p95_queued_seconds,
+                breaching_slo: p95_queued_seconds > slo_seconds as f64,
+            })
+        })
+        .collect();
+
+    metrics.sort_by(|a, b| b.p95_queued_seconds.total_cmp(&a.p95_queued_seconds));
+    metrics
+}
+
+/// Job attempts grouped by each runner tag they requested; `None` holds the
+/// untagged ones.
+fn jobs_by_tag(pipelines: &[GitLabPipeline]) -> BTreeMap<Option<&str>, Vec<&GitLabJob>> {
+    let mut by_tag: BTreeMap<Option<&str>, Vec<&GitLabJob>> = BTreeMap::new();
+    for job in pipelines.iter().flat_map(|p| &p.jobs) {
+        if job.tags.is_empty() {
+            by_tag.entry(None).or_default().push(job);
+        }
+        for tag in &job.tags {
+            by_tag.entry(Some(tag)).or_default().push(job);
+        }
+    }
+    by_tag
+}
+
+/// Seconds spent at each concurrency level above zero while `jobs` ran.
+#[allow(clippy::cast_precision_loss)]
+fn concurrency_profile(jobs: &[&GitLabJob]) -> BTreeMap<usize, f64> {
+    let mut events: Vec<(DateTime<Utc>, i32)> = jobs
+        .iter()
+        .filter_map(|j| Some((j.started_at?, j.finished_at?)))
+        .filter(|(started, finished)| finished > started)
+        .flat_map(|(started, finished)| [(started, 1), (finished, -1)])
+        .collect();
+    // Finishes sort before starts at the same instant, so back-to-back jobs
+    // don't count as overlapping
+    events.sort_unstable();
+
+    let mut profile: BTreeMap<usize, f64> = BTreeMap::new();
+    let mut running = 0_usize;
+    let mut previous: Option<DateTime<Utc>> = None;
+    for (time, change) in events {
+        if let Some(previous) = previous {
+            if running > 0 {
+                *profile.entry(running).or_default() +=
+                    (time - previous).num_milliseconds() as f64 / 1000.0;
+            }
+        }
+        running = running.saturating_add_signed(change as isize);
+        previous = Some(time);
+    }
+    profile
+}
+
+/// Concurrency profile of the jobs requesting each runner tag, translated
+/// into a runner count to keep idle and one to scale up to. Busiest first.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn calculate_tag_concurrency(pipelines: &[GitLabPipeline]) -> Vec<TagConcurrency> {
+    let mut metrics: Vec<TagConcurrency> = jobs_by_tag(pipelines)
+        .into_iter()
+        .filter_map(|(tag, jobs)| {
+            let profile = concurrency_profile(&jobs);
+            let busy: f64 = profile.values().sum();
+            if busy <= 0.0 {
+                return None;
+            }
+
+            let avg_concurrency = profile
+                .iter()
+                .map(|(&level, seconds)| level as f64 * seconds)
+                .sum::<f64>()
+                / busy;
+            let mut covered = 0.0;
+            let p95_concurrency = profile
+                .iter()
+                .find(|(_, &seconds)| {
+                    covered += seconds;
+                    covered >= busy * 0.95
+                })
+                .map_or(0, |(&level, _)| level);
+
+            Some(TagConcurrency {
+                tag: tag.map(ToString::to_string),
+                total_jobs: jobs.len(),
+                busy_minutes: busy / 60.0,
+                peak_concurrency: profile.keys().next_back().copied().unwrap_or(0),
+                avg_concurrency,
+                p95_concurrency,
+                suggested_min_runners: avg_concurrency.ceil() as usize,
+                suggested_max_runners: p95_concurrency,
+            })
+        })
+        .collect();
+
+    metrics.sort_by(|a, b| b.avg_concurrency.total_cmp(&a.avg_concurrency));
+    metrics
+}
+
+const SHARED_RUNNER: &str = "instance_type";
+
+#[allow(clippy::cast_precision_loss)]
+fn hosting_metrics(jobs: &[&GitLabJob]) -> HostingMetrics {
+    if jobs.is_empty() {
+        return HostingMetrics::default();
+    }
+    let durations: Vec<f64> = jobs.iter().filter_map(|j| j.duration).collect();
+    let mut queued: Vec<f64> = jobs.iter().filter_map(|j| j.queued_duration).collect();
+    queued.sort_by(f64::total_cmp);
+
+    HostingMetrics {
+        total_jobs: jobs.len(),
+        avg_duration_seconds: if durations.is_empty() {
+            0.0
+        } else {
+            durations.iter().sum::<f64>() / durations.len() as f64
+        },
+        avg_queued_seconds: if queued.is_empty() {
+            0.0
+        } else {
+            queued.iter().sum::<f64>() / queued.len() as f64
+        },
+        p95_queued_seconds: percentile(&queued, 95.0),
+        failure_rate: percentage(
+            jobs.iter().filter(|j| j.status == FAILED).count() as f64,
+            jobs.len() as f64,
+        ),
+    }
+}
+
+/// Duration, queue time and failure rate of job attempts on shared runners
+/// against self-hosted ones, overall and per job that ran on both. Attempts
+/// whose runner type is unknown are left out.
+pub fn calculate_hosting_comparison(pipelines: &[GitLabPipeline]) -> HostingComparison {
+    let mut shared: BTreeMap<&str, Vec<&GitLabJob>> = BTreeMap::new();
+    let mut self_hosted: BTreeMap<&str, Vec<&GitLabJob>> = BTreeMap::new();
+    for job in pipelines.iter().flat_map(|p| &p.jobs) {
+        match job.runner_type.as_deref() {
+            Some(SHARED_RUNNER) => shared.entry(&job.name).or_default().push(job),
+            Some(_) => self_hosted.entry(&job.name).or_default().push(job),
+            None => {}
+        }
+    }
+
+    let mut jobs: Vec<JobHostingComparison> = shared
+        .iter()
+        .filter_map(|(name, shared_jobs)| {
+            let shared = hosting_metrics(shared_jobs);
+            let self_hosted = hosting_metrics(self_hosted.get(name)?);
+            Some(JobHostingComparison {
+                name: (*name).to_string(),
+                duration_difference_seconds: self_hosted.avg_duration_seconds
+                    - shared.avg_duration_seconds,
+                shared,
+                self_hosted,
+            })
+        })
+        .collect();
+    jobs.sort_by(|a, b| {
+        a.duration_difference_seconds
+            .total_cmp(&b.duration_difference_seconds)
+    });
+
+    let all = |by_name: &BTreeMap<&str, Vec<&GitLabJob>>| {
+        hosting_metrics(&by_name.values().flatten().copied().collect::<Vec<_>>())
+    };
+    HostingComparison {
+        shared: all(&shared),
+        self_hosted: all(&self_hosted),
+        jobs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline, time};
+
+    fn runner(id: u64, status: &str) -> Runner {
+        Runner {
+            id,
+            description: Some(format!("runner-{id}")),
+            status: status.to_string(),
+            paused: false,
+            kind: "project_type".to_string(),
+        }
+    }
+
+    fn job_on(id: u64, name: &str, status: &str, runner_id: u64, queued: f64) -> GitLabJob {
+        let mut job = job(id, name, "test", 60.0, status);
+        job.runner_id = Some(runner_id);
+        job.queued_duration = Some(queued);
+        job
+    }
+
+    #[test]
+    fn test_runner_metrics_flag_runners_failing_more_than_the_fleet() {
+        let mut pipeline = pipeline(1, "failed", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        pipeline.jobs = vec![
+            job_on(1, "test", "SUCCESS", 1, 10.0),
+            job_on(2, "test", "SUCCESS", 1, 10.0),
+            job_on(3, "test", "FAILED", 2, 30.0),
+            job_on(4, "test", "FAILED", 2, 50.0),
+        ];
+
+        let metrics = calculate_runner_metrics(
+            &[pipeline],
+            &[
+                runner(1, "online"),
+                runner(2, "online"),
+                runner(3, "offline"),
+            ],
+        );
+
+        let bad = metrics.iter().find(|m| m.id == 2).unwrap();
+        assert!((bad.failure_rate - 100.0).abs() < f64::EPSILON);
+        // `test` fails half the time fleet-wide
+        assert!((bad.excess_failure_rate - 50.0).abs() < f64::EPSILON);
+        assert!((bad.queue_share - 80.0).abs() < f64::EPSILON);
+
+        let good = metrics.iter().find(|m| m.id == 1).unwrap();
+        assert!((good.excess_failure_rate + 50.0).abs() < f64::EPSILON);
+
+        let idle = metrics.iter().find(|m| m.id == 3).unwrap();
+        assert_eq!(idle.total_jobs, 0);
+        assert_eq!(idle.status.as_deref(), Some("offline"));
+    }
+
+    #[test]
+    fn test_tag_queue_metrics_flag_tags_breaching_the_slo() {
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        pipeline.jobs = (0..20)
+            .map(|i| {
+                let mut job = job_on(i, "build", "SUCCESS", 1, 10.0);
+                job.tags = vec!["docker".to_string()];
+                if i == 0 {
+                    job.tags.push("gpu".to_string());
+                    job.queued_duration = Some(600.0);
+                }
+                job
+            })
+            .chain(std::iter::once(job_on(20, "lint", "SUCCESS", 1, 5.0)))
+            .collect();
+
+        let metrics = calculate_tag_queue_metrics(&[pipeline], 120);
+
+        let tags: Vec<Option<&str>> = metrics.iter().map(|m| m.tag.as_deref()).collect();
+        assert_eq!(tags, vec![Some("gpu"), Some("docker"), None]);
+        assert!(metrics[0].breaching_slo);
+        // One slow job in 20 stays out of the docker tag's p95
+        assert!((metrics[1].p95_queued_seconds - 10.0).abs() < f64::EPSILON);
+        assert!(!metrics[1].breaching_slo);
+        assert_eq!(metrics[2].total_jobs, 1);
+    }
+
+    #[test]
+    fn test_tag_concurrency_sizes_runners_from_overlapping_jobs() {
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T11:00:00Z");
+        let timed = |id: u64, start: &str, end: &str| {
+            let mut job = job(id, "test", "test", 60.0, "SUCCESS");
+            job.started_at = Some(time(&format!("2025-01-01T{start}:00Z")));
+            job.finished_at = Some(time(&format!("2025-01-01T{end}:00Z")));
+            job
+        };
+        // 10:00-10:10 one job, 10:10-10:20 three, 10:20-10:30 two; the
+        // 10:30 job starts as the last one finishes
+        pipeline.jobs = vec![
+            timed(1, "10:00", "10:30"),
+            timed(2, "10:10", "10:20"),
+            timed(3, "10:10", "10:30"),
+            timed(4, "10:30", "10:40"),
+        ];
+
+        let metrics = calculate_tag_concurrency(&[pipeline]);
+
+        assert_eq!(metrics.len(), 1);
+        let untagged = &metrics[0];
+        assert_eq!(untagged.tag, None);
+        assert_eq!(untagged.peak_concurrency, 3);
+        assert!((untagged.busy_minutes - 40.0).abs() < 1e-9);
+        // (1 * 10 + 3 * 10 + 2 * 10 + 1 * 10) / 40 minutes
+        assert!((untagged.avg_concurrency - 1.75).abs() < 1e-9);
+        assert_eq!(untagged.p95_concurrency, 3);
+        assert_eq!(untagged.suggested_min_runners, 2);
+    }
+
+    #[test]
+    fn test_hosting_comparison_compares_jobs_run_on_both() {
+        let on = |id: u64, name: &str, runner_type: &str, duration: f64| {
+            let mut job = job_on(id, name, "SUCCESS", id, duration / 10.0);
+            job.duration = Some(duration);
+            job.runner_type = Some(runner_type.to_string());
+            job
+        };
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        pipeline.jobs = vec![
+            on(1, "build", "instance_type", 300.0),
+            on(2, "build", "project_type", 120.0),
+            on(3, "build", "group_type", 180.0),
+            on(4, "lint", "instance_type", 30.0),
+            job(5, "manual", "test", 0.0, "SUCCESS"),
+        ];
+
+        let hosting = calculate_hosting_comparison(&[pipeline]);
+
+        assert_eq!(hosting.shared.total_jobs, 2);
+        assert_eq!(hosting.self_hosted.total_jobs, 2);
+        assert!((hosting.self_hosted.avg_duration_seconds - 150.0).abs() < f64::EPSILON);
+        assert_eq!(hosting.jobs.len(), 1);
+        assert_eq!(hosting.jobs[0].name, "build");
+        assert!((hosting.jobs[0].duration_difference_seconds + 150.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,149 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+
+use super::types::GitLabPipeline;
+use crate::insights::SamplingInfo;
+
+/// z-score of a 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// How many of the listed pipelines to analyze in depth.
+#[derive(Debug, Clone, Copy)]
+pub enum Sample {
+    /// Percentage (0-100) of the listed pipelines
+    Percentage(f64),
+    Count(usize),
+}
+
+impl Sample {
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn size(self, population: usize) -> usize {
+        let size = match self {
+            Sample::Percentage(percentage) => {
+                (population as f64 * percentage / 100.0).ceil() as usize
+            }
+            Sample::Count(count) => count,
+        };
+        size.clamp(usize::from(population > 0), population)
+    }
+}
+
+/// Picks `size` items without replacement, favouring recent ones: the newest
+/// item is twice as likely to be picked as the oldest, with weights falling
+/// linearly in between (Efraimidis-Spirakis weighted sampling).
+pub fn sample_by_recency<T, R: Rng>(
+    items: Vec<T>,
+    created_at: impl Fn(&T) -> DateTime<Utc>,
+    size: usize,
+    rng: &mut R,
+) -> Vec<T> {
+    if size >= items.len() {
+        return items;
+    }
+
+    let mut by_age: Vec<T> = items;
+    by_age.sort_by_key(|item| std::cmp::Reverse(created_at(item)));
+
+    #[allow(clippy::cast_precision_loss)]
+    let total = by_age.len() as f64;
+
+    let mut keyed: Vec<(f64, T)> = by_age
+        .into_iter()
+        .enumerate()
+        .map(|(rank, item)| {
+            #[allow(clippy::cast_precision_loss)]
+            let weight = 2.0 - rank as f64 / total;
+            let u: f64 = rng.random();
+            (u.powf(1.0 / weight), item)
+        })
+        .collect();
+
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.truncate(size);
+
+    let mut sampled: Vec<T> = keyed.into_iter().map(|(_, item)| item).collect();
+    sampled.sort_by_key(|item| std::cmp::Reverse(created_at(item)));
+    sampled
+}
+
+/// Describes the sample and the 95% margin of error of its success rate,
+/// including the finite population correction.
+#[allow(clippy::cast_precision_loss)]
+pub fn sampling_info(population: usize, sampled: &[GitLabPipeline]) -> SamplingInfo {
+    let n = sampled.len() as f64;
+    let big_n = population as f64;
+
+    let success_rate_margin_of_error = if sampled.is_empty() || population <= 1 {
+        0.0
+    } else {
+        let p = sampled.iter().filter(|p| p.status == "success").count() as f64 / n;
+        let correction = ((big_n - n) / (big_n - 1.0)).max(0.0).sqrt();
+        Z_95 * (p * (1.0 - p) / n).sqrt() * correction * 100.0
+    };
+
+    SamplingInfo {
+        population,
+        sampled: sampled.len(),
+        success_rate_margin_of_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sample_size() {
+        assert_eq!(Sample::Percentage(10.0).size(95), 10);
+        assert_eq!(Sample::Count(200).size(50), 50);
+        assert_eq!(Sample::Percentage(0.0).size(50), 1);
+    }
+
+    #[test]
+    fn test_sample_by_recency_favours_recent_items() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let base = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let items: Vec<i64> = (0..1000).collect();
+
+        let mut recent = 0;
+        for _ in 0..20 {
+            let sampled = sample_by_recency(
+                items.clone(),
+                |&i| base + chrono::Duration::hours(i),
+                100,
+                &mut rng,
+            );
+            assert_eq!(sampled.len(), 100);
+            recent += sampled.iter().filter(|&&i| i >= 500).count();
+        }
+
+        // Newer half carries ~58% of the weight
+        assert!(recent > 1050, "only {recent} of 2000 picks were recent");
+    }
+
+    #[test]
+    fn test_sampling_info_margin_of_error() {
+        let sampled: Vec<GitLabPipeline> = (0..100)
+            .map(|i| {
+                let status = if i % 2 == 0 { "success" } else { "failed" };
+                pipeline(i, status, "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z")
+            })
+            .collect();
+
+        let info = sampling_info(100, &sampled);
+        assert!(info.success_rate_margin_of_error.abs() < f64::EPSILON);
+
+        let info = sampling_info(1_000_000, &sampled);
+        assert!((info.success_rate_margin_of_error - 9.8).abs() < 0.01);
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use croner::Cron;
+use log::warn;
+
+use super::client::schedules::{PipelineSchedule, SchedulePipeline};
+use crate::insights::ScheduleDrift;
+
+/// Fire times this recent may not have had their pipeline created yet: GitLab's
+/// scheduler worker only runs every few minutes, and later under load.
+const SCHEDULER_GRACE_MINUTES: i64 = 60;
+/// Bounds the fire times checked for schedules running every minute over long windows
+const MAX_FIRE_TIMES: usize = 50_000;
+
+/// Compares when `schedule`'s cron expression fired between `since` and
+/// `until` with when its `pipelines` were created. Each fire time is run by
+/// the first pipeline created before the next one; fire times without such a
+/// pipeline are missed. `None` when the cron expression does not parse.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_schedule_drift(
+    schedule: &PipelineSchedule,
+    pipelines: &[SchedulePipeline],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Option<ScheduleDrift> {
+    let cron = match Cron::new(&schedule.cron).parse() {
+        Ok(cron) => cron,
+        Err(e) => {
+            warn!(
+                "Skipping schedule {} with unsupported cron '{}': {e}",
+                schedule.id, schedule.cron
+            );
+            return None;
+        }
+    };
+    let timezone: Tz = schedule.cron_timezone.parse().unwrap_or_else(|_| {
+        warn!(
+            "Unknown timezone '{}' of schedule {}, assuming UTC",
+            schedule.cron_timezone, schedule.id
+        );
+        Tz::UTC
+    });
+
+    let evaluated_until = until - Duration::minutes(SCHEDULER_GRACE_MINUTES);
+    let fire_times: Vec<DateTime<Utc>> = cron
+        .iter_after(since.with_timezone(&timezone))
+        .map(|fire_time| fire_time.with_timezone(&Utc))
+        .take_while(|fire_time| *fire_time <= until)
+        .take(MAX_FIRE_TIMES)
+        .collect();
+
+    let mut created: Vec<DateTime<Utc>> = pipelines
+        .iter()
+        .map(|p| p.created_at)
+        .filter(|created_at| (since..=until).contains(created_at))
+        .collect();
+    created.sort();
+
+    let mut drifts = Vec::new();
+    let mut missed_runs = 0;
+    for (i, fire_time) in fire_times.iter().enumerate() {
+        if *fire_time > evaluated_until {
+            break;
+        }
+        let next_fire_time = fire_times.get(i + 1).copied().unwrap_or(until);
+        let first = created.partition_point(|created_at| created_at < fire_time);
+        match created.get(first).filter(|c| **c < next_fire_time) {
+            Some(created_at) => drifts.push((*created_at - *fire_time).num_seconds() as f64),
+            None => missed_runs += 1,
+        }
+    }
+
+    Some(ScheduleDrift {
+        id: schedule.id,
+        description: schedule.description.clone(),
+        cron: schedule.cron.clone(),
+        cron_timezone: schedule.cron_timezone.clone(),
+        ref_: schedule.ref_.clone(),
+        expected_runs: drifts.len() + missed_runs,
+        actual_runs: created.len(),
+        missed_runs,
+        avg_drift_seconds: if drifts.is_empty() {
+            0.0
+        } else {
+            drifts.iter().sum::<f64>() / drifts.len() as f64
+        },
+        max_drift_seconds: drifts.iter().copied().fold(0.0, f64::max),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::time;
+
+    #[test]
+    fn test_schedule_drift_matches_pipelines_to_fire_times_in_schedule_timezone() {
+        let schedule = PipelineSchedule {
+            id: 7,
+            description: "Nightly".to_string(),
+            ref_: "main".to_string(),
+            cron: "0 2 * * *".to_string(),
+            cron_timezone: "Europe/Berlin".to_string(),
+            active: true,
+        };
+        // 02:00 in Berlin is 01:00 UTC in January
+        let pipelines = [
+            SchedulePipeline {
+                created_at: time("2025-01-01T01:04:00Z"),
+            },
+            SchedulePipeline {
+                created_at: time("2025-01-03T01:10:00Z"),
+            },
+            // A manual play the same day does not count as another run
+            SchedulePipeline {
+                created_at: time("2025-01-03T15:00:00Z"),
+            },
+        ];
+
+        let drift = calculate_schedule_drift(
+            &schedule,
+            &pipelines,
+            time("2025-01-01T00:00:00Z"),
+            time("2025-01-04T01:30:00Z"),
+        )
+        .unwrap();
+
+        // The 4th's fire time is within the grace period
+        assert_eq!(drift.expected_runs, 3);
+        assert_eq!(drift.actual_runs, 3);
+        assert_eq!(drift.missed_runs, 1);
+        assert!((drift.avg_drift_seconds - 420.0).abs() < f64::EPSILON);
+        assert!((drift.max_drift_seconds - 600.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use chrono_tz::Tz;
+
+use super::developer_wait::week_start;
+use super::stats::{mean, rate};
+use super::types::GitLabPipeline;
+use crate::insights::{SlaAttainment, SlaTrend, WeeklySlaAttainment};
+
+/// Attainment change (in percentage points) below which the trend counts as stable.
+const TREND_TOLERANCE: f64 = 5.0;
+
+fn mean_rate(weeks: &[WeeklySlaAttainment]) -> f64 {
+    mean(weeks.iter().map(|w| w.attainment_rate))
+}
+
+/// Compares the later half of the weeks against the earlier half.
+fn trend(weekly: &[WeeklySlaAttainment]) -> SlaTrend {
+    if weekly.len() < 2 {
+        return SlaTrend::Stable;
+    }
+
+    let (earlier, later) = weekly.split_at(weekly.len() / 2);
+    let change = mean_rate(later) - mean_rate(earlier);
+
+    if change > TREND_TOLERANCE {
+        SlaTrend::Improving
+    } else if change < -TREND_TOLERANCE {
+        SlaTrend::Worsening
+    } else {
+        SlaTrend::Stable
+    }
+}
+
+/// Share of pipelines finishing within `target_seconds`, overall and per week
+/// of `timezone`.
+pub fn calculate_sla(
+    pipelines: &[&GitLabPipeline],
+    target_seconds: u64,
+    timezone: Tz,
+) -> SlaAttainment {
+    let within = |p: &GitLabPipeline| p.duration as u64 <= target_seconds;
+
+    let mut weeks: BTreeMap<_, (usize, usize)> = BTreeMap::new();
+    for pipeline in pipelines {
+        let week = weeks
+            .entry(week_start(pipeline.created_at, timezone))
+            .or_default();
+        week.0 += usize::from(within(pipeline));
+        week.1 += 1;
+    }
+
+    let weekly: Vec<WeeklySlaAttainment> = weeks
+        .into_iter()
+        .map(|(week_start, (met, total))| WeeklySlaAttainment {
+            week_start,
+            pipelines: total,
+            attainment_rate: rate(met, total),
+        })
+        .collect();
+
+    let met = pipelines.iter().filter(|p| within(p)).count();
+
+    SlaAttainment {
+        target_seconds,
+        attainment_rate: rate(met, pipelines.len()),
+        trend: trend(&weekly),
+        weekly,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+
+    #[test]
+    fn test_calculate_sla_attainment_and_trend() {
+        let pipelines = [
+            // Week 1: one of two within 10 minutes
+            pipeline(1, "success", "2025-01-06T10:00:00Z", "2025-01-06T10:05:00Z"),
+            pipeline(2, "success", "2025-01-07T10:00:00Z", "2025-01-07T10:20:00Z"),
+            // Week 2: all within 10 minutes
+            pipeline(3, "failed", "2025-01-13T10:00:00Z", "2025-01-13T10:10:00Z"),
+        ];
+        let refs: Vec<&GitLabPipeline> = pipelines.iter().collect();
+
+        let sla = calculate_sla(&refs, 600, Tz::UTC);
+
+        assert!((sla.attainment_rate - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(sla.weekly.len(), 2);
+        assert!((sla.weekly[0].attainment_rate - 50.0).abs() < f64::EPSILON);
+        assert_eq!(sla.trend, SlaTrend::Improving);
+    }
+}
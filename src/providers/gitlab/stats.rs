@@ -0,0 +1,63 @@
+//! Small statistics shared by the analyses.
+
+/// `part` as a percentage of `total`, or 0 when `total` is 0.
+pub fn percentage(part: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        part / total * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Percentage of `total` items that `count` of them make up.
+#[allow(clippy::cast_precision_loss)]
+pub fn rate(count: usize, total: usize) -> f64 {
+    percentage(count as f64, total as f64)
+}
+
+/// Average of `values`, or 0 when there are none.
+#[allow(clippy::cast_precision_loss)]
+pub fn mean(values: impl IntoIterator<Item = f64>) -> f64 {
+    let (sum, count) = values
+        .into_iter()
+        .fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        return 0.0;
+    }
+    sum / count as f64
+}
+
+/// Nearest-rank percentile of ascending `sorted` values.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_inputs_yield_zero() {
+        assert!(rate(3, 0).abs() < f64::EPSILON);
+        assert!(mean([]).abs() < f64::EPSILON);
+        assert!(percentile(&[], 95.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_percentile_uses_nearest_rank() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+
+        assert!((percentile(&sorted, 50.0) - 2.0).abs() < f64::EPSILON);
+        assert!((percentile(&sorted, 95.0) - 4.0).abs() < f64::EPSILON);
+        assert!((percentile(&sorted, 0.0) - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -1,12 +1,17 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
+use chrono::{DateTime, Utc};
+
+use super::pipeline_types::{AnalysisOptions, JobSort};
 use super::types::{GitLabJob, GitLabPipeline};
 use super::url_utils::{job_id_to_url, pipeline_id_to_url};
 use crate::insights::{
-    JobCountWithLinks, JobMetrics, PipelineCountWithLinks, PredecessorJob, TypeMetrics,
+    HistogramBucket, JobCountWithLinks, JobMetrics, PipelineCountWithLinks, PredecessorJob,
+    TypeMetrics, VariantMetrics,
 };
 
+#[allow(clippy::trivially_copy_pass_by_ref)]
 fn cmp_f64(a: &f64, b: &f64) -> Ordering {
     a.partial_cmp(b).unwrap_or(Ordering::Equal)
 }
@@ -30,41 +35,66 @@ fn empty_job_count() -> JobCountWithLinks {
 pub fn calculate_type_metrics(
     pipelines: &[&GitLabPipeline],
     percentage: f64,
+    options: &AnalysisOptions,
     base_url: &str,
     project_path: &str,
 ) -> TypeMetrics {
     let total_pipelines = pipelines.len();
 
-    let successful: Vec<_> = pipelines
-        .iter()
-        .filter(|p| p.status == "success")
-        .copied()
-        .collect();
-
-    let failed: Vec<_> = pipelines
-        .iter()
-        .filter(|p| p.status == "failed")
-        .copied()
-        .collect();
+    let with_status = |status: &str| -> Vec<&GitLabPipeline> {
+        pipelines
+            .iter()
+            .filter(|p| p.status == status)
+            .copied()
+            .collect()
+    };
+    let successful = with_status("success");
+    let failed = with_status("failed");
 
     let successful_pipelines = to_pipeline_links(&successful, base_url, project_path);
     let failed_pipelines = to_pipeline_links(&failed, base_url, project_path);
 
     let (jobs, avg_time_to_feedback_seconds) =
-        aggregate_job_metrics(&successful, pipelines, base_url, project_path);
+        aggregate_job_metrics(&successful, pipelines, options, base_url, project_path);
+    let waste = super::waste::calculate_failure_waste(pipelines);
 
     TypeMetrics {
         percentage,
         total_pipelines,
         successful_pipelines,
         failed_pipelines,
-        success_rate: calculate_success_rate(successful.len(), total_pipelines),
+        canceled_pipelines: to_pipeline_links(&with_status("canceled"), base_url, project_path),
+        skipped_pipelines: to_pipeline_links(&with_status("skipped"), base_url, project_path),
+        success_rate: calculate_success_rate(successful.len(), successful.len() + failed.len()),
         avg_duration_seconds: calculate_avg_duration(&successful),
         avg_time_to_feedback_seconds,
+        failed_pipeline_minutes: waste.failed_pipeline_minutes,
+        doomed_minutes: waste.doomed_minutes,
+        sla: None,
+        by_ref: None,
+        artifact_transfer: None,
+        recommendations: super::recommendations::recommend(pipelines, options),
         jobs,
     }
 }
 
+/// Job metrics over all `pipelines` as one group, skipping pipeline type clustering.
+pub fn calculate_job_ranking(
+    pipelines: &[GitLabPipeline],
+    options: &AnalysisOptions,
+    base_url: &str,
+    project_path: &str,
+) -> Vec<JobMetrics> {
+    let all: Vec<&GitLabPipeline> = pipelines.iter().collect();
+    let successful: Vec<&GitLabPipeline> = all
+        .iter()
+        .filter(|p| p.status == "success")
+        .copied()
+        .collect();
+
+    aggregate_job_metrics(&successful, &all, options, base_url, project_path).0
+}
+
 fn to_pipeline_links(
     pipelines: &[&GitLabPipeline],
     base_url: &str,
@@ -96,6 +126,7 @@ fn calculate_avg_duration(pipelines: &[&GitLabPipeline]) -> f64 {
 fn aggregate_job_metrics(
     successful_pipelines: &[&GitLabPipeline],
     all_pipelines: &[&GitLabPipeline],
+    options: &AnalysisOptions,
     base_url: &str,
     project_path: &str,
 ) -> (Vec<JobMetrics>, f64) {
@@ -128,10 +159,17 @@ fn aggregate_job_metrics(
 
     // Aggregate job data across all pipelines
     let mut job_data: HashMap<String, JobData> = HashMap::new();
-    for metrics in &per_pipeline_metrics {
+    for (pipeline, metrics) in successful_pipelines.iter().zip(&per_pipeline_metrics) {
+        let attempts = super::job_analysis::final_attempts(pipeline);
         for job_metric in metrics {
             let data = job_data.entry(job_metric.name.clone()).or_default();
-            data.durations.push(job_metric.avg_duration_seconds);
+            // Estimated durations only time the DAG; averages stick to reported ones
+            if let Some(duration) = attempts
+                .get(job_metric.name.as_str())
+                .and_then(|job| job.duration)
+            {
+                data.durations.push(duration);
+            }
             data.total_durations
                 .push(job_metric.avg_time_to_feedback_seconds);
             let predecessor_names = job_metric
@@ -140,6 +178,8 @@ fn aggregate_job_metrics(
                 .map(|p| p.name.clone())
                 .collect();
             data.all_predecessor_names.push(predecessor_names);
+            data.absent_needs
+                .extend(job_metric.absent_needs.iter().cloned());
         }
     }
 
@@ -149,15 +189,158 @@ fn aggregate_job_metrics(
         .collect();
 
     let reliability_data = calculate_job_reliability(all_pipelines, base_url, project_path);
+    let scheduling = job_scheduling(all_pipelines);
+    let flakiness_scores = recency_weighted_flakiness(all_pipelines, options.flakiness_half_life);
+    let failure_classes = super::failure_classes::classify_failures(all_pipelines);
+    let mut variants = if options.matrix_variants {
+        calculate_variant_metrics(all_pipelines)
+    } else {
+        HashMap::new()
+    };
 
     let mut jobs: Vec<JobMetrics> = job_data
         .into_iter()
-        .map(|(name, data)| build_job_metrics(&name, &data, &avg_durations, &reliability_data))
+        .map(|(name, data)| {
+            let mut job = build_job_metrics(&name, &data, &avg_durations, &reliability_data);
+            job.duration_histogram = options
+                .histogram_buckets
+                .as_deref()
+                .map(|buckets| duration_histogram(&data.durations, buckets));
+            if let Some(scheduling) = scheduling.get(name.as_str()) {
+                job.avg_start_offset_seconds = (!scheduling.start_offsets.is_empty())
+                    .then(|| compute_mean(&scheduling.start_offsets));
+                job.last_started_at = scheduling.last_started_at;
+            }
+            job.variants = variants.remove(&name);
+            if let Some(&score) = flakiness_scores.get(name.as_str()) {
+                job.flakiness_score = score;
+            }
+            if let Some(counts) = failure_classes.get(name.as_str()) {
+                job.transient_failure_rate = calculate_rate(counts.transient, counts.executions);
+                job.persistent_failure_rate = calculate_rate(counts.persistent, counts.executions);
+            }
+            job
+        })
         .collect();
 
-    jobs.sort_by(|a, b| cmp_f64(&b.avg_time_to_feedback_seconds, &a.avg_time_to_feedback_seconds));
+    sort_jobs(&mut jobs, options.sort_jobs);
+
+    (select_jobs(jobs, options), avg_time_to_feedback)
+}
+
+/// Flaky retries over executions per job, with each pipeline weighted by
+/// `0.5^(age / half_life)`, its age counted back from the newest pipeline.
+/// Without a half-life every pipeline weighs the same, as in the raw rate.
+#[allow(clippy::cast_precision_loss)]
+fn recency_weighted_flakiness<'a>(
+    pipelines: &[&'a GitLabPipeline],
+    half_life_seconds: u64,
+) -> HashMap<&'a str, f64> {
+    let Some(newest) = pipelines.iter().map(|p| p.created_at).max() else {
+        return HashMap::new();
+    };
+    let mut totals: HashMap<&str, (f64, f64)> = HashMap::new();
+
+    for pipeline in pipelines {
+        let weight = if half_life_seconds == 0 {
+            1.0
+        } else {
+            let age = (newest - pipeline.created_at).num_seconds() as f64;
+            0.5_f64.powf(age / half_life_seconds as f64)
+        };
+
+        for ((name, _), jobs) in group_jobs_by_name(&pipeline.jobs) {
+            let entry = totals.entry(name).or_default();
+            entry.1 += weight * jobs.len() as f64;
+            if is_job_flaky(&jobs) {
+                entry.0 += weight * jobs.iter().filter(|j| j.retried).count() as f64;
+            }
+        }
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, (_, executions))| *executions > 0.0)
+        .map(|(name, (flaky, executions))| (name, flaky / executions * 100.0))
+        .collect()
+}
+
+#[derive(Default)]
+struct JobScheduling {
+    start_offsets: Vec<f64>,
+    last_started_at: Option<DateTime<Utc>>,
+}
+
+/// Observed start times of each job's final attempt, relative to its pipeline's start.
+#[allow(clippy::cast_precision_loss)]
+fn job_scheduling<'a>(pipelines: &[&'a GitLabPipeline]) -> HashMap<&'a str, JobScheduling> {
+    let mut scheduling: HashMap<&str, JobScheduling> = HashMap::new();
+
+    for pipeline in pipelines {
+        let pipeline_start = pipeline.started_at.unwrap_or(pipeline.created_at);
+
+        for job in pipeline.jobs.iter().filter(|j| !j.retried) {
+            let Some(started_at) = job.started_at else {
+                continue;
+            };
+
+            let entry = scheduling.entry(job.name.as_str()).or_default();
+            entry
+                .start_offsets
+                .push((started_at - pipeline_start).num_milliseconds() as f64 / 1000.0);
+            entry.last_started_at = entry.last_started_at.max(Some(started_at));
+        }
+    }
+
+    scheduling
+}
+
+fn sort_jobs(jobs: &mut [JobMetrics], sort: JobSort) {
+    let key = |job: &JobMetrics| match sort {
+        JobSort::Feedback => job.avg_time_to_feedback_seconds,
+        JobSort::Duration => job.avg_duration_seconds,
+        JobSort::FailureRate => job.failure_rate,
+        JobSort::Flakiness => job.flakiness_rate,
+        #[allow(clippy::cast_precision_loss)]
+        JobSort::Executions => job.total_executions as f64,
+    };
+
+    // Ties (e.g. many jobs with no failures) fall back to time-to-feedback
+    jobs.sort_by(|a, b| {
+        cmp_f64(&key(b), &key(a)).then_with(|| {
+            cmp_f64(
+                &b.avg_time_to_feedback_seconds,
+                &a.avg_time_to_feedback_seconds,
+            )
+        })
+    });
+}
+
+/// Drops jobs with too few executions to be meaningful, then keeps the
+/// `top_jobs` first jobs plus the `top_flaky` flakiest ones so flaky jobs
+/// aren't hidden by the cut. `jobs` must already be sorted.
+fn select_jobs(mut jobs: Vec<JobMetrics>, options: &AnalysisOptions) -> Vec<JobMetrics> {
+    jobs.retain(|job| job.total_executions >= options.min_executions);
 
-    (jobs, avg_time_to_feedback)
+    let Some(top_jobs) = options.top_jobs else {
+        return jobs;
+    };
+
+    let mut by_flakiness: Vec<&JobMetrics> =
+        jobs.iter().filter(|job| job.flakiness_rate > 0.0).collect();
+    by_flakiness.sort_by(|a, b| cmp_f64(&b.flakiness_rate, &a.flakiness_rate));
+
+    let flakiest: HashSet<String> = by_flakiness
+        .into_iter()
+        .take(options.top_flaky)
+        .map(|job| job.name.clone())
+        .collect();
+
+    jobs.into_iter()
+        .enumerate()
+        .filter(|(rank, job)| *rank < top_jobs || flakiest.contains(&job.name))
+        .map(|(_, job)| job)
+        .collect()
 }
 
 #[derive(Default)]
@@ -165,6 +348,7 @@ struct JobData {
     durations: Vec<f64>,
     total_durations: Vec<f64>,
     all_predecessor_names: Vec<Vec<String>>,
+    absent_needs: BTreeSet<String>,
 }
 
 #[allow(clippy::cast_precision_loss)]
@@ -202,6 +386,10 @@ fn build_job_metrics(
             ),
             None => (0, 0.0, empty_job_count(), 0.0, empty_job_count()),
         };
+    let retry_cost = reliability_data
+        .get(name)
+        .map(|r| r.retry_cost)
+        .unwrap_or_default();
 
     JobMetrics {
         name: name.to_string(),
@@ -209,10 +397,66 @@ fn build_job_metrics(
         avg_time_to_feedback_seconds,
         predecessors,
         flakiness_rate,
+        flakiness_score: flakiness_rate,
         flaky_retries,
         failed_executions,
         failure_rate,
+        transient_failure_rate: 0.0,
+        persistent_failure_rate: 0.0,
         total_executions,
+        wasted_retry_minutes: retry_cost.wasted_seconds / 60.0,
+        retry_wait_minutes: retry_cost.wait_seconds / 60.0,
+        duration_histogram: None,
+        avg_start_offset_seconds: None,
+        last_started_at: None,
+        variants: None,
+        absent_needs: data.absent_needs.iter().cloned().collect(),
+    }
+}
+
+/// Cumulative counts of `durations` at or below each bound, plus a final
+/// unbounded bucket holding every duration (Prometheus `le` semantics).
+fn duration_histogram(durations: &[f64], bounds: &[f64]) -> Vec<HistogramBucket> {
+    bounds
+        .iter()
+        .map(|&bound| HistogramBucket {
+            le: Some(bound),
+            count: durations.iter().filter(|&&d| d <= bound).count(),
+        })
+        .chain(std::iter::once(HistogramBucket {
+            le: None,
+            count: durations.len(),
+        }))
+        .collect()
+}
+
+/// Time lost to retried job attempts.
+#[derive(Debug, Default, Clone, Copy)]
+struct RetryCost {
+    wasted_seconds: f64,
+    wait_seconds: f64,
+}
+
+impl RetryCost {
+    fn add(&mut self, jobs: &[&GitLabJob]) {
+        let retried: Vec<&GitLabJob> = jobs.iter().copied().filter(|j| j.retried).collect();
+        if retried.is_empty() {
+            return;
+        }
+
+        let wasted: f64 = retried.iter().filter_map(|j| j.duration).sum();
+        self.wasted_seconds += wasted;
+
+        let first_start = retried.iter().filter_map(|j| j.started_at).min();
+        let final_start = jobs.iter().find(|j| !j.retried).and_then(|j| j.started_at);
+
+        // Without timestamps, assume the retries ran back to back
+        #[allow(clippy::cast_precision_loss)]
+        let wait = match (first_start, final_start) {
+            (Some(first), Some(last)) if last > first => (last - first).num_seconds() as f64,
+            _ => wasted,
+        };
+        self.wait_seconds += wait;
     }
 }
 
@@ -224,6 +468,7 @@ struct JobReliabilityMetrics {
     failure_rate: f64,
     failed_executions: usize,
     failed_job_links: Vec<String>,
+    retry_cost: RetryCost,
 }
 
 fn aggregate_predecessors(
@@ -272,12 +517,14 @@ fn calculate_job_reliability(
     let mut flaky_job_links: HashMap<String, Vec<String>> = HashMap::new();
     let mut failed_executions: HashMap<String, usize> = HashMap::new();
     let mut failed_job_links: HashMap<String, Vec<String>> = HashMap::new();
+    let mut retry_costs: HashMap<String, RetryCost> = HashMap::new();
 
     for pipeline in pipelines {
         let jobs_by_name = group_jobs_by_name(&pipeline.jobs);
 
-        for (name, jobs) in jobs_by_name {
+        for ((name, _), jobs) in jobs_by_name {
             *execution_counts.entry(name.to_string()).or_insert(0) += jobs.len();
+            retry_costs.entry(name.to_string()).or_default().add(&jobs);
 
             if is_job_flaky(&jobs) {
                 let retry_links: Vec<String> = jobs
@@ -309,6 +556,7 @@ fn calculate_job_reliability(
         &failed_executions,
         &failed_job_links,
         &execution_counts,
+        &retry_costs,
     )
 }
 
@@ -318,6 +566,7 @@ fn compute_reliability_metrics(
     failure_counts: &HashMap<String, usize>,
     failure_job_links: &HashMap<String, Vec<String>>,
     execution_counts: &HashMap<String, usize>,
+    retry_costs: &HashMap<String, RetryCost>,
 ) -> HashMap<String, JobReliabilityMetrics> {
     execution_counts
         .iter()
@@ -337,19 +586,80 @@ fn compute_reliability_metrics(
                     failure_rate: calculate_rate(failed_executions, total_executions),
                     failed_executions,
                     failed_job_links,
+                    retry_cost: retry_costs.get(name).copied().unwrap_or_default(),
                 },
             )
         })
         .collect()
 }
 
-fn group_jobs_by_name(jobs: &[GitLabJob]) -> HashMap<&str, Vec<&GitLabJob>> {
+/// Groups the attempts of each job, keeping parallel/matrix variants apart.
+fn group_jobs_by_name(jobs: &[GitLabJob]) -> HashMap<(&str, Option<&str>), Vec<&GitLabJob>> {
     jobs.iter().fold(HashMap::new(), |mut grouped, job| {
-        grouped.entry(job.name.as_str()).or_default().push(job);
+        grouped
+            .entry((job.name.as_str(), job.variant.as_deref()))
+            .or_default()
+            .push(job);
         grouped
     })
 }
 
+#[derive(Default)]
+struct VariantTotals {
+    executions: usize,
+    durations: Vec<f64>,
+    flaky_retries: usize,
+    failed_executions: usize,
+}
+
+/// Per-variant reliability of `parallel`/`matrix` jobs, keyed by base job name.
+fn calculate_variant_metrics(
+    pipelines: &[&GitLabPipeline],
+) -> HashMap<String, Vec<VariantMetrics>> {
+    let mut totals: HashMap<(&str, &str), VariantTotals> = HashMap::new();
+
+    for pipeline in pipelines {
+        for ((name, variant), jobs) in group_jobs_by_name(&pipeline.jobs) {
+            let Some(variant) = variant else {
+                continue;
+            };
+
+            let entry = totals.entry((name, variant)).or_default();
+            entry.executions += jobs.len();
+            entry.durations.extend(
+                jobs.iter()
+                    .filter(|j| !j.retried)
+                    .filter_map(|j| j.duration),
+            );
+            if is_job_flaky(&jobs) {
+                entry.flaky_retries += jobs.iter().filter(|j| j.retried).count();
+            } else if is_job_failed(&jobs) {
+                entry.failed_executions += 1;
+            }
+        }
+    }
+
+    let mut variants: HashMap<String, Vec<VariantMetrics>> = HashMap::new();
+    for ((name, variant), totals) in totals {
+        variants
+            .entry(name.to_string())
+            .or_default()
+            .push(VariantMetrics {
+                variant: variant.to_string(),
+                avg_duration_seconds: compute_mean(&totals.durations),
+                flakiness_rate: calculate_rate(totals.flaky_retries, totals.executions),
+                failure_rate: calculate_rate(totals.failed_executions, totals.executions),
+                total_executions: totals.executions,
+            });
+    }
+
+    for list in variants.values_mut() {
+        list.sort_by(|a, b| cmp_f64(&b.avg_duration_seconds, &a.avg_duration_seconds));
+    }
+
+    variants
+}
+
 fn is_job_flaky(jobs: &[&GitLabJob]) -> bool {
     // Flaky = job was retried AND eventually succeeded
     let was_retried = jobs.iter().any(|j| j.retried);
@@ -368,3 +678,198 @@ fn is_job_failed(jobs: &[&GitLabJob]) -> bool {
         .find(|j| !j.retried)
         .is_none_or(|j| j.status != "SUCCESS")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, time};
+
+    #[test]
+    fn test_retry_cost_uses_attempt_start_times() {
+        let mut first = job(1, "lint", "test", 60.0, "FAILED");
+        first.retried = true;
+        first.started_at = Some(time("2025-01-01T10:00:00Z"));
+        let mut last = job(2, "lint", "test", 60.0, "SUCCESS");
+        last.started_at = Some(time("2025-01-01T10:05:00Z"));
+
+        let mut cost = RetryCost::default();
+        cost.add(&[&first, &last]);
+
+        assert!((cost.wasted_seconds - 60.0).abs() < f64::EPSILON);
+        assert!((cost.wait_seconds - 300.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_recency_weighted_flakiness_discounts_old_retries() {
+        use crate::providers::gitlab::types::fixtures::pipeline;
+
+        let mut retried = job(1, "e2e", "test", 60.0, "FAILED");
+        retried.retried = true;
+        let mut old = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z");
+        old.jobs = vec![retried, job(2, "e2e", "test", 60.0, "SUCCESS")];
+        let mut new = pipeline(2, "success", "2025-01-15T10:00:00Z", "2025-01-15T10:05:00Z");
+        new.jobs = vec![job(3, "e2e", "test", 60.0, "SUCCESS")];
+
+        let unweighted = recency_weighted_flakiness(&[&old, &new], 0);
+        let weighted = recency_weighted_flakiness(&[&old, &new], 14 * 86_400);
+
+        assert!((unweighted["e2e"] - 100.0 / 3.0).abs() < 1e-9);
+        assert!((weighted["e2e"] - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_job_ranking_spans_pipelines_with_different_jobs() {
+        use crate::providers::gitlab::types::fixtures::pipeline;
+
+        let mut docs = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z");
+        docs.jobs = vec![job(1, "docs", "test", 20.0, "SUCCESS")];
+        let mut app = pipeline(2, "success", "2025-01-01T11:00:00Z", "2025-01-01T11:05:00Z");
+        app.jobs = vec![job(2, "build", "test", 90.0, "SUCCESS")];
+
+        let ranking = calculate_job_ranking(
+            &[docs, app],
+            &AnalysisOptions::default(),
+            "https://gitlab.com",
+            "group/project",
+        );
+
+        let names: Vec<&str> = ranking.iter().map(|job| job.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "docs"]);
+    }
+
+    #[test]
+    fn test_canceled_pipelines_are_counted_apart_from_failures() {
+        use crate::providers::gitlab::types::fixtures::pipeline;
+
+        let pipelines = [
+            pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z"),
+            pipeline(2, "failed", "2025-01-01T11:00:00Z", "2025-01-01T11:05:00Z"),
+            pipeline(
+                3,
+                "canceled",
+                "2025-01-01T12:00:00Z",
+                "2025-01-01T12:01:00Z",
+            ),
+        ];
+        let refs: Vec<&GitLabPipeline> = pipelines.iter().collect();
+
+        let metrics = calculate_type_metrics(
+            &refs,
+            100.0,
+            &AnalysisOptions::default(),
+            "https://gitlab.com",
+            "group/project",
+        );
+
+        assert_eq!(metrics.total_pipelines, 3);
+        assert_eq!(metrics.failed_pipelines.count, 1);
+        assert_eq!(metrics.canceled_pipelines.count, 1);
+        assert!(metrics.skipped_pipelines.is_empty());
+        assert!((metrics.success_rate - 50.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_duration_histogram_is_cumulative() {
+        let histogram = duration_histogram(&[10.0, 45.0, 60.0, 400.0], &[30.0, 60.0, 300.0]);
+
+        let counts: Vec<usize> = histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 3, 3, 4]);
+        assert_eq!(histogram.last().unwrap().le, None);
+    }
+
+    #[test]
+    fn test_select_jobs_keeps_flakiest_beyond_top_jobs() {
+        use crate::insights::fixtures::job;
+
+        let mut flaky = job("e2e", 10.0, 10);
+        flaky.flakiness_rate = 20.0;
+        let jobs = vec![
+            job("build", 100.0, 10),
+            job("lint", 50.0, 10),
+            job("rare", 40.0, 1),
+            flaky,
+        ];
+        let options = AnalysisOptions {
+            top_jobs: Some(1),
+            top_flaky: 1,
+            min_executions: 2,
+            ..AnalysisOptions::default()
+        };
+
+        let names: Vec<String> = select_jobs(jobs, &options)
+            .into_iter()
+            .map(|job| job.name)
+            .collect();
+
+        assert_eq!(names, vec!["build", "e2e"]);
+    }
+
+    #[test]
+    fn test_sort_jobs_by_failure_rate_breaks_ties_by_feedback() {
+        use crate::insights::fixtures::job;
+
+        let mut failing = job("e2e", 10.0, 10);
+        failing.failure_rate = 30.0;
+        let mut jobs = vec![job("lint", 50.0, 10), failing, job("build", 100.0, 10)];
+
+        sort_jobs(&mut jobs, JobSort::FailureRate);
+
+        let names: Vec<&str> = jobs.iter().map(|job| job.name.as_str()).collect();
+        assert_eq!(names, vec!["e2e", "build", "lint"]);
+    }
+
+    #[test]
+    fn test_job_scheduling_uses_final_attempts() {
+        use crate::providers::gitlab::types::fixtures::pipeline;
+
+        let mut retried = job(1, "lint", "test", 60.0, "FAILED");
+        retried.retried = true;
+        retried.started_at = Some(time("2025-01-01T10:00:10Z"));
+        let mut last = job(2, "lint", "test", 60.0, "SUCCESS");
+        last.started_at = Some(time("2025-01-01T10:02:00Z"));
+        let mut pipeline = pipeline(1, "success", "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z");
+        pipeline.jobs = vec![retried, last];
+
+        let scheduling = job_scheduling(&[&pipeline]);
+
+        let lint = &scheduling["lint"];
+        assert_eq!(lint.start_offsets, vec![120.0]);
+        assert_eq!(lint.last_started_at, Some(time("2025-01-01T10:02:00Z")));
+    }
+
+    #[test]
+    fn test_variant_metrics_keep_matrix_variants_apart() {
+        use crate::providers::gitlab::types::fixtures::pipeline;
+
+        let variant = |id, name: &str, variant: &str, duration, status: &str| {
+            let mut job = job(id, name, "test", duration, status);
+            job.variant = Some(variant.to_string());
+            job
+        };
+        let mut pipeline = pipeline(1, "failed", "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z");
+        pipeline.jobs = vec![
+            variant(1, "rspec", "1/2", 60.0, "SUCCESS"),
+            variant(2, "rspec", "2/2", 120.0, "FAILED"),
+            job(3, "lint", "test", 10.0, "SUCCESS"),
+        ];
+
+        let variants = calculate_variant_metrics(&[&pipeline]);
+
+        assert_eq!(variants.len(), 1);
+        let rspec = &variants["rspec"];
+        assert_eq!(rspec[0].variant, "2/2");
+        assert!((rspec[0].failure_rate - 100.0).abs() < f64::EPSILON);
+        assert!(rspec[1].failure_rate.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_retry_cost_ignores_jobs_without_retries() {
+        let job = job(1, "lint", "test", 60.0, "SUCCESS");
+
+        let mut cost = RetryCost::default();
+        cost.add(&[&job]);
+
+        assert!(cost.wasted_seconds.abs() < f64::EPSILON);
+        assert!(cost.wait_seconds.abs() < f64::EPSILON);
+    }
+}
@@ -1,21 +1,148 @@
-#[derive(Debug)]
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
 pub struct GitLabPipeline {
     pub id: String,
     pub ref_: String,
+    /// Full ref, e.g. `refs/heads/main`, `refs/tags/v1.0` or `refs/merge-requests/1/head`
+    pub ref_path: String,
+    /// Commit the pipeline ran for
+    pub sha: String,
     pub source: String,
     pub status: String,
+    pub merge_request_event_type: Option<String>,
     pub duration: usize,
+    /// When the pipeline's commit was committed
+    pub committed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Who triggered the pipeline; absent for deleted users
+    pub user: Option<GitLabUser>,
     pub stages: Vec<String>,
     pub jobs: Vec<GitLabJob>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
+pub struct GitLabUser {
+    pub username: String,
+    pub bot: bool,
+}
+
+#[derive(Debug, Serialize)]
 pub struct GitLabJob {
     pub id: String,
+    /// Name with any `parallel`/`matrix` suffix removed
     pub name: String,
+    /// `parallel`/`matrix` suffix, e.g. `2/5` or `[aws, prod]`
+    pub variant: Option<String>,
     pub stage: String,
-    pub duration: f64,
+    /// Seconds the job ran; `None` when GitLab did not report it
+    pub duration: Option<f64>,
+    /// Stands in for a missing `duration` in DAG timing: the time between
+    /// start and finish, or the job's average reported duration
+    pub estimated_duration: Option<f64>,
     pub status: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
     pub retried: bool,
     pub needs: Option<Vec<String>>,
+    /// Numeric ID of the runner that picked the job up
+    pub runner_id: Option<u64>,
+    /// `instance_type` for shared runners, `group_type` or `project_type` for
+    /// self-hosted ones
+    pub runner_type: Option<String>,
+    /// Seconds the job waited for a runner
+    pub queued_duration: Option<f64>,
+    /// Runner tags the job requested
+    pub tags: Vec<String>,
+    /// Why the job failed, as worded by GitLab (e.g. "There has been a
+    /// runner system failure, please try again")
+    pub failure_message: Option<String>,
+    /// Seconds the downstream pipeline of a trigger (bridge) job ran
+    pub downstream_duration: Option<f64>,
+}
+
+impl GitLabJob {
+    /// Reported duration, or its estimate when GitLab did not report one.
+    #[must_use]
+    pub fn dag_duration(&self) -> f64 {
+        self.duration.or(self.estimated_duration).unwrap_or(0.0)
+    }
+
+    /// Seconds the job holds up its dependents: a trigger job's own duration
+    /// is near zero, so the downstream pipeline's counts instead.
+    #[must_use]
+    pub fn path_duration(&self) -> f64 {
+        let duration = self.dag_duration();
+        self.downstream_duration
+            .map_or(duration, |downstream| downstream.max(duration))
+    }
+
+    /// Name as shown in GitLab, including any `parallel`/`matrix` suffix.
+    #[must_use]
+    pub fn full_name(&self) -> String {
+        match &self.variant {
+            Some(variant) if variant.starts_with('[') => format!("{}: {variant}", self.name),
+            Some(variant) => format!("{} {variant}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use super::*;
+
+    pub fn time(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    pub fn pipeline(id: u64, status: &str, created_at: &str, finished_at: &str) -> GitLabPipeline {
+        let created_at = time(created_at);
+        let finished_at = time(finished_at);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let duration = (finished_at - created_at).num_seconds() as usize;
+
+        GitLabPipeline {
+            id: format!("gid://gitlab/Ci::Pipeline/{id}"),
+            ref_: "main".to_string(),
+            ref_path: "refs/heads/main".to_string(),
+            sha: format!("{id:040x}"),
+            source: "push".to_string(),
+            status: status.to_string(),
+            merge_request_event_type: None,
+            duration,
+            committed_at: None,
+            created_at,
+            started_at: Some(created_at),
+            finished_at: Some(finished_at),
+            user: None,
+            stages: vec![],
+            jobs: vec![],
+        }
+    }
+
+    pub fn job(id: u64, name: &str, stage: &str, duration: f64, status: &str) -> GitLabJob {
+        GitLabJob {
+            id: format!("gid://gitlab/Ci::Job/{id}"),
+            name: name.to_string(),
+            variant: None,
+            stage: stage.to_string(),
+            duration: Some(duration),
+            estimated_duration: None,
+            status: status.to_string(),
+            started_at: None,
+            finished_at: None,
+            retried: false,
+            needs: None,
+            runner_id: None,
+            runner_type: None,
+            queued_duration: None,
+            tags: vec![],
+            failure_message: None,
+            downstream_duration: None,
+        }
+    }
 }
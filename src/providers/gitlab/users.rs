@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use super::types::GitLabPipeline;
+use crate::insights::UserMetrics;
+
+const UNKNOWN_USER: &str = "(unknown)";
+
+/// Pipeline counts and failure rates per triggering user, busiest first.
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_user_metrics(pipelines: &[GitLabPipeline]) -> Vec<UserMetrics> {
+    let mut by_user: BTreeMap<&str, (bool, usize, usize)> = BTreeMap::new();
+    for pipeline in pipelines {
+        let (username, bot) = pipeline
+            .user
+            .as_ref()
+            .map_or((UNKNOWN_USER, false), |u| (u.username.as_str(), u.bot));
+        let entry = by_user.entry(username).or_insert((bot, 0, 0));
+        entry.1 += 1;
+        entry.2 += usize::from(pipeline.status == "failed");
+    }
+
+    let mut metrics: Vec<UserMetrics> = by_user
+        .into_iter()
+        .map(|(username, (bot, total, failed))| UserMetrics {
+            username: username.to_string(),
+            bot,
+            total_pipelines: total,
+            pipeline_share: total as f64 / pipelines.len() as f64 * 100.0,
+            failed_pipelines: failed,
+            failure_rate: failed as f64 / total as f64 * 100.0,
+        })
+        .collect();
+
+    metrics.sort_by_key(|m| std::cmp::Reverse(m.total_pipelines));
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+    use crate::providers::gitlab::types::GitLabUser;
+
+    fn triggered_by(id: u64, status: &str, username: &str, bot: bool) -> GitLabPipeline {
+        let mut pipeline = pipeline(id, status, "2025-01-01T10:00:00Z", "2025-01-01T10:05:00Z");
+        pipeline.user = Some(GitLabUser {
+            username: username.to_string(),
+            bot,
+        });
+        pipeline
+    }
+
+    #[test]
+    fn test_user_metrics_rank_by_pipelines_triggered() {
+        let pipelines = vec![
+            triggered_by(1, "success", "alice", false),
+            triggered_by(2, "failed", "renovate", true),
+            triggered_by(3, "failed", "renovate", true),
+            triggered_by(4, "success", "renovate", true),
+        ];
+
+        let metrics = calculate_user_metrics(&pipelines);
+
+        assert_eq!(metrics[0].username, "renovate");
+        assert!(metrics[0].bot);
+        assert!((metrics[0].pipeline_share - 75.0).abs() < f64::EPSILON);
+        assert_eq!(metrics[0].failed_pipelines, 2);
+        assert!((metrics[1].failure_rate).abs() < f64::EPSILON);
+    }
+}
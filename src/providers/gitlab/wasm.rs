@@ -0,0 +1,128 @@
+//! Analyzers compiled to WebAssembly, loaded from a plugins directory.
+//!
+//! A plugin module exports `memory`, `alloc(len: i32) -> i32` and
+//! `analyze(ptr: i32, len: i32) -> i64`. `analyze` receives the pipelines as
+//! a JSON array and returns its JSON section packed as `ptr << 32 | len`; a
+//! `null` section is left out of the report. Sections are named after the
+//! plugin's file stem, so `plugins/cost.wasm` contributes `cost`.
+
+use std::path::Path;
+
+use log::info;
+use serde_json::Value;
+use wasmtime::{Engine, Instance, Module, Store};
+
+use super::analyzers::Analyzer;
+use super::types::GitLabPipeline;
+use crate::error::{CILensError, Result};
+
+pub struct WasmAnalyzer {
+    name: String,
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmAnalyzer {
+    fn new(engine: &Engine, name: String, bytes: &[u8]) -> Result<Self> {
+        let module = Module::new(engine, bytes)
+            .map_err(|e| CILensError::Config(format!("Invalid plugin {name}: {e:#}")))?;
+        Ok(Self {
+            name,
+            engine: engine.clone(),
+            module,
+        })
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn call(&self, input: &[u8]) -> wasmtime::Result<Vec<u8>> {
+        // Fresh instance per report, so plugins cannot carry state across projects
+        let mut store = Store::new(&self.engine, ());
+        let instance = Instance::new(&mut store, &self.module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("missing `memory` export"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let analyze = instance.get_typed_func::<(i32, i32), i64>(&mut store, "analyze")?;
+
+        let len = i32::try_from(input.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as usize, input)?;
+
+        let packed = analyze.call(&mut store, (ptr, len))? as u64;
+        let mut output = vec![0; (packed & 0xffff_ffff) as usize];
+        memory.read(&store, (packed >> 32) as usize, &mut output)?;
+
+        Ok(output)
+    }
+}
+
+impl Analyzer for WasmAnalyzer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn analyze(&self, pipelines: &[GitLabPipeline]) -> Result<Option<Value>> {
+        let output = self
+            .call(&serde_json::to_vec(pipelines)?)
+            .map_err(|e| CILensError::Analysis(format!("Plugin {} failed: {e:#}", self.name)))?;
+        let section: Value = serde_json::from_slice(&output)?;
+        Ok((!section.is_null()).then_some(section))
+    }
+}
+
+/// Loads every `*.wasm` file in `dir`, in file name order.
+pub fn load_plugins(dir: &Path) -> Result<Vec<WasmAnalyzer>> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "wasm"));
+    paths.sort();
+
+    let engine = Engine::default();
+    paths
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            info!("Loading analyzer plugin: {}", path.display());
+            WasmAnalyzer::new(&engine, name, &std::fs::read(path)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::pipeline;
+    use serde_json::json;
+
+    /// Ignores its input and returns the 11-byte section stored at offset 1024.
+    const CONSTANT_PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 1024) "{\"ok\":true}")
+          (func (export "alloc") (param i32) (result i32) i32.const 0)
+          (func (export "analyze") (param i32 i32) (result i64)
+            i64.const 4398046511115))
+    "#;
+
+    #[test]
+    fn test_wasm_analyzer_returns_plugin_section() {
+        let bytes = wat::parse_str(CONSTANT_PLUGIN).unwrap();
+        let analyzer =
+            WasmAnalyzer::new(&Engine::default(), "constant".to_string(), &bytes).unwrap();
+        let pipelines = vec![pipeline(
+            1,
+            "success",
+            "2025-01-01T10:00:00Z",
+            "2025-01-01T10:05:00Z",
+        )];
+
+        let section = analyzer.analyze(&pipelines).unwrap();
+
+        assert_eq!(analyzer.name(), "constant");
+        assert_eq!(section, Some(json!({ "ok": true })));
+    }
+}
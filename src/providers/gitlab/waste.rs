@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::types::{GitLabJob, GitLabPipeline};
+
+/// Compute time burnt by pipelines that ended up failing.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FailureWaste {
+    /// Minutes consumed by all jobs of failed pipelines
+    pub failed_pipeline_minutes: f64,
+    /// Minutes jobs kept running after the pipeline's first failing job finished
+    pub doomed_minutes: f64,
+}
+
+pub fn calculate_failure_waste(pipelines: &[&GitLabPipeline]) -> FailureWaste {
+    let mut waste = FailureWaste::default();
+
+    for pipeline in pipelines.iter().filter(|p| p.status == "failed") {
+        waste.failed_pipeline_minutes +=
+            pipeline.jobs.iter().filter_map(|j| j.duration).sum::<f64>() / 60.0;
+        waste.doomed_minutes += doomed_seconds(pipeline) / 60.0;
+    }
+
+    waste
+}
+
+fn is_final_failure(job: &GitLabJob) -> bool {
+    !job.retried && job.status == "FAILED"
+}
+
+/// Seconds spent in jobs after the first job that failed for good. Uses job
+/// timestamps when available, otherwise counts every job of a later stage.
+#[allow(clippy::cast_precision_loss)]
+fn doomed_seconds(pipeline: &GitLabPipeline) -> f64 {
+    let first_failure = pipeline
+        .jobs
+        .iter()
+        .filter(|j| is_final_failure(j))
+        .filter_map(|j| j.finished_at.map(|finished| (finished, j)))
+        .min_by_key(|(finished, _)| *finished);
+
+    if let Some((failed_at, failed_job)) = first_failure {
+        return pipeline
+            .jobs
+            .iter()
+            .filter(|j| j.id != failed_job.id)
+            .map(|j| seconds_after(j, failed_at))
+            .sum();
+    }
+
+    let stage_index: HashMap<&str, usize> = pipeline
+        .stages
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.as_str(), i))
+        .collect();
+    let stage_of = |job: &GitLabJob| stage_index.get(job.stage.as_str()).copied().unwrap_or(0);
+
+    let Some(failed_stage) = pipeline
+        .jobs
+        .iter()
+        .filter(|j| is_final_failure(j))
+        .map(stage_of)
+        .min()
+    else {
+        return 0.0;
+    };
+
+    pipeline
+        .jobs
+        .iter()
+        .filter(|j| stage_of(j) > failed_stage)
+        .filter_map(|j| j.duration)
+        .sum()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn seconds_after(job: &GitLabJob, moment: DateTime<Utc>) -> f64 {
+    match (job.started_at, job.finished_at) {
+        (Some(started), Some(finished)) if finished > moment => {
+            (finished - started.max(moment)).num_milliseconds() as f64 / 1000.0
+        }
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::gitlab::types::fixtures::{job, pipeline, time};
+
+    fn timed_job(
+        id: u64,
+        name: &str,
+        status: &str,
+        started_at: &str,
+        finished_at: &str,
+    ) -> GitLabJob {
+        let mut job = job(id, name, "test", 0.0, status);
+        job.started_at = Some(time(started_at));
+        job.finished_at = Some(time(finished_at));
+        #[allow(clippy::cast_precision_loss)]
+        let duration = (time(finished_at) - time(started_at)).num_seconds() as f64;
+        job.duration = Some(duration);
+        job
+    }
+
+    #[test]
+    fn test_doomed_minutes_counts_work_after_first_failure() {
+        let mut failed = pipeline(1, "failed", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        failed.jobs = vec![
+            timed_job(
+                1,
+                "lint",
+                "FAILED",
+                "2025-01-01T10:00:00Z",
+                "2025-01-01T10:01:00Z",
+            ),
+            // Half of this job ran after lint already failed the pipeline
+            timed_job(
+                2,
+                "unit",
+                "SUCCESS",
+                "2025-01-01T10:00:00Z",
+                "2025-01-01T10:02:00Z",
+            ),
+            timed_job(
+                3,
+                "e2e",
+                "SUCCESS",
+                "2025-01-01T10:02:00Z",
+                "2025-01-01T10:05:00Z",
+            ),
+        ];
+        let passed = pipeline(2, "success", "2025-01-01T11:00:00Z", "2025-01-01T11:10:00Z");
+
+        let waste = calculate_failure_waste(&[&failed, &passed]);
+
+        assert!((waste.failed_pipeline_minutes - 6.0).abs() < f64::EPSILON);
+        assert!((waste.doomed_minutes - 4.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_doomed_minutes_falls_back_to_later_stages() {
+        let mut failed = pipeline(1, "failed", "2025-01-01T10:00:00Z", "2025-01-01T10:10:00Z");
+        failed.stages = vec!["build".to_string(), "test".to_string()];
+        failed.jobs = vec![
+            job(1, "build", "build", 60.0, "FAILED"),
+            job(2, "unit", "test", 120.0, "SUCCESS"),
+        ];
+
+        let waste = calculate_failure_waste(&[&failed]);
+
+        assert!((waste.doomed_minutes - 2.0).abs() < f64::EPSILON);
+    }
+}
@@ -1,3 +1,8 @@
+#[cfg(feature = "gitlab")]
 mod gitlab;
 
-pub use gitlab::GitLabProvider;
+#[cfg(feature = "gitlab")]
+pub use gitlab::{
+    AnalysisOptions, Analyzer, Api, CollectOptions, DedupePreference, GitLabJob, GitLabPipeline,
+    GitLabProvider, HttpOptions, JobSort, Middleware, Next, PipelineStatus, Sample,
+};
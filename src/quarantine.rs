@@ -0,0 +1,226 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CILensError, Result};
+use crate::insights::CIInsights;
+
+/// Jobs that should be marked `allow_failure` until they stop flaking.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuarantineList {
+    pub generated_at: DateTime<Utc>,
+    pub threshold: f64,
+    pub jobs: Vec<QuarantinedJob>,
+    /// Jobs from the previous list that dropped below the threshold
+    #[serde(default)]
+    pub recovered: Vec<RecoveredJob>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedJob {
+    pub name: String,
+    pub flakiness_rate: f64,
+    /// Recency-weighted flakiness; the list is sorted by it
+    #[serde(default)]
+    pub flakiness_score: f64,
+    pub quarantined_since: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveredJob {
+    pub name: String,
+    pub flakiness_rate: f64,
+    pub quarantined_since: DateTime<Utc>,
+    pub recovered_at: DateTime<Utc>,
+}
+
+impl QuarantineList {
+    /// Reads a list written by a previous run (YAML or JSON).
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content).map_err(|e| {
+            CILensError::Config(format!("Invalid quarantine list {}: {e}", path.display()))
+        })
+    }
+
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self)
+            .map_err(|e| CILensError::Config(format!("Failed to serialize quarantine list: {e}")))
+    }
+}
+
+/// Flakiness rate per job name, weighted by executions across pipeline types.
+#[allow(clippy::cast_precision_loss)]
+pub fn flakiness_rates(insights: &CIInsights) -> BTreeMap<&str, f64> {
+    let mut totals: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+
+    for job in insights
+        .pipeline_types
+        .iter()
+        .flat_map(|pt| &pt.metrics.jobs)
+    {
+        let entry = totals.entry(job.name.as_str()).or_default();
+        entry.0 += job.flaky_retries.count;
+        entry.1 += job.total_executions;
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, (_, executions))| *executions > 0)
+        .map(|(name, (flaky, executions))| (name, flaky as f64 / executions as f64 * 100.0))
+        .collect()
+}
+
+/// Recency-weighted flakiness score per job name, averaged across pipeline
+/// types by executions.
+#[allow(clippy::cast_precision_loss)]
+fn flakiness_scores(insights: &CIInsights) -> BTreeMap<&str, f64> {
+    let mut totals: BTreeMap<&str, (f64, usize)> = BTreeMap::new();
+
+    for job in insights
+        .pipeline_types
+        .iter()
+        .flat_map(|pt| &pt.metrics.jobs)
+    {
+        let entry = totals.entry(job.name.as_str()).or_default();
+        entry.0 += job.flakiness_score * job.total_executions as f64;
+        entry.1 += job.total_executions;
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, (_, executions))| *executions > 0)
+        .map(|(name, (weighted, executions))| (name, weighted / executions as f64))
+        .collect()
+}
+
+/// Builds the quarantine list for `insights`, carrying over `quarantined_since`
+/// from `previous` and reporting previously quarantined jobs that recovered.
+pub fn build(
+    insights: &CIInsights,
+    threshold: f64,
+    previous: Option<&QuarantineList>,
+) -> QuarantineList {
+    let now = insights.collected_at;
+    let rates = flakiness_rates(insights);
+    let scores = flakiness_scores(insights);
+    let previous_jobs: HashMap<&str, &QuarantinedJob> = previous
+        .map(|list| {
+            list.jobs
+                .iter()
+                .map(|job| (job.name.as_str(), job))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut jobs: Vec<QuarantinedJob> = rates
+        .iter()
+        .filter(|(_, rate)| **rate > threshold)
+        .map(|(name, rate)| QuarantinedJob {
+            name: (*name).to_string(),
+            flakiness_rate: *rate,
+            flakiness_score: scores.get(name).copied().unwrap_or(*rate),
+            quarantined_since: previous_jobs
+                .get(name)
+                .map_or(now, |job| job.quarantined_since),
+        })
+        .collect();
+
+    let mut recovered = Vec::new();
+    for (name, job) in previous_jobs {
+        match rates.get(name) {
+            Some(rate) if *rate <= threshold => recovered.push(RecoveredJob {
+                name: name.to_string(),
+                flakiness_rate: *rate,
+                quarantined_since: job.quarantined_since,
+                recovered_at: now,
+            }),
+            Some(_) => {}
+            // The job didn't run in this window, so there's no evidence it recovered
+            None => jobs.push(job.clone()),
+        }
+    }
+
+    jobs.sort_by(|a, b| {
+        b.flakiness_score
+            .total_cmp(&a.flakiness_score)
+            .then_with(|| b.flakiness_rate.total_cmp(&a.flakiness_rate))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    recovered.sort_by(|a, b| a.name.cmp(&b.name));
+
+    QuarantineList {
+        generated_at: now,
+        threshold,
+        jobs,
+        recovered,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+    use crate::insights::JobMetrics;
+
+    fn flaky_job(name: &str, flaky: usize, executions: usize) -> JobMetrics {
+        let mut job = job(name, 60.0, executions);
+        job.flaky_retries.count = flaky;
+        #[allow(clippy::cast_precision_loss)]
+        let rate = flaky as f64 / executions as f64 * 100.0;
+        job.flakiness_rate = rate;
+        job.flakiness_score = rate;
+        job
+    }
+
+    #[test]
+    fn test_build_quarantines_jobs_above_threshold() {
+        let report = insights(vec![pipeline_type(
+            "MR",
+            10,
+            0,
+            vec![flaky_job("lint", 3, 10), flaky_job("build", 0, 10)],
+        )]);
+
+        let list = build(&report, 5.0, None);
+
+        assert_eq!(list.jobs.len(), 1);
+        assert_eq!(list.jobs[0].name, "lint");
+        assert_eq!(list.jobs[0].quarantined_since, report.collected_at);
+    }
+
+    #[test]
+    fn test_build_tracks_recovery_and_keeps_quarantine_start() {
+        let since = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let quarantined = |name: &str| QuarantinedJob {
+            name: name.to_string(),
+            flakiness_rate: 20.0,
+            flakiness_score: 20.0,
+            quarantined_since: since,
+        };
+        let previous = QuarantineList {
+            generated_at: since,
+            threshold: 5.0,
+            jobs: vec![quarantined("lint"), quarantined("e2e"), quarantined("gone")],
+            recovered: vec![],
+        };
+        let report = insights(vec![pipeline_type(
+            "MR",
+            10,
+            0,
+            vec![flaky_job("lint", 0, 10), flaky_job("e2e", 2, 10)],
+        )]);
+
+        let list = build(&report, 5.0, Some(&previous));
+
+        let names: Vec<&str> = list.jobs.iter().map(|j| j.name.as_str()).collect();
+        assert_eq!(names, vec!["e2e", "gone"]);
+        assert_eq!(list.jobs[0].quarantined_since, since);
+        assert_eq!(list.recovered.len(), 1);
+        assert_eq!(list.recovered[0].name, "lint");
+    }
+}
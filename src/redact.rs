@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use sha2::{Digest, Sha256};
+
+use crate::insights::{CIInsights, JobMetrics, JobRanking, PipelineType};
+
+/// Refs that are the same in every project and reveal nothing.
+const GENERIC_REFS: [&str; 2] = ["main", "master"];
+
+/// How redacted values are named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RedactMode {
+    /// Numbered in order of appearance (`job-1`, `job-2`...); differs between runs
+    Sequential,
+    /// Derived from a salted SHA-256 of the value (`job-3f2a9c1e`); stable between runs
+    Hash,
+}
+
+/// Hands out one alias per distinct value and kind.
+struct Redactor<'a> {
+    mode: RedactMode,
+    salt: &'a str,
+    aliases: HashMap<(&'static str, String), String>,
+    counters: HashMap<&'static str, usize>,
+}
+
+impl<'a> Redactor<'a> {
+    fn new(mode: RedactMode, salt: &'a str) -> Self {
+        Self {
+            mode,
+            salt,
+            aliases: HashMap::new(),
+            counters: HashMap::new(),
+        }
+    }
+
+    fn alias(&mut self, kind: &'static str, value: &str) -> String {
+        if let Some(alias) = self.aliases.get(&(kind, value.to_string())) {
+            return alias.clone();
+        }
+
+        let alias = match self.mode {
+            RedactMode::Sequential => {
+                let counter = self.counters.entry(kind).or_default();
+                *counter += 1;
+                format!("{kind}-{counter}")
+            }
+            RedactMode::Hash => {
+                let digest = Sha256::new()
+                    .chain_update(self.salt)
+                    .chain_update(kind)
+                    .chain_update(value)
+                    .finalize();
+                let prefix = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+                format!("{kind}-{prefix:08x}")
+            }
+        };
+
+        self.aliases
+            .insert((kind, value.to_string()), alias.clone());
+        alias
+    }
+
+    fn replace(&mut self, kind: &'static str, value: &mut String) {
+        *value = self.alias(kind, value);
+    }
+
+    fn replace_all(&mut self, kind: &'static str, values: &mut [String]) {
+        for value in values {
+            self.replace(kind, value);
+        }
+    }
+
+    fn replace_ref(&mut self, value: &mut String) {
+        if !GENERIC_REFS.contains(&value.as_str()) {
+            self.replace("ref", value);
+        }
+    }
+}
+
+/// Replaces the project paths, job and stage names, refs, commits, images,
+/// usernames and URLs in `reports` with anonymized identifiers, so they can be shared
+/// without leaking internal naming. The same value always gets the same alias
+/// across all `reports`.
+pub fn redact(reports: &mut [CIInsights], mode: RedactMode, salt: &str) {
+    let mut redactor = Redactor::new(mode, salt);
+    for insights in reports {
+        redact_report(&mut redactor, insights);
+    }
+}
+
+fn redact_report(redactor: &mut Redactor, insights: &mut CIInsights) {
+    redactor.replace("project", &mut insights.project);
+    // Custom analyzer sections are opaque, so they cannot be scrubbed reliably
+    insights.sections.clear();
+
+    for pipeline_type in &mut insights.pipeline_types {
+        redact_pipeline_type(redactor, pipeline_type);
+    }
+
+    if let Some(summary) = &mut insights.summary {
+        for highlight in [&mut summary.slowest_job, &mut summary.flakiest_job]
+            .into_iter()
+            .flatten()
+        {
+            redactor.replace("job", &mut highlight.name);
+        }
+    }
+    if let Some(ref_) = insights
+        .meta
+        .as_mut()
+        .and_then(|meta| meta.parameters.ref_.as_mut())
+    {
+        redactor.replace_ref(ref_);
+    }
+    if let Some(comparison) = &mut insights.branch_comparison {
+        redactor.replace_ref(&mut comparison.default_branch);
+    }
+    for team in insights.teams.iter_mut().flatten() {
+        redactor.replace_all("job", &mut team.jobs);
+    }
+    for component in insights.components.iter_mut().flatten() {
+        redactor.replace_all("job", &mut component.jobs);
+    }
+    if let Some(releases) = &mut insights.releases {
+        redactor.replace_all("ref", &mut releases.unreleased_tags);
+    }
+    for user in insights.users.iter_mut().flatten() {
+        redactor.replace("user", &mut user.username);
+    }
+    for change in insights.image_changes.iter_mut().flatten() {
+        redactor.replace("job", &mut change.job);
+        redactor.replace("image", &mut change.from_image);
+        redactor.replace("image", &mut change.to_image);
+        change.summary = change.describe();
+    }
+    for change in insights.composition_changes.iter_mut().flatten() {
+        redactor.replace_ref(&mut change.ref_);
+        redactor.replace("url", &mut change.pipeline);
+        redactor.replace_all("job", &mut change.added_jobs);
+        redactor.replace_all("job", &mut change.removed_jobs);
+        change.summary = change.describe();
+    }
+    for broken in insights.currently_broken.iter_mut().flatten() {
+        redactor.replace("job", &mut broken.name);
+        redactor.replace("url", &mut broken.first_broken_pipeline);
+    }
+    for job_log in insights.job_logs.iter_mut().flatten() {
+        redactor.replace("job", &mut job_log.name);
+    }
+    if let Some(commits) = &mut insights.commits {
+        for commit in &mut commits.retried_commits {
+            redactor.replace("sha", &mut commit.sha);
+            redactor.replace_ref(&mut commit.ref_);
+            redactor.replace("url", &mut commit.green_pipeline);
+        }
+    }
+    if let Some(reverts) = &mut insights.reverts {
+        redactor.replace_ref(&mut reverts.default_branch);
+        for revert in &mut reverts.reverts {
+            redactor.replace("sha", &mut revert.sha);
+            redactor.replace("commit", &mut revert.title);
+            if let Some(reverted_sha) = &mut revert.reverted_sha {
+                redactor.replace("sha", reverted_sha);
+            }
+            if let Some(pipeline) = &mut revert.pipeline {
+                redactor.replace("url", pipeline);
+            }
+        }
+    }
+    for schedule in insights.schedules.iter_mut().flatten() {
+        redactor.replace("schedule", &mut schedule.description);
+        redactor.replace_ref(&mut schedule.ref_);
+    }
+}
+
+fn redact_pipeline_type(redactor: &mut Redactor, pipeline_type: &mut PipelineType) {
+    redactor.replace_all("stage", &mut pipeline_type.stages);
+    for ref_pattern in &mut pipeline_type.ref_patterns {
+        redactor.replace_ref(ref_pattern);
+    }
+
+    let metrics = &mut pipeline_type.metrics;
+    redactor.replace_all("url", &mut metrics.successful_pipelines.links);
+    redactor.replace_all("url", &mut metrics.failed_pipelines.links);
+    redactor.replace_all("url", &mut metrics.canceled_pipelines.links);
+    redactor.replace_all("url", &mut metrics.skipped_pipelines.links);
+
+    for job in &mut metrics.jobs {
+        redact_job(redactor, job);
+    }
+    if let Some(transfer) = &mut metrics.artifact_transfer {
+        for job in &mut transfer.jobs {
+            redactor.replace("job", &mut job.name);
+        }
+    }
+    for recommendation in &mut metrics.recommendations {
+        redactor.replace_all("stage", &mut recommendation.stages);
+        redactor.replace_all("job", &mut recommendation.jobs);
+        if let Some(straggler) = &mut recommendation.straggler {
+            redactor.replace("job", straggler);
+        }
+        recommendation.message = recommendation.describe();
+    }
+}
+
+/// Like [`redact`], for a `--jobs-only` ranking.
+pub fn redact_job_ranking(ranking: &mut JobRanking, mode: RedactMode, salt: &str) {
+    let mut redactor = Redactor::new(mode, salt);
+    redactor.replace("project", &mut ranking.project);
+    for job in &mut ranking.jobs {
+        redact_job(&mut redactor, job);
+    }
+}
+
+fn redact_job(redactor: &mut Redactor, job: &mut JobMetrics) {
+    redactor.replace("job", &mut job.name);
+    redactor.replace_all("job", &mut job.absent_needs);
+    redactor.replace_all("url", &mut job.flaky_retries.links);
+    redactor.replace_all("url", &mut job.failed_executions.links);
+    for predecessor in &mut job.predecessors {
+        redactor.replace("job", &mut predecessor.name);
+    }
+    for variant in job.variants.iter_mut().flatten() {
+        redactor.replace("variant", &mut variant.variant);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::fixtures::{insights, job, pipeline_type};
+    use crate::insights::{Recommendation, RecommendationKind, RevertCommit, RevertMetrics};
+
+    fn sample() -> CIInsights {
+        let mut build = job("build-acme", 60.0, 10);
+        build.flaky_retries.links = vec!["https://gitlab.internal/acme/-/jobs/1".to_string()];
+        let mut test = job("test-acme", 30.0, 10);
+        test.predecessors = vec![crate::insights::PredecessorJob {
+            name: "build-acme".to_string(),
+            avg_duration_seconds: 60.0,
+        }];
+
+        let mut pipeline_type = pipeline_type("Development Pipeline", 9, 1, vec![build, test]);
+        pipeline_type.ref_patterns = vec!["main".to_string(), "feature/acme-deal".to_string()];
+        pipeline_type.stages = vec!["acme-build".to_string(), "acme-test".to_string()];
+        let mut barrier = Recommendation {
+            kind: RecommendationKind::StageBarrier,
+            stages: vec!["acme-test".to_string()],
+            jobs: vec!["test-acme".to_string()],
+            straggler: Some("build-acme".to_string()),
+            executions: None,
+            estimated_savings_seconds: 60.0,
+            retry: None,
+            attempt_success_rate: None,
+            success_rate_with_retry: None,
+            target_success_rate: None,
+            message: String::new(),
+        };
+        barrier.message = barrier.describe();
+        pipeline_type.metrics.recommendations = vec![barrier];
+
+        let mut insights = insights(vec![pipeline_type]);
+        insights.reverts = Some(RevertMetrics {
+            default_branch: "main".to_string(),
+            total_commits: 10,
+            total_reverts: 1,
+            revert_rate: 10.0,
+            reverts_after_failure: 1,
+            reverts: vec![RevertCommit {
+                sha: "acme0002".to_string(),
+                title: "Revert \"Add acme billing\"".to_string(),
+                reverted_sha: Some("acme0001".to_string()),
+                after_failed_pipeline: true,
+                pipeline: Some("https://gitlab.internal/acme/-/pipelines/2".to_string()),
+            }],
+        });
+        insights
+    }
+
+    #[test]
+    fn test_redact_uses_one_alias_per_value() {
+        let mut insights = sample();
+        redact(
+            std::slice::from_mut(&mut insights),
+            RedactMode::Sequential,
+            "",
+        );
+
+        let pipeline_type = &insights.pipeline_types[0];
+        let jobs = &pipeline_type.metrics.jobs;
+        assert_eq!(jobs[0].name, "job-1");
+        assert_eq!(jobs[1].predecessors[0].name, "job-1");
+        assert_eq!(jobs[0].flaky_retries.links, vec!["url-1"]);
+        assert_eq!(pipeline_type.ref_patterns, vec!["main", "ref-1"]);
+        let recommendation = &pipeline_type.metrics.recommendations[0];
+        assert_eq!(recommendation.stages, vec!["stage-2"]);
+        assert!(recommendation.message.contains("`job-1`"));
+        let revert = &insights.reverts.as_ref().unwrap().reverts[0];
+        assert_eq!(revert.sha, "sha-1");
+        assert_eq!(revert.reverted_sha.as_deref(), Some("sha-2"));
+        assert_eq!(revert.title, "commit-1");
+        assert_eq!(revert.pipeline.as_deref(), Some("url-2"));
+        assert!(!serde_json::to_string(&insights).unwrap().contains("acme"));
+    }
+
+    #[test]
+    fn test_hash_aliases_are_stable_and_salted() {
+        let alias = |salt| {
+            let mut insights = sample();
+            redact(std::slice::from_mut(&mut insights), RedactMode::Hash, salt);
+            insights.pipeline_types[0].metrics.jobs[0].name.clone()
+        };
+
+        assert_eq!(alias("a"), alias("a"));
+        assert_ne!(alias("a"), alias("b"));
+        assert!(alias("a").starts_with("job-"));
+    }
+}